@@ -0,0 +1,59 @@
+//! Benchmarks for the card accessors that move generation calls in its inner loops.
+//!
+//! Run with `cargo bench -p freecell-game-engine --bench card`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use freecell_game_engine::card::{Card, Color, Rank, Suit};
+use freecell_game_engine::generation::generate_deal;
+
+fn full_deck() -> Vec<Card> {
+    [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs]
+        .into_iter()
+        .flat_map(|suit| (1..=13).map(move |value| Card::new(Rank::try_from(value).unwrap(), suit)))
+        .collect()
+}
+
+fn card_accessors(c: &mut Criterion) {
+    let deck = full_deck();
+
+    c.bench_function("card/color", |b| {
+        b.iter(|| black_box(&deck).iter().filter(|card| card.color() == Color::Red).count())
+    });
+
+    c.bench_function("card/rank_and_suit", |b| {
+        b.iter(|| {
+            black_box(&deck)
+                .iter()
+                .map(|card| card.rank() as u32 + card.suit().foundation_index() as u32)
+                .sum::<u32>()
+        })
+    });
+
+    // Every ordered pair, as when checking which cards can stack on which.
+    c.bench_function("card/can_stack_on", |b| {
+        b.iter(|| {
+            let deck = black_box(&deck);
+            deck.iter()
+                .flat_map(|top| deck.iter().map(move |under| (top, under)))
+                .filter(|(top, under)| under.is_one_higher_than(top) && top.color() != under.color())
+                .count()
+        })
+    });
+}
+
+/// The accessors as the engine uses them: generating every legal move for fresh deals.
+fn move_generation(c: &mut Criterion) {
+    let deals: Vec<_> = (1..=32).map(|seed| generate_deal(seed).unwrap()).collect();
+
+    c.bench_function("card/available_moves", |b| {
+        b.iter(|| {
+            black_box(&deals)
+                .iter()
+                .map(|game| game.get_available_moves().len())
+                .sum::<usize>()
+        })
+    });
+}
+
+criterion_group!(benches, card_accessors, move_generation);
+criterion_main!(benches);
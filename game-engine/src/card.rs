@@ -33,6 +33,14 @@ use core::fmt;
 /// Cards are used as the primary building block for the FreeCell solitaire game.
 /// Each card has a rank (Ace through King) and a suit (Spades, Hearts, Diamonds, or Clubs).
 ///
+/// Internally a card is a single byte: the suit in bits 0-1, a red flag in bit 2 and
+/// the rank in bits 4-7, all filled in by [`Card::new`]. Color checks and rank
+/// comparisons, which move generation performs constantly, are then plain bit
+/// operations rather than matches on the suit.
+///
+/// Cards order by rank, then by suit in [`Suit`] order, so a sorted hand runs
+/// A♠ A♥ A♦ A♣ 2♠ and so on. Equal cards always hash equally.
+///
 /// # Examples
 ///
 /// ```
@@ -41,10 +49,24 @@ use core::fmt;
 /// let card = Card::new(Rank::Ace, Suit::Spades);
 /// println!("{}", card); // Outputs: "Ace of Spades"
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Card {
-    rank: Rank,
-    suit: Suit,
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Card(u8);
+
+const SUIT_MASK: u8 = 0b0000_0011;
+const RED_BIT: u8 = 0b0000_0100;
+const RANK_SHIFT: u8 = 4;
+
+impl Ord for Card {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // The red flag sits between rank and suit and would put both black suits first.
+        (self.0 & !RED_BIT).cmp(&(other.0 & !RED_BIT))
+    }
+}
+
+impl PartialOrd for Card {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 /// Represents the rank of a playing card.
@@ -63,7 +85,7 @@ pub struct Card {
 /// let rank_from_number = Rank::try_from(5).unwrap();
 /// assert_eq!(rank_from_number, Rank::Five);
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Rank {
     Ace = 1,
     Two,
@@ -82,7 +104,8 @@ pub enum Rank {
 
 /// Represents the suit of a playing card.
 ///
-/// The four standard suits are Spades, Hearts, Diamonds, and Clubs.
+/// The four standard suits are Spades, Hearts, Diamonds, and Clubs. Suits order the
+/// same way, matching [`Suit::foundation_index`].
 ///
 /// # Examples
 ///
@@ -93,7 +116,7 @@ pub enum Rank {
 /// // Hearts are red
 /// assert_eq!(suit.color(), Color::Red);
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Suit {
     Spades,
     Hearts,
@@ -108,7 +131,7 @@ pub enum Suit {
 ///
 /// This is particularly important for FreeCell rules where
 /// cards must alternate colors in tableau columns.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Color {
     Red,
     Black,
@@ -230,8 +253,12 @@ impl Card {
     ///
     /// let card = Card::new(Rank::Ace, Suit::Spades);
     /// ```
-    pub fn new(rank: Rank, suit: Suit) -> Self {
-        Self { rank, suit }
+    pub const fn new(rank: Rank, suit: Suit) -> Self {
+        let red = match suit {
+            Suit::Hearts | Suit::Diamonds => RED_BIT,
+            Suit::Clubs | Suit::Spades => 0,
+        };
+        Self((rank as u8) << RANK_SHIFT | red | suit as u8)
     }
     
     /// Returns the color of the card (Red or Black).
@@ -248,10 +275,12 @@ impl Card {
     /// let card = Card::new(Rank::Ace, Suit::Hearts);
     /// assert_eq!(card.color(), Color::Red);
     /// ```
+    #[inline]
     pub fn color(&self) -> Color {
-        match self.suit {
-            Suit::Hearts | Suit::Diamonds => Color::Red,
-            Suit::Clubs | Suit::Spades => Color::Black,
+        if self.0 & RED_BIT != 0 {
+            Color::Red
+        } else {
+            Color::Black
         }
     }
     
@@ -265,8 +294,24 @@ impl Card {
     /// let card = Card::new(Rank::Ace, Suit::Spades);
     /// assert_eq!(card.rank(), Rank::Ace);
     /// ```
+    #[inline]
     pub fn rank(&self) -> Rank {
-        self.rank
+        // The arms mirror the discriminants, so this compiles down to a shift.
+        match self.0 >> RANK_SHIFT {
+            1 => Rank::Ace,
+            2 => Rank::Two,
+            3 => Rank::Three,
+            4 => Rank::Four,
+            5 => Rank::Five,
+            6 => Rank::Six,
+            7 => Rank::Seven,
+            8 => Rank::Eight,
+            9 => Rank::Nine,
+            10 => Rank::Ten,
+            11 => Rank::Jack,
+            12 => Rank::Queen,
+            _ => Rank::King,
+        }
     }
     
     /// Returns the card's suit.
@@ -279,8 +324,14 @@ impl Card {
     /// let card = Card::new(Rank::Ace, Suit::Spades);
     /// assert_eq!(card.suit(), Suit::Spades);
     /// ```
+    #[inline]
     pub fn suit(&self) -> Suit {
-        self.suit
+        match self.0 & SUIT_MASK {
+            0 => Suit::Spades,
+            1 => Suit::Hearts,
+            2 => Suit::Diamonds,
+            _ => Suit::Clubs,
+        }
     }
     
     /// Checks if this card is exactly one rank higher than the other card.
@@ -300,13 +351,26 @@ impl Card {
     ///
     /// assert!(higher.is_one_higher_than(&lower));
     /// ```
+    #[inline]
     pub fn is_one_higher_than(&self, other: &Card) -> bool {
-        self.rank as u8 == other.rank as u8 + 1
+        self.0 >> RANK_SHIFT == (other.0 >> RANK_SHIFT) + 1
+    }
+}
+
+impl fmt::Debug for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Card")
+            .field("rank", &self.rank())
+            .field("suit", &self.suit())
+            .finish()
     }
 }
 
 /// Formats the card for display as "Rank of Suit".
 ///
+/// Names in other languages are available through
+/// [`Locale::card_name`](crate::display::Locale::card_name).
+///
 /// # Examples
 ///
 /// ```
@@ -317,7 +381,7 @@ impl Card {
 /// ```
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?} of {:?}", self.rank, self.suit)
+        write!(f, "{}", crate::display::Locale::En.card_name(self))
     }
 }
 
@@ -332,13 +396,42 @@ mod tests {
     #[case(Suit::Spades, Color::Black)]
     #[case(Suit::Clubs, Color::Black)]
     fn card_has_correct_color(#[case] suit: Suit, #[case] expected_color: Color) {
-        let card = Card {
-            rank: Rank::Ace,
-            suit,
-        };
+        let card = Card::new(Rank::Ace, suit);
         assert_eq!(card.color(), expected_color);
     }
 
+    #[test]
+    fn packed_card_round_trips_every_rank_and_suit() {
+        for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
+            for value in 1..=13 {
+                let rank = Rank::try_from(value).unwrap();
+                let card = Card::new(rank, suit);
+                assert_eq!((card.rank(), card.suit()), (rank, suit));
+                assert_eq!(card.color(), suit.color());
+            }
+        }
+        assert_eq!(
+            format!("{:?}", Card::new(Rank::Queen, Suit::Clubs)),
+            "Card { rank: Queen, suit: Clubs }"
+        );
+    }
+
+    #[test]
+    fn cards_order_by_rank_then_suit() {
+        let mut cards = Vec::new();
+        for value in (1..=13).rev() {
+            for suit in [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades] {
+                cards.push(Card::new(Rank::try_from(value).unwrap(), suit));
+            }
+        }
+        cards.sort();
+        let keys: Vec<_> = cards.iter().map(|card| (card.rank(), card.suit())).collect();
+        let mut expected = keys.clone();
+        expected.sort();
+        assert_eq!(keys, expected);
+        assert_eq!(cards[1], Card::new(Rank::Ace, Suit::Hearts));
+    }
+
     #[rstest]
     #[case(Rank::Ace, Suit::Spades, Rank::Two, Suit::Spades, false)]
     #[case(Rank::Ace, Suit::Hearts, Rank::Ace, Suit::Hearts, false)]
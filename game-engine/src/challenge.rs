@@ -0,0 +1,260 @@
+//! Move-limited games with a par score.
+//!
+//! A daily challenge hands every player the same deal and scores them on how few moves
+//! they win in. [`ChallengeGame`] wraps a [`GameState`], counts the moves played on it,
+//! and reports a [`ChallengeStatus`]: still going, won under, at or over par, or failed
+//! because the move limit ran out or no legal move is left.
+//!
+//! The wrapper has no undo: taking a move back would need its own scoring rule, and a
+//! challenge is meant to be played forward.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::challenge::{ChallengeFailure, ChallengeGame, ChallengeRules, ChallengeStatus};
+//! use freecell_game_engine::generation::generate_deal;
+//! use freecell_game_engine::Move;
+//!
+//! let rules = ChallengeRules { max_moves: Some(2), par: Some(90) };
+//! let mut challenge = ChallengeGame::new(generate_deal(1).unwrap(), rules);
+//!
+//! let status = challenge.execute_move(&Move::tableau_to_freecell(5, 0).unwrap()).unwrap();
+//! assert_eq!(status, ChallengeStatus::InProgress);
+//! assert_eq!(challenge.moves_left(), Some(1));
+//!
+//! let status = challenge.execute_move(&Move::tableau_to_freecell(5, 1).unwrap()).unwrap();
+//! assert_eq!(status, ChallengeStatus::Failed(ChallengeFailure::MoveLimitReached));
+//! assert!(challenge.execute_move(&Move::tableau_to_freecell(4, 2).unwrap()).is_err());
+//! ```
+
+use std::fmt;
+
+use crate::game_state::{GameError, GameOutcome, GameState};
+use crate::r#move::Move;
+
+/// The limits a [`ChallengeGame`] is played under. The default has neither.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ChallengeRules {
+    /// Moves allowed in total; the challenge fails if the game is not won by then.
+    pub max_moves: Option<u32>,
+    /// The move count a win is compared against.
+    pub par: Option<u32>,
+}
+
+/// Where a challenge stands after a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChallengeStatus {
+    /// Not won, and moves remain.
+    InProgress,
+    /// Won in fewer moves than par.
+    UnderPar,
+    /// Won in exactly par moves, or won a challenge without a par.
+    AtPar,
+    /// Won, but in more moves than par.
+    OverPar,
+    /// Lost; no more moves are accepted.
+    Failed(ChallengeFailure),
+}
+
+impl ChallengeStatus {
+    /// Whether the challenge is over, won or failed.
+    pub fn is_finished(&self) -> bool {
+        *self != ChallengeStatus::InProgress
+    }
+
+    /// Whether the challenge was won, whatever the score against par.
+    pub fn is_won(&self) -> bool {
+        matches!(self, ChallengeStatus::UnderPar | ChallengeStatus::AtPar | ChallengeStatus::OverPar)
+    }
+}
+
+/// Why a challenge was lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChallengeFailure {
+    /// The last allowed move was played without winning.
+    MoveLimitReached,
+    /// The position has no legal move left.
+    NoMovesLeft,
+}
+
+impl fmt::Display for ChallengeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChallengeStatus::InProgress => write!(f, "in progress"),
+            ChallengeStatus::UnderPar => write!(f, "won under par"),
+            ChallengeStatus::AtPar => write!(f, "won at par"),
+            ChallengeStatus::OverPar => write!(f, "won over par"),
+            ChallengeStatus::Failed(ChallengeFailure::MoveLimitReached) => write!(f, "failed: out of moves"),
+            ChallengeStatus::Failed(ChallengeFailure::NoMovesLeft) => write!(f, "failed: no legal moves left"),
+        }
+    }
+}
+
+/// Why [`ChallengeGame::execute_move`] refused a move.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ChallengeError {
+    /// The challenge is already over; carries its final status.
+    Finished(ChallengeStatus),
+    /// The move is not legal in the current position. It was not counted.
+    Game(GameError),
+}
+
+impl fmt::Display for ChallengeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChallengeError::Finished(status) => write!(f, "The challenge is over ({})", status),
+            ChallengeError::Game(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ChallengeError {}
+
+impl From<GameError> for ChallengeError {
+    fn from(error: GameError) -> Self {
+        ChallengeError::Game(error)
+    }
+}
+
+/// A game played under [`ChallengeRules`].
+#[derive(Debug, Clone)]
+pub struct ChallengeGame {
+    game: GameState,
+    rules: ChallengeRules,
+    moves_made: u32,
+    status: ChallengeStatus,
+}
+
+impl ChallengeGame {
+    /// Starts a challenge from `game`. A position that is already won or stuck, or a
+    /// move limit of zero, finishes the challenge straight away.
+    pub fn new(game: GameState, rules: ChallengeRules) -> Self {
+        let mut challenge = ChallengeGame {
+            game,
+            rules,
+            moves_made: 0,
+            status: ChallengeStatus::InProgress,
+        };
+        challenge.status = challenge.evaluate();
+        challenge
+    }
+
+    /// Plays `m` and returns the challenge's status afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChallengeError::Finished`] once the challenge is over and
+    /// [`ChallengeError::Game`] for an illegal move; neither counts as a move.
+    pub fn execute_move(&mut self, m: &Move) -> Result<ChallengeStatus, ChallengeError> {
+        if self.status.is_finished() {
+            return Err(ChallengeError::Finished(self.status));
+        }
+        self.game.execute_move(m)?;
+        self.moves_made += 1;
+        self.status = self.evaluate();
+        Ok(self.status)
+    }
+
+    /// The current position.
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    /// The rules this challenge is played under.
+    pub fn rules(&self) -> ChallengeRules {
+        self.rules
+    }
+
+    /// Moves played so far.
+    pub fn moves_made(&self) -> u32 {
+        self.moves_made
+    }
+
+    /// Moves still allowed, or `None` without a move limit.
+    pub fn moves_left(&self) -> Option<u32> {
+        self.rules.max_moves.map(|max| max.saturating_sub(self.moves_made))
+    }
+
+    /// The status after the last move.
+    pub fn status(&self) -> ChallengeStatus {
+        self.status
+    }
+
+    /// Ends the challenge and returns the position.
+    pub fn into_game(self) -> GameState {
+        self.game
+    }
+
+    fn evaluate(&self) -> ChallengeStatus {
+        let outcome = self.game.outcome();
+        if outcome == GameOutcome::Won {
+            return match self.rules.par.map(|par| self.moves_made.cmp(&par)) {
+                Some(std::cmp::Ordering::Less) => ChallengeStatus::UnderPar,
+                Some(std::cmp::Ordering::Greater) => ChallengeStatus::OverPar,
+                Some(std::cmp::Ordering::Equal) | None => ChallengeStatus::AtPar,
+            };
+        }
+        if self.moves_left() == Some(0) {
+            ChallengeStatus::Failed(ChallengeFailure::MoveLimitReached)
+        } else if outcome == GameOutcome::Lost {
+            ChallengeStatus::Failed(ChallengeFailure::NoMovesLeft)
+        } else {
+            ChallengeStatus::InProgress
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+    use crate::game_state::GameStateBuilder;
+    use crate::location::{FoundationLocation, Location, TableauLocation};
+
+    /// Everything home except the two black Kings, each alone in a column.
+    fn two_moves_from_winning() -> GameState {
+        let mut builder = GameStateBuilder::new();
+        for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
+            let pile = Location::Foundation(FoundationLocation::new(suit.foundation_index()).unwrap());
+            let top = if suit.color() == crate::card::Color::Black { 12 } else { 13 };
+            for rank in 1..=top {
+                builder.place(pile, Card::new(Rank::try_from(rank).unwrap(), suit)).unwrap();
+            }
+        }
+        builder.column(TableauLocation::new(0).unwrap(), [Card::new(Rank::King, Suit::Spades)]).unwrap();
+        builder.column(TableauLocation::new(1).unwrap(), [Card::new(Rank::King, Suit::Clubs)]).unwrap();
+        builder.build().unwrap()
+    }
+
+    fn play_out(par: Option<u32>) -> ChallengeStatus {
+        let mut challenge = ChallengeGame::new(two_moves_from_winning(), ChallengeRules { max_moves: Some(2), par });
+        challenge.execute_move(&Move::tableau_to_foundation(0, 0).unwrap()).unwrap();
+        challenge.execute_move(&Move::tableau_to_foundation(1, 3).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn a_win_is_scored_against_par() {
+        assert_eq!(play_out(Some(3)), ChallengeStatus::UnderPar);
+        assert_eq!(play_out(Some(2)), ChallengeStatus::AtPar);
+        assert_eq!(play_out(Some(1)), ChallengeStatus::OverPar);
+        assert_eq!(play_out(None), ChallengeStatus::AtPar);
+        // Winning on the last allowed move is a win, not a failure.
+        assert!(play_out(None).is_won());
+    }
+
+    #[test]
+    fn illegal_moves_are_not_counted_and_stuck_games_fail() {
+        let mut challenge = ChallengeGame::new(two_moves_from_winning(), ChallengeRules::default());
+        let illegal = Move::tableau_to_foundation(0, 1).unwrap();
+        assert!(matches!(challenge.execute_move(&illegal), Err(ChallengeError::Game(_))));
+        assert_eq!(challenge.moves_made(), 0);
+        assert_eq!(challenge.moves_left(), None);
+
+        let stuck = ChallengeGame::new(GameState::new(), ChallengeRules::default());
+        assert_eq!(stuck.status(), ChallengeStatus::Failed(ChallengeFailure::NoMovesLeft));
+
+        let no_moves = ChallengeGame::new(two_moves_from_winning(), ChallengeRules { max_moves: Some(0), par: None });
+        assert_eq!(no_moves.status(), ChallengeStatus::Failed(ChallengeFailure::MoveLimitReached));
+    }
+}
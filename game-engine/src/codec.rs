@@ -0,0 +1,299 @@
+//! Compact binary storage for move lists.
+//!
+//! Benchmark runs keep a solution for every seed, and tens of thousands of them as JSON
+//! arrays of moves add up. [`encode_moves`] stores a solution in two bytes per move
+//! behind an eight-byte header, and [`armor_moves`] wraps the same bytes in base64 for
+//! places that only take text.
+//!
+//! # Format
+//!
+//! | Bytes | Content                                    |
+//! |-------|--------------------------------------------|
+//! | 0-2   | magic `FCM`                                |
+//! | 3     | format version, currently [`FORMAT_VERSION`] |
+//! | 4-7   | number of moves, little-endian `u32`       |
+//! | 8-    | two bytes per move: source, destination    |
+//!
+//! Each location byte holds the area in its top two bits (0 tableau, 1 freecell,
+//! 2 foundation) and the index in the low six.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::codec::{armor_moves, decode_moves, dearmor_moves, encode_moves};
+//! use freecell_game_engine::Move;
+//!
+//! let moves = vec![Move::tableau_to_freecell(5, 0).unwrap(), Move::freecell_to_foundation(0, 2).unwrap()];
+//! let bytes = encode_moves(&moves);
+//! assert_eq!(bytes.len(), 8 + 2 * moves.len());
+//! assert_eq!(decode_moves(&bytes).unwrap(), moves);
+//!
+//! let text = armor_moves(&moves);
+//! assert!(text.starts_with("-----BEGIN FREECELL MOVES-----"));
+//! assert_eq!(dearmor_moves(&text).unwrap(), moves);
+//! ```
+
+use std::fmt;
+
+use crate::location::{FoundationLocation, FreecellLocation, Location, TableauLocation};
+use crate::r#move::Move;
+
+/// Version written by [`encode_moves`]; the only one [`decode_moves`] reads.
+pub const FORMAT_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 3] = b"FCM";
+const HEADER_LEN: usize = 8;
+const ARMOR_BEGIN: &str = "-----BEGIN FREECELL MOVES-----";
+const ARMOR_END: &str = "-----END FREECELL MOVES-----";
+/// Base64 characters per armored line.
+const ARMOR_LINE_LEN: usize = 64;
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Why a move list could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CodecError {
+    /// The data does not start with the `FCM` magic.
+    BadMagic,
+    /// Written by a format version this engine does not read.
+    UnsupportedVersion(u8),
+    /// Fewer bytes than the header or its move count calls for.
+    Truncated { expected: usize, found: usize },
+    /// Bytes left over after the last move.
+    TrailingBytes(usize),
+    /// A location byte that names no location. `move_index` is 0-based.
+    InvalidLocation { move_index: usize, byte: u8 },
+    /// Armored text without its BEGIN and END lines, or with non-base64 content.
+    InvalidArmor,
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::BadMagic => write!(f, "Not an encoded move list"),
+            CodecError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported move list version {} (expected {})", version, FORMAT_VERSION)
+            }
+            CodecError::Truncated { expected, found } => {
+                write!(f, "Move list truncated: expected {} bytes, found {}", expected, found)
+            }
+            CodecError::TrailingBytes(count) => write!(f, "{} unexpected bytes after the last move", count),
+            CodecError::InvalidLocation { move_index, byte } => {
+                write!(f, "Move {}: byte {:#04x} is not a location", move_index + 1, byte)
+            }
+            CodecError::InvalidArmor => write!(f, "Malformed armored move list"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+/// Encodes `moves` in the compact binary format described in the [module docs](self).
+///
+/// # Panics
+///
+/// Panics if there are more than `u32::MAX` moves.
+pub fn encode_moves(moves: &[Move]) -> Vec<u8> {
+    let count = u32::try_from(moves.len()).expect("move lists are far shorter than u32::MAX");
+    let mut bytes = Vec::with_capacity(HEADER_LEN + 2 * moves.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(FORMAT_VERSION);
+    bytes.extend_from_slice(&count.to_le_bytes());
+    for m in moves {
+        bytes.push(encode_location(m.source));
+        bytes.push(encode_location(m.destination));
+    }
+    bytes
+}
+
+/// Decodes a move list written by [`encode_moves`].
+///
+/// # Errors
+///
+/// Returns a [`CodecError`] if the header is wrong, the length does not match the move
+/// count, or a byte does not name a location.
+pub fn decode_moves(bytes: &[u8]) -> Result<Vec<Move>, CodecError> {
+    if bytes.len() < HEADER_LEN {
+        return Err(if bytes.starts_with(&MAGIC[..bytes.len().min(MAGIC.len())]) {
+            CodecError::Truncated { expected: HEADER_LEN, found: bytes.len() }
+        } else {
+            CodecError::BadMagic
+        });
+    }
+    if &bytes[..3] != MAGIC {
+        return Err(CodecError::BadMagic);
+    }
+    if bytes[3] != FORMAT_VERSION {
+        return Err(CodecError::UnsupportedVersion(bytes[3]));
+    }
+    let count = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+    let expected = HEADER_LEN + 2 * count;
+    if bytes.len() < expected {
+        return Err(CodecError::Truncated { expected, found: bytes.len() });
+    }
+    if bytes.len() > expected {
+        return Err(CodecError::TrailingBytes(bytes.len() - expected));
+    }
+    bytes[HEADER_LEN..]
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(move_index, pair)| {
+            let location = |byte| decode_location(byte).ok_or(CodecError::InvalidLocation { move_index, byte });
+            Ok(Move::single(location(pair[0])?, location(pair[1])?))
+        })
+        .collect()
+}
+
+/// Encodes `moves` like [`encode_moves`] and armors the bytes as base64 between BEGIN and
+/// END lines, so a solution can be pasted into text files and messages.
+pub fn armor_moves(moves: &[Move]) -> String {
+    let encoded = base64_encode(&encode_moves(moves));
+    let mut text = String::with_capacity(encoded.len() + encoded.len() / ARMOR_LINE_LEN + 64);
+    text.push_str(ARMOR_BEGIN);
+    text.push('\n');
+    for line in encoded.as_bytes().chunks(ARMOR_LINE_LEN) {
+        text.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        text.push('\n');
+    }
+    text.push_str(ARMOR_END);
+    text.push('\n');
+    text
+}
+
+/// Decodes text written by [`armor_moves`]. Whitespace around and inside the base64 body
+/// is ignored, so rewrapped or indented text still decodes.
+///
+/// # Errors
+///
+/// Returns [`CodecError::InvalidArmor`] if the BEGIN or END line is missing or the body
+/// is not base64, and any error of [`decode_moves`] for the bytes inside.
+pub fn dearmor_moves(text: &str) -> Result<Vec<Move>, CodecError> {
+    let start = text.find(ARMOR_BEGIN).ok_or(CodecError::InvalidArmor)? + ARMOR_BEGIN.len();
+    let end = start + text[start..].find(ARMOR_END).ok_or(CodecError::InvalidArmor)?;
+    let body: String = text[start..end].chars().filter(|c| !c.is_whitespace()).collect();
+    decode_moves(&base64_decode(&body).ok_or(CodecError::InvalidArmor)?)
+}
+
+fn encode_location(location: Location) -> u8 {
+    match location {
+        Location::Tableau(column) => column.index(),
+        Location::Freecell(cell) => 0x40 | cell.index(),
+        Location::Foundation(pile) => 0x80 | pile.index(),
+    }
+}
+
+fn decode_location(byte: u8) -> Option<Location> {
+    let index = byte & 0x3f;
+    match byte >> 6 {
+        0 => TableauLocation::new(index).ok().map(Location::Tableau),
+        1 => FreecellLocation::new(index).ok().map(Location::Freecell),
+        2 => FoundationLocation::new(index).ok().map(Location::Foundation),
+        _ => None,
+    }
+}
+
+/// Standard padded base64.
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | ((b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(BASE64[((group >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// Decodes standard padded base64, or `None` if `text` is not valid base64.
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let chunks: Vec<&[u8]> = text.as_bytes().chunks(4).collect();
+    for (n, chunk) in chunks.iter().enumerate() {
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && n + 1 != chunks.len()) {
+            return None;
+        }
+        let mut group = 0u32;
+        for (i, &c) in chunk[..4 - padding].iter().enumerate() {
+            let value = BASE64.iter().position(|&b| b == c)? as u32;
+            group |= value << (18 - 6 * i);
+        }
+        bytes.extend_from_slice(&group.to_be_bytes()[1..4 - padding]);
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::generate_deal;
+
+    /// The first moves of a greedy playout of deal 1, covering every kind of move.
+    fn sample_moves() -> Vec<Move> {
+        let mut game = generate_deal(1).unwrap();
+        let mut moves = Vec::new();
+        for step in 0..60 {
+            let available = game.get_available_moves();
+            let Some(&m) = available.get(step % available.len().max(1)) else { break };
+            game.execute_move(&m).unwrap();
+            moves.push(m);
+        }
+        moves.push(Move::freecell_to_foundation(3, 3).unwrap());
+        moves
+    }
+
+    #[test]
+    fn round_trips_in_two_bytes_per_move() {
+        let moves = sample_moves();
+        let bytes = encode_moves(&moves);
+        assert_eq!(bytes.len(), 8 + 2 * moves.len());
+        assert_eq!(decode_moves(&bytes).unwrap(), moves);
+        assert_eq!(decode_moves(&encode_moves(&[])).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn rejects_damaged_data() {
+        let bytes = encode_moves(&sample_moves());
+        assert_eq!(decode_moves(b"JSON[]..."), Err(CodecError::BadMagic));
+        assert_eq!(decode_moves(&bytes[..5]), Err(CodecError::Truncated { expected: 8, found: 5 }));
+        assert!(matches!(decode_moves(&bytes[..bytes.len() - 1]), Err(CodecError::Truncated { .. })));
+
+        let mut newer = bytes.clone();
+        newer[3] = 2;
+        assert_eq!(decode_moves(&newer), Err(CodecError::UnsupportedVersion(2)));
+
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert_eq!(decode_moves(&longer), Err(CodecError::TrailingBytes(1)));
+
+        let mut bad_column = bytes;
+        bad_column[9] = 12;
+        assert_eq!(decode_moves(&bad_column), Err(CodecError::InvalidLocation { move_index: 0, byte: 12 }));
+    }
+
+    #[test]
+    fn armor_survives_rewrapping() {
+        let moves = sample_moves();
+        let text = armor_moves(&moves);
+        assert!(text.lines().all(|line| line.len() <= ARMOR_LINE_LEN || line.starts_with("-----")));
+        assert_eq!(dearmor_moves(&text).unwrap(), moves);
+
+        let rewrapped = format!("Solution for deal 1:\n  {}", text.replace('\n', "\n  "));
+        assert_eq!(dearmor_moves(&rewrapped).unwrap(), moves);
+        assert_eq!(dearmor_moves(&text.replace('A', "!")), Err(CodecError::InvalidArmor));
+        assert_eq!(dearmor_moves("no armor here"), Err(CodecError::InvalidArmor));
+
+        for len in 0..6 {
+            let bytes: Vec<u8> = (0..len).map(|b| b * 51).collect();
+            assert_eq!(base64_decode(&base64_encode(&bytes)), Some(bytes));
+        }
+        assert_eq!(base64_encode(b"FCM"), "RkNN");
+    }
+}
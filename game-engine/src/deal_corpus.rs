@@ -0,0 +1,238 @@
+//! A checked-in record of what every deal number lays out.
+//!
+//! [`generate_deal`](crate::generation::generate_deal) promises that a seed always
+//! produces the same layout, and solver results, replay traces and shared challenges all
+//! lean on that promise. A [`DealCorpus`] pins it down: for each seed in
+//! [`CORPUS_SEEDS`] it stores the [`fingerprint`](crate::GameState::fingerprint) of the
+//! dealt position. The corpus lives in `game-engine/testdata/deal_corpus.txt`; the
+//! engine's tests check every seed against the current generator, and
+//! `solver deal-corpus generate` rewrites it when the deal algorithm is changed on
+//! purpose.
+//!
+//! The file is plain text: `#` comment lines, a `deal_algorithm <n>` line naming the
+//! [`EngineInfo::deal_algorithm`](crate::EngineInfo::deal_algorithm) it was made with,
+//! then one `<seed> <fingerprint as 16 hex digits>` line per deal.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::deal_corpus::DealCorpus;
+//!
+//! let corpus = DealCorpus::generate(1..=10).unwrap();
+//! let reread = DealCorpus::parse(&corpus.to_text()).unwrap();
+//! assert_eq!(reread, corpus);
+//! assert!(reread.mismatches().is_empty());
+//! ```
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::generation::{generate_deal, GenerationError};
+use crate::ENGINE_INFO;
+
+/// The seeds the checked-in corpus covers: the classic Microsoft deal numbers and the
+/// extended range most deal lists go on to.
+pub const CORPUS_SEEDS: RangeInclusive<u64> = 1..=100_000;
+
+/// One line of the corpus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CorpusEntry {
+    pub seed: u64,
+    pub fingerprint: u64,
+}
+
+/// A seed whose deal no longer matches the corpus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CorpusMismatch {
+    pub seed: u64,
+    /// The fingerprint recorded in the corpus.
+    pub expected: u64,
+    /// The fingerprint of today's deal, or `None` if the seed no longer deals at all.
+    pub actual: Option<u64>,
+}
+
+impl fmt::Display for CorpusMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.actual {
+            Some(actual) => write!(f, "seed {}: recorded {:016x}, dealt {:016x}", self.seed, self.expected, actual),
+            None => write!(f, "seed {}: recorded {:016x}, but the seed no longer deals", self.seed, self.expected),
+        }
+    }
+}
+
+/// Errors reading a corpus file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CorpusError {
+    /// The `deal_algorithm` line is missing or comes after the first entry.
+    MissingAlgorithm,
+    /// A line is neither a comment, the algorithm line nor a `<seed> <hash>` entry.
+    InvalidLine { line: usize, text: String },
+    /// Seeds must be listed in increasing order, each once.
+    OutOfOrder { line: usize, seed: u64 },
+}
+
+impl fmt::Display for CorpusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorpusError::MissingAlgorithm => write!(f, "The corpus has no deal_algorithm line before its entries"),
+            CorpusError::InvalidLine { line, text } => write!(f, "Line {} is not a corpus entry: {:?}", line, text),
+            CorpusError::OutOfOrder { line, seed } => {
+                write!(f, "Line {}: seed {} is not greater than the seed before it", line, seed)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CorpusError {}
+
+/// Deal fingerprints for a run of seeds, stamped with the deal algorithm that made them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DealCorpus {
+    pub deal_algorithm: u32,
+    /// Sorted by seed, each seed once.
+    pub entries: Vec<CorpusEntry>,
+}
+
+impl DealCorpus {
+    /// Deals every seed in `seeds` with this build's generator.
+    ///
+    /// # Errors
+    ///
+    /// Returns the generator's error for a seed it cannot deal, such as 0.
+    pub fn generate(seeds: RangeInclusive<u64>) -> Result<Self, GenerationError> {
+        let entries = seeds
+            .map(|seed| {
+                generate_deal(seed).map(|game| CorpusEntry {
+                    seed,
+                    fingerprint: game.fingerprint(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(DealCorpus {
+            deal_algorithm: ENGINE_INFO.deal_algorithm,
+            entries,
+        })
+    }
+
+    /// Reads the text format described in the [module documentation](self).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CorpusError`] naming the first line that could not be read.
+    pub fn parse(text: &str) -> Result<Self, CorpusError> {
+        let mut deal_algorithm = None;
+        let mut entries: Vec<CorpusEntry> = Vec::new();
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = raw.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let invalid = || CorpusError::InvalidLine {
+                line,
+                text: raw.to_string(),
+            };
+            let mut fields = trimmed.split_whitespace();
+            let (Some(first), Some(second), None) = (fields.next(), fields.next(), fields.next()) else {
+                return Err(invalid());
+            };
+            if first == "deal_algorithm" {
+                if deal_algorithm.is_some() || !entries.is_empty() {
+                    return Err(invalid());
+                }
+                deal_algorithm = Some(second.parse().map_err(|_| invalid())?);
+                continue;
+            }
+            if deal_algorithm.is_none() {
+                return Err(CorpusError::MissingAlgorithm);
+            }
+            let seed: u64 = first.parse().map_err(|_| invalid())?;
+            let fingerprint = u64::from_str_radix(second, 16).map_err(|_| invalid())?;
+            if entries.last().is_some_and(|last| last.seed >= seed) {
+                return Err(CorpusError::OutOfOrder { line, seed });
+            }
+            entries.push(CorpusEntry { seed, fingerprint });
+        }
+        Ok(DealCorpus {
+            deal_algorithm: deal_algorithm.ok_or(CorpusError::MissingAlgorithm)?,
+            entries,
+        })
+    }
+
+    /// Writes the corpus in the format [`parse`](Self::parse) reads.
+    pub fn to_text(&self) -> String {
+        let mut text = String::with_capacity(self.entries.len() * 24 + 128);
+        text.push_str("# FreeCell deal fingerprints: <seed> <GameState::fingerprint of the deal>\n");
+        text.push_str("# Regenerate with: cargo run --release --bin solver -- deal-corpus generate\n");
+        text.push_str(&format!("deal_algorithm {}\n", self.deal_algorithm));
+        for entry in &self.entries {
+            text.push_str(&format!("{} {:016x}\n", entry.seed, entry.fingerprint));
+        }
+        text
+    }
+
+    /// The entries whose seed no longer deals the recorded layout.
+    pub fn mismatches(&self) -> Vec<CorpusMismatch> {
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                let actual = generate_deal(entry.seed).ok().map(|game| game.fingerprint());
+                (actual != Some(entry.fingerprint)).then_some(CorpusMismatch {
+                    seed: entry.seed,
+                    expected: entry.fingerprint,
+                    actual,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the corpus was made with this build's deal algorithm. When it was not,
+    /// every mismatch is expected and the file should be regenerated.
+    pub fn matches_engine(&self) -> bool {
+        self.deal_algorithm == ENGINE_INFO.deal_algorithm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKED_IN: &str = include_str!("../testdata/deal_corpus.txt");
+
+    #[test]
+    fn checked_in_corpus_matches_the_generator() {
+        let corpus = DealCorpus::parse(CHECKED_IN).unwrap();
+        assert!(corpus.matches_engine(), "the corpus was made with another deal algorithm");
+        let seeds: Vec<u64> = corpus.entries.iter().map(|entry| entry.seed).collect();
+        assert_eq!(seeds.first(), Some(CORPUS_SEEDS.start()));
+        assert_eq!(seeds.last(), Some(CORPUS_SEEDS.end()));
+        assert_eq!(seeds.len() as u64, CORPUS_SEEDS.end() - CORPUS_SEEDS.start() + 1);
+
+        let mismatches = corpus.mismatches();
+        assert!(mismatches.is_empty(), "deals changed: {:?}", &mismatches[..mismatches.len().min(5)]);
+    }
+
+    #[test]
+    fn mismatches_name_the_seed_and_both_hashes() {
+        let mut corpus = DealCorpus::generate(1..=3).unwrap();
+        corpus.entries[1].fingerprint ^= 1;
+        let mismatches = corpus.mismatches();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].seed, 2);
+        assert_eq!(mismatches[0].actual, Some(corpus.entries[1].fingerprint ^ 1));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_files() {
+        assert_eq!(DealCorpus::parse("1 00ff\n"), Err(CorpusError::MissingAlgorithm));
+        assert!(matches!(
+            DealCorpus::parse("deal_algorithm 1\n1 xyz\n"),
+            Err(CorpusError::InvalidLine { line: 2, .. })
+        ));
+        assert_eq!(
+            DealCorpus::parse("deal_algorithm 1\n2 00\n2 01\n"),
+            Err(CorpusError::OutOfOrder { line: 3, seed: 2 })
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Localized names for ranks, suits and cards.
+//!
+//! [`Locale`] holds the name tables for each supported language and knows how that
+//! language orders a rank and suit into a card name ("Ace of Spades", "Pik-Ass",
+//! "As de pique", "As de picas"). `Card`'s own `Display` uses the English table.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::card::{Card, Rank, Suit};
+//! use freecell_game_engine::display::Locale;
+//!
+//! let card = Card::new(Rank::Queen, Suit::Hearts);
+//! assert_eq!(Locale::En.card_name(&card).to_string(), "Queen of Hearts");
+//! assert_eq!(Locale::De.card_name(&card).to_string(), "Herz-Dame");
+//! assert_eq!(Locale::Fr.card_name(&card).to_string(), "Dame de cœur");
+//! assert_eq!(Locale::Es.card_name(&card).to_string(), "Reina de corazones");
+//! ```
+
+use std::fmt;
+
+use crate::card::{Card, Rank, Suit};
+
+/// A language for card, rank and suit names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    /// English (the default, and what `Card`'s `Display` uses).
+    #[default]
+    En,
+    /// German.
+    De,
+    /// French.
+    Fr,
+    /// Spanish.
+    Es,
+}
+
+impl Locale {
+    /// Every supported locale.
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::De, Locale::Fr, Locale::Es];
+
+    /// Parses a language tag such as `"fr"` or `"de-AT"`, ignoring region and case.
+    ///
+    /// Returns `None` for unsupported languages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::display::Locale;
+    ///
+    /// assert_eq!(Locale::from_tag("es_MX"), Some(Locale::Es));
+    /// assert_eq!(Locale::from_tag("DE"), Some(Locale::De));
+    /// assert_eq!(Locale::from_tag("ja"), None);
+    /// ```
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_']).next().unwrap_or("");
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::En),
+            "de" => Some(Locale::De),
+            "fr" => Some(Locale::Fr),
+            "es" => Some(Locale::Es),
+            _ => None,
+        }
+    }
+
+    /// The two-letter language code for this locale.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+            Locale::Fr => "fr",
+            Locale::Es => "es",
+        }
+    }
+
+    /// The name of `rank` in this locale.
+    pub fn rank_name(self, rank: Rank) -> &'static str {
+        let names = match self {
+            Locale::En => [
+                "Ace", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten",
+                "Jack", "Queen", "King",
+            ],
+            Locale::De => [
+                "Ass", "Zwei", "Drei", "Vier", "Fünf", "Sechs", "Sieben", "Acht", "Neun", "Zehn",
+                "Bube", "Dame", "König",
+            ],
+            Locale::Fr => [
+                "As", "Deux", "Trois", "Quatre", "Cinq", "Six", "Sept", "Huit", "Neuf", "Dix",
+                "Valet", "Dame", "Roi",
+            ],
+            Locale::Es => [
+                "As", "Dos", "Tres", "Cuatro", "Cinco", "Seis", "Siete", "Ocho", "Nueve", "Diez",
+                "Jota", "Reina", "Rey",
+            ],
+        };
+        names[rank as usize - 1]
+    }
+
+    /// The name of `suit` in this locale, in the form used inside a card name.
+    pub fn suit_name(self, suit: Suit) -> &'static str {
+        match (self, suit) {
+            (Locale::En, Suit::Spades) => "Spades",
+            (Locale::En, Suit::Hearts) => "Hearts",
+            (Locale::En, Suit::Diamonds) => "Diamonds",
+            (Locale::En, Suit::Clubs) => "Clubs",
+            (Locale::De, Suit::Spades) => "Pik",
+            (Locale::De, Suit::Hearts) => "Herz",
+            (Locale::De, Suit::Diamonds) => "Karo",
+            (Locale::De, Suit::Clubs) => "Kreuz",
+            (Locale::Fr, Suit::Spades) => "pique",
+            (Locale::Fr, Suit::Hearts) => "cœur",
+            (Locale::Fr, Suit::Diamonds) => "carreau",
+            (Locale::Fr, Suit::Clubs) => "trèfle",
+            (Locale::Es, Suit::Spades) => "picas",
+            (Locale::Es, Suit::Hearts) => "corazones",
+            (Locale::Es, Suit::Diamonds) => "diamantes",
+            (Locale::Es, Suit::Clubs) => "tréboles",
+        }
+    }
+
+    /// Returns a `Display` value that writes `card`'s full name in this locale.
+    pub fn card_name(self, card: &Card) -> CardName {
+        CardName {
+            card: *card,
+            locale: self,
+        }
+    }
+}
+
+/// A card paired with the locale to name it in. Created by [`Locale::card_name`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CardName {
+    card: Card,
+    locale: Locale,
+}
+
+impl fmt::Display for CardName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rank = self.locale.rank_name(self.card.rank());
+        let suit = self.locale.suit_name(self.card.suit());
+        match self.locale {
+            Locale::En => write!(f, "{} of {}", rank, suit),
+            Locale::De => write!(f, "{}-{}", suit, rank),
+            Locale::Fr | Locale::Es => write!(f, "{} de {}", rank, suit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn english_names_match_variant_names() {
+        for suit in 0..4u8 {
+            for rank in 1..=13u8 {
+                let card = Card::new(Rank::try_from(rank).unwrap(), Suit::try_from(suit).unwrap());
+                let expected = format!("{:?} of {:?}", card.rank(), card.suit());
+                assert_eq!(Locale::En.card_name(&card).to_string(), expected);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(Locale::En, "King of Clubs")]
+    #[case(Locale::De, "Kreuz-König")]
+    #[case(Locale::Fr, "Roi de trèfle")]
+    #[case(Locale::Es, "Rey de tréboles")]
+    fn card_name_per_locale(#[case] locale: Locale, #[case] expected: &str) {
+        let card = Card::new(Rank::King, Suit::Clubs);
+        assert_eq!(locale.card_name(&card).to_string(), expected);
+    }
+
+    #[test]
+    fn tags_round_trip() {
+        for locale in Locale::ALL {
+            assert_eq!(Locale::from_tag(locale.tag()), Some(locale));
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Deals in the text layout of published Microsoft FreeCell deal lists.
+//!
+//! Deal dumps for the Microsoft numbers print the tableau row by row, as the cards were
+//! dealt: the first card of every column on the first line, the second on the next,
+//! and so on until the shorter columns run out. Each card is two characters, rank then
+//! suit, with `T` for ten (`JD`, `TS`, `AH`); cards are separated by one space, lines
+//! end in `\n` and carry no trailing space. [`MicrosoftLayout`] writes exactly that, so
+//! its output can be compared byte for byte with a published dump.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::display::MicrosoftLayout;
+//! use freecell_game_engine::generation::generate_deal;
+//!
+//! let game = generate_deal(1).unwrap();
+//! let text = MicrosoftLayout::new(&game).to_string();
+//! assert_eq!(text.lines().next(), Some("JD 2D 9H JC 5D 7H 7C 5H"));
+//! assert_eq!(text.lines().last(), Some("6S 9C 2H 6H"));
+//! ```
+
+use std::fmt;
+
+use crate::card::{Card, Suit};
+use crate::game_state::GameState;
+
+/// Writes a position's tableau in the row-by-row layout of Microsoft deal lists.
+///
+/// Only the tableau is written; the layout has no place for freecells or foundations,
+/// so it describes a deal rather than a game in progress.
+#[derive(Debug, Clone, Copy)]
+pub struct MicrosoftLayout<'a> {
+    state: &'a GameState,
+}
+
+impl<'a> MicrosoftLayout<'a> {
+    /// Creates a formatter for `state`.
+    pub fn new(state: &'a GameState) -> Self {
+        Self { state }
+    }
+}
+
+impl fmt::Display for MicrosoftLayout<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns: Vec<&Vec<Card>> = self.state.tableau().columns().collect();
+        let rows = columns.iter().map(|column| column.len()).max().unwrap_or(0);
+        for row in 0..rows {
+            let mut cards = columns.iter().filter_map(|column| column.get(row));
+            if let Some(first) = cards.next() {
+                write_card(f, first)?;
+            }
+            for card in cards {
+                f.write_str(" ")?;
+                write_card(f, card)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_card(f: &mut fmt::Formatter<'_>, card: &Card) -> fmt::Result {
+    let rank = "A23456789TJQK".as_bytes()[card.rank() as usize - 1] as char;
+    let suit = match card.suit() {
+        Suit::Spades => 'S',
+        Suit::Hearts => 'H',
+        Suit::Diamonds => 'D',
+        Suit::Clubs => 'C',
+    };
+    write!(f, "{}{}", rank, suit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::generate_deal;
+
+    #[test]
+    fn matches_the_published_layout_of_deal_617() {
+        let game = generate_deal(617).unwrap();
+        let expected = "\
+7D AD 5C 3S 5S 8C 2D AH
+TD 7S QD AC 6D 8H AS KH
+TH QC 3H 9D 6S 8D 3D TC
+KD 5H 9S 3C 8S 7H 4D JS
+4C QS 9C 9H 7C 6H 2C 2S
+4S TS 2H 5D JC 6C JH QH
+JD KS KC 4H
+";
+        assert_eq!(MicrosoftLayout::new(&game).to_string(), expected);
+    }
+}
@@ -0,0 +1,254 @@
+//! Alternate text formatters for game state and moves.
+//!
+//! The `Display` impls on [`GameState`] and [`Move`] are compact, grid-shaped and full of
+//! symbols, which reads well on a terminal but poorly through a screen reader. This module
+//! provides spelled-out formatters that describe the board one area per line, in plain
+//! sentences, with 1-based numbering for columns, cells and piles.
+//!
+//! Card names can be given in another language with `with_locale`; see [`Locale`]. The
+//! surrounding sentence templates are English only.
+//!
+//! [`SolutionPhases`] lays out a whole solution as labelled groups of moves, and
+//! [`TerminalBoard`] draws the board in plain ASCII, optionally with red suits coloured.
+//! [`MicrosoftLayout`] writes a deal exactly as published Microsoft deal lists do.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::display::{ScreenReaderBoard, ScreenReaderMove};
+//! use freecell_game_engine::generation::generate_deal;
+//! use freecell_game_engine::Move;
+//!
+//! let game = generate_deal(1).unwrap();
+//! let text = ScreenReaderBoard::new(&game).to_string();
+//! assert!(text.starts_with("Foundation 1: empty."));
+//!
+//! let mv = Move::tableau_to_freecell(0, 0).unwrap();
+//! let spoken = ScreenReaderMove::new(&game, &mv).to_string();
+//! assert!(spoken.ends_with("from column 1 to free cell 1."));
+//! ```
+
+mod locale;
+mod microsoft;
+mod phases;
+mod terminal;
+
+pub use locale::{CardName, Locale};
+pub use microsoft::MicrosoftLayout;
+pub use phases::{Phase, PhaseIntent, SolutionPhases};
+pub use terminal::TerminalBoard;
+
+use std::fmt;
+
+use crate::card::Card;
+use crate::foundations::FOUNDATION_COUNT;
+use crate::game_state::GameState;
+use crate::location::{FoundationLocation, FreecellLocation, Location};
+use crate::r#move::Move;
+
+/// Describes a whole board in unambiguous sentences, one area per line.
+///
+/// Foundations come first, then free cells, then tableau columns listed from the bottom
+/// card to the top card, e.g. `Column 3: King of Hearts, covered by Queen of Spades.`
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenReaderBoard<'a> {
+    state: &'a GameState,
+    locale: Locale,
+}
+
+impl<'a> ScreenReaderBoard<'a> {
+    /// Creates a formatter for `state` that names cards in English.
+    pub fn new(state: &'a GameState) -> Self {
+        Self {
+            state,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Names cards in `locale` instead.
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+impl fmt::Display for ScreenReaderBoard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let foundations = self.state.foundations();
+        for i in 0..FOUNDATION_COUNT {
+            let location = FoundationLocation::new(i as u8).unwrap();
+            let count = foundations.pile_cards(location).count();
+            match foundations.get_card(location) {
+                Ok(Some(card)) => writeln!(
+                    f,
+                    "Foundation {}: {} on top, {} {}.",
+                    i + 1,
+                    self.locale.card_name(card),
+                    count,
+                    if count == 1 { "card" } else { "cards" }
+                )?,
+                _ => writeln!(f, "Foundation {}: empty.", i + 1)?,
+            }
+        }
+
+        let freecells = self.state.freecells();
+        for i in 0..freecells.slot_count() {
+            let location = FreecellLocation::new(i as u8).unwrap();
+            match freecells.get_card(location) {
+                Ok(Some(card)) => {
+                    writeln!(f, "Free cell {}: {}.", i + 1, self.locale.card_name(card))?
+                }
+                _ => writeln!(f, "Free cell {}: empty.", i + 1)?,
+            }
+        }
+
+        for i in 0..self.state.tableau().column_count() {
+            let column = self.state.tableau().get_column(i).unwrap_or(&[]);
+            write!(f, "Column {}: ", i + 1)?;
+            write_column(f, column, self.locale)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `column` bottom to top as "A, covered by B, covered by C."
+fn write_column(f: &mut fmt::Formatter<'_>, column: &[Card], locale: Locale) -> fmt::Result {
+    let mut cards = column.iter();
+    match cards.next() {
+        None => write!(f, "empty."),
+        Some(first) => {
+            write!(f, "{}", locale.card_name(first))?;
+            for card in cards {
+                write!(f, ", covered by {}", locale.card_name(card))?;
+            }
+            write!(f, ".")
+        }
+    }
+}
+
+/// Describes a single move as a sentence, naming the card being moved.
+///
+/// The card is looked up in the state *before* the move is executed, so format the move
+/// first and execute it afterwards. If the source is empty the card is simply omitted.
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenReaderMove<'a> {
+    state: &'a GameState,
+    mv: &'a Move,
+    locale: Locale,
+}
+
+impl<'a> ScreenReaderMove<'a> {
+    /// Creates a formatter for `mv` as played from `state` that names cards in English.
+    pub fn new(state: &'a GameState, mv: &'a Move) -> Self {
+        Self {
+            state,
+            mv,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Names the moved card in `locale` instead.
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+impl fmt::Display for ScreenReaderMove<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.state.get_card(self.mv.source) {
+            Ok(Some(card)) => write!(f, "Move {} ", self.locale.card_name(card))?,
+            _ => write!(f, "Move ")?,
+        }
+        write!(
+            f,
+            "from {} to {}.",
+            spoken_location(self.mv.source),
+            spoken_location(self.mv.destination)
+        )
+    }
+}
+
+/// Names a location the way it is read aloud, with 1-based numbering.
+fn spoken_location(location: Location) -> String {
+    match location {
+        Location::Tableau(loc) => format!("column {}", loc.index() + 1),
+        Location::Freecell(loc) => format!("free cell {}", loc.index() + 1),
+        Location::Foundation(loc) => format!("foundation {}", loc.index() + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Rank, Suit};
+    use crate::foundations::Foundations;
+    use crate::freecells::FreeCells;
+    use crate::location::TableauLocation;
+    use crate::freecells::FREECELL_COUNT;
+    use crate::tableau::{Tableau, TABLEAU_COLUMN_COUNT};
+
+    fn sample_state() -> GameState {
+        let mut tableau = Tableau::new();
+        let column = TableauLocation::new(2).unwrap();
+        tableau.place_card_at_no_checks(column, Card::new(Rank::King, Suit::Hearts));
+        tableau.place_card_at_no_checks(column, Card::new(Rank::Queen, Suit::Spades));
+        let mut freecells = FreeCells::new();
+        freecells
+            .place_card_at(FreecellLocation::new(1).unwrap(), Card::new(Rank::Two, Suit::Clubs))
+            .unwrap();
+        let mut foundations = Foundations::new();
+        foundations
+            .place_card_at(FoundationLocation::new(0).unwrap(), Card::new(Rank::Ace, Suit::Clubs))
+            .unwrap();
+        GameState::from_components(tableau, freecells, foundations)
+    }
+
+    #[test]
+    fn board_lists_every_area_in_order() {
+        let text = ScreenReaderBoard::new(&sample_state()).to_string();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), FOUNDATION_COUNT + FREECELL_COUNT + TABLEAU_COLUMN_COUNT);
+        assert_eq!(lines[0], "Foundation 1: Ace of Clubs on top, 1 card.");
+        assert_eq!(lines[1], "Foundation 2: empty.");
+        assert_eq!(lines[4], "Free cell 1: empty.");
+        assert_eq!(lines[5], "Free cell 2: Two of Clubs.");
+        assert_eq!(lines[8], "Column 1: empty.");
+        assert_eq!(lines[10], "Column 3: King of Hearts, covered by Queen of Spades.");
+    }
+
+    #[test]
+    fn move_names_card_and_both_locations() {
+        let state = sample_state();
+        let mv = Move::freecell_to_foundation(1, 0).unwrap();
+        assert_eq!(
+            ScreenReaderMove::new(&state, &mv).to_string(),
+            "Move Two of Clubs from free cell 2 to foundation 1."
+        );
+    }
+
+    #[test]
+    fn locale_changes_card_names_only() {
+        let state = sample_state();
+        let text = ScreenReaderBoard::new(&state).with_locale(Locale::Fr).to_string();
+        assert!(text.contains("Column 3: Roi de cœur, covered by Dame de pique."));
+
+        let mv = Move::freecell_to_foundation(1, 0).unwrap();
+        assert_eq!(
+            ScreenReaderMove::new(&state, &mv).with_locale(Locale::De).to_string(),
+            "Move Kreuz-Zwei from free cell 2 to foundation 1."
+        );
+    }
+
+    #[test]
+    fn move_from_empty_source_omits_card() {
+        let state = sample_state();
+        let mv = Move::tableau_to_freecell(0, 0).unwrap();
+        assert_eq!(
+            ScreenReaderMove::new(&state, &mv).to_string(),
+            "Move from column 1 to free cell 1."
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Solutions grouped into labelled phases.
+//!
+//! A solver's answer is a flat list of often 100 moves or more, which is hard for a
+//! player to follow. [`SolutionPhases`] replays the solution and groups consecutive
+//! moves that serve the same purpose, e.g. "Excavate column 5" for several moves that
+//! dig cards out of one column, or "Send Hearts home" for a run of foundation moves.
+
+use std::fmt;
+use std::ops::Range;
+
+use super::Locale;
+use crate::card::Suit;
+use crate::game_state::{GameError, GameState};
+use crate::location::Location;
+use crate::r#move::Move;
+
+/// What a group of consecutive moves is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhaseIntent {
+    /// Moving cards off a tableau column to reach the cards underneath.
+    Excavate { column: u8 },
+    /// Moving parked cards out of the free cells onto a tableau column.
+    Build { column: u8 },
+    /// Moving cards to the foundations. `suit` is set when they are all one suit.
+    SendHome { suit: Option<Suit> },
+}
+
+/// A run of consecutive solution moves sharing one [`PhaseIntent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phase {
+    pub intent: PhaseIntent,
+    /// Indices into the solution's move list.
+    pub moves: Range<usize>,
+}
+
+/// A solution split into phases, printable one phase per paragraph.
+///
+/// # Examples
+///
+/// ```
+/// use freecell_game_engine::display::SolutionPhases;
+/// use freecell_game_engine::generation::generate_deal;
+/// use freecell_game_engine::Move;
+///
+/// let game = generate_deal(1).unwrap();
+/// let moves = [
+///     Move::tableau_to_freecell(4, 0).unwrap(),
+///     Move::tableau_to_freecell(4, 1).unwrap(),
+/// ];
+/// let phases = SolutionPhases::new(&game, &moves).unwrap();
+/// assert_eq!(phases.phases().len(), 1);
+/// assert!(phases.to_string().starts_with("Excavate column 5 (moves 1-2)"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SolutionPhases<'a> {
+    moves: &'a [Move],
+    phases: Vec<Phase>,
+    locale: Locale,
+}
+
+impl<'a> SolutionPhases<'a> {
+    /// Replays `moves` from `start` and groups them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`GameError`] of the first move that cannot be played.
+    pub fn new(start: &GameState, moves: &'a [Move]) -> Result<Self, GameError> {
+        let mut state = start.clone();
+        let mut phases: Vec<Phase> = Vec::new();
+        for (index, m) in moves.iter().enumerate() {
+            let intent = intent_of(&state, m);
+            state.execute_move(m)?;
+            if let Some(phase) = phases.last_mut().filter(|phase| merges(phase.intent, intent)) {
+                if phase.intent != intent {
+                    phase.intent = PhaseIntent::SendHome { suit: None };
+                }
+                phase.moves.end = index + 1;
+            } else {
+                phases.push(Phase {
+                    intent,
+                    moves: index..index + 1,
+                });
+            }
+        }
+        Ok(Self {
+            moves,
+            phases,
+            locale: Locale::default(),
+        })
+    }
+
+    /// Names suits in `locale` instead. The phase labels themselves stay English.
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// The phases in play order.
+    pub fn phases(&self) -> &[Phase] {
+        &self.phases
+    }
+
+    fn label(&self, intent: PhaseIntent) -> String {
+        match intent {
+            PhaseIntent::Excavate { column } => format!("Excavate column {}", column + 1),
+            PhaseIntent::Build { column } => format!("Build column {}", column + 1),
+            PhaseIntent::SendHome { suit: Some(suit) } => {
+                format!("Send {} home", self.locale.suit_name(suit))
+            }
+            PhaseIntent::SendHome { suit: None } => "Send cards home".to_string(),
+        }
+    }
+}
+
+/// Classifies a move by where it comes from and goes to, looking at the card it moves.
+fn intent_of(state: &GameState, m: &Move) -> PhaseIntent {
+    match (m.source, m.destination) {
+        (_, Location::Foundation(_)) => PhaseIntent::SendHome {
+            suit: state.get_card(m.source).ok().flatten().map(|card| card.suit()),
+        },
+        (Location::Tableau(column), _) => PhaseIntent::Excavate { column: column.index() },
+        (_, Location::Tableau(column)) => PhaseIntent::Build { column: column.index() },
+        // Freecell to freecell is not a legal move; `new` stops at it right after this.
+        _ => PhaseIntent::SendHome { suit: None },
+    }
+}
+
+/// Whether a move with intent `next` continues a phase with intent `current`. Any
+/// foundation moves continue a foundation phase; a mix of suits is relabelled as such.
+fn merges(current: PhaseIntent, next: PhaseIntent) -> bool {
+    match (current, next) {
+        (PhaseIntent::SendHome { .. }, PhaseIntent::SendHome { .. }) => true,
+        (current, next) => current == next,
+    }
+}
+
+impl fmt::Display for SolutionPhases<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, phase) in self.phases.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let Range { start, end } = phase.moves;
+            if end - start == 1 {
+                writeln!(f, "{} (move {})", self.label(phase.intent), start + 1)?;
+            } else {
+                writeln!(f, "{} (moves {}-{})", self.label(phase.intent), start + 1, end)?;
+            }
+            for m in &self.moves[phase.moves.clone()] {
+                writeln!(f, "  {}", m)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank};
+    use crate::game_state::GameStateBuilder;
+    use crate::location::{FreecellLocation, TableauLocation};
+
+    #[test]
+    fn foundation_runs_merge_and_name_their_suit() {
+        let mut builder = GameStateBuilder::new();
+        let column = TableauLocation::new(0).unwrap();
+        builder
+            .column(column, [Rank::Two, Rank::Ace].map(|rank| Card::new(rank, Suit::Hearts)))
+            .unwrap();
+        builder
+            .place(
+                Location::Freecell(FreecellLocation::new(0).unwrap()),
+                Card::new(Rank::Ace, Suit::Clubs),
+            )
+            .unwrap();
+        let game = builder.state().clone();
+        let moves = [
+            Move::tableau_to_foundation(0, 0).unwrap(),
+            Move::tableau_to_foundation(0, 0).unwrap(),
+            Move::freecell_to_foundation(0, 1).unwrap(),
+        ];
+
+        let hearts = SolutionPhases::new(&game, &moves[..2]).unwrap();
+        assert_eq!(
+            hearts.phases(),
+            [Phase { intent: PhaseIntent::SendHome { suit: Some(Suit::Hearts) }, moves: 0..2 }]
+        );
+
+        let mixed = SolutionPhases::new(&game, &moves).unwrap();
+        assert_eq!(mixed.phases()[0].intent, PhaseIntent::SendHome { suit: None });
+        assert_eq!(mixed.phases()[0].moves, 0..3);
+    }
+
+    #[test]
+    fn illegal_move_is_reported() {
+        let game = GameState::new();
+        let moves = [Move::tableau_to_freecell(0, 0).unwrap()];
+        assert!(SolutionPhases::new(&game, &moves).is_err());
+    }
+}
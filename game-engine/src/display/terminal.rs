@@ -0,0 +1,239 @@
+//! A plain-text board for terminals and log files.
+//!
+//! `GameState`'s own `Display` draws suits as emoji, whose width varies between
+//! terminals and which many log viewers cannot show at all. [`TerminalBoard`] writes
+//! every card as three ASCII characters (`" AS"`, `"10H"`) so columns always line up,
+//! and can colour hearts and diamonds red with ANSI escapes. Colour is off by default;
+//! turn it on only when writing to a terminal, e.g. with
+//! `std::io::stdout().is_terminal()`.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::display::TerminalBoard;
+//! use freecell_game_engine::generation::generate_deal;
+//!
+//! let game = generate_deal(1).unwrap();
+//! let text = TerminalBoard::new(&game).to_string();
+//! assert!(text.lines().nth(2).unwrap().starts_with(" JD  2D  9H  JC"));
+//!
+//! let colored = TerminalBoard::new(&game).with_color(true).to_string();
+//! assert!(colored.contains("\u{1b}[31m 2D\u{1b}[0m"));
+//! ```
+//!
+//! For mouse input, [`TerminalBoard::location_at`] maps a character cell of the drawn
+//! board back to the pile it shows, and
+//! [`GameState::legal_destinations`](crate::GameState::legal_destinations) gives the
+//! piles to highlight once a card is picked up.
+
+use std::fmt;
+
+use crate::card::{Card, Color, Rank, Suit};
+use crate::foundations::FOUNDATION_COUNT;
+use crate::game_state::GameState;
+use crate::location::{FoundationLocation, FreecellLocation, Location, TableauLocation};
+
+const RED: &str = "\u{1b}[31m";
+const RESET: &str = "\u{1b}[0m";
+/// What an empty foundation or freecell shows, as wide as a card.
+const EMPTY_SLOT: &str = " --";
+/// Characters per card plus the space after it.
+const SLOT_WIDTH: usize = 4;
+/// Where the first freecell starts on the top line: the foundations, the two-space gap
+/// and the space before the cell.
+const FIRST_FREECELL: usize = FOUNDATION_COUNT * SLOT_WIDTH + 3;
+/// The line the tableau starts on.
+const FIRST_TABLEAU_ROW: usize = 2;
+
+/// Draws a board as a grid of fixed-width cards: foundations and freecells on the first
+/// line, then the tableau one row at a time.
+#[derive(Debug, Clone, Copy)]
+pub struct TerminalBoard<'a> {
+    state: &'a GameState,
+    color: bool,
+}
+
+impl<'a> TerminalBoard<'a> {
+    /// Creates an uncoloured formatter for `state`.
+    pub fn new(state: &'a GameState) -> Self {
+        Self { state, color: false }
+    }
+
+    /// Colours red cards with ANSI escapes when `color` is true.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// The pile drawn at `row`, `column` (0-based line and character) of this board's
+    /// text, e.g. under a mouse click. A click anywhere in a tableau column's width below
+    /// the top line picks that column, so an empty column can be dropped onto. Gaps
+    /// between cards and the blank line pick nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::display::TerminalBoard;
+    /// use freecell_game_engine::generation::generate_deal;
+    /// use freecell_game_engine::location::{FreecellLocation, Location, TableauLocation};
+    ///
+    /// let game = generate_deal(1).unwrap();
+    /// let board = TerminalBoard::new(&game);
+    /// assert_eq!(board.location_at(0, 20), Some(Location::Freecell(FreecellLocation::new(0).unwrap())));
+    /// assert_eq!(board.location_at(30, 5), Some(Location::Tableau(TableauLocation::new(1).unwrap())));
+    /// assert_eq!(board.location_at(1, 5), None);
+    /// ```
+    pub fn location_at(&self, row: usize, column: usize) -> Option<Location> {
+        // Only the card's own characters count, not the space after it.
+        let slot = |offset: usize| {
+            (column >= offset && (column - offset) % SLOT_WIDTH < 3).then(|| (column - offset) / SLOT_WIDTH)
+        };
+        match row {
+            0 if column < FIRST_FREECELL - 3 => {
+                let pile = slot(0)?;
+                FoundationLocation::new(pile as u8).ok().map(Location::Foundation)
+            }
+            0 => {
+                let cell = slot(FIRST_FREECELL)?;
+                (cell < self.state.freecells().slot_count())
+                    .then(|| FreecellLocation::new(cell as u8).ok().map(Location::Freecell))
+                    .flatten()
+            }
+            row if row >= FIRST_TABLEAU_ROW => {
+                let index = slot(0)?;
+                (index < self.state.tableau().column_count())
+                    .then(|| TableauLocation::new(index as u8).ok().map(Location::Tableau))
+                    .flatten()
+            }
+            _ => None,
+        }
+    }
+
+    fn write_card(&self, f: &mut fmt::Formatter<'_>, card: &Card) -> fmt::Result {
+        let rank = match card.rank() {
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+        };
+        let suit = match card.suit() {
+            Suit::Spades => 'S',
+            Suit::Hearts => 'H',
+            Suit::Diamonds => 'D',
+            Suit::Clubs => 'C',
+        };
+        if self.color && card.color() == Color::Red {
+            write!(f, "{}{:>2}{}{}", RED, rank, suit, RESET)
+        } else {
+            write!(f, "{:>2}{}", rank, suit)
+        }
+    }
+
+    fn write_slot(&self, f: &mut fmt::Formatter<'_>, card: Option<&Card>) -> fmt::Result {
+        match card {
+            Some(card) => self.write_card(f, card),
+            None => f.write_str(EMPTY_SLOT),
+        }
+    }
+}
+
+impl fmt::Display for TerminalBoard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let foundations = self.state.foundations();
+        for i in 0..FOUNDATION_COUNT {
+            let card = foundations.get_card(FoundationLocation::new(i as u8).unwrap()).ok().flatten();
+            self.write_slot(f, card)?;
+            f.write_str(" ")?;
+        }
+        f.write_str("  ")?;
+        let freecells = self.state.freecells();
+        for i in 0..freecells.slot_count() {
+            let card = freecells.get_card(FreecellLocation::new(i as u8).unwrap()).ok().flatten();
+            f.write_str(" ")?;
+            self.write_slot(f, card)?;
+        }
+        writeln!(f)?;
+        writeln!(f)?;
+
+        let columns: Vec<&Vec<Card>> = self.state.tableau().columns().collect();
+        let rows = columns.iter().map(|column| column.len()).max().unwrap_or(0);
+        for row in 0..rows {
+            // Stop at the last column still holding a card, so rows carry no trailing blanks.
+            let last = columns.iter().rposition(|column| column.len() > row).unwrap_or(0);
+            for (i, column) in columns[..=last].iter().enumerate() {
+                if i > 0 {
+                    f.write_str(" ")?;
+                }
+                match column.get(row) {
+                    Some(card) => self.write_card(f, card)?,
+                    None => f.write_str("   ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::generate_deal;
+    use crate::r#move::Move;
+
+    #[test]
+    fn every_row_lines_up_without_color() {
+        let mut game = generate_deal(1).unwrap();
+        game.execute_move(&Move::tableau_to_freecell(0, 2).unwrap()).unwrap();
+        let text = TerminalBoard::new(&game).to_string();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], " --  --  --  --     --  --  6S  --");
+        assert_eq!(lines[1], "");
+        // Every card is three characters wide with one space between columns.
+        assert!(lines[2..].iter().all(|line| line.len() % 4 == 3));
+        assert!(text.is_ascii());
+    }
+
+    #[test]
+    fn hit_test_follows_the_drawn_layout() {
+        let mut game = generate_deal(1).unwrap();
+        game.execute_move(&Move::tableau_to_freecell(0, 2).unwrap()).unwrap();
+        let board = TerminalBoard::new(&game);
+        let text = board.to_string();
+        let top = text.lines().next().unwrap();
+
+        // The freecell holding the six of spades is drawn where it is hit.
+        let six = top.find("6S").unwrap();
+        assert_eq!(board.location_at(0, six), Some(Location::Freecell(FreecellLocation::new(2).unwrap())));
+        assert_eq!(board.location_at(0, 0), Some(Location::Foundation(FoundationLocation::new(0).unwrap())));
+        assert_eq!(board.location_at(0, 14), Some(Location::Foundation(FoundationLocation::new(3).unwrap())));
+        // The gap between foundations and freecells, and the spaces between cards.
+        assert_eq!(board.location_at(0, 16), None);
+        assert_eq!(board.location_at(0, 3), None);
+        assert_eq!(board.location_at(0, top.len()), None);
+
+        let nine_h = text.lines().nth(2).unwrap().find(" 9H").unwrap();
+        assert_eq!(board.location_at(2, nine_h), Some(Location::Tableau(TableauLocation::new(2).unwrap())));
+        assert_eq!(board.location_at(2, 8 * SLOT_WIDTH), None);
+    }
+
+    #[test]
+    fn color_wraps_only_red_cards() {
+        let game = generate_deal(1).unwrap();
+        let colored = TerminalBoard::new(&game).with_color(true).to_string();
+        let plain = TerminalBoard::new(&game).to_string();
+
+        assert_eq!(colored.matches(RED).count(), 26);
+        assert_eq!(colored.replace(RED, "").replace(RESET, ""), plain);
+    }
+}
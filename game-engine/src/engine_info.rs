@@ -0,0 +1,67 @@
+//! Which engine semantics produced a piece of data.
+//!
+//! Solver result files and saved games outlive the engine build that wrote them. Deal
+//! numbers only mean something together with the shuffle that turns them into layouts,
+//! and packed states only together with the canonical layout they were packed from.
+//! [`ENGINE_INFO`] records both revisions alongside the crate version, so writers can
+//! stamp their output and loaders can refuse data from an incompatible engine.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::ENGINE_INFO;
+//!
+//! let stamp = ENGINE_INFO.clone();
+//! assert!(ENGINE_INFO.is_compatible_with(&stamp));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Version information for the engine, suitable for storing next to its output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EngineInfo {
+    /// The crate version, e.g. `"0.1.0"`.
+    pub version: Cow<'static, str>,
+    /// Revision of the deal generator. A deal number produces the same layout for
+    /// every engine with the same revision.
+    pub deal_algorithm: u32,
+    /// Revision of the canonical state layout that solvers pack and hash
+    /// (`extract_canonical_data` on the tableau, freecells and foundations).
+    pub packing_format: u32,
+}
+
+/// The semantics of this build of the engine.
+pub const ENGINE_INFO: EngineInfo = EngineInfo {
+    version: Cow::Borrowed(env!("CARGO_PKG_VERSION")),
+    deal_algorithm: 1,
+    packing_format: 2,
+};
+
+impl EngineInfo {
+    /// Whether data stamped with `other` means the same thing to this engine: same deal
+    /// algorithm and same packing format. The crate version is informational only.
+    pub fn is_compatible_with(&self, other: &EngineInfo) -> bool {
+        self.deal_algorithm == other.deal_algorithm && self.packing_format == other.packing_format
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revisions_decide_compatibility() {
+        let newer_patch = EngineInfo {
+            version: Cow::Borrowed("99.0.0"),
+            ..ENGINE_INFO
+        };
+        assert!(ENGINE_INFO.is_compatible_with(&newer_patch));
+
+        let reshuffled = EngineInfo {
+            deal_algorithm: ENGINE_INFO.deal_algorithm + 1,
+            ..ENGINE_INFO
+        };
+        assert!(!ENGINE_INFO.is_compatible_with(&reshuffled));
+    }
+}
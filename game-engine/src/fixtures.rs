@@ -0,0 +1,310 @@
+//! A shared corpus of curated positions for tests.
+//!
+//! Strategies and downstream applications keep rebuilding the same kinds of test
+//! positions by hand: something one move from winning, something hopelessly stuck,
+//! something that only works if a run is shuffled through the freecells. This module
+//! ships a dozen of them, each with a stable name and what is known about it, so every
+//! crate tests against the same boards.
+//!
+//! Positions are written in the text format of [`import_board`](crate::import::import_board)
+//! and built on demand. The module is only compiled with the `test-fixtures` feature;
+//! enable it from `[dev-dependencies]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::fixtures::{self, Expectation};
+//!
+//! let fixture = fixtures::by_name("kings_in_freecells").unwrap();
+//! assert_eq!(fixture.expectation, Expectation::Solvable { min_moves: Some(4) });
+//!
+//! let game = fixture.state();
+//! assert!(game.check_integrity().is_ok());
+//! assert!(!game.is_won().unwrap());
+//! ```
+
+use crate::generation::generate_deal;
+use crate::import::{import_board, ImportWarning};
+use crate::{GameState, Variant};
+
+/// What a fixture is meant to exercise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// A few moves from a win.
+    NearWin,
+    /// Cannot be won.
+    Deadlock,
+    /// The next foundation card is buried under an alternating run that has to be moved
+    /// one card at a time through free cells or empty columns.
+    Supermove,
+}
+
+/// What is known about a fixture's outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Expectation {
+    /// Can be won; `min_moves` is the length of the shortest win when it is known.
+    Solvable { min_moves: Option<usize> },
+    Unsolvable,
+}
+
+/// One curated position.
+#[derive(Debug, Clone, Copy)]
+pub struct Fixture {
+    /// Stable identifier, safe to use in test names and snapshots.
+    pub name: &'static str,
+    pub category: Category,
+    pub expectation: Expectation,
+    pub description: &'static str,
+    build: fn() -> GameState,
+}
+
+impl Fixture {
+    /// Builds a fresh copy of the position.
+    pub fn state(&self) -> GameState {
+        (self.build)()
+    }
+}
+
+/// Every fixture, grouped by category.
+pub fn all() -> &'static [Fixture] {
+    &FIXTURES
+}
+
+/// Looks a fixture up by its name.
+pub fn by_name(name: &str) -> Option<&'static Fixture> {
+    FIXTURES.iter().find(|fixture| fixture.name == name)
+}
+
+/// Fixtures of one category.
+pub fn in_category(category: Category) -> impl Iterator<Item = &'static Fixture> {
+    FIXTURES.iter().filter(move |fixture| fixture.category == category)
+}
+
+/// Imports a fixture board. Missing trailing columns are expected; anything else is a
+/// mistake in the fixture.
+fn board(text: &str) -> GameState {
+    let import = import_board(text, Variant::Standard).expect("fixture board imports");
+    debug_assert!(
+        import.warnings.iter().all(|w| matches!(w, ImportWarning::MissingColumns { .. })),
+        "fixture board has problems: {:?}",
+        import.warnings
+    );
+    import.state
+}
+
+static FIXTURES: [Fixture; 14] = [
+    Fixture {
+        name: "one_move_from_win",
+        category: Category::NearWin,
+        expectation: Expectation::Solvable { min_moves: Some(1) },
+        description: "Only the king of clubs is left, alone in the first column.",
+        build: || board("Foundations: KS KH KD QC\n: KC"),
+    },
+    Fixture {
+        name: "kings_in_freecells",
+        category: Category::NearWin,
+        expectation: Expectation::Solvable { min_moves: Some(4) },
+        description: "All four kings wait in the freecells on top of complete queens.",
+        build: || board("Foundations: QS QH QD QC\nFreecells: KS KH KD KC"),
+    },
+    Fixture {
+        name: "kings_left",
+        category: Category::NearWin,
+        expectation: Expectation::Solvable { min_moves: Some(4) },
+        description: "Only the kings are left: the club king on the spade king in the \
+                      first column, the heart and diamond kings alone in the next two.",
+        build: || board("Foundations: QS QH QD QC\n: KS KC\n: KH\n: KD\n"),
+    },
+    Fixture {
+        name: "kings_in_one_column",
+        category: Category::NearWin,
+        expectation: Expectation::Solvable { min_moves: Some(4) },
+        description: "Only the kings are left, stacked spades, hearts, diamonds, clubs in \
+                      the first column; they go home from the top down.",
+        build: || board("Foundations: QS QH QD QC\n: KS KH KD KC"),
+    },
+    Fixture {
+        name: "buried_next_card",
+        category: Category::NearWin,
+        expectation: Expectation::Solvable { min_moves: Some(7) },
+        description: "The ten of clubs is under its own jack, queen and king, which must be \
+                      parked before anything can go home.",
+        build: || board("Foundations: KS KH KD 9C\n: 10C JC QC KC"),
+    },
+    Fixture {
+        name: "freecells_full_use_empty_column",
+        category: Category::NearWin,
+        expectation: Expectation::Solvable { min_moves: Some(7) },
+        description: "Every freecell is taken and the king of spades covers the jack; \
+                      the king has to go to an empty column.",
+        build: || board("Foundations: 10S QH QD QC\nFreecells: KH KD KC QS\n: JS KS"),
+    },
+    Fixture {
+        name: "no_legal_moves",
+        category: Category::Deadlock,
+        expectation: Expectation::Unsolvable,
+        description: "Kings fill the freecells, every nine is buried and no exposed card \
+                      fits on another.",
+        build: || {
+            board(
+                "Foundations: 8S 8H 8D 8C\n\
+                 Freecells: KS KH KD KC\n\
+                 : 9S QS\n: 9H QC\n: 9D QH\n: 9C QD\n\
+                 : JS 10S\n: JH 10C\n: JD 10H\n: JC 10D",
+            )
+        },
+    },
+    Fixture {
+        name: "blocked_opening",
+        category: Category::Deadlock,
+        expectation: Expectation::Unsolvable,
+        description: "The whole deck is still in play, the jacks fill the freecells and \
+                      every column ends in a king or a ten: there is no first move.",
+        build: || {
+            board(
+                "Freecells: JS JH JD JC\n\
+                 : AS 2H 3D 4C QS KS\n: AH 2D 3C 4S QH KH\n\
+                 : AD 2C 3S 4H QD KD\n: AC 2S 3H 4D QC KC\n\
+                 : 5S 6H 7D 8C 9S 10S\n: 5H 6D 7C 8S 9H 10H\n\
+                 : 5D 6C 7S 8H 9D 10D\n: 5C 6S 7H 8D 9C 10C",
+            )
+        },
+    },
+    Fixture {
+        name: "spades_without_freecells",
+        category: Category::Deadlock,
+        expectation: Expectation::Unsolvable,
+        description: "Only spades are left, the five under the six, on a board with no \
+                      freecells; black cards cannot stack on each other.",
+        build: || {
+            let mut game = board(
+                "Foundations: 4S KH KD KC\n\
+                 : 5S 6S\n: 7S\n: 8S\n: 9S\n: 10S\n: JS\n: QS\n: KS",
+            );
+            game.set_freecell_capacity(0).expect("fixture board has empty freecells");
+            game
+        },
+    },
+    Fixture {
+        name: "deal_11982",
+        category: Category::Deadlock,
+        expectation: Expectation::Unsolvable,
+        description: "Microsoft deal 11982, the one deal among the first 32000 that is \
+                      known to be unsolvable. Too large to prove in a unit test.",
+        build: || generate_deal(11982).expect("11982 is a valid deal number"),
+    },
+    Fixture {
+        name: "two_card_run_one_cell",
+        category: Category::Supermove,
+        expectation: Expectation::Solvable { min_moves: None },
+        description: "The ten of clubs is under the run JH 10S and there is one free \
+                      freecell and no empty column: the run moves onto the queen of clubs \
+                      through the cell.",
+        build: || {
+            board(
+                "Foundations: 9S 9H 9D 9C\n\
+                 Freecells: KS KH KD\n\
+                 : 10C JH 10S\n: QC\n: JC 10H\n: 10D QS\n: JS QD\n: JD\n: QH\n: KC",
+            )
+        },
+    },
+    Fixture {
+        name: "run_through_freecells",
+        category: Category::Supermove,
+        expectation: Expectation::Solvable { min_moves: None },
+        description: "The seven of clubs is under the run 10H 9S 8H, which has to move \
+                      onto the jack of clubs using the two free freecells.",
+        build: || {
+            board(
+                "Foundations: 7S 7H KD 6C\n\
+                 Freecells: KS KH\n\
+                 : 7C 10H 9S 8H\n: JC\n: 8S QC\n: 9H KC\n\
+                 : 8C JS\n: 9C QH\n: 10C JH\n: 10S QS",
+            )
+        },
+    },
+    Fixture {
+        name: "run_via_empty_column",
+        category: Category::Supermove,
+        expectation: Expectation::Solvable { min_moves: None },
+        description: "The six of diamonds is under the four-card run 10S 9H 8S 7H. With one \
+                      free freecell, moving it onto the jack of diamonds needs the empty \
+                      column as well.",
+        build: || {
+            board(
+                "Foundations: 5S 5H 5D KC\n\
+                 Freecells: KS KH KD\n\
+                 : 6D 10S 9H 8S 7H\n: JD\n: -\n: 6S QD 7S\n\
+                 : 6H 9S 8H\n: 9D JS QS\n: 7D JH 10D\n: 8D 10H QH",
+            )
+        },
+    },
+    Fixture {
+        name: "run_into_empty_column",
+        category: Category::Supermove,
+        expectation: Expectation::Solvable { min_moves: None },
+        description: "The nine of clubs is under the king-headed run KH QS JH 10S, which \
+                      only an empty column can take; three free freecells are just enough.",
+        build: || {
+            board(
+                "Foundations: 8S 8H 8D 8C\n\
+                 Freecells: JD\n\
+                 : 9C KH QS JH 10S\n: QD KS 10H\n: -\n: 9S QC\n\
+                 : 9H\n: 9D JC\n: 10D\n: 10C JS KD QH KC",
+            )
+        },
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Depth-first search over every reachable position, up to `limit` positions.
+    /// Returns whether a win was found and whether the search was exhaustive.
+    fn search(start: &GameState, limit: usize) -> (bool, bool) {
+        let mut seen = HashSet::from([start.clone()]);
+        let mut stack = vec![start.clone()];
+        while let Some(game) = stack.pop() {
+            if game.is_won().unwrap() {
+                return (true, true);
+            }
+            for m in game.get_available_moves().into_iter().rev() {
+                let mut next = game.clone();
+                next.execute_move(&m).unwrap();
+                if seen.len() >= limit {
+                    return (false, false);
+                }
+                if seen.insert(next.clone()) {
+                    stack.push(next);
+                }
+            }
+        }
+        (false, true)
+    }
+
+    #[test]
+    fn names_are_unique_and_positions_are_complete() {
+        let names: HashSet<_> = all().iter().map(|f| f.name).collect();
+        assert_eq!(names.len(), all().len());
+        for fixture in all() {
+            assert!(fixture.state().check_integrity().is_ok(), "{}", fixture.name);
+            assert_eq!(by_name(fixture.name).map(|f| f.name), Some(fixture.name));
+        }
+        assert_eq!(in_category(Category::Supermove).count(), 4);
+    }
+
+    #[test]
+    fn expectations_hold() {
+        for fixture in all().iter().filter(|f| f.name != "deal_11982") {
+            let (won, exhaustive) = search(&fixture.state(), 20_000);
+            match fixture.expectation {
+                Expectation::Solvable { .. } => assert!(won, "{} should be solvable", fixture.name),
+                Expectation::Unsolvable => {
+                    assert!(exhaustive && !won, "{} should be proven unsolvable", fixture.name)
+                }
+            }
+        }
+    }
+}
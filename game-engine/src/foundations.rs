@@ -63,19 +63,24 @@
 //! parameter for type safety, which ensures that only valid foundation indices can be used.
 //!
 //! The implementation uses fixed-size arrays for efficient memory usage and performance:
-//! - Each pile is represented as an array of `Option<Card>` with capacity for 13 cards (A-K)
+//! - Each pile is represented as an array of `Option<Card>` with room for 13 cards (A-K) per deck
 //! - A separate `heights` array tracks the current size of each pile for O(1) access
 //! - This approach offers better performance than vectors when accessing top cards or checking pile status
 
 use crate::card::{Card, Rank, Suit};
 use crate::location::FoundationLocation;
+use crate::rules::RulesProfile;
 use std::fmt;
 
 /// The number of foundation piles in FreeCell (one for each suit).
-pub const FOUNDATION_COUNT: usize = 4;
+pub const FOUNDATION_COUNT: usize = RulesProfile::STANDARD.foundation_piles();
 
 /// The maximum number of cards in each foundation pile (Ace through King).
-pub const FOUNDATION_CAPACITY: usize = 13;
+pub const FOUNDATION_CAPACITY: usize = RulesProfile::STANDARD.pile_capacity();
+
+/// The most decks any supported variant uses. Double FreeCell builds each pile
+/// from Ace to King twice.
+pub const MAX_DECKS: usize = RulesProfile::DOUBLE_FREECELL.decks();
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// Error type for foundation operations.
@@ -97,6 +102,7 @@ pub const FOUNDATION_CAPACITY: usize = 13;
 /// let result = foundations.validate_card_placement(location, &Card::new(Rank::Two, Suit::Hearts));
 /// assert!(matches!(result, Err(FoundationError::NonAceOnEmptyPile { .. })));
 /// ```
+#[non_exhaustive]
 pub enum FoundationError {
     /// Attempted to access an invalid pile index.
     InvalidPile(u8),
@@ -112,6 +118,9 @@ pub enum FoundationError {
 
     /// No suitable foundation pile available for this card.
     NoAvailablePile { card: Card },
+
+    /// Requested a deck count of zero or above [`MAX_DECKS`].
+    InvalidDeckCount(usize),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -143,10 +152,12 @@ pub enum FoundationError {
 pub struct Foundations {
     // Fixed-size array for each pile with options for each card position
     // Using fixed-size arrays for efficient memory usage and stack allocation
-    piles: [[Option<Card>; FOUNDATION_CAPACITY]; FOUNDATION_COUNT],
+    piles: [[Option<Card>; FOUNDATION_CAPACITY * MAX_DECKS]; FOUNDATION_COUNT],
     // Track the current height of each pile for O(1) access to pile information
     // This avoids having to scan through arrays to find the first None element
     heights: [usize; FOUNDATION_COUNT],
+    // Number of times each pile is built from Ace to King (2 in Double FreeCell)
+    decks: u8,
 }
 
 impl fmt::Display for FoundationError {
@@ -176,6 +187,11 @@ impl fmt::Display for FoundationError {
             FoundationError::NoAvailablePile { card } => {
                 write!(f, "No available foundation pile for {}", card)
             }
+            FoundationError::InvalidDeckCount(decks) => write!(
+                f,
+                "Invalid deck count {}: must be 1-{}",
+                decks, MAX_DECKS
+            ),
         }
     }
 }
@@ -200,7 +216,7 @@ impl fmt::Display for Foundations {
                     writeln!(
                         f,
                         "  {}: {} (height: {}/{})",
-                        suit_name, card, height, FOUNDATION_CAPACITY
+                        suit_name, card, height, self.pile_capacity()
                     )?;
                 }
                 Ok(None) => writeln!(
@@ -243,7 +259,94 @@ impl Foundations {
         Self {
             piles: std::array::from_fn(|_| std::array::from_fn(|_| None)),
             heights: [0; FOUNDATION_COUNT],
+            decks: 1,
+        }
+    }
+
+    /// Create empty foundations for a game played with `decks` decks.
+    ///
+    /// With two decks each pile runs Ace to King and then Ace to King again, as in
+    /// Double FreeCell.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FoundationError::InvalidDeckCount` if `decks` is zero or exceeds [`MAX_DECKS`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::foundations::Foundations;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::FoundationLocation;
+    ///
+    /// let mut foundations = Foundations::with_decks(2).unwrap();
+    /// let pile = FoundationLocation::new(0).unwrap();
+    /// for rank in 1..=13u8 {
+    ///     foundations.place_card_at(pile, Card::new(Rank::try_from(rank).unwrap(), Suit::Hearts)).unwrap();
+    /// }
+    /// // The King does not close the pile: the second Ace goes on top of it.
+    /// foundations.place_card_at(pile, Card::new(Rank::Ace, Suit::Hearts)).unwrap();
+    /// assert_eq!(foundations.pile_capacity(), 26);
+    /// ```
+    pub fn with_decks(decks: usize) -> Result<Self, FoundationError> {
+        if decks == 0 || decks > MAX_DECKS {
+            return Err(FoundationError::InvalidDeckCount(decks));
         }
+        Ok(Self {
+            decks: decks as u8,
+            ..Self::new()
+        })
+    }
+
+    /// The number of decks the piles are built from (1 in standard FreeCell).
+    pub fn decks(&self) -> usize {
+        self.decks as usize
+    }
+
+    /// The number of cards a complete pile holds: 13 per deck.
+    pub fn pile_capacity(&self) -> usize {
+        FOUNDATION_CAPACITY * self.decks()
+    }
+
+    /// The number of cards all the piles hold when complete: 52 per deck.
+    ///
+    /// Code that sizes progress bars, packed layouts or completeness checks should ask
+    /// for this rather than assume a single deck.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::foundations::Foundations;
+    ///
+    /// assert_eq!(Foundations::new().capacity(), 52);
+    /// assert_eq!(Foundations::with_decks(2).unwrap().capacity(), 104);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        FOUNDATION_COUNT * self.pile_capacity()
+    }
+
+    /// The suit a pile is built in, or `None` while it is empty.
+    ///
+    /// Piles have no fixed suit: each takes whichever Ace reaches it first, so the suit
+    /// follows from the pile's bottom card. An empty pile may take any suit whose pile
+    /// has not started yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::foundations::Foundations;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::FoundationLocation;
+    ///
+    /// let mut foundations = Foundations::new();
+    /// let first = FoundationLocation::new(0).unwrap();
+    /// assert_eq!(foundations.pile_suit(first), None);
+    ///
+    /// foundations.place_card(Card::new(Rank::Ace, Suit::Clubs)).unwrap();
+    /// assert_eq!(foundations.pile_suit(first), Some(Suit::Clubs));
+    /// ```
+    pub fn pile_suit(&self, location: FoundationLocation) -> Option<Suit> {
+        self.piles[location.index() as usize][0].map(|card| card.suit())
     }
 
     /// Extract canonical foundation data for efficient packed representation.
@@ -294,6 +397,8 @@ impl Foundations {
                 255 // Empty foundation
             };
         }
+        // Which pile a suit was started on is not part of the position.
+        foundation_data.sort_unstable();
     }
 
     /// Place a card in the appropriate foundation pile automatically.
@@ -389,6 +494,49 @@ impl Foundations {
         Ok(())
     }
 
+    /// Place a card on a foundation pile without validating the sequence rules.
+    ///
+    /// This is the raw counterpart to [`place_card_at`](Self::place_card_at), matching
+    /// `Tableau::place_card_at_no_checks` and `FreeCells::place_card_at_no_checks`. It is
+    /// intended for undo paths, where the card is known to have just come off this pile.
+    /// Debug builds still assert the placement is legal.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the pile is already complete or the card does not
+    /// continue it. Release builds only panic once the pile's storage, room for
+    /// [`MAX_DECKS`] decks, runs out, so misuse on a single-deck pile goes unnoticed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::foundations::Foundations;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::FoundationLocation;
+    ///
+    /// let mut foundations = Foundations::new();
+    /// let location = FoundationLocation::new(0).unwrap();
+    /// foundations.place_card_at_no_checks(location, Card::new(Rank::Ace, Suit::Hearts));
+    /// assert_eq!(foundations.total_cards(), 1);
+    /// ```
+    pub fn place_card_at_no_checks(&mut self, location: FoundationLocation, card: Card) {
+        debug_assert!(
+            self.height(location) < self.pile_capacity(),
+            "foundation pile {} is already complete; cannot place {}",
+            location.index(),
+            card
+        );
+        debug_assert!(
+            self.validate_card_placement(location, &card).is_ok(),
+            "raw foundation placement of {} breaks pile {}",
+            card,
+            location.index()
+        );
+        let idx = location.index() as usize;
+        self.piles[idx][self.heights[idx]] = Some(card);
+        self.heights[idx] += 1;
+    }
+
     /// Remove and return the top card from a foundation pile.
     ///
     /// Returns the card if one was present, or `None` if the pile was empty.
@@ -521,9 +669,68 @@ impl Foundations {
         self.heights.iter().sum()
     }
 
+    /// The next card each suit needs, indexed by [`Suit::foundation_index`].
+    ///
+    /// Unstarted suits need their Ace; a suit whose pile is complete needs nothing. In
+    /// multi-deck games a pile topped by a King that still has room needs the Ace again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::foundations::Foundations;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    ///
+    /// let mut foundations = Foundations::new();
+    /// foundations.place_card(Card::new(Rank::Ace, Suit::Hearts)).unwrap();
+    ///
+    /// let needed = foundations.needed_cards();
+    /// assert_eq!(needed[Suit::Hearts.foundation_index() as usize], Some(Card::new(Rank::Two, Suit::Hearts)));
+    /// assert_eq!(needed[Suit::Clubs.foundation_index() as usize], Some(Card::new(Rank::Ace, Suit::Clubs)));
+    /// ```
+    pub fn needed_cards(&self) -> [Option<Card>; FOUNDATION_COUNT] {
+        let mut needed = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs]
+            .map(|suit| Some(Card::new(Rank::Ace, suit)));
+        for (pile, &height) in self.heights.iter().enumerate() {
+            let Some(top) = height.checked_sub(1).and_then(|top| self.piles[pile][top]) else {
+                continue;
+            };
+            needed[top.suit().foundation_index() as usize] = if height == self.pile_capacity() {
+                None
+            } else {
+                // Wraps a King around to the next deck's Ace.
+                let next = Rank::try_from(top.rank() as u8 % 13 + 1).expect("ranks run 1 to 13");
+                Some(Card::new(next, top.suit()))
+            };
+        }
+        needed
+    }
+
+    /// Iterate over the cards of a single foundation pile, from the bottom (Ace) up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::foundations::Foundations;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::FoundationLocation;
+    ///
+    /// let mut foundations = Foundations::new();
+    /// let location = FoundationLocation::new(0).unwrap();
+    /// foundations.place_card_at(location, Card::new(Rank::Ace, Suit::Clubs)).unwrap();
+    /// foundations.place_card_at(location, Card::new(Rank::Two, Suit::Clubs)).unwrap();
+    ///
+    /// let ranks: Vec<Rank> = foundations.pile_cards(location).map(|c| c.rank()).collect();
+    /// assert_eq!(ranks, vec![Rank::Ace, Rank::Two]);
+    /// ```
+    pub fn pile_cards(&self, location: FoundationLocation) -> impl Iterator<Item = &Card> + '_ {
+        let idx = location.index() as usize;
+        self.piles[idx][..self.heights[idx]].iter().flatten()
+    }
+
     /// Check if all foundations are complete (game won).
     ///
-    /// The game is considered complete when all foundation piles have all 13 cards.
+    /// The game is considered complete when every foundation pile holds
+    /// [`pile_capacity`](Self::pile_capacity) cards (13 per deck).
     ///
     /// # Examples
     ///
@@ -539,7 +746,32 @@ impl Foundations {
     pub fn is_complete(&self) -> bool {
         self.heights
             .iter()
-            .all(|&height| height == FOUNDATION_CAPACITY)
+            .all(|&height| height == self.pile_capacity())
+    }
+
+    /// The suits whose piles are complete, in pile order.
+    ///
+    /// A pile is complete once it holds [`pile_capacity`](Self::pile_capacity) cards, so
+    /// in multi-deck games the first King does not complete it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::foundations::Foundations;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    ///
+    /// let mut foundations = Foundations::new();
+    /// for rank in 1..=13u8 {
+    ///     foundations.place_card(Card::new(Rank::try_from(rank).unwrap(), Suit::Clubs)).unwrap();
+    /// }
+    /// foundations.place_card(Card::new(Rank::Ace, Suit::Hearts)).unwrap();
+    /// assert_eq!(foundations.completed_suits(), vec![Suit::Clubs]);
+    /// ```
+    pub fn completed_suits(&self) -> Vec<Suit> {
+        (0..FOUNDATION_COUNT)
+            .filter(|&pile| self.heights[pile] == self.pile_capacity())
+            .filter_map(|pile| self.piles[pile][0].map(|card| card.suit()))
+            .collect()
     }
 
     /// Validates if a card can be legally placed on a foundation pile according to FreeCell rules
@@ -548,7 +780,8 @@ impl Foundations {
     /// # Rules checked:
     /// - Only Aces can be placed on empty piles
     /// - Cards must be same suit and one rank higher than the top card
-    /// - Cannot add to a pile that already has a King (complete pile)
+    /// - With more than one deck, a King is followed by the same suit's Ace
+    /// - Cannot add to a pile that already has its last King (complete pile)
     ///
     /// # Errors
     ///
@@ -575,10 +808,20 @@ impl Foundations {
         if let Some(top_card) = self.get_card(location)? {
             // Check if pile is already complete
             if top_card.rank() == Rank::King {
-                return Err(FoundationError::PileComplete {
-                    pile_index: location.index(),
-                    new_card: *card,
-                });
+                if height >= self.pile_capacity() {
+                    return Err(FoundationError::PileComplete {
+                        pile_index: location.index(),
+                        new_card: *card,
+                    });
+                }
+                // Multi-deck piles start over at the Ace of the same suit
+                if card.suit() != top_card.suit() || card.rank() != Rank::Ace {
+                    return Err(FoundationError::InvalidSequence {
+                        top_card: *top_card,
+                        new_card: *card,
+                    });
+                }
+                return Ok(());
             }
 
             // Check if card follows sequence rules
@@ -692,6 +935,58 @@ mod tests {
         assert_eq!(top_card.suit(), card.suit());
     }
 
+    #[test]
+    fn needed_cards_track_each_suit() {
+        let mut foundations = Foundations::new();
+        let location = FoundationLocation::new(2).unwrap();
+        for rank in 1..=13u8 {
+            foundations
+                .place_card_at(location, Card::new(Rank::try_from(rank).unwrap(), Suit::Diamonds))
+                .unwrap();
+        }
+        let diamonds = Suit::Diamonds.foundation_index() as usize;
+        assert_eq!(foundations.needed_cards()[diamonds], None);
+
+        let mut double = Foundations::with_decks(2).unwrap();
+        for rank in 1..=13u8 {
+            double
+                .place_card_at(location, Card::new(Rank::try_from(rank).unwrap(), Suit::Diamonds))
+                .unwrap();
+        }
+        assert_eq!(double.needed_cards()[diamonds], Some(Card::new(Rank::Ace, Suit::Diamonds)));
+    }
+
+    #[test]
+    fn capacity_and_pile_suit_follow_the_deck_count() {
+        let mut double = Foundations::with_decks(2).unwrap();
+        assert_eq!((double.pile_capacity(), double.capacity()), (26, 104));
+        let third = FoundationLocation::new(2).unwrap();
+        double.place_card_at(third, Card::new(Rank::Ace, Suit::Hearts)).unwrap();
+        let suits: Vec<Option<Suit>> =
+            (0..FOUNDATION_COUNT as u8).map(|pile| double.pile_suit(FoundationLocation::new(pile).unwrap())).collect();
+        assert_eq!(suits, [None, None, Some(Suit::Hearts), None]);
+    }
+
+    #[test]
+    fn double_deck_piles_start_over_after_king() {
+        let mut foundations = Foundations::with_decks(2).unwrap();
+        let location = FoundationLocation::new(1).unwrap();
+        for _ in 0..2 {
+            for rank in 1..=13u8 {
+                let card = Card::new(Rank::try_from(rank).unwrap(), Suit::Spades);
+                foundations.place_card_at(location, card).unwrap();
+            }
+        }
+        assert_eq!(foundations.height(location), 26);
+
+        let ace = Card::new(Rank::Ace, Suit::Spades);
+        assert_eq!(
+            foundations.place_card_at(location, ace),
+            Err(FoundationError::PileComplete { pile_index: 1, new_card: ace })
+        );
+        assert!(Foundations::with_decks(3).is_err());
+    }
+
     #[test]
     fn can_build_foundation_stack() {
         let mut foundations = Foundations::new();
@@ -717,6 +1012,42 @@ mod tests {
     }
 
     // Note: With FoundationLocation, out-of-bounds errors are prevented at compile time.
+    #[test]
+    fn place_card_at_no_checks_matches_checked_placement() {
+        let location = FoundationLocation::new(2).unwrap();
+        let mut checked = Foundations::new();
+        let mut raw = Foundations::new();
+        for rank in [Rank::Ace, Rank::Two, Rank::Three] {
+            let card = Card::new(rank, Suit::Diamonds);
+            checked.place_card_at(location, card).unwrap();
+            raw.place_card_at_no_checks(location, card);
+        }
+        assert_eq!(raw, checked);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "raw foundation placement")]
+    fn place_card_at_no_checks_asserts_sequence_in_debug() {
+        let mut foundations = Foundations::new();
+        foundations.place_card_at_no_checks(
+            FoundationLocation::new(0).unwrap(),
+            Card::new(Rank::Five, Suit::Clubs),
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "foundation pile 0 is already complete")]
+    fn place_card_at_no_checks_rejects_a_complete_pile_in_debug() {
+        let location = FoundationLocation::new(0).unwrap();
+        let mut foundations = Foundations::new();
+        for rank in 1..=13 {
+            foundations.place_card_at(location, Card::new(Rank::try_from(rank).unwrap(), Suit::Hearts)).unwrap();
+        }
+        foundations.place_card_at_no_checks(location, Card::new(Rank::King, Suit::Hearts));
+    }
+
     #[test]
     fn foundation_location_prevents_out_of_bounds() {
         let mut foundations = Foundations::new();
@@ -51,10 +51,14 @@
 
 use crate::card::Card;
 use crate::location::FreecellLocation;
+use crate::rules::RulesProfile;
 use std::fmt;
 
 /// The number of free cells in a standard FreeCell game.
-pub const FREECELL_COUNT: usize = 4;
+pub const FREECELL_COUNT: usize = RulesProfile::STANDARD.freecells();
+
+/// The most free cells any supported variant uses (Double FreeCell has 6).
+pub const MAX_FREECELLS: usize = RulesProfile::DOUBLE_FREECELL.freecells();
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Represents the free cells where individual cards can be stored.
@@ -85,7 +89,10 @@ pub const FREECELL_COUNT: usize = 4;
 /// freecells.place_card_at(location, card).unwrap();
 /// ```
 pub struct FreeCells {
-    cells: [Option<Card>; FREECELL_COUNT],
+    cells: [Option<Card>; MAX_FREECELLS],
+    // Number of usable cells, counted from index 0. Variants with fewer freecells
+    // leave the cells at and above this index permanently unavailable.
+    capacity: u8,
 }
 
 impl Default for FreeCells {
@@ -108,8 +115,78 @@ impl FreeCells {
     /// ```
     pub fn new() -> Self {
         Self {
-            cells: [None; FREECELL_COUNT],
+            cells: [None; MAX_FREECELLS],
+            capacity: FREECELL_COUNT as u8,
+        }
+    }
+
+    /// Create a set of freecells where only the first `capacity` cells are usable.
+    ///
+    /// This supports the common difficulty ladder of playing a deal with 3, 2, 1 or
+    /// even 0 freecells, and variants with more than four (Double FreeCell uses 6).
+    /// Cells at index `capacity` and above reject every card.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FreeCellError::InvalidCapacity` if `capacity` exceeds `MAX_FREECELLS`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::freecells::FreeCells;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::FreecellLocation;
+    ///
+    /// let mut freecells = FreeCells::with_capacity(2).unwrap();
+    /// assert_eq!(freecells.empty_cells_count(), 2);
+    ///
+    /// let blocked = FreecellLocation::new(3).unwrap();
+    /// assert!(freecells.place_card_at(blocked, Card::new(Rank::Ace, Suit::Spades)).is_err());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Result<Self, FreeCellError> {
+        if capacity > MAX_FREECELLS {
+            return Err(FreeCellError::InvalidCapacity(capacity));
+        }
+        Ok(Self {
+            cells: [None; MAX_FREECELLS],
+            capacity: capacity as u8,
+        })
+    }
+
+    /// The number of usable freecells (4 in standard FreeCell).
+    pub fn capacity(&self) -> usize {
+        self.capacity as usize
+    }
+
+    /// The number of cell slots on the board: the standard four, or the capacity when a
+    /// variant has more. Slots beyond the capacity are shown but unusable.
+    pub fn slot_count(&self) -> usize {
+        self.capacity().max(FREECELL_COUNT)
+    }
+
+    /// Whether `location` is usable under the current capacity.
+    pub fn is_available(&self, location: FreecellLocation) -> bool {
+        location.index() < self.capacity
+    }
+
+    /// Change the number of usable freecells.
+    ///
+    /// # Errors
+    ///
+    /// - `FreeCellError::InvalidCapacity` if `capacity` exceeds `MAX_FREECELLS`
+    /// - `FreeCellError::CellUnavailable` if a card sits in a cell the new capacity would block
+    pub fn set_capacity(&mut self, capacity: usize) -> Result<(), FreeCellError> {
+        if capacity > MAX_FREECELLS {
+            return Err(FreeCellError::InvalidCapacity(capacity));
         }
+        if let Some((index, _)) = self.occupied_cells().find(|(index, _)| *index >= capacity) {
+            return Err(FreeCellError::CellUnavailable {
+                cell_index: index as u8,
+                capacity: capacity as u8,
+            });
+        }
+        self.capacity = capacity as u8;
+        Ok(())
     }
 
     /// Place a card in the first available empty freecell automatically.
@@ -141,7 +218,8 @@ impl FreeCells {
     /// assert_eq!(freecells.get_card(location).unwrap(), Some(&card));
     /// ```
     pub fn place_card(&mut self, card: Card) -> Result<FreecellLocation, FreeCellError> {
-        for (idx, cell) in self.cells.iter_mut().enumerate() {
+        let capacity = self.capacity as usize;
+        for (idx, cell) in self.cells[..capacity].iter_mut().enumerate() {
             if cell.is_none() {
                 *cell = Some(card);
                 return Ok(FreecellLocation::new(idx as u8).unwrap());
@@ -190,11 +268,13 @@ impl FreeCells {
     /// Does not modify any state - only provides validation.
     ///
     /// # Rules checked:
+    /// - The cell must be usable under the current capacity
     /// - The cell must be empty
     ///
     /// # Errors
     ///
-    /// Returns `FreeCellError::CellOccupied` if the cell already contains a card.
+    /// - `FreeCellError::CellUnavailable` if the cell is beyond the current capacity
+    /// - `FreeCellError::CellOccupied` if the cell already contains a card
     ///
     /// # Examples
     ///
@@ -222,6 +302,12 @@ impl FreeCells {
         location: FreecellLocation,
         card: &Card,
     ) -> Result<(), FreeCellError> {
+        if !self.is_available(location) {
+            return Err(FreeCellError::CellUnavailable {
+                cell_index: location.index(),
+                capacity: self.capacity,
+            });
+        }
         if let Some(existing_card) = self.cells[location.index() as usize] {
             return Err(FreeCellError::CellOccupied {
                 cell_index: location.index(),
@@ -309,7 +395,7 @@ impl FreeCells {
     /// assert_eq!(freecells.empty_cells_count(), 3);
     /// ```
     pub fn empty_cells_count(&self) -> usize {
-        self.cells.iter().filter(|c| c.is_none()).count()
+        self.cells[..self.capacity as usize].iter().filter(|c| c.is_none()).count()
     }
 
     // is_cell_empty was removed in favor of using get_card().is_none()
@@ -378,13 +464,19 @@ impl FreeCells {
     ) where
         F: Fn(&Card) -> u8,
     {
+        debug_assert!(
+            self.occupied_cells().count() <= FREECELL_COUNT,
+            "canonical packing only supports the standard four freecells"
+        );
         // Collect freecell data efficiently, using 255 as temporary empty value for sorting
+        let mut all_cells = [255u8; MAX_FREECELLS];
         for (i, cell) in self.cells.iter().enumerate() {
-            freecell_data[i] = cell.as_ref().map_or(255, &pack_card_fn);
+            all_cells[i] = cell.as_ref().map_or(255, &pack_card_fn);
         }
         
         // Sort for canonical ordering (empty values 255 go to end)
-        freecell_data.sort_unstable();
+        all_cells.sort_unstable();
+        freecell_data.copy_from_slice(&all_cells[..FREECELL_COUNT]);
     }
 }
 
@@ -409,6 +501,7 @@ impl FreeCells {
 /// let result = freecells.place_card_at(location, Card::new(Rank::Two, Suit::Hearts));
 /// assert!(matches!(result, Err(FreeCellError::CellOccupied { .. })));
 /// ```
+#[non_exhaustive]
 pub enum FreeCellError {
     /// Attempted to access an invalid cell index.
     InvalidCell(u8),
@@ -420,6 +513,10 @@ pub enum FreeCellError {
     },
     /// Attempted to place a card but all cells are full.
     NoEmptyCells,
+    /// Attempted to use a cell beyond the configured number of freecells.
+    CellUnavailable { cell_index: u8, capacity: u8 },
+    /// Requested more freecells than the board has.
+    InvalidCapacity(usize),
 }
 
 impl std::fmt::Display for FreeCellError {
@@ -436,6 +533,16 @@ impl std::fmt::Display for FreeCellError {
                 new_card, cell_index, existing_card
             ),
             FreeCellError::NoEmptyCells => write!(f, "No empty freecells available"),
+            FreeCellError::CellUnavailable { cell_index, capacity } => write!(
+                f,
+                "Freecell {} is unavailable: only {} freecell(s) in play",
+                cell_index, capacity
+            ),
+            FreeCellError::InvalidCapacity(capacity) => write!(
+                f,
+                "Invalid freecell capacity {}: at most {} allowed",
+                capacity, MAX_FREECELLS
+            ),
         }
     }
 }
@@ -443,10 +550,11 @@ impl std::fmt::Display for FreeCellError {
 impl fmt::Display for FreeCells {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "FreeCells:")?;
-        for i in 0..FREECELL_COUNT {
+        for i in 0..self.slot_count() {
             let location = FreecellLocation::new(i as u8).unwrap();
             match self.get_card(location) {
                 Ok(Some(card)) => writeln!(f, "  Cell {}: {}", i, card)?,
+                Ok(None) if !self.is_available(location) => writeln!(f, "  Cell {}: Unavailable", i)?,
                 Ok(None) => writeln!(f, "  Cell {}: Empty", i)?,
                 Err(_) => writeln!(f, "  Cell {}: Invalid", i)?,
             }
@@ -658,4 +766,37 @@ mod tests {
         assert_eq!(freecells.remove_card(location).unwrap(), Some(card));
         assert_eq!(freecells.get_card(location).unwrap(), None);
     }
+
+    #[test]
+    fn reduced_capacity_blocks_higher_cells() {
+        let mut freecells = FreeCells::with_capacity(1).unwrap();
+        let card = Card::new(Rank::Ace, Suit::Spades);
+        let blocked = FreecellLocation::new(1).unwrap();
+
+        assert!(matches!(
+            freecells.place_card_at(blocked, card),
+            Err(FreeCellError::CellUnavailable { cell_index: 1, capacity: 1 })
+        ));
+        assert_eq!(freecells.place_card(card).unwrap().index(), 0);
+        assert!(matches!(
+            freecells.place_card(Card::new(Rank::Two, Suit::Spades)),
+            Err(FreeCellError::NoEmptyCells)
+        ));
+        assert_eq!(freecells.empty_cells_count(), 0);
+    }
+
+    #[test]
+    fn capacity_cannot_exceed_board_or_strand_cards() {
+        assert!(matches!(
+            FreeCells::with_capacity(MAX_FREECELLS + 1),
+            Err(FreeCellError::InvalidCapacity(7))
+        ));
+
+        let mut freecells = FreeCells::new();
+        let location = FreecellLocation::new(2).unwrap();
+        freecells.place_card_at(location, Card::new(Rank::Ace, Suit::Spades)).unwrap();
+        assert!(freecells.set_capacity(2).is_err());
+        assert!(freecells.set_capacity(3).is_ok());
+        assert_eq!(freecells.capacity(), 3);
+    }
 }
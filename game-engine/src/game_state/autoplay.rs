@@ -0,0 +1,173 @@
+//! Sending cards home automatically.
+//!
+//! Most FreeCell programs move cards to the foundations on the player's behalf, but they
+//! disagree on which ones: some only play cards nothing else can need, others play
+//! everything they can. [`GameState::auto_play_safe_moves`] plays foundation moves for
+//! as long as an [`AutoplayPolicy`] allows them.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::card::{Card, Rank, Suit};
+//! use freecell_game_engine::game_state::{AutoplayPolicy, GameStateBuilder};
+//! use freecell_game_engine::location::{Location, TableauLocation, FoundationLocation};
+//!
+//! let mut builder = GameStateBuilder::new();
+//! let spades = Location::Foundation(FoundationLocation::new(0).unwrap());
+//! builder.place(spades, Card::new(Rank::Ace, Suit::Spades)).unwrap();
+//! builder.place(spades, Card::new(Rank::Two, Suit::Spades)).unwrap();
+//! let column = TableauLocation::new(0).unwrap();
+//! builder.column(column, [Card::new(Rank::Three, Suit::Spades)]).unwrap();
+//! let game = builder.state().clone();
+//!
+//! // The red twos are not home yet, so a red two might still want the 3♠ to build on.
+//! assert!(game.clone().auto_play_safe_moves(AutoplayPolicy::Safe).is_empty());
+//! assert_eq!(game.clone().auto_play_safe_moves(AutoplayPolicy::Aggressive).len(), 1);
+//! ```
+
+use super::GameState;
+use crate::card::{Card, Suit};
+use crate::location::FoundationLocation;
+use crate::r#move::Move;
+
+/// Which foundation moves [`GameState::auto_play_safe_moves`] makes by itself.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum AutoplayPolicy {
+    /// Never move cards automatically.
+    None,
+    /// Only cards no tableau card can still need: Aces and Twos always, anything else
+    /// once both foundations of the other colour hold the rank below it.
+    #[default]
+    Safe,
+    /// Every card that can legally go home.
+    Aggressive,
+    /// Cards for which the function returns true, asked only about cards that can
+    /// legally go home in the given position.
+    Custom(fn(&GameState, &Card) -> bool),
+}
+
+impl AutoplayPolicy {
+    /// Whether this policy sends `card` home in `game`. The move itself is assumed legal.
+    pub fn allows(&self, game: &GameState, card: &Card) -> bool {
+        match self {
+            AutoplayPolicy::None => false,
+            AutoplayPolicy::Safe => is_safe(game, card),
+            AutoplayPolicy::Aggressive => true,
+            AutoplayPolicy::Custom(allows) => allows(game, card),
+        }
+    }
+}
+
+/// The safe rule, counted in cards home rather than ranks so that a second deck's
+/// piles are judged the same way as the first.
+fn is_safe(game: &GameState, card: &Card) -> bool {
+    let home = |suit: Suit| game.foundations.pile_cards(FoundationLocation::new(suit.foundation_index()).unwrap()).count();
+    // Cards home in this suit once `card` has joined them.
+    let height = home(card.suit()) + 1;
+    let is_ace_or_two = (height - 1) % 13 < 2;
+    is_ace_or_two
+        || [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs]
+            .into_iter()
+            .filter(|suit| suit.color() != card.color())
+            .all(|suit| home(suit) >= height - 1)
+}
+
+impl GameState {
+    /// Whether `card` can go home now and the [`Safe`](AutoplayPolicy::Safe) rule would
+    /// send it: it is on top of a column or in a freecell, it is next on its foundation,
+    /// and no tableau card can still need it. For UIs that send a single card home, say
+    /// the one the player double-clicked, without running the rest of the autoplay.
+    pub fn is_safe_to_foundation(&self, card: &Card) -> bool {
+        let mut moves = Vec::new();
+        self.get_tableau_to_foundation_moves(&mut moves);
+        self.get_freecell_to_foundation_moves(&mut moves);
+        moves.iter().any(|m| self.get_card(m.source).ok().flatten() == Some(card)) && is_safe(self, card)
+    }
+
+    /// Plays foundation moves the policy allows until there are none left, and returns
+    /// them in the order played. Each move can expose a card that becomes playable in
+    /// turn; tableau cards are tried before freecell cards.
+    pub fn auto_play_safe_moves(&mut self, policy: AutoplayPolicy) -> Vec<Move> {
+        let mut played = Vec::new();
+        let mut moves = Vec::new();
+        loop {
+            moves.clear();
+            self.get_tableau_to_foundation_moves(&mut moves);
+            self.get_freecell_to_foundation_moves(&mut moves);
+            let next = moves.iter().copied().find(|m| {
+                let card = self.get_card(m.source).ok().flatten();
+                card.is_some_and(|card| policy.allows(self, card))
+            });
+            let Some(m) = next else { break };
+            self.execute_move(&m).expect("generated foundation moves are legal");
+            played.push(m);
+        }
+        played
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Rank;
+    use crate::game_state::GameStateBuilder;
+    use crate::generation::generate_deal;
+    use crate::location::{Location, TableauLocation};
+
+    /// Aces home and one column holding, from bottom to top, `cards`.
+    fn with_aces_home(cards: &[Card]) -> GameState {
+        let mut builder = GameStateBuilder::new();
+        for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
+            let pile = Location::Foundation(FoundationLocation::new(suit.foundation_index()).unwrap());
+            builder.place(pile, Card::new(Rank::Ace, suit)).unwrap();
+        }
+        builder.column(TableauLocation::new(0).unwrap(), cards.iter().copied()).unwrap();
+        builder.state().clone()
+    }
+
+    #[test]
+    fn safe_plays_cards_nothing_can_need() {
+        // 3♥ waits for both black twos, even once its own two is home.
+        let [two_s, two_c, two_h, three_h] = [(Rank::Two, Suit::Spades), (Rank::Two, Suit::Clubs), (Rank::Two, Suit::Hearts), (Rank::Three, Suit::Hearts)]
+            .map(|(rank, suit)| Card::new(rank, suit));
+        let mut game = with_aces_home(&[two_s, three_h, two_h]);
+        assert_eq!(game.auto_play_safe_moves(AutoplayPolicy::Safe).len(), 1);
+
+        let mut game = with_aces_home(&[three_h, two_s, two_h, two_c]);
+        let played = game.auto_play_safe_moves(AutoplayPolicy::Safe);
+        assert_eq!(played.len(), 4);
+        assert_eq!(game.tableau().get_column(0).unwrap(), &[] as &[Card]);
+    }
+
+    #[test]
+    fn policies_differ_only_in_what_they_allow() {
+        let cards = [Card::new(Rank::Three, Suit::Hearts), Card::new(Rank::Two, Suit::Hearts)];
+        assert_eq!(with_aces_home(&cards).auto_play_safe_moves(AutoplayPolicy::None).len(), 0);
+        assert_eq!(with_aces_home(&cards).auto_play_safe_moves(AutoplayPolicy::Safe).len(), 1);
+        assert_eq!(with_aces_home(&cards).auto_play_safe_moves(AutoplayPolicy::Aggressive).len(), 2);
+
+        fn hearts_only(_: &GameState, card: &Card) -> bool {
+            card.suit() == Suit::Hearts
+        }
+        assert_eq!(with_aces_home(&cards).auto_play_safe_moves(AutoplayPolicy::Custom(hearts_only)).len(), 2);
+
+        let mut deal = generate_deal(1).unwrap();
+        assert!(deal.auto_play_safe_moves(AutoplayPolicy::Aggressive).is_empty());
+    }
+
+    #[test]
+    fn single_cards_are_safe_only_when_playable_and_unneeded() {
+        let [two_h, three_h, two_s] =
+            [(Rank::Two, Suit::Hearts), (Rank::Three, Suit::Hearts), (Rank::Two, Suit::Spades)].map(|(rank, suit)| Card::new(rank, suit));
+        let game = with_aces_home(&[two_s, three_h, two_h]);
+        assert!(game.is_safe_to_foundation(&two_h));
+        // Buried, and not yet next on their foundations.
+        assert!(!game.is_safe_to_foundation(&three_h));
+        assert!(!game.is_safe_to_foundation(&two_s));
+
+        // On top and next once 2♥ is home, but the black twos may still want it.
+        let mut game = game;
+        game.execute_move(&Move::tableau_to_foundation(0, 1).unwrap()).unwrap();
+        assert!(!game.is_safe_to_foundation(&three_h));
+    }
+}
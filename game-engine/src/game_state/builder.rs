@@ -0,0 +1,389 @@
+//! Incremental construction of arbitrary positions.
+//!
+//! [`GameStateBuilder`] lets a caller assign cards to tableau columns, freecells and
+//! foundation piles one at a time, which is what a board editor or an importer needs.
+//! Tableau columns accept cards in any order (dealt columns are not sequences), freecells
+//! must be empty, and foundation piles still follow their Ace-to-King rules. No card may
+//! be placed more often than there are decks. [`GameStateBuilder::build`] then runs the
+//! full integrity check so the resulting [`GameState`] always holds every card.
+//!
+//! Tutorial puzzles often play with only part of the deck, say two suits.
+//! [`GameStateBuilder::partial_deal`] builds such a position: its integrity check only
+//! rules out duplicates and cards that could never go home, and it is won once every card
+//! it holds is on the foundations.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::card::{Card, Rank, Suit};
+//! use freecell_game_engine::game_state::{GameStateBuilder, IntegrityError, GameError};
+//! use freecell_game_engine::location::{Location, FoundationLocation};
+//!
+//! let mut builder = GameStateBuilder::new();
+//! let pile = Location::Foundation(FoundationLocation::new(0).unwrap());
+//! builder.place(pile, Card::new(Rank::Ace, Suit::Spades)).unwrap();
+//!
+//! // The other 51 cards are still missing, so the position cannot be built yet.
+//! assert!(matches!(
+//!     builder.build(),
+//!     Err(GameError::Integrity(IntegrityError::MissingCards(_)))
+//! ));
+//! ```
+
+use super::{GameError, GameState, IntegrityError};
+use crate::card::Card;
+use crate::location::{Location, TableauLocation};
+use crate::tableau::{TableauError, MAX_COLUMN_HEIGHT};
+use crate::variant::Variant;
+
+/// Builds a [`GameState`] card by card.
+///
+/// Each placement is checked immediately so an interactive caller can report the
+/// problem next to the action that caused it; whole-position checks happen in
+/// [`build`](Self::build).
+#[derive(Debug, Clone, Default)]
+pub struct GameStateBuilder {
+    state: GameState,
+}
+
+impl GameStateBuilder {
+    /// Creates a builder for an empty standard board.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder for an empty board laid out for `variant`.
+    pub fn for_variant(variant: Variant) -> Self {
+        Self {
+            state: GameState::with_variant(variant),
+        }
+    }
+
+    /// Places `card` at `location`.
+    ///
+    /// Tableau cards are pushed on top of the column without sequence checks.
+    ///
+    /// # Errors
+    ///
+    /// - [`GameError::Integrity`] with [`IntegrityError::DuplicateCard`] if every copy of
+    ///   the card is already placed
+    /// - [`GameError::Tableau`] if the column is not in play on this board, or already
+    ///   holds [`MAX_COLUMN_HEIGHT`] cards
+    /// - [`GameError::FreeCell`] if the freecell is occupied
+    /// - [`GameError::Foundation`] if the card does not continue the foundation pile
+    /// - [`GameError::FoundationOverflow`] if the foundation pile is already complete
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::game_state::GameStateBuilder;
+    /// use freecell_game_engine::location::{Location, TableauLocation};
+    ///
+    /// let column = Location::Tableau(TableauLocation::new(0).unwrap());
+    /// let mut builder = GameStateBuilder::new();
+    /// builder
+    ///     .place(column, Card::new(Rank::Two, Suit::Hearts)).unwrap()
+    ///     .place(column, Card::new(Rank::King, Suit::Hearts)).unwrap();
+    ///
+    /// // Placing the same card again is rejected.
+    /// assert!(builder.place(column, Card::new(Rank::Two, Suit::Hearts)).is_err());
+    /// ```
+    pub fn place(&mut self, location: Location, card: Card) -> Result<&mut Self, GameError> {
+        let decks = self.state.foundations.decks();
+        let first = if decks == 1 {
+            self.state.find_card(&card)
+        } else {
+            let mut copies = self.state.iter_cards().filter(|(placed, _)| *placed == card);
+            copies
+                .next()
+                .filter(|_| 1 + copies.count() >= decks)
+                .map(|(_, first)| first)
+        };
+        if let Some(first) = first {
+            return Err(GameError::Integrity(IntegrityError::DuplicateCard {
+                card,
+                first,
+                second: location,
+            }));
+        }
+        match location {
+            Location::Tableau(column) if column.index() as usize >= self.state.tableau.column_count() => {
+                return Err(GameError::Tableau {
+                    error: TableauError::InvalidColumn(column.index()),
+                    attempted_move: None,
+                    operation: "build_position",
+                });
+            }
+            Location::Tableau(column) if self.state.tableau.column_length(column).unwrap_or(0) >= MAX_COLUMN_HEIGHT => {
+                return Err(GameError::Tableau {
+                    error: TableauError::ColumnFull(column.index()),
+                    attempted_move: None,
+                    operation: "build_position",
+                });
+            }
+            Location::Tableau(column) => self.state.tableau.place_card_at_no_checks(column, card),
+            Location::Freecell(cell) => self
+                .state
+                .freecells
+                .place_card_at(cell, card)
+                .map_err(|error| GameError::FreeCell {
+                    error,
+                    attempted_move: None,
+                    operation: "build_position",
+                })?,
+            Location::Foundation(pile) => self
+                .state
+                .foundations
+                .place_card_at(pile, card)
+                .map_err(|error| GameError::foundation(error, None, "build_position"))?,
+        }
+        self.state.note_card_location(&card, Some(location));
+        Ok(self)
+    }
+
+    /// Makes the position a partial deal, played with only the cards placed on it.
+    ///
+    /// [`build`](Self::build) then accepts a position without the full deck, as long as
+    /// no card is placed twice and each card it holds comes with the card below it in its
+    /// suit. The built game is won once every card it holds is home.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::game_state::GameStateBuilder;
+    /// use freecell_game_engine::location::TableauLocation;
+    /// use freecell_game_engine::Move;
+    ///
+    /// let column = TableauLocation::new(0).unwrap();
+    /// let mut builder = GameStateBuilder::new();
+    /// builder.partial_deal().column(column, [Card::new(Rank::Ace, Suit::Spades)]).unwrap();
+    /// let mut game = builder.build().unwrap();
+    ///
+    /// assert!(!game.is_won().unwrap());
+    /// game.execute_move(&Move::tableau_to_foundation(0, 0).unwrap()).unwrap();
+    /// assert!(game.is_won().unwrap());
+    /// ```
+    pub fn partial_deal(&mut self) -> &mut Self {
+        self.state.partial_deal = true;
+        self
+    }
+
+    /// Places `cards` on a tableau column, bottom card first.
+    ///
+    /// Stops at the first card that cannot be placed; cards before it stay placed.
+    pub fn column<I>(&mut self, column: TableauLocation, cards: I) -> Result<&mut Self, GameError>
+    where
+        I: IntoIterator<Item = Card>,
+    {
+        for card in cards {
+            self.place(Location::Tableau(column), card)?;
+        }
+        Ok(self)
+    }
+
+    /// Removes and returns the top card at `location`, if any.
+    pub fn remove(&mut self, location: Location) -> Result<Option<Card>, GameError> {
+        let removed = match location {
+            Location::Tableau(column) => {
+                self.state
+                    .tableau
+                    .remove_card(column)
+                    .map_err(|error| GameError::Tableau {
+                        error,
+                        attempted_move: None,
+                        operation: "build_position",
+                    })
+            }
+            Location::Freecell(cell) => {
+                self.state
+                    .freecells
+                    .remove_card(cell)
+                    .map_err(|error| GameError::FreeCell {
+                        error,
+                        attempted_move: None,
+                        operation: "build_position",
+                    })
+            }
+            Location::Foundation(pile) => {
+                self.state
+                    .foundations
+                    .remove_card(pile)
+                    .map_err(|error| GameError::Foundation {
+                        error,
+                        attempted_move: None,
+                        operation: "build_position",
+                    })
+            }
+        }?;
+        if let Some(card) = &removed {
+            self.state.note_card_location(card, None);
+        }
+        Ok(removed)
+    }
+
+    /// The position as built so far, which may not yet hold the full deck.
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Returns the finished position after checking that it holds all 52 cards exactly once,
+    /// or for a [partial deal](Self::partial_deal) that its cards can all be built home.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::Integrity`] if [`GameState::check_integrity`] fails.
+    pub fn build(&self) -> Result<GameState, GameError> {
+        self.state.check_integrity()?;
+        Ok(self.state.clone())
+    }
+}
+
+impl From<GameState> for GameStateBuilder {
+    /// Starts editing from an existing position.
+    fn from(state: GameState) -> Self {
+        Self { state }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Rank, Suit};
+    use crate::foundations::FoundationError;
+    use crate::freecells::FreeCellError;
+    use crate::generation::generate_deal;
+    use crate::location::{FoundationLocation, FreecellLocation};
+    use crate::r#move::Move;
+    use crate::rules::WinCondition;
+
+    #[test]
+    fn rebuilding_a_deal_card_by_card_gives_the_same_state() {
+        let deal = generate_deal(164).unwrap();
+        let mut builder = GameStateBuilder::new();
+        for (index, cards) in deal.tableau().columns().enumerate() {
+            builder
+                .column(TableauLocation::new(index as u8).unwrap(), cards.iter().copied())
+                .unwrap();
+        }
+        assert_eq!(builder.build().unwrap(), deal);
+    }
+
+    #[test]
+    fn edited_deal_stays_valid_when_a_card_is_moved() {
+        let mut builder = GameStateBuilder::from(generate_deal(1).unwrap());
+        let column = Location::Tableau(TableauLocation::new(3).unwrap());
+        let card = builder.remove(column).unwrap().unwrap();
+        assert!(builder.build().is_err());
+
+        builder
+            .place(Location::Freecell(FreecellLocation::new(0).unwrap()), card)
+            .unwrap();
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn two_suit_partial_deal_is_won_with_its_own_cards() {
+        let hearts: Vec<Card> = (1..=13).map(|rank| Card::new(Rank::try_from(rank).unwrap(), Suit::Hearts)).collect();
+        let mut builder = GameStateBuilder::new();
+        builder.partial_deal();
+        builder.column(TableauLocation::new(0).unwrap(), hearts.iter().rev().copied()).unwrap();
+        builder
+            .place(Location::Freecell(FreecellLocation::new(0).unwrap()), Card::new(Rank::Ace, Suit::Spades))
+            .unwrap();
+        let mut game = builder.build().unwrap();
+        assert!(game.is_partial_deal());
+        assert!(!game.is_won().unwrap());
+
+        // The spade in the freecell keeps the game from finishing itself until it goes home.
+        assert_eq!(game.play_auto_finish().len(), 0);
+        let spade_home = Move::freecell_to_foundation(0, 0).unwrap();
+        assert_eq!(game.execute_move_with_events(&spade_home).unwrap(), Vec::new());
+        assert_eq!(game.play_auto_finish().len(), 13);
+        assert!(game.is_won().unwrap());
+        assert_eq!(game.normalize().0.rules().win_condition(), WinCondition::PresentCardsHome);
+    }
+
+    #[test]
+    fn occupied_freecell_is_rejected() {
+        let cell = Location::Freecell(FreecellLocation::new(0).unwrap());
+        let mut builder = GameStateBuilder::new();
+        builder.place(cell, Card::new(Rank::Ace, Suit::Clubs)).unwrap();
+
+        let result = builder.place(cell, Card::new(Rank::Two, Suit::Clubs));
+        assert!(matches!(
+            result,
+            Err(GameError::FreeCell { error: FreeCellError::CellOccupied { .. }, .. })
+        ));
+    }
+
+    #[test]
+    fn foundation_rules_still_apply() {
+        let pile = Location::Foundation(FoundationLocation::new(0).unwrap());
+        let mut builder = GameStateBuilder::new();
+
+        let result = builder.place(pile, Card::new(Rank::Two, Suit::Clubs));
+        assert!(matches!(
+            result,
+            Err(GameError::Foundation { error: FoundationError::NonAceOnEmptyPile { .. }, .. })
+        ));
+    }
+
+    #[test]
+    fn columns_outside_the_layout_are_rejected() {
+        let column = Location::Tableau(TableauLocation::new(8).unwrap());
+        let card = Card::new(Rank::Five, Suit::Hearts);
+
+        let result = GameStateBuilder::new().place(column, card).map(|_| ());
+        assert!(matches!(
+            result,
+            Err(GameError::Tableau { error: TableauError::InvalidColumn(8), .. })
+        ));
+        assert!(GameStateBuilder::for_variant(Variant::DoubleFreecell).place(column, card).is_ok());
+    }
+
+    #[test]
+    fn columns_stop_at_the_height_limit() {
+        let column = TableauLocation::new(0).unwrap();
+        let deck = [Suit::Spades, Suit::Hearts].into_iter().flat_map(|suit| {
+            (1..=13).map(move |rank| Card::new(Rank::try_from(rank).unwrap(), suit))
+        });
+        let mut builder = GameStateBuilder::new();
+        let result = builder.column(column, deck).map(|_| ());
+        assert!(matches!(
+            result,
+            Err(GameError::Tableau { error: TableauError::ColumnFull(0), .. })
+        ));
+        assert_eq!(builder.state().tableau().column_length(column), Ok(MAX_COLUMN_HEIGHT));
+    }
+
+    #[test]
+    fn double_freecell_allows_two_copies() {
+        let card = Card::new(Rank::Queen, Suit::Diamonds);
+        let first = Location::Tableau(TableauLocation::new(0).unwrap());
+        let third = Location::Tableau(TableauLocation::new(9).unwrap());
+        let mut builder = GameStateBuilder::for_variant(Variant::DoubleFreecell);
+        builder.place(first, card).unwrap();
+        builder.place(Location::Tableau(TableauLocation::new(1).unwrap()), card).unwrap();
+
+        assert_eq!(
+            builder.place(third, card).unwrap_err(),
+            GameError::Integrity(IntegrityError::DuplicateCard { card, first, second: third })
+        );
+    }
+
+    #[test]
+    fn duplicate_placement_is_rejected() {
+        let card = Card::new(Rank::Queen, Suit::Diamonds);
+        let first = Location::Tableau(TableauLocation::new(0).unwrap());
+        let second = Location::Tableau(TableauLocation::new(1).unwrap());
+        let mut builder = GameStateBuilder::new();
+        builder.place(first, card).unwrap();
+
+        assert_eq!(
+            builder.place(second, card).unwrap_err(),
+            GameError::Integrity(IntegrityError::DuplicateCard { card, first, second })
+        );
+    }
+}
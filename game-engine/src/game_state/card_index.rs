@@ -0,0 +1,218 @@
+//! Optional card-to-location index for `GameState`.
+//!
+//! Answering "where is the 5 of Diamonds?" normally means scanning the tableau,
+//! freecells and foundations. Callers that ask this often (heuristics, renderers) can
+//! turn on a 52-entry index with [`GameState::enable_card_index`]; it is then kept up to
+//! date by `execute_move`, `undo_move` and [`GameStateBuilder`](super::GameStateBuilder),
+//! and [`GameState::find_card`] becomes a single array lookup.
+//!
+//! The index is a cache: it is not part of the state's equality or hash, and it is
+//! off by default so states cloned in bulk by a solver stay small. Multi-deck games hold
+//! two copies of every card, so they never use the index and `find_card` always scans.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::card::{Card, Rank, Suit};
+//! use freecell_game_engine::generation::generate_deal;
+//! use freecell_game_engine::location::Location;
+//! use freecell_game_engine::Move;
+//!
+//! let mut game = generate_deal(1).unwrap();
+//! game.enable_card_index();
+//!
+//! let mv = Move::tableau_to_freecell(0, 0).unwrap();
+//! let card = *game.get_card(mv.source).unwrap().unwrap();
+//! game.execute_move(&mv).unwrap();
+//! assert_eq!(game.find_card(&card), Some(mv.destination));
+//! ```
+
+use super::GameState;
+use crate::card::{Card, Rank, Suit};
+use crate::location::Location;
+use crate::rules::RulesProfile;
+
+/// Number of distinct cards in a standard deck.
+pub(super) const DECK_SIZE: usize = RulesProfile::STANDARD.card_count();
+
+/// Maps a card to a unique slot in `0..DECK_SIZE`.
+pub(super) fn deck_index(card: &Card) -> usize {
+    card.suit().foundation_index() as usize * 13 + (card.rank() as usize - 1)
+}
+
+/// Inverse of [`deck_index`].
+pub(super) fn card_at_deck_index(index: usize) -> Card {
+    let suit = Suit::try_from((index / 13) as u8).expect("deck index in range");
+    let rank = Rank::try_from((index % 13) as u8 + 1).expect("deck index in range");
+    Card::new(rank, suit)
+}
+
+/// Location of each card, indexed by [`deck_index`].
+#[derive(Debug, Clone)]
+pub(super) struct CardIndex {
+    locations: [Option<Location>; DECK_SIZE],
+}
+
+impl CardIndex {
+    /// Builds the index by scanning `state` once.
+    fn build(state: &GameState) -> Self {
+        let mut locations = [None; DECK_SIZE];
+        for (card, location) in state.iter_cards() {
+            locations[deck_index(&card)] = Some(location);
+        }
+        Self { locations }
+    }
+
+    pub(super) fn get(&self, card: &Card) -> Option<Location> {
+        self.locations[deck_index(card)]
+    }
+
+    pub(super) fn set(&mut self, card: &Card, location: Option<Location>) {
+        self.locations[deck_index(card)] = location;
+    }
+}
+
+impl GameState {
+    /// Turns on the card location index, building it from the current position.
+    ///
+    /// Calling this when the index is already enabled rebuilds it. This does nothing
+    /// for multi-deck games, where a card is not identified by its rank and suit alone.
+    pub fn enable_card_index(&mut self) {
+        if self.foundations.decks() > 1 {
+            return;
+        }
+        self.card_index = Some(Box::new(CardIndex::build(self)));
+    }
+
+    /// Turns off the card location index and frees it.
+    pub fn disable_card_index(&mut self) {
+        self.card_index = None;
+    }
+
+    /// Whether [`find_card`](Self::find_card) is currently served by the index.
+    pub fn has_card_index(&self) -> bool {
+        self.card_index.is_some()
+    }
+
+    /// Returns where `card` currently is, or `None` if it is not in the position.
+    /// In multi-deck games this is the first copy found by [`iter_cards`](Self::iter_cards).
+    ///
+    /// This is O(1) when the card index is enabled and a full board scan otherwise.
+    /// Tableau locations name the column only; the card may be anywhere in it.
+    pub fn find_card(&self, card: &Card) -> Option<Location> {
+        match &self.card_index {
+            Some(index) => index.get(card),
+            None => self
+                .iter_cards()
+                .find(|(candidate, _)| candidate == card)
+                .map(|(_, location)| location),
+        }
+    }
+
+    /// Records that `card` is now at `location` (or gone, for `None`) if the index is enabled.
+    pub(super) fn note_card_location(&mut self, card: &Card, location: Option<Location>) {
+        if let Some(index) = self.card_index.as_mut() {
+            index.set(card, location);
+        }
+    }
+
+    /// With the `card-count-checks` feature, panics if the card just placed on foundation
+    /// `pile` after leaving `from` is still somewhere else: in the tableau or a freecell,
+    /// or recorded by the card index anywhere but `from`. A duplicate made by a raw
+    /// placement is then caught at the move that made it, not at win detection. Must
+    /// run before the index is told of the move.
+    #[cfg(feature = "card-count-checks")]
+    pub(super) fn check_foundation_card(&self, from: Location, pile: Location) {
+        if self.foundations.decks() > 1 {
+            return;
+        }
+        let Ok(Some(&card)) = self.get_card(pile) else {
+            panic!("nothing placed on foundation {:?}", pile);
+        };
+        if let Some(index) = &self.card_index {
+            let recorded = index.get(&card);
+            assert!(recorded == Some(from), "{} left {:?} for a foundation, but the card index has it at {:?}", card, from, recorded);
+        }
+        let elsewhere = self.iter_cards().find(|&(candidate, location)| candidate == card && !matches!(location, Location::Foundation(_)));
+        if let Some((_, location)) = elsewhere {
+            panic!("{} is on a foundation and also at {:?}", card, location);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::generate_deal;
+    use crate::r#move::Move;
+
+    fn assert_index_matches_scan(game: &GameState) {
+        let index = game.card_index.as_ref().expect("index enabled");
+        for i in 0..DECK_SIZE {
+            let card = card_at_deck_index(i);
+            let scanned = game
+                .iter_cards()
+                .find(|(candidate, _)| *candidate == card)
+                .map(|(_, location)| location);
+            assert_eq!(index.get(&card), scanned, "index disagrees for {}", card);
+        }
+    }
+
+    #[test]
+    fn deck_index_round_trips() {
+        for i in 0..DECK_SIZE {
+            assert_eq!(deck_index(&card_at_deck_index(i)), i);
+        }
+    }
+
+    #[test]
+    fn index_follows_execute_and_undo() {
+        let mut game = generate_deal(1).unwrap();
+        game.enable_card_index();
+        let mut played = Vec::new();
+        for _ in 0..20 {
+            let Some(mv) = game.get_available_moves().into_iter().next() else {
+                break;
+            };
+            game.execute_move(&mv).unwrap();
+            assert_index_matches_scan(&game);
+            played.push(mv);
+        }
+        assert!(!played.is_empty());
+        for mv in played.iter().rev() {
+            game.undo_move(mv);
+            assert_index_matches_scan(&game);
+        }
+    }
+
+    #[test]
+    fn failed_move_leaves_index_untouched() {
+        let mut game = generate_deal(1).unwrap();
+        game.enable_card_index();
+        let invalid = Move::freecell_to_tableau(0, 0).unwrap();
+        assert!(game.execute_move(&invalid).is_err());
+        assert_index_matches_scan(&game);
+    }
+
+    #[test]
+    fn index_does_not_affect_equality() {
+        let plain = generate_deal(5).unwrap();
+        let mut indexed = plain.clone();
+        indexed.enable_card_index();
+        assert_eq!(plain, indexed);
+    }
+
+    #[test]
+    #[cfg(feature = "card-count-checks")]
+    #[should_panic(expected = "is on a foundation and also at")]
+    fn duplicate_cards_are_caught_going_home() {
+        use crate::card::{Rank, Suit};
+        use crate::{floc, tloc};
+        // The same ace in a column and a freecell.
+        let ace = Card::new(Rank::Ace, Suit::Spades);
+        let mut game = GameState::new();
+        game.tableau.place_card_at_no_checks(tloc!(0), ace);
+        game.freecells.place_card_at_no_checks(floc!(0), ace);
+        game.execute_move(&Move::freecell_to_foundation(0, 0).unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+//! Noticing when suits complete and when a game can finish itself.
+//!
+//! UIs celebrate a finished suit and offer an "auto-finish" button once the rest of the
+//! game is a formality. [`GameState::execute_move_with_events`] reports the first, and
+//! [`GameState::is_trivially_winnable`] the second: every card still in play sits in a
+//! column ordered from high at the bottom to low at the top, with the freecells empty,
+//! so the cards can go home one at a time straight off the columns.
+//! [`GameState::auto_finish`] lists those moves and [`GameState::play_auto_finish`]
+//! plays them.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::card::{Card, Rank, Suit};
+//! use freecell_game_engine::game_state::{FoundationEvent, GameStateBuilder};
+//! use freecell_game_engine::location::{FoundationLocation, Location, TableauLocation};
+//! use freecell_game_engine::Move;
+//!
+//! // Everything is home except the King of Spades.
+//! let mut builder = GameStateBuilder::new();
+//! for (pile, suit) in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs].into_iter().enumerate() {
+//!     let location = Location::Foundation(FoundationLocation::new(pile as u8).unwrap());
+//!     let top = if suit == Suit::Spades { 12 } else { 13 };
+//!     for rank in 1..=top {
+//!         builder.place(location, Card::new(Rank::try_from(rank).unwrap(), suit)).unwrap();
+//!     }
+//! }
+//! builder.column(TableauLocation::new(0).unwrap(), [Card::new(Rank::King, Suit::Spades)]).unwrap();
+//! let mut game = builder.build().unwrap();
+//! assert!(game.is_trivially_winnable());
+//! assert_eq!(game.auto_finish(), vec![Move::tableau_to_foundation(0, 0).unwrap()]);
+//!
+//! let events = game.execute_move_with_events(&Move::tableau_to_foundation(0, 0).unwrap()).unwrap();
+//! assert_eq!(events, vec![FoundationEvent::SuitCompleted(Suit::Spades), FoundationEvent::GameWon]);
+//! ```
+
+use super::{GameError, GameState};
+use crate::card::Suit;
+use crate::location::Location;
+use crate::r#move::Move;
+
+/// Something a move did to the foundations, as reported by
+/// [`GameState::execute_move_with_events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FoundationEvent {
+    /// The suit's pile just received its last card.
+    SuitCompleted(Suit),
+    /// Every pile is complete. Follows the `SuitCompleted` of the last suit.
+    GameWon,
+}
+
+impl GameState {
+    /// Executes `m` like [`execute_move`](Self::execute_move) and reports any suit it
+    /// completed and whether it won the game.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`execute_move`](Self::execute_move); nothing is reported then.
+    pub fn execute_move_with_events(&mut self, m: &Move) -> Result<Vec<FoundationEvent>, GameError> {
+        let before = self.foundations.completed_suits();
+        self.execute_move(m)?;
+        let mut events = Vec::new();
+        if let Location::Foundation(_) = m.destination {
+            events.extend(
+                self.foundations
+                    .completed_suits()
+                    .into_iter()
+                    .filter(|suit| !before.contains(suit))
+                    .map(FoundationEvent::SuitCompleted),
+            );
+            if self.rules().win_condition().is_met(self) {
+                events.push(FoundationEvent::GameWon);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Whether the game can be won by moving cards straight to the foundations.
+    ///
+    /// True when the freecells are empty and every column's ranks never rise from the
+    /// bottom card to the top one: the lowest card left is then always exposed and always
+    /// the next one its suit needs. A game already won counts as trivially winnable.
+    pub fn is_trivially_winnable(&self) -> bool {
+        if self.freecells.empty_cells_count() < self.freecells.capacity() {
+            return false;
+        }
+        let ordered = self
+            .tableau
+            .columns()
+            .all(|column| column.windows(2).all(|pair| pair[0].rank() >= pair[1].rank()));
+        // With more than one deck a suit needs its Ace again after the King, so order
+        // alone does not guarantee the right card is exposed; play it out to be sure.
+        ordered && (self.foundations.decks() == 1 || self.foundation_playout().is_some())
+    }
+
+    /// The moves that finish a [trivially winnable](Self::is_trivially_winnable) game,
+    /// sending every card in play to its foundation. Empty if the game is not trivially
+    /// winnable or already won.
+    ///
+    /// Every move goes to a foundation, so no shorter finish exists.
+    pub fn auto_finish(&self) -> Vec<Move> {
+        if !self.is_trivially_winnable() {
+            return Vec::new();
+        }
+        self.foundation_playout().unwrap_or_default()
+    }
+
+    /// Plays the moves of [`auto_finish`](Self::auto_finish) and returns them. Leaves the
+    /// game untouched, and returns no moves, if it is not trivially winnable.
+    pub fn play_auto_finish(&mut self) -> Vec<Move> {
+        let moves = self.auto_finish();
+        for m in &moves {
+            self.execute_move(m).expect("auto-finish moves were replayed on a clone");
+        }
+        moves
+    }
+
+    /// The moves that send every card in play home straight off the columns, or `None`
+    /// if that gets stuck before the game is won.
+    fn foundation_playout(&self) -> Option<Vec<Move>> {
+        let mut game = self.clone();
+        let mut playout = Vec::new();
+        let mut moves = Vec::new();
+        while !game.rules().win_condition().is_met(&game) {
+            moves.clear();
+            game.get_tableau_to_foundation_moves(&mut moves);
+            let m = *moves.first()?;
+            game.execute_move(&m).ok()?;
+            playout.push(m);
+        }
+        Some(playout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank};
+    use crate::game_state::GameStateBuilder;
+    use crate::generation::generate_deal;
+    use crate::location::{FoundationLocation, TableauLocation};
+
+    const SUITS: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    /// Each suit home up to `top`, the rest stacked King-down in the suit's own column.
+    fn ordered_endgame(top: u8) -> GameState {
+        let mut builder = GameStateBuilder::new();
+        for (pile, suit) in SUITS.into_iter().enumerate() {
+            let location = Location::Foundation(FoundationLocation::new(pile as u8).unwrap());
+            for rank in 1..=top {
+                builder.place(location, Card::new(Rank::try_from(rank).unwrap(), suit)).unwrap();
+            }
+            let column = (top + 1..=13).rev().map(|rank| Card::new(Rank::try_from(rank).unwrap(), suit));
+            builder.column(TableauLocation::new(pile as u8).unwrap(), column).unwrap();
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn ordered_columns_are_trivially_winnable() {
+        assert!(ordered_endgame(6).is_trivially_winnable());
+        assert!(!generate_deal(1).unwrap().is_trivially_winnable());
+
+        let mut parked = ordered_endgame(6);
+        parked.execute_move(&Move::tableau_to_freecell(0, 0).unwrap()).unwrap();
+        assert!(!parked.is_trivially_winnable());
+    }
+
+    #[test]
+    fn reports_each_suit_and_then_the_win() {
+        let mut game = ordered_endgame(12);
+        let mut events = Vec::new();
+        for pile in 0..4 {
+            events.extend(game.execute_move_with_events(&Move::tableau_to_foundation(pile, pile).unwrap()).unwrap());
+        }
+        let mut expected: Vec<FoundationEvent> = SUITS.map(FoundationEvent::SuitCompleted).to_vec();
+        expected.push(FoundationEvent::GameWon);
+        assert_eq!(events, expected);
+        assert_eq!(game.foundations().completed_suits(), SUITS.to_vec());
+    }
+
+    #[test]
+    fn auto_finish_wins_from_ordered_columns() {
+        let mut game = ordered_endgame(6);
+        let moves = game.play_auto_finish();
+        assert_eq!(moves.len(), 28);
+        assert!(game.is_won().unwrap());
+        assert!(game.auto_finish().is_empty());
+
+        let mut deal = generate_deal(1).unwrap();
+        assert!(deal.play_auto_finish().is_empty());
+        crate::assert_game_states_eq!(deal, generate_deal(1).unwrap());
+    }
+}
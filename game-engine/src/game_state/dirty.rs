@@ -0,0 +1,122 @@
+//! Tracking which piles changed.
+//!
+//! Incremental consumers such as hashers, scorers and UI redraws need to know what
+//! changed, not a full diff between two positions. A `GameState` remembers every
+//! column, freecell and foundation pile touched by [`execute_move`](GameState::execute_move)
+//! or [`undo_move`](GameState::undo_move) since the last
+//! [`clear_dirty`](GameState::clear_dirty).
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::generation::generate_deal;
+//! use freecell_game_engine::location::{FreecellLocation, Location, TableauLocation};
+//! use freecell_game_engine::Move;
+//!
+//! let mut game = generate_deal(1).unwrap();
+//! assert!(game.dirty_piles().is_empty());
+//!
+//! game.execute_move(&Move::tableau_to_freecell(2, 0).unwrap()).unwrap();
+//! let dirty = game.dirty_piles();
+//! assert!(dirty.contains(Location::Tableau(TableauLocation::new(2).unwrap())));
+//! assert!(dirty.contains(Location::Freecell(FreecellLocation::new(0).unwrap())));
+//! assert_eq!(dirty.iter().count(), 2);
+//!
+//! game.clear_dirty();
+//! assert!(game.dirty_piles().is_empty());
+//! ```
+
+use super::GameState;
+use crate::foundations::FOUNDATION_COUNT;
+use crate::freecells::MAX_FREECELLS;
+use crate::location::{FoundationLocation, FreecellLocation, Location, TableauLocation};
+use crate::tableau::MAX_TABLEAU_COLUMNS;
+
+const FREECELL_BASE: usize = MAX_TABLEAU_COLUMNS;
+const FOUNDATION_BASE: usize = FREECELL_BASE + MAX_FREECELLS;
+const PILE_COUNT: usize = FOUNDATION_BASE + FOUNDATION_COUNT;
+
+/// A set of piles, one bit per column, freecell and foundation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DirtyPiles(u32);
+
+impl DirtyPiles {
+    fn bit(location: Location) -> u32 {
+        let index = match location {
+            Location::Tableau(column) => column.index() as usize,
+            Location::Freecell(cell) => FREECELL_BASE + cell.index() as usize,
+            Location::Foundation(pile) => FOUNDATION_BASE + pile.index() as usize,
+        };
+        1 << index
+    }
+
+    /// Whether `location` changed.
+    pub fn contains(&self, location: Location) -> bool {
+        self.0 & Self::bit(location) != 0
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The changed piles: columns first, then freecells, then foundations.
+    pub fn iter(&self) -> impl Iterator<Item = Location> + '_ {
+        (0..PILE_COUNT).filter(|i| self.0 & (1 << i) != 0).map(|i| {
+            if i < FREECELL_BASE {
+                Location::Tableau(TableauLocation::new(i as u8).unwrap())
+            } else if i < FOUNDATION_BASE {
+                Location::Freecell(FreecellLocation::new((i - FREECELL_BASE) as u8).unwrap())
+            } else {
+                Location::Foundation(FoundationLocation::new((i - FOUNDATION_BASE) as u8).unwrap())
+            }
+        })
+    }
+
+    pub(super) fn mark(&mut self, location: Location) {
+        self.0 |= Self::bit(location);
+    }
+}
+
+impl GameState {
+    /// Piles changed by moves or undos since the last [`clear_dirty`](Self::clear_dirty).
+    ///
+    /// Like the card index, this is bookkeeping and does not take part in equality.
+    pub fn dirty_piles(&self) -> DirtyPiles {
+        self.dirty
+    }
+
+    /// Forgets which piles changed.
+    pub fn clear_dirty(&mut self) {
+        self.dirty = DirtyPiles::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::generate_deal;
+    use crate::r#move::Move;
+
+    #[test]
+    fn undo_marks_both_ends_and_equality_ignores_flags() {
+        let deal = generate_deal(1).unwrap();
+        let mut game = deal.clone();
+        let m = Move::tableau_to_freecell(5, 3).unwrap();
+        game.execute_move(&m).unwrap();
+        game.clear_dirty();
+
+        game.undo_move(&m);
+        assert_eq!(game.dirty_piles().iter().collect::<Vec<_>>(), [m.source, m.destination]);
+        crate::assert_game_states_eq!(game, deal);
+    }
+
+    #[test]
+    fn every_pile_has_its_own_bit() {
+        let mut dirty = DirtyPiles::default();
+        let last = Location::Foundation(FoundationLocation::new(3).unwrap());
+        dirty.mark(last);
+        assert_eq!(dirty.iter().collect::<Vec<_>>(), [last]);
+        assert!(!dirty.contains(Location::Tableau(TableauLocation::new(9).unwrap())));
+    }
+}
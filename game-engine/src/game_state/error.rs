@@ -21,10 +21,22 @@
 //!
 //! if let Err(err) = result {
 //!    // The error message will be more specific now, e.g.:
-//!    // "Invalid move T(0)->F(0): Source tableau column is empty"
+//!    // "Invalid move T(0)->F(0): the source is empty"
 //!    println!("Invalid move: {}", err);
 //! }
 //! ```
+//!
+//! Rule violations can be told apart without matching on messages: [`GameError::reason`]
+//! classifies them as an [`InvalidMoveReason`], whichever component reported them.
+//!
+//! ```
+//! use freecell_game_engine::game_state::InvalidMoveReason;
+//! use freecell_game_engine::{GameState, Move};
+//!
+//! let game = GameState::new();
+//! let err = game.is_move_valid(&Move::tableau_to_freecell(0, 0).unwrap()).unwrap_err();
+//! assert_eq!(err.reason(), Some(InvalidMoveReason::EmptySource));
+//! ```
 
 use crate::r#move::Move;
 
@@ -37,33 +49,145 @@ pub enum GameError {
     FreeCell {
         error: FreeCellError,
         attempted_move: Option<Move>,
-        operation: String,
+        operation: &'static str,
     },
     /// A foundation-related error occurred.
     Foundation {
         error: FoundationError,
         attempted_move: Option<Move>,
-        operation: String,
+        operation: &'static str,
     },
     /// A tableau-related error occurred.
     Tableau {
         error: TableauError,
         attempted_move: Option<Move>,
-        operation: String,
+        operation: &'static str,
     },
     /// The attempted move is invalid for a specific reason.
     InvalidMove {
-        reason: String,
+        reason: InvalidMoveReason,
         attempted_move: Move,
     },
     /// Indicates that a multi-card move was attempted when only single card moves are supported.
     OnlySingleCardMovesSupported,
+    /// A sequence move needs more free cells and empty columns than the position has.
+    /// Problems with the run itself are reported as [`TableauError::BrokenSequence`].
+    InsufficientResources {
+        /// Cards in the run.
+        needed: usize,
+        /// The longest run the free cells and empty columns can carry.
+        available: usize,
+        empty_freecells: usize,
+        /// Empty columns other than the destination.
+        empty_columns: usize,
+    },
+    /// A hand-built position lost or duplicated a card.
+    Integrity(IntegrityError),
+    /// A card was sent to a foundation pile that already holds all the cards it can.
+    FoundationOverflow {
+        pile: FoundationLocation,
+        card: Card,
+        attempted_move: Option<Move>,
+    },
+}
+
+/// Why a move breaks the rules, as returned by [`GameError::reason`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InvalidMoveReason {
+    /// There is no card to move.
+    EmptySource,
+    /// The destination freecell already holds a card.
+    DestinationOccupied,
+    /// The card's rank does not follow the destination's top card.
+    RankMismatch,
+    /// The card is the same color as the tableau card it would go on.
+    ColorMismatch,
+    /// The card belongs to another suit's foundation.
+    SuitMismatch,
+    /// The destination is full or out of play, or the run is longer than the free
+    /// cells and empty columns can carry.
+    CapacityExceeded,
+    /// The rules have no move from the source's kind of pile to the destination's, or
+    /// source and destination are the same pile.
+    UnsupportedPair,
+}
+
+impl fmt::Display for InvalidMoveReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            InvalidMoveReason::EmptySource => "the source is empty",
+            InvalidMoveReason::DestinationOccupied => "the destination is occupied",
+            InvalidMoveReason::RankMismatch => "the rank does not follow the destination card",
+            InvalidMoveReason::ColorMismatch => "the color matches the destination card",
+            InvalidMoveReason::SuitMismatch => "the suit does not match the foundation",
+            InvalidMoveReason::CapacityExceeded => "there is no room at the destination",
+            InvalidMoveReason::UnsupportedPair => "moves between these locations are not supported",
+        };
+        f.write_str(text)
+    }
+}
+
+impl GameError {
+    /// Classifies a rule violation, whether reported by move validation itself or by the
+    /// tableau, freecells or foundations. `None` for errors that are not about the rules,
+    /// such as out-of-range locations or a broken position.
+    pub fn reason(&self) -> Option<InvalidMoveReason> {
+        use InvalidMoveReason::*;
+        match self {
+            GameError::InvalidMove { reason, .. } => Some(*reason),
+            GameError::InsufficientResources { .. } => Some(CapacityExceeded),
+            GameError::Tableau { error, .. } => match error {
+                TableauError::EmptyColumn(_) | TableauError::InsufficientCards { .. } => Some(EmptySource),
+                TableauError::InvalidColor { .. } => Some(ColorMismatch),
+                TableauError::InvalidRank { .. } | TableauError::InvalidStack => Some(RankMismatch),
+                TableauError::BrokenSequence { lower, upper, .. } if lower.color() == upper.color() => {
+                    Some(ColorMismatch)
+                }
+                TableauError::BrokenSequence { .. } => Some(RankMismatch),
+                _ => None,
+            },
+            GameError::Foundation { error, .. } => match error {
+                FoundationError::NonAceOnEmptyPile { .. } => Some(RankMismatch),
+                FoundationError::InvalidSequence { top_card, new_card } if top_card.suit() != new_card.suit() => {
+                    Some(SuitMismatch)
+                }
+                FoundationError::InvalidSequence { .. } => Some(RankMismatch),
+                FoundationError::PileComplete { .. } | FoundationError::NoAvailablePile { .. } => {
+                    Some(CapacityExceeded)
+                }
+                _ => None,
+            },
+            GameError::FoundationOverflow { .. } => Some(CapacityExceeded),
+            GameError::FreeCell { error, .. } => match error {
+                FreeCellError::CellOccupied { .. } => Some(DestinationOccupied),
+                FreeCellError::NoEmptyCells | FreeCellError::CellUnavailable { .. } => Some(CapacityExceeded),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Wraps a foundation error from a checked placement, reporting a full pile as
+    /// [`GameError::FoundationOverflow`].
+    pub(crate) fn foundation(error: FoundationError, attempted_move: Option<Move>, operation: &'static str) -> Self {
+        match error {
+            FoundationError::PileComplete { pile_index, new_card } => GameError::FoundationOverflow {
+                pile: FoundationLocation::new(pile_index).expect("the pile was just placed on"),
+                card: new_card,
+                attempted_move,
+            },
+            error => GameError::Foundation { error, attempted_move, operation },
+        }
+    }
 }
 
 use std::fmt;
+use crate::card::Card;
 use crate::freecells::FreeCellError;
 use crate::foundations::FoundationError;
-use crate::location::LocationError;
+use crate::location::{FoundationLocation, LocationError};
+use super::IntegrityError;
 use crate::tableau::TableauError;
 
 impl From<LocationError> for GameError {
@@ -72,6 +196,12 @@ impl From<LocationError> for GameError {
     }
 }
 
+impl From<IntegrityError> for GameError {
+    fn from(err: IntegrityError) -> Self {
+        GameError::Integrity(err)
+    }
+}
+
 
 impl fmt::Display for GameError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -93,6 +223,16 @@ impl fmt::Display for GameError {
                 write!(f, "Invalid move {}: {}", attempted_move, reason)
             }
             GameError::OnlySingleCardMovesSupported => write!(f, "Only single card moves are supported"),
+            GameError::InsufficientResources { needed, available, empty_freecells, empty_columns } => write!(
+                f,
+                "Cannot move {} cards at once: {} free cells and {} empty columns allow at most {}",
+                needed, empty_freecells, empty_columns, available
+            ),
+            GameError::Integrity(err) => write!(f, "Integrity error: {}", err),
+            GameError::FoundationOverflow { pile, card, attempted_move } => {
+                let move_str = attempted_move.map_or("".to_string(), |m| format!(" during move {}", m));
+                write!(f, "Cannot place {} on foundation {}: the pile is complete{}", card, pile.index(), move_str)
+            }
         }
     }
 }
@@ -104,6 +244,7 @@ impl std::error::Error for GameError {
             GameError::FreeCell { error, .. } => Some(error),
             GameError::Foundation { error, .. } => Some(error),
             GameError::Tableau { error, .. } => Some(error),
+            GameError::Integrity(err) => Some(err),
             _ => None,
         }
     }
@@ -1,6 +1,6 @@
 //! Move execution and undo logic for GameState.
 
-use super::{GameError, GameState};
+use super::{GameError, GameState, InvalidMoveReason};
 use crate::r#move::Move;
 
 impl GameState {
@@ -18,6 +18,10 @@ impl GameState {
     /// * `Ok(())` if the move was successfully executed.
     /// * `Err(GameError)` if the move is invalid or an internal error occurs during execution.
     ///
+    /// Neither this nor [`undo_move`](Self::undo_move) allocates for a single-card move,
+    /// whether it succeeds or fails: errors name the failing operation with a static
+    /// string, and tableau columns are allocated at their greatest possible height.
+    ///
     /// # Examples
     ///
     /// ```
@@ -38,7 +42,11 @@ impl GameState {
     /// ```
     pub fn execute_move(&mut self, m: &Move) -> Result<(), GameError> {
         use crate::location::Location::*;
-        match (m.source, m.destination) {
+        let moved_card = match self.card_index {
+            Some(_) => self.get_card(m.source).ok().flatten().copied(),
+            None => None,
+        };
+        let result = match (m.source, m.destination) {
             (Tableau(from), Foundation(to)) => {
                 self.execute_tableau_to_foundation(from.index(), to.index(), m)
             }
@@ -55,10 +63,22 @@ impl GameState {
                 self.execute_tableau_to_tableau(from.index(), to.index(), m)
             }
             _ => Err(GameError::InvalidMove {
-                reason: "Moves between these locations are not supported".to_string(),
+                reason: InvalidMoveReason::UnsupportedPair,
                 attempted_move: *m,
             }),
+        };
+        if result.is_ok() {
+            self.dirty.mark(m.source);
+            self.dirty.mark(m.destination);
+            #[cfg(feature = "card-count-checks")]
+            if matches!(m.destination, Foundation(_)) {
+                self.check_foundation_card(m.source, m.destination);
+            }
         }
+        if let (Ok(()), Some(card)) = (&result, moved_card) {
+            self.note_card_location(&card, Some(m.destination));
+        }
+        result
     }
 
     /// Executes a move from a tableau column to a foundation pile.
@@ -91,21 +111,17 @@ impl GameState {
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "execute_tableau_to_foundation".to_string(),
+                operation: "execute_tableau_to_foundation",
             })?;
-        let removed_card = removed.ok_or_else(|| GameError::InvalidMove {
-            reason: "Source tableau column is empty".to_string(),
+        let removed_card = removed.ok_or(GameError::InvalidMove {
+            reason: InvalidMoveReason::EmptySource,
             attempted_move: *m,
         })?;
         let to_location =
             crate::location::FoundationLocation::new(to_pile).map_err(GameError::Location)?;
         self.foundations
             .place_card_at(to_location, removed_card)
-            .map_err(|e| GameError::Foundation {
-                error: e,
-                attempted_move: Some(*m),
-                operation: "execute_tableau_to_foundation".to_string(),
-            })?;
+            .map_err(|e| GameError::foundation(e, Some(*m), "execute_tableau_to_foundation"))?;
         Ok(())
     }
 
@@ -139,10 +155,10 @@ impl GameState {
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "execute_tableau_to_freecell".to_string(),
+                operation: "execute_tableau_to_freecell",
             })?;
-        let removed_card = removed.ok_or_else(|| GameError::InvalidMove {
-            reason: "Source tableau column is empty".to_string(),
+        let removed_card = removed.ok_or(GameError::InvalidMove {
+            reason: InvalidMoveReason::EmptySource,
             attempted_move: *m,
         })?;
         let to_location =
@@ -152,7 +168,7 @@ impl GameState {
             .map_err(|e| GameError::FreeCell {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "execute_tableau_to_freecell".to_string(),
+                operation: "execute_tableau_to_freecell",
             })?;
         Ok(())
     }
@@ -187,10 +203,10 @@ impl GameState {
                 .map_err(|e| GameError::FreeCell {
                     error: e,
                     attempted_move: Some(*m),
-                    operation: "execute_freecell_to_tableau".to_string(),
+                    operation: "execute_freecell_to_tableau",
                 })?;
-        let removed_card = removed.ok_or_else(|| GameError::InvalidMove {
-            reason: "Source freecell is empty".to_string(),
+        let removed_card = removed.ok_or(GameError::InvalidMove {
+            reason: InvalidMoveReason::EmptySource,
             attempted_move: *m,
         })?;
         let to_location =
@@ -200,7 +216,7 @@ impl GameState {
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "execute_freecell_to_tableau".to_string(),
+                operation: "execute_freecell_to_tableau",
             })?;
         Ok(())
     }
@@ -235,21 +251,17 @@ impl GameState {
                 .map_err(|e| GameError::FreeCell {
                     error: e,
                     attempted_move: Some(*m),
-                    operation: "execute_freecell_to_foundation".to_string(),
+                    operation: "execute_freecell_to_foundation",
                 })?;
-        let removed_card = removed.ok_or_else(|| GameError::InvalidMove {
-            reason: "Source freecell is empty".to_string(),
+        let removed_card = removed.ok_or(GameError::InvalidMove {
+            reason: InvalidMoveReason::EmptySource,
             attempted_move: *m,
         })?;
         let to_location =
             crate::location::FoundationLocation::new(to_pile).map_err(GameError::Location)?;
         self.foundations
             .place_card_at(to_location, removed_card)
-            .map_err(|e| GameError::Foundation {
-                error: e,
-                attempted_move: Some(*m),
-                operation: "execute_freecell_to_foundation".to_string(),
-            })?;
+            .map_err(|e| GameError::foundation(e, Some(*m), "execute_freecell_to_foundation"))?;
         Ok(())
     }
 
@@ -284,10 +296,10 @@ impl GameState {
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "execute_tableau_to_tableau".to_string(),
+                operation: "execute_tableau_to_tableau",
             })?;
-        let removed_card = removed.ok_or_else(|| GameError::InvalidMove {
-            reason: "Source tableau column is empty".to_string(),
+        let removed_card = removed.ok_or(GameError::InvalidMove {
+            reason: InvalidMoveReason::EmptySource,
             attempted_move: *m,
         })?;
         let to_location =
@@ -297,7 +309,7 @@ impl GameState {
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "execute_tableau_to_tableau".to_string(),
+                operation: "execute_tableau_to_tableau",
             })?;
         Ok(())
     }
@@ -319,6 +331,12 @@ impl GameState {
     /// an empty pile during undo). This is by design, as undo operations should
     /// only be called on states that were previously validly reached.
     ///
+    /// Cards are put back with the components' `place_card_at_no_checks` methods,
+    /// since the reversed placement would often fail the forward-move rules (e.g. a
+    /// card returning to a column it did not legally stack on). Debug builds assert
+    /// the invariants the raw paths skip: a freecell being restored is empty and a
+    /// foundation card being restored continues its pile.
+    ///
     /// # Examples
     ///
     /// ```
@@ -369,6 +387,11 @@ impl GameState {
                     .expect("Undo: tableau error");
                 let card = removed.expect("Undo: tableau not empty");
                 let from_location = crate::location::FreecellLocation::new(from.index()).unwrap();
+                debug_assert!(
+                    self.freecells.get_card(from_location).unwrap().is_none(),
+                    "Undo: freecell {} already occupied",
+                    from.index()
+                );
                 self.freecells.place_card_at_no_checks(from_location, card);
             }
             (Freecell(from), Foundation(to)) => {
@@ -379,6 +402,11 @@ impl GameState {
                     .expect("Undo: foundation error");
                 let card = removed.expect("Undo: foundation not empty");
                 let from_location = crate::location::FreecellLocation::new(from.index()).unwrap();
+                debug_assert!(
+                    self.freecells.get_card(from_location).unwrap().is_none(),
+                    "Undo: freecell {} already occupied",
+                    from.index()
+                );
                 self.freecells.place_card_at_no_checks(from_location, card);
             }
             (Tableau(from), Tableau(to)) => {
@@ -391,7 +419,202 @@ impl GameState {
                 let from_location = crate::location::TableauLocation::new(from.index()).unwrap();
                 self.tableau.place_card_at_no_checks(from_location, card);
             }
-            _ => {}
+            (Foundation(from), Tableau(to)) => {
+                let to_location = crate::location::TableauLocation::new(to.index()).unwrap();
+                let removed = self
+                    .tableau
+                    .remove_card(to_location)
+                    .expect("Undo: tableau error");
+                let card = removed.expect("Undo: tableau not empty");
+                let from_location = crate::location::FoundationLocation::new(from.index()).unwrap();
+                self.foundations.place_card_at_no_checks(from_location, card);
+            }
+            (Foundation(from), Freecell(to)) => {
+                let to_location = crate::location::FreecellLocation::new(to.index()).unwrap();
+                let removed = self
+                    .freecells
+                    .remove_card(to_location)
+                    .expect("Undo: freecell error");
+                let card = removed.expect("Undo: freecell not empty");
+                let from_location = crate::location::FoundationLocation::new(from.index()).unwrap();
+                self.foundations.place_card_at_no_checks(from_location, card);
+            }
+            _ => return,
+        }
+        self.dirty.mark(m.source);
+        self.dirty.mark(m.destination);
+        #[cfg(feature = "card-count-checks")]
+        if matches!(m.source, Foundation(_)) {
+            self.check_foundation_card(m.destination, m.source);
+        }
+        if self.card_index.is_some() {
+            if let Ok(Some(card)) = self.get_card(m.source) {
+                let card = *card;
+                self.note_card_location(&card, Some(m.source));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+    use crate::foundations::Foundations;
+    use crate::freecells::FreeCells;
+    use crate::location::Location;
+    use crate::{floc, hloc, tloc};
+    use crate::tableau::Tableau;
+
+    #[test]
+    fn undo_restores_foundation_card_taken_to_tableau() {
+        let pile = hloc!(1);
+        let column = tloc!(4);
+        let mut foundations = Foundations::new();
+        foundations.place_card_at(pile, Card::new(Rank::Ace, Suit::Hearts)).unwrap();
+        let mut tableau = Tableau::new();
+        tableau.place_card_at_no_checks(column, Card::new(Rank::Two, Suit::Hearts));
+        let mut game = GameState::from_components(tableau, FreeCells::new(), foundations);
+
+        game.undo_move(&Move::single(Location::Foundation(pile), Location::Tableau(column)));
+
+        assert!(game.tableau().is_column_empty(column).unwrap());
+        assert_eq!(game.foundations().pile_cards(pile).count(), 2);
+    }
+
+    #[test]
+    fn execute_then_undo_to_foundation_round_trips() {
+        let cell = floc!(0);
+        let mut freecells = FreeCells::new();
+        freecells.place_card_at(cell, Card::new(Rank::Ace, Suit::Spades)).unwrap();
+        let mut game = GameState::from_components(Tableau::new(), freecells, Foundations::new());
+        let before = game.clone();
+
+        let mv = Move::freecell_to_foundation(0, 0).unwrap();
+        game.execute_move(&mv).unwrap();
+        game.undo_move(&mv);
+
+        assert_eq!(game, before);
+    }
+
+    fn complete_hearts(pile: crate::location::FoundationLocation) -> Foundations {
+        let mut foundations = Foundations::new();
+        for rank in 1..=13 {
+            foundations.place_card_at(pile, Card::new(Rank::try_from(rank).unwrap(), Suit::Hearts)).unwrap();
+        }
+        foundations
+    }
+
+    #[test]
+    fn moves_onto_a_complete_pile_are_foundation_overflows() {
+        let mut freecells = FreeCells::new();
+        freecells.place_card_at(floc!(0), Card::new(Rank::Ace, Suit::Spades)).unwrap();
+        let mut game = GameState::from_components(Tableau::new(), freecells, complete_hearts(hloc!(0)));
+        let before = game.clone();
+
+        let mv = Move::freecell_to_foundation(0, 0).unwrap();
+        let err = game.execute_move(&mv).unwrap_err();
+
+        assert_eq!(
+            err,
+            GameError::FoundationOverflow {
+                pile: hloc!(0),
+                card: Card::new(Rank::Ace, Suit::Spades),
+                attempted_move: Some(mv),
+            }
+        );
+        assert_eq!(err.reason(), Some(InvalidMoveReason::CapacityExceeded));
+        assert_eq!(game, before);
+    }
+
+    #[test]
+    fn undo_of_the_completing_king_reopens_the_pile() {
+        let pile = hloc!(0);
+        let mut foundations = complete_hearts(pile);
+        let king = foundations.remove_card(pile).unwrap().unwrap();
+        let mut freecells = FreeCells::new();
+        freecells.place_card_at(floc!(0), king).unwrap();
+        let mut game = GameState::from_components(Tableau::new(), freecells, foundations);
+        let before = game.clone();
+
+        let mv = Move::freecell_to_foundation(0, 0).unwrap();
+        game.execute_move(&mv).unwrap();
+        game.undo_move(&mv);
+        assert_eq!(game, before);
+        game.execute_move(&mv).unwrap();
+        assert_eq!(game.foundations().pile_cards(pile).count(), 13);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "foundation pile 0 is already complete")]
+    fn undo_onto_a_complete_pile_panics_in_debug() {
+        let column = tloc!(0);
+        let mut tableau = Tableau::new();
+        tableau.place_card_at_no_checks(column, Card::new(Rank::Ace, Suit::Spades));
+        let mut game = GameState::from_components(tableau, FreeCells::new(), complete_hearts(hloc!(0)));
+
+        // Never played, so undoing it would push a fourteenth card onto the pile.
+        game.undo_move(&Move::single(Location::Foundation(hloc!(0)), Location::Tableau(column)));
+    }
+
+    /// Counts the calling thread's heap allocations, so tests running in parallel do not
+    /// see each other's.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    // SAFETY: forwards every call to the system allocator unchanged.
+    unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            std::alloc::System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            std::alloc::System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            std::alloc::System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn allocations_in(f: impl FnOnce()) -> usize {
+        let before = ALLOCATIONS.with(|count| count.get());
+        f();
+        ALLOCATIONS.with(|count| count.get()) - before
+    }
+
+    #[test]
+    fn single_card_moves_and_undos_do_not_allocate() {
+        for index in [false, true] {
+            let mut game = crate::generation::generate_deal(11).unwrap();
+            if index {
+                game.enable_card_index();
+            }
+            // A cloned position too, since clones are what searches move on.
+            let mut game = game.clone();
+            for step in 0..150 {
+                let moves = game.get_available_moves();
+                let Some(&next) = moves.get(step % moves.len().max(1)) else { break };
+                let illegal = Move::freecell_to_foundation(3, 0).unwrap();
+                let count = allocations_in(|| {
+                    for m in &moves {
+                        game.execute_move(m).unwrap();
+                        game.undo_move(m);
+                    }
+                    assert!(game.execute_move(&illegal).is_err());
+                });
+                assert_eq!(count, 0, "step {} of {:?}", step, moves);
+                game.execute_move(&next).unwrap();
+            }
         }
     }
 }
@@ -1,5 +1,6 @@
 //! Utility functions for evaluating heuristics on GameState.
 
+use crate::card::{Card, Rank};
 use crate::game_state::GameState;
 
 /// Calculates a heuristic score for the given game state.
@@ -21,6 +22,127 @@ pub fn score_state(state: &GameState) -> i32 {
     score
 }
 
+/// Counts the legal moves in `state`, a measure of how much room the player has.
+///
+/// Higher is better; zero means the position is dead. Uses
+/// [`GameState::count_available_moves`], so no moves are built.
+pub fn mobility(state: &GameState) -> usize {
+    state.count_available_moves()
+}
+
+/// How far one tableau column is from being emptied; see [`column_clearance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColumnClearance {
+    /// Cards in the column.
+    pub cards: usize,
+    /// Cards that can be moved off, counted from the top, before one has nowhere to go.
+    pub placeable: usize,
+}
+
+impl ColumnClearance {
+    /// Cards left in the column once the placeable ones are gone.
+    pub fn remaining(&self) -> usize {
+        self.cards - self.placeable
+    }
+
+    /// Whether every card has somewhere to go, so the column can be emptied now.
+    /// True for an empty column.
+    pub fn is_clearable(&self) -> bool {
+        self.placeable == self.cards
+    }
+}
+
+/// Estimates, for every tableau column in order, how many of its cards could be moved
+/// off before one gets stuck.
+///
+/// Each column is dug out from the top on its own, one card at a time. A card goes home
+/// if its foundation needs it (counting cards sent home earlier from the same column),
+/// otherwise onto another column's top card if it fits there, otherwise into a free
+/// cell or another empty column while any is left. A column top takes one card, which
+/// then becomes the top. The other columns are taken as they are, so the estimate is
+/// optimistic where two columns would need the same spot, and pessimistic where clearing
+/// one column would free room for another.
+///
+/// # Examples
+///
+/// ```
+/// use freecell_game_engine::game_state::heuristics::column_clearance;
+/// use freecell_game_engine::import::import_board;
+/// use freecell_game_engine::Variant;
+///
+/// // The ace of spades goes home, then the two of spades follows it.
+/// let board = "Foundations: 9H 9D 9C\n: 2S AS\n: KS";
+/// let game = import_board(board, Variant::Standard).unwrap().state;
+/// let clearance = column_clearance(&game);
+/// assert!(clearance[0].is_clearable());
+/// assert_eq!(clearance[1].placeable, 1);
+/// ```
+pub fn column_clearance(state: &GameState) -> Vec<ColumnClearance> {
+    let columns: Vec<&Vec<Card>> = state.tableau().columns().collect();
+    let empty_columns = columns.iter().filter(|column| column.is_empty()).count();
+    columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let mut needed = state.foundations().needed_cards();
+            let mut tops: Vec<Card> = columns
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index)
+                .filter_map(|(_, other)| other.last().copied())
+                .collect();
+            let mut spare = state.freecells().empty_cells_count() + empty_columns;
+            let placeable = column
+                .iter()
+                .rev()
+                .take_while(|card| {
+                    let home = card.suit().foundation_index() as usize;
+                    if needed[home] == Some(**card) {
+                        needed[home] = Rank::try_from(card.rank() as u8 + 1).ok().map(|rank| Card::new(rank, card.suit()));
+                    } else if let Some(top) = tops
+                        .iter_mut()
+                        .find(|top| top.is_one_higher_than(card) && top.color() != card.color())
+                    {
+                        *top = **card;
+                    } else if spare > 0 {
+                        spare -= 1;
+                    } else {
+                        return false;
+                    }
+                    true
+                })
+                .count();
+            ColumnClearance { cards: column.len(), placeable }
+        })
+        .collect()
+}
+
+/// Scores how close the position is to gaining empty columns, for rewarding near-empty
+/// columns before they are actually empty. Higher is better.
+///
+/// Each column contributes `12 / (1 + remaining)` from [`column_clearance`]: 12 for a
+/// column that is empty or can be emptied now, 6 for one card short, 4 for two, and so
+/// on down to 0 for a column with more than a dozen cards stuck in it.
+///
+/// # Examples
+///
+/// ```
+/// use freecell_game_engine::game_state::heuristics::empty_column_potential;
+/// use freecell_game_engine::import::import_board;
+/// use freecell_game_engine::Variant;
+///
+/// // Both columns can be dug out, and the other six are empty already.
+/// let board = "Foundations: 9H 9D 9C\n: 2S AS\n: KS";
+/// let game = import_board(board, Variant::Standard).unwrap().state;
+/// assert_eq!(empty_column_potential(&game), 8 * 12);
+/// ```
+pub fn empty_column_potential(state: &GameState) -> u32 {
+    column_clearance(state)
+        .iter()
+        .map(|clearance| 12 / (1 + clearance.remaining() as u32))
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,4 +217,29 @@ mod tests {
         let state = GameState::from_components(tableau, FreeCells::new(), Foundations::new());
         assert_eq!(score_state(&state), 1);
     }
+
+    #[test]
+    fn test_mobility_of_a_dead_position() {
+        let empty = GameState::from_components(Tableau::new(), FreeCells::new(), Foundations::new());
+        assert_eq!(mobility(&empty), 0);
+
+        let deal = crate::generation::generate_deal(1).unwrap();
+        assert_eq!(mobility(&deal), deal.get_available_moves().len());
+    }
+
+    #[test]
+    fn clearance_stops_at_the_first_card_with_nowhere_to_go() {
+        // Full freecells and no empty column. Column 0 sheds the 9♥ onto the 10♠, then
+        // its 5♣ has nowhere to go; column 1 sends the A♦ home, then the 7♣ is stuck.
+        let board = "Freecells: 2H 3H 4H 5H\n: KD 5C 9H\n: 7C AD\n: 10S\n: QC\n: KC\n: JD\n: 6S\n: 2C";
+        let game = crate::import::import_board(board, crate::Variant::Standard).unwrap().state;
+        let clearance = column_clearance(&game);
+        assert_eq!(clearance[0], ColumnClearance { cards: 3, placeable: 1 });
+        assert_eq!(clearance[0].remaining(), 2);
+        assert_eq!(clearance[1], ColumnClearance { cards: 2, placeable: 1 });
+        // The 10♠ fits on the J♦ and the J♦ on the Q♣; the other single cards are stuck.
+        let clearable: Vec<bool> = clearance.iter().map(ColumnClearance::is_clearable).collect();
+        assert_eq!(clearable, [false, false, true, false, false, true, false, false]);
+        assert_eq!(empty_column_potential(&game), 12 / 3 + 2 * 12 + 5 * (12 / 2));
+    }
 }
@@ -0,0 +1,245 @@
+//! Structural integrity checks for a `GameState`.
+//!
+//! Positions reached by dealing and then executing legal moves are always consistent,
+//! but positions assembled by hand (an editor, an imported board, a test fixture) can
+//! easily lose or duplicate a card. [`GameState::check_integrity`] verifies that a
+//! position accounts for every card of each deck in play exactly once: one copy of each
+//! card in standard FreeCell, two in Double FreeCell.
+//!
+//! A [partial deal](super::GameStateBuilder::partial_deal) leaves cards out on purpose,
+//! so it is only checked for duplicates and for suits that can still be built home: each
+//! card it holds needs the card below it in its suit, or the card could never reach its
+//! foundation.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::GameState;
+//! use freecell_game_engine::game_state::IntegrityError;
+//!
+//! // An empty board is missing every card.
+//! let game = GameState::new();
+//! match game.check_integrity() {
+//!     Err(IntegrityError::MissingCards(missing)) => assert_eq!(missing.len(), 52),
+//!     other => panic!("unexpected result: {:?}", other),
+//! }
+//! ```
+
+use std::fmt;
+
+use super::card_index::{card_at_deck_index, deck_index, DECK_SIZE};
+use super::GameState;
+use crate::card::{Card, Rank};
+use crate::location::Location;
+
+/// Describes why a position fails the integrity check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IntegrityError {
+    /// A card appears more often than there are decks.
+    DuplicateCard {
+        card: Card,
+        first: Location,
+        second: Location,
+    },
+    /// Cards from the deck that do not appear anywhere in the position, one entry per
+    /// missing copy, ordered by suit (Spades, Hearts, Diamonds, Clubs) and then rank.
+    MissingCards(Vec<Card>),
+    /// A partial deal holds more copies of `card` than of `missing`, the card below it in
+    /// its suit, so `card` could never reach its foundation.
+    StrandedCard { card: Card, missing: Card },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::DuplicateCard { card, first, second } => write!(
+                f,
+                "{} appears more than once ({:?} and {:?})",
+                card, first, second
+            ),
+            IntegrityError::MissingCards(cards) => {
+                write!(f, "{} card(s) missing from the position:", cards.len())?;
+                for card in cards {
+                    write!(f, " {}", card)?;
+                }
+                Ok(())
+            }
+            IntegrityError::StrandedCard { card, missing } => {
+                write!(f, "{} can never reach its foundation without {}", card, missing)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+impl GameState {
+    /// Checks that the position contains each of the 52 cards exactly once per deck, or
+    /// for a partial deal, no card more than once per deck and no card without the one
+    /// below it.
+    ///
+    /// This does not check whether the position is *reachable* or *solvable*, only
+    /// that no card has been lost or duplicated. Foundation ordering is already
+    /// enforced by [`Foundations`](crate::foundations::Foundations) itself.
+    ///
+    /// # Errors
+    ///
+    /// - [`IntegrityError::DuplicateCard`] for the first card found more often than there are decks
+    /// - [`IntegrityError::MissingCards`] listing every card absent from the position
+    /// - [`IntegrityError::StrandedCard`] for the first card of a partial deal missing the
+    ///   card below it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    ///
+    /// let game = generate_deal(1).unwrap();
+    /// assert!(game.check_integrity().is_ok());
+    /// ```
+    pub fn check_integrity(&self) -> Result<(), IntegrityError> {
+        let decks = self.foundations.decks();
+        // Copies seen so far and where the first one was, per card.
+        let mut seen: [(usize, Option<Location>); DECK_SIZE] = [(0, None); DECK_SIZE];
+        for (card, location) in self.iter_cards() {
+            let (count, first) = &mut seen[deck_index(&card)];
+            if *count == decks {
+                return Err(IntegrityError::DuplicateCard {
+                    card,
+                    first: first.expect("a counted card has a first location"),
+                    second: location,
+                });
+            }
+            *count += 1;
+            first.get_or_insert(location);
+        }
+
+        if self.partial_deal {
+            for (i, &(count, _)) in seen.iter().enumerate() {
+                let card = card_at_deck_index(i);
+                if card.rank() == Rank::Ace {
+                    continue;
+                }
+                let below = Card::new(Rank::try_from(card.rank() as u8 - 1).expect("not an Ace"), card.suit());
+                if count > seen[deck_index(&below)].0 {
+                    return Err(IntegrityError::StrandedCard { card, missing: below });
+                }
+            }
+            return Ok(());
+        }
+
+        let missing: Vec<Card> = (0..DECK_SIZE)
+            .flat_map(|i| std::iter::repeat_n(card_at_deck_index(i), decks - seen[i].0))
+            .collect();
+        if !missing.is_empty() {
+            return Err(IntegrityError::MissingCards(missing));
+        }
+        Ok(())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Rank, Suit};
+    use crate::foundations::Foundations;
+    use crate::freecells::FreeCells;
+    use crate::game_state::GameStateBuilder;
+    use crate::generation::{generate_deal, generate_double_deal};
+    use crate::location::{FoundationLocation, FreecellLocation, TableauLocation};
+    use crate::tableau::Tableau;
+
+    #[test]
+    fn dealt_games_pass_integrity_check() {
+        for seed in [1, 617, 11982, 31999] {
+            assert_eq!(generate_deal(seed).unwrap().check_integrity(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn double_deal_needs_every_card_twice() {
+        let deal = generate_double_deal(3).unwrap();
+        assert_eq!(deal.check_integrity(), Ok(()));
+        assert_eq!(deal.iter_cards().count(), deal.card_count());
+
+        let mut tableau = deal.tableau().clone();
+        let removed = tableau.remove_card(TableauLocation::new(9).unwrap()).unwrap().unwrap();
+        let state = GameState::from_components(tableau, *deal.freecells(), deal.foundations().clone());
+        assert_eq!(
+            state.check_integrity(),
+            Err(IntegrityError::MissingCards(vec![removed]))
+        );
+    }
+
+    #[test]
+    fn missing_card_is_reported() {
+        let deal = generate_deal(1).unwrap();
+        let mut tableau = deal.tableau().clone();
+        let column = TableauLocation::new(0).unwrap();
+        let removed = tableau.remove_card(column).unwrap().unwrap();
+        let state = GameState::from_components(tableau, FreeCells::new(), Foundations::new());
+
+        assert_eq!(
+            state.check_integrity(),
+            Err(IntegrityError::MissingCards(vec![removed]))
+        );
+    }
+
+    #[test]
+    fn duplicate_card_is_reported() {
+        let deal = generate_deal(1).unwrap();
+        let column = TableauLocation::new(0).unwrap();
+        let top = *deal.tableau().get_card(column).unwrap().unwrap();
+        let mut freecells = FreeCells::new();
+        let cell = FreecellLocation::new(2).unwrap();
+        freecells.place_card_at(cell, top).unwrap();
+        let state = GameState::from_components(deal.tableau().clone(), freecells, Foundations::new());
+
+        assert_eq!(
+            state.check_integrity(),
+            Err(IntegrityError::DuplicateCard {
+                card: top,
+                first: Location::Tableau(column),
+                second: Location::Freecell(cell),
+            })
+        );
+    }
+
+    #[test]
+    fn partial_deals_need_every_card_below_the_ones_they_hold() {
+        let column = Location::Tableau(TableauLocation::new(0).unwrap());
+        let ace = Card::new(Rank::Ace, Suit::Clubs);
+        let three = Card::new(Rank::Three, Suit::Clubs);
+        let mut builder = GameStateBuilder::new();
+        builder.partial_deal().place(column, three).unwrap().place(column, ace).unwrap();
+        assert_eq!(
+            builder.state().check_integrity(),
+            Err(IntegrityError::StrandedCard { card: three, missing: Card::new(Rank::Two, Suit::Clubs) })
+        );
+
+        builder.place(column, Card::new(Rank::Two, Suit::Clubs)).unwrap();
+        assert_eq!(builder.state().check_integrity(), Ok(()));
+        assert!(builder.place(column, ace).is_err());
+    }
+
+    #[test]
+    fn find_card_finds_each_area() {
+        let ace = Card::new(Rank::Ace, Suit::Hearts);
+        let two = Card::new(Rank::Two, Suit::Hearts);
+        let king = Card::new(Rank::King, Suit::Clubs);
+        let mut tableau = Tableau::new();
+        tableau.place_card_at_no_checks(TableauLocation::new(5).unwrap(), king);
+        let mut freecells = FreeCells::new();
+        freecells.place_card_at(FreecellLocation::new(1).unwrap(), two).unwrap();
+        let mut foundations = Foundations::new();
+        foundations.place_card_at(FoundationLocation::new(3).unwrap(), ace).unwrap();
+        let state = GameState::from_components(tableau, freecells, foundations);
+
+        assert_eq!(state.find_card(&king), Some(Location::Tableau(TableauLocation::new(5).unwrap())));
+        assert_eq!(state.find_card(&two), Some(Location::Freecell(FreecellLocation::new(1).unwrap())));
+        assert_eq!(state.find_card(&ace), Some(Location::Foundation(FoundationLocation::new(3).unwrap())));
+        assert_eq!(state.find_card(&Card::new(Rank::Ace, Suit::Spades)), None);
+    }
+}
@@ -18,22 +18,99 @@ mod error;
 mod validation;
 mod execution;
 mod moves;
+mod integrity;
+mod card_index;
+mod builder;
+mod normalize;
+mod dirty;
+mod relocation;
+mod phase;
+mod pool;
+mod completion;
+mod autoplay;
+mod outcome;
+mod record;
 pub mod heuristics;
 
-pub use error::GameError;
+pub use error::{GameError, InvalidMoveReason};
+pub use integrity::IntegrityError;
+pub use builder::GameStateBuilder;
+pub use normalize::Normalization;
+pub use dirty::DirtyPiles;
+pub use relocation::RelocationCost;
+pub use phase::{GamePhase, ENDGAME_CARD_LIMIT};
+pub use pool::{StatePool, DEFAULT_POOL_LIMIT};
+pub use completion::FoundationEvent;
+pub use autoplay::AutoplayPolicy;
+pub use outcome::GameOutcome;
+pub use record::{MoveProvenance, MoveRecord};
 
-use crate::location::{FoundationLocation, FreecellLocation};
-use crate::tableau::{Tableau, TABLEAU_COLUMN_COUNT};
-use crate::freecells::{FreeCells, FREECELL_COUNT};
+use crate::location::{FoundationLocation, FreecellLocation, Location, TableauLocation};
+use crate::tableau::Tableau;
+use crate::freecells::FreeCells;
 use crate::foundations::{Foundations, FOUNDATION_COUNT};
+use crate::rules::{RulesProfile, WinCondition};
+use crate::variant::Variant;
 use crate::{Card, Rank, Suit};
 
 /// Represents the complete state of a FreeCell game
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Equality and hashing cover the position only: two states holding the same cards in
+/// the same places are equal and hash equally, whatever their card index or dirty piles.
+#[derive(Debug)]
 pub struct GameState {
     tableau: Tableau,
     freecells: FreeCells,
     foundations: Foundations,
+    /// Optional cache of each card's location; see [`GameState::enable_card_index`].
+    card_index: Option<Box<card_index::CardIndex>>,
+    /// Piles touched since the last [`GameState::clear_dirty`].
+    dirty: DirtyPiles,
+    /// Played with only part of the deck; see [`GameStateBuilder::partial_deal`].
+    partial_deal: bool,
+}
+
+impl Clone for GameState {
+    fn clone(&self) -> Self {
+        Self {
+            tableau: self.tableau.clone(),
+            freecells: self.freecells,
+            foundations: self.foundations.clone(),
+            card_index: self.card_index.clone(),
+            dirty: self.dirty,
+            partial_deal: self.partial_deal,
+        }
+    }
+
+    /// Reuses this state's column buffers, so [`StatePool`] can recycle states
+    /// without going through the allocator.
+    fn clone_from(&mut self, source: &Self) {
+        self.tableau.clone_from(&source.tableau);
+        self.freecells.clone_from(&source.freecells);
+        self.foundations.clone_from(&source.foundations);
+        self.card_index.clone_from(&source.card_index);
+        self.dirty = source.dirty;
+        self.partial_deal = source.partial_deal;
+    }
+}
+
+// Equality and hashing cover the position only, not the card index or dirty flags.
+impl PartialEq for GameState {
+    fn eq(&self, other: &Self) -> bool {
+        self.tableau == other.tableau
+            && self.freecells == other.freecells
+            && self.foundations == other.foundations
+    }
+}
+
+impl Eq for GameState {}
+
+impl std::hash::Hash for GameState {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.tableau.hash(state);
+        self.freecells.hash(state);
+        self.foundations.hash(state);
+    }
 }
 
 impl GameState {
@@ -43,6 +120,9 @@ impl GameState {
             tableau: Tableau::new(),
             freecells: FreeCells::new(),
             foundations: Foundations::new(),
+            card_index: None,
+            dirty: DirtyPiles::default(),
+            partial_deal: false,
         }
     }
 
@@ -52,6 +132,9 @@ impl GameState {
             tableau,
             freecells: FreeCells::new(),
             foundations: Foundations::new(),
+            card_index: None,
+            dirty: DirtyPiles::default(),
+            partial_deal: false,
         }
     }
 
@@ -61,12 +144,64 @@ impl GameState {
             tableau,
             freecells,
             foundations,
+            card_index: None,
+            dirty: DirtyPiles::default(),
+            partial_deal: false,
         }
     }
+
+    /// Create an empty board laid out for `variant`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::variant::Variant;
+    /// use freecell_game_engine::GameState;
+    ///
+    /// assert_eq!(GameState::with_variant(Variant::Standard), GameState::new());
+    /// ```
+    pub fn with_variant(variant: Variant) -> Self {
+        let rules = variant.rules();
+        let layout_error = "variant layouts fit the engine's maximum sizes";
+        rules.check_column_height().expect(layout_error);
+        Self::from_components(
+            Tableau::with_columns(rules.tableau_columns()).expect(layout_error),
+            FreeCells::with_capacity(rules.freecells()).expect(layout_error),
+            Foundations::with_decks(rules.decks()).expect(layout_error),
+        )
+    }
+
+    /// The board dimensions of this game: columns in play, usable freecells and decks.
+    ///
+    /// Reflects the current components, so a reduced freecell capacity shows up here.
+    pub fn rules(&self) -> RulesProfile {
+        let rules = RulesProfile::new(
+            self.tableau.column_count(),
+            self.freecells.capacity(),
+            self.foundations.decks(),
+        );
+        if self.partial_deal {
+            rules.with_win_condition(WinCondition::PresentCardsHome)
+        } else {
+            rules
+        }
+    }
+
+    /// Whether the game is played with only part of the deck, as built by
+    /// [`GameStateBuilder::partial_deal`]. It is won once every card it holds is home.
+    pub fn is_partial_deal(&self) -> bool {
+        self.partial_deal
+    }
+
+    /// The number of cards this game is played with: 52 per deck.
+    pub fn card_count(&self) -> usize {
+        self.rules().card_count()
+    }
     
     /// Returns an immutable reference to the game's tableau.
     ///
-    /// The tableau consists of 8 columns where most of the cards are initially dealt.
+    /// The tableau consists of 8 columns (10 in Double FreeCell) where most of the cards
+    /// are initially dealt.
     /// Cards are moved within and between tableau columns according to specific rules.
     ///
     /// # Examples
@@ -110,13 +245,20 @@ impl GameState {
     /// // You can now inspect the foundations, e.g., foundations.is_complete()
     /// ```
     pub fn foundations(&self) -> &Foundations { &self.foundations }
+
+    /// The next card each foundation needs, indexed by suit; see
+    /// [`Foundations::needed_cards`]. Allocation-free, for per-node use in searches.
+    pub fn needed_cards(&self) -> [Option<Card>; FOUNDATION_COUNT] {
+        self.foundations.needed_cards()
+    }
     
     
     /// Checks if the game has been won.
     ///
-    /// A FreeCell game is won when all cards have been successfully moved
-    /// to their respective foundation piles (i.e., all four foundation piles
-    /// are complete from Ace to King for each suit).
+    /// A FreeCell game is won when it meets the [`WinCondition`](crate::rules::WinCondition)
+    /// of its rules: in every supported variant, all cards have been moved to their
+    /// foundation piles (Ace to King per suit, twice over in Double FreeCell). See
+    /// [`outcome`](Self::outcome) to also tell lost games from undecided ones.
     ///
     /// # Returns
     ///
@@ -138,7 +280,7 @@ impl GameState {
     /// // assert!(game.is_won().unwrap());
     /// ```
     pub fn is_won(&self) -> Result<bool, GameError> {
-        Ok(self.foundations.is_complete())
+        Ok(self.rules().win_condition().is_met(self))
     }
 
     pub fn get_card(&self, location: crate::location::Location) -> Result<Option<&crate::card::Card>, GameError> {
@@ -147,20 +289,152 @@ impl GameState {
             Tableau(l) => self.tableau.get_card(l).map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: None,
-                operation: "get_card".to_string(),
+                operation: "get_card",
             }),
             Freecell(l) => self.freecells.get_card(l).map_err(|e| GameError::FreeCell {
                 error: e,
                 attempted_move: None,
-                operation: "get_card".to_string(),
+                operation: "get_card",
             }),
             Foundation(l) => self.foundations.get_card(l).map_err(|e| GameError::Foundation {
                 error: e,
                 attempted_move: None,
-                operation: "get_card".to_string(),
+                operation: "get_card",
             }),
         }
     }
+
+    /// Limits the game to the first `capacity` freecells, for reduced-freecell variants.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::FreeCell`] if `capacity` is above
+    /// [`MAX_FREECELLS`](crate::freecells::MAX_FREECELLS) or a card sits in a cell that
+    /// would be blocked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    /// use freecell_game_engine::Move;
+    ///
+    /// let mut game = generate_deal(1).unwrap();
+    /// game.set_freecell_capacity(2).unwrap();
+    /// assert!(game.is_move_valid(&Move::tableau_to_freecell(0, 1).unwrap()).is_ok());
+    /// assert!(game.is_move_valid(&Move::tableau_to_freecell(0, 2).unwrap()).is_err());
+    /// ```
+    pub fn set_freecell_capacity(&mut self, capacity: usize) -> Result<(), GameError> {
+        self.freecells
+            .set_capacity(capacity)
+            .map_err(|error| GameError::FreeCell {
+                error,
+                attempted_move: None,
+                operation: "set_freecell_capacity",
+            })
+    }
+
+    /// Iterates over every card in the game together with its current location.
+    ///
+    /// Tableau columns are visited first (each from bottom to top), then occupied
+    /// freecells, then foundation piles (each from Ace up). Tableau locations name the
+    /// column only, not the card's depth within it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    /// use freecell_game_engine::location::Location;
+    ///
+    /// let game = generate_deal(1).unwrap();
+    /// assert_eq!(game.iter_cards().count(), 52);
+    /// assert!(game.iter_cards().all(|(_, loc)| matches!(loc, Location::Tableau(_))));
+    /// ```
+    pub fn iter_cards(&self) -> impl Iterator<Item = (Card, Location)> + '_ {
+        let tableau = self.tableau.columns().enumerate().flat_map(|(index, column)| {
+            let location = Location::Tableau(TableauLocation::new(index as u8).unwrap());
+            column.iter().map(move |card| (*card, location))
+        });
+        let freecells = self.freecells.occupied_cells().map(|(index, card)| {
+            (*card, Location::Freecell(FreecellLocation::new(index as u8).unwrap()))
+        });
+        let foundations = (0..FOUNDATION_COUNT).flat_map(move |index| {
+            let pile = FoundationLocation::new(index as u8).unwrap();
+            self.foundations
+                .pile_cards(pile)
+                .map(move |card| (*card, Location::Foundation(pile)))
+        });
+        tableau.chain(freecells).chain(foundations)
+    }
+
+    /// The usable freecells that hold no card, lowest index first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    /// use freecell_game_engine::Move;
+    ///
+    /// let mut game = generate_deal(1).unwrap();
+    /// game.execute_move(&Move::tableau_to_freecell(0, 1).unwrap()).unwrap();
+    /// let empty: Vec<u8> = game.empty_freecells().map(|cell| cell.index()).collect();
+    /// assert_eq!(empty, [0, 2, 3]);
+    /// ```
+    pub fn empty_freecells(&self) -> impl Iterator<Item = FreecellLocation> + '_ {
+        (0..self.freecells.capacity() as u8)
+            .map(|index| FreecellLocation::new(index).unwrap())
+            .filter(|&cell| matches!(self.freecells.get_card(cell), Ok(None)))
+    }
+
+    /// The tableau columns in play that hold no card, lowest index first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    ///
+    /// assert_eq!(generate_deal(1).unwrap().empty_columns().count(), 0);
+    /// ```
+    pub fn empty_columns(&self) -> impl Iterator<Item = TableauLocation> + '_ {
+        self.tableau
+            .columns()
+            .enumerate()
+            .filter(|(_, column)| column.is_empty())
+            .map(|(index, _)| TableauLocation::new(index as u8).unwrap())
+    }
+
+    /// A hash of where every card is, independent of how the engine stores or hashes it.
+    ///
+    /// Each card's suit, rank and location is fed to FNV-1a in [`iter_cards`](Self::iter_cards)
+    /// order. The constants are fixed, so the value is the same on every build and
+    /// platform and only changes when the position does; recorded fingerprints can be
+    /// compared against later builds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    ///
+    /// assert_eq!(generate_deal(1).unwrap().fingerprint(), generate_deal(1).unwrap().fingerprint());
+    /// assert_ne!(generate_deal(1).unwrap().fingerprint(), generate_deal(2).unwrap().fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |byte: u8| {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        };
+        for (card, location) in self.iter_cards() {
+            let (kind, index) = match location {
+                Location::Tableau(column) => (0, column.index()),
+                Location::Freecell(cell) => (1, cell.index()),
+                Location::Foundation(pile) => (2, pile.index()),
+            };
+            for byte in [card.suit().foundation_index(), card.rank() as u8, kind, index] {
+                feed(byte);
+            }
+        }
+        hash
+    }
 }
 
 impl Default for GameState {
@@ -170,6 +444,9 @@ impl Default for GameState {
             tableau: Tableau::new(),
             freecells: FreeCells::new(),
             foundations: Foundations::new(),
+            card_index: None,
+            dirty: DirtyPiles::default(),
+            partial_deal: false,
         }
     }
 }
@@ -187,7 +464,7 @@ impl std::fmt::Display for GameState {
             write!(f, "{} ", card_display)?;
         }
         write!(f, "  ")?;
-        for i in 0..FREECELL_COUNT {
+        for i in 0..self.freecells.slot_count() {
             let location = FreecellLocation::new(i as u8).unwrap();
             let card_display = match self.freecells.get_card(location) {
                 Ok(Some(card)) => fmt_card(card),
@@ -202,7 +479,7 @@ impl std::fmt::Display for GameState {
         while more_rows {
             more_rows = false;
             let mut row = String::new();
-            for i in 0..TABLEAU_COLUMN_COUNT {
+            for i in 0..self.tableau.column_count() {
                 let column_result = self.tableau.get_column(i);
                 let card_display = match column_result {
                     Ok(column) => match column.get(row_index) {
@@ -4,10 +4,7 @@
 //! It contains methods to identify possible moves between tableau columns, freecells and foundations.
 
 use super::GameState;
-use crate::{
-    freecells::FREECELL_COUNT, location::FoundationLocation,
-    r#move::Move, tableau::TABLEAU_COLUMN_COUNT,
-};
+use crate::{location::FoundationLocation, location::Location, r#move::Move};
 
 impl GameState {
     /// Returns all valid moves from the current state.
@@ -22,6 +19,20 @@ impl GameState {
     /// A `Vec<Move>` containing all legal moves that can be made from the
     /// current game state, including both single-card and multi-card sequences.
     ///
+    /// # Ordering
+    ///
+    /// The order is part of the contract. It depends only on the position, never on
+    /// hashing, allocation or platform, so solvers that take the first good move and
+    /// snapshot tests see the same sequence on every build. Moves are grouped as:
+    ///
+    /// 1. tableau to foundation
+    /// 2. freecell to foundation
+    /// 3. freecell to tableau
+    /// 4. tableau to tableau
+    /// 5. tableau to freecell (only to the lowest-numbered empty freecell)
+    ///
+    /// Within a group, moves are ordered by source index, then by destination index.
+    ///
     /// # Examples
     ///
     /// ```
@@ -42,6 +53,49 @@ impl GameState {
         moves
     }
 
+    /// Counts the moves [`get_available_moves`](Self::get_available_moves) would return,
+    /// without building them.
+    ///
+    /// Meant for mobility heuristics and deadlock checks, which only need the number: it
+    /// allocates nothing and reads each pile's top card once. A count of zero means the
+    /// position has no legal move at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    ///
+    /// let game = generate_deal(1).unwrap();
+    /// assert_eq!(game.count_available_moves(), game.get_available_moves().len());
+    /// ```
+    pub fn count_available_moves(&self) -> usize {
+        let tableau = self.tableau();
+        let columns = tableau.column_count();
+        let to_foundation = |card: &crate::card::Card| {
+            let pile = FoundationLocation::new(card.suit().foundation_index()).unwrap();
+            self.foundations().validate_card_placement(pile, card).is_ok()
+        };
+        let onto_columns = |card: &crate::card::Card, skip: Option<usize>| {
+            (0..columns)
+                .filter(|&to_col| Some(to_col) != skip)
+                .filter(|&to_col| tableau.validate_card_placement_raw(to_col, card).is_ok())
+                .count()
+        };
+
+        let mut count = 0;
+        let capacity = self.freecells().capacity();
+        for (_, card) in self.freecells().occupied_cells().filter(|&(cell, _)| cell < capacity) {
+            count += usize::from(to_foundation(card)) + onto_columns(card, None);
+        }
+        let has_empty_cell = self.freecells().empty_cells_count() > 0;
+        for from_col in 0..columns {
+            let Ok(Some(card)) = tableau.get_card_raw(from_col) else { continue };
+            count += usize::from(to_foundation(card)) + onto_columns(card, Some(from_col));
+            count += usize::from(has_empty_cell);
+        }
+        count
+    }
+
     /// Generates all valid moves from tableau columns to foundation piles.
     ///
     /// This method iterates through all tableau columns and checks if their
@@ -67,7 +121,7 @@ impl GameState {
     /// // assert!(moves.contains(&Move::TableauToFoundation { from: TableauLocation::new(0).unwrap(), to: FoundationLocation::new(0).unwrap() }));
     /// ```
     pub fn get_tableau_to_foundation_moves(&self, moves: &mut Vec<Move>) {
-        for from_col in 0..TABLEAU_COLUMN_COUNT {
+        for from_col in 0..self.tableau().column_count() {
             let location = crate::location::TableauLocation::new(from_col as u8).unwrap();
             let card_result = self.tableau().get_card(location);
             let card = match card_result {
@@ -114,7 +168,7 @@ impl GameState {
     /// // assert!(moves.contains(&Move::FreecellToFoundation { from: FreecellLocation::new(0).unwrap(), to: FoundationLocation::new(0).unwrap() }));
     /// ```
     pub fn get_freecell_to_foundation_moves(&self, moves: &mut Vec<Move>) {
-        for from_cell in 0..FREECELL_COUNT {
+        for from_cell in 0..self.freecells().capacity() {
             let location = crate::location::FreecellLocation::new(from_cell as u8).unwrap();
             let card_result = self.freecells().get_card(location);
             let card = match card_result {
@@ -163,7 +217,7 @@ impl GameState {
     /// // assert!(moves.contains(&Move::FreecellToTableau { from: FreecellLocation::new(0).unwrap(), to: TableauLocation::new(0).unwrap() }));
     /// ```
     pub fn get_freecell_to_tableau_moves(&self, moves: &mut Vec<Move>) {
-        for from_cell in 0..crate::freecells::FREECELL_COUNT {
+        for from_cell in 0..self.freecells().capacity() {
             let location = crate::location::FreecellLocation::new(from_cell as u8).unwrap();
             let card_result = self.freecells().get_card(location);
             let card = match card_result {
@@ -171,7 +225,7 @@ impl GameState {
                 _ => continue, // Skip this cell if no card or error
             };
 
-            for to_col in 0..TABLEAU_COLUMN_COUNT {
+            for to_col in 0..self.tableau().column_count() {
                 let to_location = crate::location::TableauLocation::new(to_col as u8).unwrap();
                 if self
                     .tableau()
@@ -212,22 +266,8 @@ impl GameState {
     /// // With 4 empty freecells and 0 empty columns: (4+1) * 2^0 = 5
     /// ```
     fn calculate_max_movable_cards(&self) -> usize {
-        // Count empty freecells
-        let mut empty_freecells = 0;
-        for cell in 0..crate::freecells::FREECELL_COUNT {
-            let location = crate::location::FreecellLocation::new(cell as u8).unwrap();
-            if self
-                .freecells()
-                .get_card(location)
-                .unwrap_or(None)
-                .is_none()
-            {
-                empty_freecells += 1;
-            }
-        }
-
-        // Count empty tableau columns
-        let empty_tableau_columns = self.tableau().empty_columns_count();
+        let empty_freecells = self.empty_freecells().count();
+        let empty_tableau_columns = self.empty_columns().count();
 
         // Cap empty_tableau_columns to prevent overflow (2^20 is reasonable upper bound)
         let capped_empty_columns = empty_tableau_columns.min(20);
@@ -391,13 +431,13 @@ impl GameState {
     //         return;
     //     }
 
-    //     for from_col in 0..TABLEAU_COLUMN_COUNT {
+    //     for from_col in 0..self.tableau().column_count() {
     //         let sequence = self.get_movable_sequence_from_column(from_col);
     //         if sequence.is_empty() {
     //             continue;
     //         }
 
-    //         for to_col in 0..TABLEAU_COLUMN_COUNT {
+    //         for to_col in 0..self.tableau().column_count() {
     //             if from_col == to_col {
     //                 continue;
     //             }
@@ -428,14 +468,14 @@ impl GameState {
     // }
 
     pub fn get_tableau_to_tableau_moves_single_card(&self, moves: &mut Vec<Move>) {
-        for from_col in 0..TABLEAU_COLUMN_COUNT {
+        for from_col in 0..self.tableau().column_count() {
             let card_result = self.tableau().get_card_raw(from_col);
             let card = match card_result {
                 Ok(Some(card)) => card,
                 _ => continue, // Skip this cell if no card or error
             };
 
-            for to_col in 0..TABLEAU_COLUMN_COUNT {
+            for to_col in 0..self.tableau().column_count() {
                 if from_col == to_col {
                     continue;
                 }
@@ -477,28 +517,97 @@ impl GameState {
     /// // assert!(moves.contains(&Move::TableauToFreecell { from: TableauLocation::new(0).unwrap(), to: FreecellLocation::new(0).unwrap() }));
     /// ```
     pub fn get_tableau_to_freecell_moves(&self, moves: &mut Vec<Move>) {
-        for from_col in 0..TABLEAU_COLUMN_COUNT {
+        // Only one move per tableau column, to the first empty freecell
+        let Some(to_cell) = self.empty_freecells().next() else {
+            return;
+        };
+        for from_col in 0..self.tableau().column_count() {
             let location = crate::location::TableauLocation::new(from_col as u8).unwrap();
-            let card_result = self.tableau().get_card(location);
-            let _card = match card_result {
-                Ok(Some(card)) => card,
-                _ => continue, // Skip this column if no card or error
-            };
+            if let Ok(Some(_)) = self.tableau().get_card(location) {
+                moves.push(Move::single(Location::Tableau(location), Location::Freecell(to_cell)));
+            }
+        }
+    }
+}
 
-            // Find the first available freecell and add only one move per tableau column
-            for to_cell in 0..crate::freecells::FREECELL_COUNT {
-                let location = crate::location::FreecellLocation::new(to_cell as u8).unwrap();
-                if self
-                    .freecells()
-                    .get_card(location)
-                    .unwrap_or(None)
-                    .is_none()
-                {
-                    if let Ok(m) = Move::tableau_to_freecell(from_col as u8, to_cell as u8) {
-                        moves.push(m);
-                        break; // Only add move to first available freecell, then move to next tableau column
-                    }
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+    use crate::game_state::GameStateBuilder;
+    use crate::generation::generate_deal;
+    use crate::location::{FreecellLocation, Location, TableauLocation};
+
+    /// Sort key matching the documented order: group, then source, then destination.
+    fn order_key(m: &Move) -> (u8, u8, u8) {
+        let index = |location: Location| match location {
+            Location::Tableau(l) => l.index(),
+            Location::Freecell(l) => l.index(),
+            Location::Foundation(l) => l.index(),
+        };
+        let group = match (m.source, m.destination) {
+            (Location::Tableau(_), Location::Foundation(_)) => 0,
+            (Location::Freecell(_), Location::Foundation(_)) => 1,
+            (Location::Freecell(_), Location::Tableau(_)) => 2,
+            (Location::Tableau(_), Location::Tableau(_)) => 3,
+            _ => 4,
+        };
+        (group, index(m.source), index(m.destination))
+    }
+
+    #[test]
+    fn moves_come_in_documented_order() {
+        let mut builder = GameStateBuilder::new();
+        let columns = [
+            (Rank::Ace, Suit::Spades),
+            (Rank::Five, Suit::Diamonds),
+            (Rank::Six, Suit::Clubs),
+            (Rank::Nine, Suit::Hearts),
+            (Rank::Nine, Suit::Spades),
+            (Rank::Eight, Suit::Clubs),
+            (Rank::King, Suit::Diamonds),
+            (Rank::King, Suit::Clubs),
+        ];
+        for (col, (rank, suit)) in columns.into_iter().enumerate() {
+            let location = Location::Tableau(TableauLocation::new(col as u8).unwrap());
+            builder.place(location, Card::new(rank, suit)).unwrap();
+        }
+        let cell = |i| Location::Freecell(FreecellLocation::new(i).unwrap());
+        builder.place(cell(0), Card::new(Rank::Ace, Suit::Hearts)).unwrap();
+        builder.place(cell(1), Card::new(Rank::Four, Suit::Spades)).unwrap();
+
+        let mut expected = vec![
+            Move::tableau_to_foundation(0, 0).unwrap(),
+            Move::freecell_to_foundation(0, 1).unwrap(),
+            Move::freecell_to_tableau(1, 1).unwrap(),
+            Move::tableau_to_tableau(1, 2).unwrap(),
+            Move::tableau_to_tableau(5, 3).unwrap(),
+        ];
+        expected.extend((0..8).map(|col| Move::tableau_to_freecell(col, 2).unwrap()));
+        assert_eq!(builder.state().get_available_moves(), expected);
+    }
+
+    #[test]
+    fn dealt_positions_follow_the_order_along_a_game() {
+        let mut game = generate_deal(7).unwrap();
+        for _ in 0..20 {
+            let moves = game.get_available_moves();
+            assert!(moves.windows(2).all(|w| order_key(&w[0]) < order_key(&w[1])), "{:?}", moves);
+            assert_eq!(game.clone().get_available_moves(), moves);
+            let Some(mv) = moves.first() else { break };
+            game.execute_move(mv).unwrap();
+        }
+    }
+
+    #[test]
+    fn count_matches_generated_moves_along_a_game() {
+        for seed in 1..=20 {
+            let mut game = generate_deal(seed).unwrap();
+            for step in 0..40 {
+                let moves = game.get_available_moves();
+                assert_eq!(game.count_available_moves(), moves.len(), "seed {} step {}", seed, step);
+                let Some(m) = moves.get(step % moves.len().max(1)) else { break };
+                game.execute_move(m).unwrap();
             }
         }
     }
@@ -0,0 +1,257 @@
+//! Canonical rearrangement of a `GameState`.
+//!
+//! Two positions that differ only in *which* column, freecell or foundation pile holds
+//! what are the same position for every practical purpose. [`GameState::normalize`]
+//! returns the canonical representative of such a family, together with a
+//! [`Normalization`] that translates locations and moves from the original layout into
+//! the normalized one.
+//!
+//! The ordering matches the one the tableau and freecells use for
+//! `extract_canonical_data`:
+//!
+//! - tableau columns are sorted by their bottom card, empty columns last;
+//! - occupied freecells are sorted by card, empty cells last;
+//! - each started foundation pile moves to its suit's
+//!   [`foundation_index`](crate::card::Suit::foundation_index), and empty piles fill the rest.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::generation::generate_deal;
+//! use freecell_game_engine::Move;
+//!
+//! let mut a = generate_deal(1).unwrap();
+//! let mut b = a.clone();
+//! a.execute_move(&Move::tableau_to_freecell(0, 0).unwrap()).unwrap();
+//! b.execute_move(&Move::tableau_to_freecell(0, 3).unwrap()).unwrap();
+//!
+//! assert_ne!(a, b);
+//! assert_eq!(a.normalize().0, b.normalize().0);
+//! assert!(a.eq_canonical(&b));
+//! ```
+//!
+//! # Two notions of equality
+//!
+//! `==` (and `Hash`) compare positions pile by pile: the same cards in the same columns,
+//! cells and piles. [`GameState::eq_canonical`] compares them up to the rearrangement
+//! above. Equal states are always canonically equal. Solvers that pack states keep the
+//! two apart the same way: the plain packed form is equal exactly when the states are
+//! `==`, and the canonical packed form exactly when they are `eq_canonical`, for
+//! positions that hold the whole deck under the same rules.
+
+use super::GameState;
+use crate::card::Card;
+use crate::foundations::{Foundations, FOUNDATION_COUNT};
+use crate::freecells::{FreeCells, MAX_FREECELLS};
+use crate::location::{FoundationLocation, FreecellLocation, Location, TableauLocation};
+use crate::r#move::Move;
+use crate::tableau::{Tableau, MAX_TABLEAU_COLUMNS};
+
+/// How [`GameState::normalize`] rearranged a position.
+///
+/// Each array maps an original index to its normalized index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Normalization {
+    columns: [u8; MAX_TABLEAU_COLUMNS],
+    freecells: [u8; MAX_FREECELLS],
+    foundations: [u8; FOUNDATION_COUNT],
+}
+
+impl Normalization {
+    /// Translates a location in the original position to the normalized one.
+    pub fn map_location(&self, location: Location) -> Location {
+        match location {
+            Location::Tableau(column) => Location::Tableau(
+                TableauLocation::new(self.columns[column.index() as usize]).unwrap(),
+            ),
+            Location::Freecell(cell) => Location::Freecell(
+                FreecellLocation::new(self.freecells[cell.index() as usize]).unwrap(),
+            ),
+            Location::Foundation(pile) => Location::Foundation(
+                FoundationLocation::new(self.foundations[pile.index() as usize]).unwrap(),
+            ),
+        }
+    }
+
+    /// Translates a move in the original position to the equivalent normalized move.
+    pub fn map_move(&self, m: &Move) -> Move {
+        Move::single(self.map_location(m.source), self.map_location(m.destination))
+    }
+}
+
+/// Same card ordering the canonical packers use.
+fn sort_key(card: &Card) -> u8 {
+    card.suit() as u8 * 13 + card.rank() as u8
+}
+
+/// Turns "normalized slot `i` came from original index `order[i]`" into the
+/// original-to-normalized map.
+fn invert<const N: usize>(order: [usize; N]) -> [u8; N] {
+    let mut map = [0u8; N];
+    for (normalized, original) in order.into_iter().enumerate() {
+        map[original] = normalized as u8;
+    }
+    map
+}
+
+impl GameState {
+    /// Returns the canonical equivalent of this position and the mapping used to get there.
+    ///
+    /// The normalized state holds the same cards in the same stacking order, with the
+    /// same freecell capacity, so every move that is legal here is legal there once it
+    /// goes through [`Normalization::map_move`]. The card index is rebuilt if it was enabled.
+    pub fn normalize(&self) -> (GameState, Normalization) {
+        // Only in-play columns and cells are reordered; the rest map to themselves.
+        let mut columns: [usize; MAX_TABLEAU_COLUMNS] = std::array::from_fn(|i| i);
+        for (slot, &column) in columns.iter_mut().zip(self.tableau.canonical_order()) {
+            *slot = column as usize;
+        }
+
+        let mut cells: [usize; MAX_FREECELLS] = std::array::from_fn(|i| i);
+        let cell_cards: [Option<Card>; MAX_FREECELLS] = std::array::from_fn(|i| {
+            self.freecells.get_card(FreecellLocation::new(i as u8).unwrap()).ok().flatten().copied()
+        });
+        cells[..self.freecells.capacity()]
+            .sort_by_key(|&i| (cell_cards[i].is_none(), cell_cards[i].as_ref().map(sort_key)));
+
+        let mut piles: [Option<usize>; FOUNDATION_COUNT] = [None; FOUNDATION_COUNT];
+        let mut empty_piles = Vec::new();
+        for i in 0..FOUNDATION_COUNT {
+            let location = FoundationLocation::new(i as u8).unwrap();
+            match self.foundations.get_card(location).ok().flatten() {
+                Some(top) => piles[top.suit().foundation_index() as usize] = Some(i),
+                None => empty_piles.push(i),
+            }
+        }
+        let mut empty_piles = empty_piles.into_iter();
+        let piles: [usize; FOUNDATION_COUNT] =
+            std::array::from_fn(|slot| piles[slot].unwrap_or_else(|| empty_piles.next().unwrap()));
+
+        let mut tableau = Tableau::with_columns(self.tableau.column_count())
+            .expect("column count comes from an existing Tableau");
+        let original_columns: Vec<&Vec<Card>> = self.tableau.columns().collect();
+        for (normalized, &original) in columns[..original_columns.len()].iter().enumerate() {
+            let location = TableauLocation::new(normalized as u8).unwrap();
+            for card in original_columns[original] {
+                tableau.place_card_at_no_checks(location, *card);
+            }
+        }
+
+        let mut freecells = FreeCells::with_capacity(self.freecells.capacity())
+            .expect("capacity comes from an existing FreeCells");
+        for (normalized, &original) in cells.iter().enumerate() {
+            if let Some(card) = cell_cards[original] {
+                freecells.place_card_at_no_checks(FreecellLocation::new(normalized as u8).unwrap(), card);
+            }
+        }
+
+        let mut foundations = Foundations::with_decks(self.foundations.decks())
+            .expect("deck count comes from existing Foundations");
+        for (normalized, &original) in piles.iter().enumerate() {
+            let target = FoundationLocation::new(normalized as u8).unwrap();
+            let source = FoundationLocation::new(original as u8).unwrap();
+            for card in self.foundations.pile_cards(source) {
+                foundations.place_card_at_no_checks(target, *card);
+            }
+        }
+
+        let mut normalized = GameState::from_components(tableau, freecells, foundations);
+        normalized.partial_deal = self.partial_deal;
+        if self.has_card_index() {
+            normalized.enable_card_index();
+        }
+        let mapping = Normalization {
+            columns: invert(columns),
+            freecells: invert(cells),
+            foundations: invert(piles),
+        };
+        (normalized, mapping)
+    }
+
+    /// Whether the two positions are the same up to which column, freecell and foundation
+    /// pile holds what: whether they [`normalize`](Self::normalize) to equal states.
+    ///
+    /// Implied by `==`; positions with different rules are never canonically equal.
+    pub fn eq_canonical(&self, other: &GameState) -> bool {
+        self.rules() == other.rules() && self.normalize().0 == other.normalize().0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::generate_deal;
+
+    #[test]
+    fn eq_canonical_is_implied_by_eq_and_ignores_placement() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let deal = generate_deal(5).unwrap();
+        let mut indexed = deal.clone();
+        indexed.enable_card_index();
+        // Equality and hashing ignore the card index and dirty piles.
+        assert_eq!(deal, indexed);
+        let hasher = RandomState::new();
+        assert_eq!(hasher.hash_one(&deal), hasher.hash_one(&indexed));
+        assert!(deal.eq_canonical(&indexed));
+
+        let mut a = deal.clone();
+        let mut b = deal.clone();
+        a.execute_move(&Move::tableau_to_freecell(4, 0).unwrap()).unwrap();
+        b.execute_move(&Move::tableau_to_freecell(4, 2).unwrap()).unwrap();
+        assert_ne!(a, b);
+        assert!(a.eq_canonical(&b) && b.eq_canonical(&a));
+        assert!(!a.eq_canonical(&deal));
+
+        let mut fewer_cells = a.clone();
+        fewer_cells.set_freecell_capacity(3).unwrap();
+        assert!(!fewer_cells.eq_canonical(&a));
+    }
+
+    #[test]
+    fn normalize_is_idempotent() {
+        let mut game = generate_deal(617).unwrap();
+        for _ in 0..15 {
+            let Some(mv) = game.get_available_moves().into_iter().last() else {
+                break;
+            };
+            game.execute_move(&mv).unwrap();
+        }
+        let (once, _) = game.normalize();
+        let (twice, mapping) = once.normalize();
+        assert_eq!(once, twice);
+        let identity = Move::tableau_to_freecell(3, 1).unwrap();
+        assert_eq!(mapping.map_move(&identity), identity);
+    }
+
+    #[test]
+    fn mapped_moves_replay_on_normalized_state() {
+        let mut game = generate_deal(1).unwrap();
+        let (mut normalized, mapping) = game.normalize();
+        for _ in 0..25 {
+            let Some(mv) = game.get_available_moves().into_iter().next() else {
+                break;
+            };
+            game.execute_move(&mv).unwrap();
+            normalized.execute_move(&mapping.map_move(&mv)).unwrap();
+        }
+        assert_eq!(game.normalize().0, normalized.normalize().0);
+        assert_eq!(normalized.check_integrity(), Ok(()));
+    }
+
+    #[test]
+    fn foundation_piles_move_to_their_suit_index() {
+        let mut foundations = Foundations::new();
+        let hearts_ace = Card::new(crate::card::Rank::Ace, crate::card::Suit::Hearts);
+        foundations.place_card_at(FoundationLocation::new(3).unwrap(), hearts_ace).unwrap();
+        let game = GameState::from_components(Tableau::new(), FreeCells::new(), foundations);
+
+        let (normalized, mapping) = game.normalize();
+        let hearts_pile = FoundationLocation::new(hearts_ace.suit().foundation_index()).unwrap();
+        assert_eq!(normalized.foundations().get_card(hearts_pile).unwrap(), Some(&hearts_ace));
+        assert_eq!(
+            mapping.map_location(Location::Foundation(FoundationLocation::new(3).unwrap())),
+            Location::Foundation(hearts_pile)
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Whether a game is over, and how it ended.
+//!
+//! [`GameState::is_won`] answers only half the question a search or a front end asks
+//! after every move. [`GameState::outcome`] answers all of it: won under the variant's
+//! [`WinCondition`](crate::rules::WinCondition), lost because no legal move is left, or
+//! still undecided. A game that still has moves may be unwinnable, but telling that
+//! apart takes a search, so the engine leaves it [`Undecided`](GameOutcome::Undecided).
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::game_state::GameOutcome;
+//! use freecell_game_engine::generation::generate_deal;
+//! use freecell_game_engine::GameState;
+//!
+//! assert_eq!(generate_deal(1).unwrap().outcome(), GameOutcome::Undecided);
+//! // An empty board has nothing to move and nothing home.
+//! assert_eq!(GameState::new().outcome(), GameOutcome::Lost);
+//! ```
+
+use super::GameState;
+
+/// How a game stands, as returned by [`GameState::outcome`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameOutcome {
+    /// The variant's win condition is met.
+    Won,
+    /// Not won, and no legal move is left.
+    Lost,
+    /// Moves remain; whether they lead to a win is not known.
+    Undecided,
+}
+
+impl GameOutcome {
+    /// Whether the game is over, won or lost.
+    pub fn is_terminal(self) -> bool {
+        self != GameOutcome::Undecided
+    }
+}
+
+impl GameState {
+    /// Classifies the position as won, lost or undecided.
+    ///
+    /// A won game is never reported lost, though a finished game has no moves either.
+    pub fn outcome(&self) -> GameOutcome {
+        if self.rules().win_condition().is_met(self) {
+            GameOutcome::Won
+        } else if self.count_available_moves() == 0 {
+            GameOutcome::Lost
+        } else {
+            GameOutcome::Undecided
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+    use crate::game_state::GameStateBuilder;
+    use crate::location::{FoundationLocation, Location, TableauLocation};
+    use crate::variant::Variant;
+
+    /// A Double FreeCell board with every card home except one King of spades.
+    fn double_one_king_out() -> GameState {
+        let mut builder = GameStateBuilder::for_variant(Variant::DoubleFreecell);
+        for (pile, suit) in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs].into_iter().enumerate() {
+            let location = Location::Foundation(FoundationLocation::new(pile as u8).unwrap());
+            let cards = if suit == Suit::Spades { 25 } else { 26 };
+            for index in 0..cards {
+                builder.place(location, Card::new(Rank::try_from(index % 13 + 1).unwrap(), suit)).unwrap();
+            }
+        }
+        builder.column(TableauLocation::new(0).unwrap(), [Card::new(Rank::King, Suit::Spades)]).unwrap();
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn double_freecell_is_won_only_with_both_decks_home() {
+        let mut game = double_one_king_out();
+        // One deck's worth is home on every pile, which would win a standard game.
+        assert!(!game.is_won().unwrap());
+        assert_eq!(game.outcome(), GameOutcome::Undecided);
+
+        game.execute_move(&crate::Move::tableau_to_foundation(0, 0).unwrap()).unwrap();
+        assert!(game.is_won().unwrap());
+        assert_eq!(game.outcome(), GameOutcome::Won);
+        assert!(game.outcome().is_terminal());
+    }
+}
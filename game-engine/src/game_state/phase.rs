@@ -0,0 +1,109 @@
+//! Coarse classification of how far a game has progressed.
+//!
+//! Strategies switch tactics as a game goes on (an exact search only pays off once few
+//! cards are left) and hint systems can afford to be more forthcoming late in a game.
+//! [`GameState::phase`] gives both a shared answer based on foundation progress and on
+//! how much room the position has to manoeuvre.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::game_state::GamePhase;
+//! use freecell_game_engine::generation::generate_deal;
+//!
+//! let game = generate_deal(1).unwrap();
+//! assert_eq!(game.phase(), GamePhase::Opening);
+//! ```
+
+use super::GameState;
+
+/// Positions with at most this many cards outside the foundations are always endgames.
+pub const ENDGAME_CARD_LIMIT: usize = 12;
+
+/// The stage of a game, as returned by [`GameState::phase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GamePhase {
+    /// Few cards are home and no column has been cleared yet.
+    Opening,
+    Midgame,
+    /// Few cards are left in play, or half the deck is home with at least two empty
+    /// columns to work with.
+    Endgame,
+}
+
+impl GameState {
+    /// Classifies the position as opening, midgame or endgame.
+    ///
+    /// * Endgame: at most [`ENDGAME_CARD_LIMIT`] cards outside the foundations, or at
+    ///   least half the deck home and two or more empty columns.
+    /// * Opening: fewer than an eighth of the deck home and no empty column.
+    /// * Midgame: everything in between.
+    ///
+    /// Thresholds scale with the variant's deck size.
+    pub fn phase(&self) -> GamePhase {
+        let deck = self.card_count();
+        let home = self.foundations().total_cards();
+        let empty_columns = self.tableau().empty_columns_count();
+
+        if deck - home <= ENDGAME_CARD_LIMIT || (home * 2 >= deck && empty_columns >= 2) {
+            GamePhase::Endgame
+        } else if home * 8 < deck && empty_columns == 0 {
+            GamePhase::Opening
+        } else {
+            GamePhase::Midgame
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+    use crate::game_state::GameStateBuilder;
+    use crate::location::{FoundationLocation, Location};
+
+    const SUITS: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    /// A builder with each suit's foundation filled up to `top` and nothing else placed.
+    fn with_foundations(top: u8) -> GameStateBuilder {
+        let mut builder = GameStateBuilder::new();
+        for (pile, suit) in SUITS.into_iter().enumerate() {
+            let location = Location::Foundation(FoundationLocation::new(pile as u8).unwrap());
+            for rank in 1..=top {
+                builder.place(location, Card::new(Rank::try_from(rank).unwrap(), suit)).unwrap();
+            }
+        }
+        builder
+    }
+
+    #[test]
+    fn empty_columns_open_up_the_game() {
+        // Only the foundations are filled, so every column is empty: mobility alone
+        // makes a position with one card home a midgame.
+        assert_eq!(with_foundations(1).state().phase(), GamePhase::Midgame);
+        assert_eq!(with_foundations(7).state().phase(), GamePhase::Endgame);
+        assert_eq!(with_foundations(10).state().phase(), GamePhase::Endgame);
+    }
+
+    #[test]
+    fn few_cards_left_is_an_endgame_without_empty_columns() {
+        let mut builder = with_foundations(10);
+        let mut remaining = SUITS
+            .into_iter()
+            .flat_map(|suit| [Rank::Jack, Rank::Queen, Rank::King].map(|rank| Card::new(rank, suit)));
+        for column in 0..8 {
+            let location = crate::location::TableauLocation::new(column).unwrap();
+            builder.column(location, remaining.next()).unwrap();
+        }
+        let game = builder.state();
+        assert_eq!(game.tableau().empty_columns_count(), 0);
+        assert_eq!(game.phase(), GamePhase::Endgame);
+    }
+
+    #[test]
+    fn a_fresh_deal_is_an_opening() {
+        let game = crate::generation::generate_deal(617).unwrap();
+        assert_eq!(game.phase(), GamePhase::Opening);
+        assert!(GamePhase::Opening < GamePhase::Midgame && GamePhase::Midgame < GamePhase::Endgame);
+    }
+}
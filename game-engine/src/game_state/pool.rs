@@ -0,0 +1,118 @@
+//! Recycling `GameState` buffers.
+//!
+//! A search that keeps whole states around (work queues, frontiers) clones one for
+//! every node it queues and drops it soon after, so most of its allocator traffic is
+//! tableau columns being freed and allocated again. A [`StatePool`] holds on to states
+//! that are no longer needed and copies the next position into their existing column
+//! buffers instead.
+//!
+//! Pools are not shared: give each worker thread its own. A state acquired from one
+//! pool can be released into any other.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::game_state::StatePool;
+//! use freecell_game_engine::generation::generate_deal;
+//!
+//! let deal = generate_deal(1).unwrap();
+//! let mut pool = StatePool::new();
+//!
+//! let copy = pool.acquire_clone_of(&deal);
+//! assert_eq!(copy, deal);
+//! pool.release(copy);
+//! assert_eq!(pool.idle(), 1);
+//!
+//! // The released state's buffers now hold the next copy.
+//! let again = pool.acquire_clone_of(&deal);
+//! assert_eq!(again, deal);
+//! assert_eq!(pool.idle(), 0);
+//! ```
+
+use super::GameState;
+
+/// Idle states a pool keeps by default before dropping released ones.
+pub const DEFAULT_POOL_LIMIT: usize = 1024;
+
+/// A free list of states whose buffers are reused by [`StatePool::acquire_clone_of`].
+#[derive(Debug)]
+pub struct StatePool {
+    free: Vec<GameState>,
+    limit: usize,
+}
+
+impl StatePool {
+    /// An empty pool holding at most [`DEFAULT_POOL_LIMIT`] idle states.
+    pub fn new() -> Self {
+        Self::with_limit(DEFAULT_POOL_LIMIT)
+    }
+
+    /// An empty pool holding at most `limit` idle states; further releases are dropped.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            free: Vec::new(),
+            limit,
+        }
+    }
+
+    /// A copy of `source`, written into a released state's buffers when one is idle.
+    ///
+    /// The copy is equal to `source`, including its card index and dirty piles.
+    pub fn acquire_clone_of(&mut self, source: &GameState) -> GameState {
+        match self.free.pop() {
+            Some(mut state) => {
+                state.clone_from(source);
+                state
+            }
+            None => source.clone(),
+        }
+    }
+
+    /// Hands a state back for reuse. Dropped instead once the pool is at its limit.
+    pub fn release(&mut self, state: GameState) {
+        if self.free.len() < self.limit {
+            self.free.push(state);
+        }
+    }
+
+    /// Number of released states waiting to be reused.
+    pub fn idle(&self) -> usize {
+        self.free.len()
+    }
+}
+
+impl Default for StatePool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::generate_deal;
+    use crate::r#move::Move;
+
+    #[test]
+    fn recycled_state_matches_source_exactly() {
+        let mut pool = StatePool::new();
+        let mut used = generate_deal(2).unwrap();
+        used.enable_card_index();
+        used.execute_move(&Move::tableau_to_freecell(0, 0).unwrap()).unwrap();
+        pool.release(used);
+
+        let source = generate_deal(7).unwrap();
+        let copy = pool.acquire_clone_of(&source);
+        assert_eq!(copy, source);
+        assert_eq!(copy.dirty_piles(), source.dirty_piles());
+        assert!(!copy.has_card_index());
+    }
+
+    #[test]
+    fn releases_past_the_limit_are_dropped() {
+        let mut pool = StatePool::with_limit(1);
+        pool.release(GameState::new());
+        pool.release(GameState::new());
+        assert_eq!(pool.idle(), 1);
+    }
+}
@@ -0,0 +1,144 @@
+//! Recording who made each move.
+//!
+//! A game's history mixes moves the player chose with moves the program made for them:
+//! autoplay sending safe cards home, and auto-finish playing out a won game. UIs animate
+//! the two differently, and statistics count only the player's decisions. A
+//! [`MoveRecord`] pairs each move with its [`MoveProvenance`].
+//! [`GameState::play_recorded`] plays a player's move followed by the autoplay it
+//! triggers, and [`GameState::play_auto_finish_recorded`] the auto-finish, appending
+//! records of both.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::card::{Card, Rank, Suit};
+//! use freecell_game_engine::game_state::{AutoplayPolicy, GameStateBuilder, MoveProvenance, MoveRecord};
+//! use freecell_game_engine::location::{FoundationLocation, Location, TableauLocation};
+//! use freecell_game_engine::Move;
+//!
+//! // The Ace of Spades is under the Two, with a free column beside them.
+//! let mut builder = GameStateBuilder::new();
+//! let column = TableauLocation::new(0).unwrap();
+//! builder.column(column, [Card::new(Rank::Ace, Suit::Spades), Card::new(Rank::Two, Suit::Spades)]).unwrap();
+//! let mut game = builder.state().clone();
+//!
+//! let mut history = Vec::new();
+//! game.play_recorded(&Move::tableau_to_tableau(0, 1).unwrap(), AutoplayPolicy::Safe, &mut history).unwrap();
+//! let provenance: Vec<_> = history.iter().map(|record| record.provenance).collect();
+//! assert_eq!(provenance, [MoveProvenance::Player, MoveProvenance::Autoplay, MoveProvenance::Autoplay]);
+//! assert_eq!(MoveRecord::player_moves(&history), 1);
+//! ```
+
+use super::{AutoplayPolicy, GameError, GameState};
+use crate::r#move::Move;
+use serde::{Deserialize, Serialize};
+
+/// Who made a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum MoveProvenance {
+    /// The player chose it.
+    #[default]
+    Player,
+    /// [`GameState::auto_play_safe_moves`] made it after another move.
+    Autoplay,
+    /// [`GameState::play_auto_finish`] made it to finish a won game.
+    AutoFinish,
+}
+
+impl MoveProvenance {
+    /// Whether the program made the move rather than the player.
+    pub fn is_automatic(self) -> bool {
+        self != MoveProvenance::Player
+    }
+}
+
+/// A move in a game's history, with who made it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MoveRecord {
+    pub m: Move,
+    #[serde(default)]
+    pub provenance: MoveProvenance,
+}
+
+impl MoveRecord {
+    pub fn new(m: Move, provenance: MoveProvenance) -> Self {
+        Self { m, provenance }
+    }
+
+    /// Records `moves`, all made by `provenance`.
+    pub fn tag(moves: impl IntoIterator<Item = Move>, provenance: MoveProvenance) -> impl Iterator<Item = MoveRecord> {
+        moves.into_iter().map(move |m| MoveRecord::new(m, provenance))
+    }
+
+    /// How many of `records` the player made.
+    pub fn player_moves(records: &[MoveRecord]) -> usize {
+        records.iter().filter(|record| !record.provenance.is_automatic()).count()
+    }
+}
+
+impl GameState {
+    /// Executes the player's move `m`, then the foundation moves `policy` allows
+    /// afterwards, and appends a record of each to `history`.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`execute_move`](Self::execute_move); nothing is played or recorded
+    /// then.
+    pub fn play_recorded(&mut self, m: &Move, policy: AutoplayPolicy, history: &mut Vec<MoveRecord>) -> Result<(), GameError> {
+        self.execute_move(m)?;
+        history.push(MoveRecord::new(*m, MoveProvenance::Player));
+        history.extend(MoveRecord::tag(self.auto_play_safe_moves(policy), MoveProvenance::Autoplay));
+        Ok(())
+    }
+
+    /// Plays [`play_auto_finish`](Self::play_auto_finish) and appends a record of each
+    /// move to `history`. Returns how many moves it played.
+    pub fn play_auto_finish_recorded(&mut self, history: &mut Vec<MoveRecord>) -> usize {
+        let moves = self.play_auto_finish();
+        let count = moves.len();
+        history.extend(MoveRecord::tag(moves, MoveProvenance::AutoFinish));
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+    use crate::game_state::GameStateBuilder;
+    use crate::location::{FoundationLocation, Location, TableauLocation};
+
+    #[test]
+    fn records_tell_player_moves_from_automatic_ones() {
+        // Everything home but the two black kings, the spade one buried under the club.
+        let mut builder = GameStateBuilder::new();
+        for (pile, suit) in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs].into_iter().enumerate() {
+            let location = Location::Foundation(FoundationLocation::new(pile as u8).unwrap());
+            let top = if matches!(suit, Suit::Spades | Suit::Clubs) { 12 } else { 13 };
+            for rank in 1..=top {
+                builder.place(location, Card::new(Rank::try_from(rank).unwrap(), suit)).unwrap();
+            }
+        }
+        let kings = [Card::new(Rank::King, Suit::Spades), Card::new(Rank::King, Suit::Clubs)];
+        builder.column(TableauLocation::new(0).unwrap(), kings).unwrap();
+        let mut game = builder.build().unwrap();
+
+        let mut history = Vec::new();
+        let illegal = Move::freecell_to_tableau(0, 1).unwrap();
+        assert!(game.play_recorded(&illegal, AutoplayPolicy::None, &mut history).is_err());
+        assert!(history.is_empty());
+
+        game.play_recorded(&Move::tableau_to_freecell(0, 0).unwrap(), AutoplayPolicy::None, &mut history).unwrap();
+        assert_eq!(game.play_auto_finish_recorded(&mut history), 0);
+        game.play_recorded(&Move::freecell_to_tableau(0, 1).unwrap(), AutoplayPolicy::None, &mut history).unwrap();
+        assert_eq!(game.play_auto_finish_recorded(&mut history), 2);
+        assert!(game.is_won().unwrap());
+
+        let provenance: Vec<_> = history.iter().map(|record| record.provenance).collect();
+        assert_eq!(
+            provenance,
+            [MoveProvenance::Player, MoveProvenance::Player, MoveProvenance::AutoFinish, MoveProvenance::AutoFinish]
+        );
+        assert_eq!(MoveRecord::player_moves(&history), 2);
+    }
+}
@@ -0,0 +1,113 @@
+//! How much effort a move takes.
+//!
+//! Two legal moves can be very different to watch or to reason about: dropping the top
+//! card of a column onto its neighbour is quick, while parking a card in a freecell on
+//! the far side of the board ties up a scarce resource and travels a long way.
+//! [`GameState::relocation_cost`] puts numbers on that so animations can be paced and
+//! move sequences compared.
+//!
+//! Distances use a two-row layout measured in card widths: freecells followed by
+//! foundations along the top, tableau columns along the bottom, both starting at the
+//! left edge.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::generation::generate_deal;
+//! use freecell_game_engine::Move;
+//!
+//! let game = generate_deal(1).unwrap();
+//! let near = game.relocation_cost(&Move::tableau_to_freecell(0, 0).unwrap()).unwrap();
+//! let far = game.relocation_cost(&Move::tableau_to_freecell(7, 0).unwrap()).unwrap();
+//!
+//! assert_eq!(near.freecell_delta, 1);
+//! assert!(far.distance > near.distance);
+//! assert!(far.total() > near.total());
+//! ```
+
+use super::{GameError, GameState};
+use crate::location::Location;
+use crate::r#move::Move;
+
+/// The effort of a single move, as returned by [`GameState::relocation_cost`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RelocationCost {
+    /// Cards that change location.
+    pub cards_moved: u8,
+    /// Change in occupied freecells: 1 when a card is parked, -1 when one leaves a cell.
+    pub freecell_delta: i8,
+    /// Card widths travelled across the board layout, counting a change of row as one.
+    pub distance: u8,
+}
+
+impl RelocationCost {
+    /// A single number for comparing moves: cards moved plus freecells taken plus
+    /// distance. Freeing a freecell does not make a move cheaper than zero.
+    pub fn total(&self) -> u32 {
+        self.cards_moved as u32 + self.freecell_delta.max(0) as u32 + self.distance as u32
+    }
+}
+
+impl GameState {
+    /// Estimates the effort of `m` in this position.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same error as [`is_move_valid`](Self::is_move_valid) if the move is
+    /// not legal here.
+    pub fn relocation_cost(&self, m: &Move) -> Result<RelocationCost, GameError> {
+        self.is_move_valid(m)?;
+
+        let freecell_delta = match (m.source, m.destination) {
+            (Location::Freecell(_), Location::Freecell(_)) => 0,
+            (_, Location::Freecell(_)) => 1,
+            (Location::Freecell(_), _) => -1,
+            _ => 0,
+        };
+        let (from_row, from_x) = self.layout_position(m.source);
+        let (to_row, to_x) = self.layout_position(m.destination);
+        let distance = from_x.abs_diff(to_x) + from_row.abs_diff(to_row);
+
+        Ok(RelocationCost {
+            cards_moved: 1,
+            freecell_delta,
+            distance,
+        })
+    }
+
+    /// Row (0 top, 1 bottom) and horizontal slot of `location` in the board layout.
+    fn layout_position(&self, location: Location) -> (u8, u8) {
+        match location {
+            Location::Freecell(cell) => (0, cell.index()),
+            Location::Foundation(pile) => (0, self.freecells.slot_count() as u8 + pile.index()),
+            Location::Tableau(column) => (1, column.index()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+    use crate::game_state::GameStateBuilder;
+    use crate::generation::generate_deal;
+    use crate::location::FreecellLocation;
+
+    #[test]
+    fn leaving_a_freecell_releases_it() {
+        let mut builder = GameStateBuilder::new();
+        let cell = Location::Freecell(FreecellLocation::new(3).unwrap());
+        builder.place(cell, Card::new(Rank::Ace, Suit::Hearts)).unwrap();
+        let game = builder.state().clone();
+
+        let home = game.relocation_cost(&Move::freecell_to_foundation(3, 1).unwrap()).unwrap();
+        assert_eq!(home, RelocationCost { cards_moved: 1, freecell_delta: -1, distance: 2 });
+        assert_eq!(home.total(), 3);
+    }
+
+    #[test]
+    fn illegal_moves_have_no_cost() {
+        let game = generate_deal(1).unwrap();
+        assert!(game.relocation_cost(&Move::freecell_to_tableau(0, 0).unwrap()).is_err());
+    }
+}
@@ -3,9 +3,10 @@
 //! This module provides validation functionality for different moves in a FreeCell game.
 //! All validation methods return a Result indicating whether the move is valid.
 
-use super::{GameState, GameError};
+use super::{GameState, GameError, InvalidMoveReason};
 use crate::r#move::Move;
-use crate::location::FoundationLocation;
+use crate::card::Card;
+use crate::location::{FoundationLocation, FreecellLocation, Location, TableauLocation};
 
 impl GameState {
     /// Validates a move without executing it.
@@ -60,12 +61,125 @@ impl GameState {
                 self.validate_tableau_to_tableau(from.index(), to.index(), m)
             }
             _ => Err(GameError::InvalidMove {
-                reason: "Moves between these locations are not supported".to_string(),
+                reason: InvalidMoveReason::UnsupportedPair,
                 attempted_move: *m,
             }),
         }
     }
 
+    /// Validates every move in `moves` against this position, in order.
+    ///
+    /// The top card of every column and the contents of every freecell are read once for
+    /// the whole batch, and legal moves are confirmed from that snapshot alone. Illegal
+    /// ones are passed to [`is_move_valid`](Self::is_move_valid) for their error, so each
+    /// result is exactly what validating the move on its own returns. This suits callers
+    /// that check many candidates against one position: highlighting the legal drops for
+    /// a card, or re-checking a generated move list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    /// use freecell_game_engine::Move;
+    ///
+    /// let game = generate_deal(1).unwrap();
+    /// let candidates = [Move::tableau_to_freecell(0, 0).unwrap(), Move::freecell_to_tableau(0, 1).unwrap()];
+    /// let results = game.validate_moves(&candidates);
+    /// assert!(results[0].is_ok());
+    /// assert_eq!(results[1], game.is_move_valid(&candidates[1]));
+    /// ```
+    pub fn validate_moves(&self, moves: &[Move]) -> Vec<Result<(), GameError>> {
+        let snapshot = ValidationSnapshot::new(self);
+        moves
+            .iter()
+            .map(|m| if snapshot.allows(self, m) { Ok(()) } else { self.is_move_valid(m) })
+            .collect()
+    }
+
+    /// Every pile the top card of `from` may legally move to, tableau columns first, then
+    /// freecells, then foundations: the targets to highlight once a player picks a card up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    /// use freecell_game_engine::location::{Location, TableauLocation};
+    ///
+    /// let game = generate_deal(1).unwrap();
+    /// let targets = game.legal_destinations(Location::Tableau(TableauLocation::new(0).unwrap()));
+    /// // Any of the four empty freecells.
+    /// assert_eq!(targets.len(), 4);
+    /// ```
+    pub fn legal_destinations(&self, from: Location) -> Vec<Location> {
+        let columns = (0..self.tableau.column_count()).map(|i| Location::Tableau(TableauLocation::new(i as u8).unwrap()));
+        let cells = (0..self.freecells.capacity()).map(|i| Location::Freecell(FreecellLocation::new(i as u8).unwrap()));
+        let piles = (0..self.rules().foundation_piles())
+            .map(|i| Location::Foundation(FoundationLocation::new(i as u8).unwrap()));
+        let candidates: Vec<Move> = columns.chain(cells).chain(piles).map(|to| Move::single(from, to)).collect();
+        candidates
+            .iter()
+            .zip(self.validate_moves(&candidates))
+            .filter(|(_, result)| result.is_ok())
+            .map(|(m, _)| m.destination)
+            .collect()
+    }
+
+    /// Every pile `card` may legally move to from where it lies now, in [`Location`]
+    /// order: tableau columns first, then freecells, then foundations.
+    ///
+    /// A card on top of its column or in a freecell moves alone, as in
+    /// [`legal_destinations`](Self::legal_destinations). A card further down a column
+    /// takes the cards above it along, so it can only go onto another column, and only
+    /// when [`validate_sequence_move`](Self::validate_sequence_move) allows the run. Cards
+    /// on a foundation, or not in the position, have nowhere to go. In multi-deck games
+    /// the destinations of every copy are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::game_state::GameStateBuilder;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::{Location, TableauLocation};
+    ///
+    /// let mut builder = GameStateBuilder::new();
+    /// let nine = Card::new(Rank::Nine, Suit::Spades);
+    /// builder.column(TableauLocation::new(0).unwrap(), [nine, Card::new(Rank::Eight, Suit::Hearts)]).unwrap();
+    /// builder.column(TableauLocation::new(1).unwrap(), [Card::new(Rank::Ten, Suit::Diamonds)]).unwrap();
+    /// let game = builder.state();
+    ///
+    /// // The nine takes the eight along: onto the red ten, or into one of six empty columns.
+    /// let targets = game.destinations_for_card(nine);
+    /// assert_eq!(targets.len(), 7);
+    /// assert_eq!(targets[0], Location::Tableau(TableauLocation::new(1).unwrap()));
+    /// ```
+    pub fn destinations_for_card(&self, card: Card) -> Vec<Location> {
+        let mut destinations = Vec::new();
+        for (index, column) in self.tableau.columns().enumerate() {
+            // The topmost copy is the one that could move.
+            let Some(position) = column.iter().rposition(|held| *held == card) else {
+                continue;
+            };
+            let from = TableauLocation::new(index as u8).unwrap();
+            let count = column.len() - position;
+            if count == 1 {
+                destinations.extend(self.legal_destinations(Location::Tableau(from)));
+            } else {
+                let columns = (0..self.tableau.column_count()).map(|i| TableauLocation::new(i as u8).unwrap());
+                destinations.extend(
+                    columns.filter(|&to| self.validate_sequence_move(from, to, count).is_ok()).map(Location::Tableau),
+                );
+            }
+        }
+        for (index, held) in self.freecells.occupied_cells() {
+            if *held == card {
+                destinations.extend(self.legal_destinations(Location::Freecell(FreecellLocation::new(index as u8).unwrap())));
+            }
+        }
+        destinations.sort();
+        destinations.dedup();
+        destinations
+    }
+
     /// Validates a move from a tableau column to a foundation pile.
     ///
     /// Checks if the top card of `from_column` can be legally placed on `to_pile`.
@@ -104,19 +218,15 @@ impl GameState {
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "validate_tableau_to_foundation".to_string(),
+                operation: "validate_tableau_to_foundation",
             })?
-            .ok_or_else(|| GameError::InvalidMove {
-                reason: "Source tableau column is empty".to_string(),
+            .ok_or(GameError::InvalidMove {
+                reason: InvalidMoveReason::EmptySource,
                 attempted_move: *m,
             })?;
         let foundation_location = FoundationLocation::new(to_pile).unwrap();
         self.foundations.validate_card_placement(foundation_location, card)
-            .map_err(|e| GameError::Foundation {
-                error: e,
-                attempted_move: Some(*m),
-                operation: "validate_tableau_to_foundation".to_string(),
-            })?;
+            .map_err(|e| GameError::foundation(e, Some(*m), "validate_tableau_to_foundation"))?;
         Ok(())
     }
 
@@ -158,26 +268,32 @@ impl GameState {
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "validate_tableau_to_freecell".to_string(),
+                operation: "validate_tableau_to_freecell",
             })?
             .is_none()
         {
             return Err(GameError::InvalidMove {
-                reason: "Source tableau column is empty".to_string(),
+                reason: InvalidMoveReason::EmptySource,
                 attempted_move: *m,
             });
         }
         let location = crate::location::FreecellLocation::new(to_cell).map_err(GameError::Location)?;
+        if !self.freecells.is_available(location) {
+            return Err(GameError::InvalidMove {
+                reason: InvalidMoveReason::CapacityExceeded,
+                attempted_move: *m,
+            });
+        }
         if self.freecells.get_card(location)
             .map_err(|e| GameError::FreeCell {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "validate_tableau_to_freecell".to_string(),
+                operation: "validate_tableau_to_freecell",
             })?
             .is_some()
         {
             return Err(GameError::InvalidMove {
-                reason: "Destination freecell is occupied".to_string(),
+                reason: InvalidMoveReason::DestinationOccupied,
                 attempted_move: *m,
             });
         }
@@ -224,10 +340,10 @@ impl GameState {
             .map_err(|e| GameError::FreeCell {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "validate_freecell_to_tableau".to_string(),
+                operation: "validate_freecell_to_tableau",
             })?
-            .ok_or_else(|| GameError::InvalidMove {
-                reason: "Source freecell is empty".to_string(),
+            .ok_or(GameError::InvalidMove {
+                reason: InvalidMoveReason::EmptySource,
                 attempted_move: *m,
             })?;
         let to_location = crate::location::TableauLocation::new(to_column).map_err(GameError::Location)?;
@@ -235,7 +351,7 @@ impl GameState {
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "validate_freecell_to_tableau".to_string(),
+                operation: "validate_freecell_to_tableau",
             })?;
         Ok(())
     }
@@ -278,19 +394,15 @@ impl GameState {
             .map_err(|e| GameError::FreeCell {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "validate_freecell_to_foundation".to_string(),
+                operation: "validate_freecell_to_foundation",
             })?
-            .ok_or_else(|| GameError::InvalidMove {
-                reason: "Source freecell is empty".to_string(),
+            .ok_or(GameError::InvalidMove {
+                reason: InvalidMoveReason::EmptySource,
                 attempted_move: *m,
             })?;
         let foundation_location = FoundationLocation::new(to_pile).unwrap();
         self.foundations.validate_card_placement(foundation_location, card)
-            .map_err(|e| GameError::Foundation {
-                error: e,
-                attempted_move: Some(*m),
-                operation: "validate_freecell_to_foundation".to_string(),
-            })?;
+            .map_err(|e| GameError::foundation(e, Some(*m), "validate_freecell_to_foundation"))?;
         Ok(())
     }
 
@@ -307,15 +419,21 @@ impl GameState {
     /// * `Ok(())` if the move is legal
     /// * `Err(GameError)` with a specific error if the move is invalid
     fn validate_tableau_to_tableau(&self, from_column: u8, to_column: u8, m: &Move) -> Result<(), GameError> {
+        if from_column == to_column {
+            return Err(GameError::InvalidMove {
+                reason: InvalidMoveReason::UnsupportedPair,
+                attempted_move: *m,
+            });
+        }
         let from_location = crate::location::TableauLocation::new(from_column).map_err(GameError::Location)?;
         let card = self.tableau.get_card(from_location)
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "validate_tableau_to_tableau".to_string(),
+                operation: "validate_tableau_to_tableau",
             })?
-            .ok_or_else(|| GameError::InvalidMove {
-                reason: "Source tableau column is empty".to_string(),
+            .ok_or(GameError::InvalidMove {
+                reason: InvalidMoveReason::EmptySource,
                 attempted_move: *m,
             })?;
         let to_location = crate::location::TableauLocation::new(to_column).map_err(GameError::Location)?;
@@ -323,8 +441,252 @@ impl GameState {
             .map_err(|e| GameError::Tableau {
                 error: e,
                 attempted_move: Some(*m),
-                operation: "validate_tableau_to_tableau".to_string(),
+                operation: "validate_tableau_to_tableau",
             })?;
         Ok(())
     }
+
+    /// Validates moving the top `count` cards of one tableau column onto another as a
+    /// single sequence move (a "supermove").
+    ///
+    /// Move execution still works one card at a time; this check lets interfaces explain
+    /// why a run the player dragged cannot go where it was dropped.
+    ///
+    /// # Errors
+    ///
+    /// * [`GameError::Tableau`] wrapping `InsufficientCards` if the column is shorter than
+    ///   `count`, `BrokenSequence` naming the first pair that breaks the run, or
+    ///   `InvalidColor`/`InvalidRank` if the run's bottom card does not fit the destination.
+    /// * [`GameError::InsufficientResources`] if the run is longer than
+    ///   `(empty_freecells + 1) × 2^empty_columns`, where an empty destination column
+    ///   does not count.
+    /// * [`GameError::InvalidMove`] if the source and destination are the same column.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::game_state::{GameError, GameStateBuilder};
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::TableauLocation;
+    ///
+    /// let mut builder = GameStateBuilder::new();
+    /// let from = TableauLocation::new(0).unwrap();
+    /// let to = TableauLocation::new(1).unwrap();
+    /// builder.column(from, [Card::new(Rank::Nine, Suit::Spades), Card::new(Rank::Eight, Suit::Hearts)]).unwrap();
+    /// builder.column(to, [Card::new(Rank::Ten, Suit::Diamonds)]).unwrap();
+    /// let game = builder.state();
+    ///
+    /// assert!(game.validate_sequence_move(from, to, 2).is_ok());
+    /// ```
+    pub fn validate_sequence_move(
+        &self,
+        from: TableauLocation,
+        to: TableauLocation,
+        count: usize,
+    ) -> Result<(), GameError> {
+        let m = Move::single(Location::Tableau(from), Location::Tableau(to));
+        let tableau_error = |error| GameError::Tableau {
+            error,
+            attempted_move: Some(m),
+            operation: "validate_sequence_move",
+        };
+        if from == to {
+            return Err(GameError::InvalidMove {
+                reason: InvalidMoveReason::UnsupportedPair,
+                attempted_move: m,
+            });
+        }
+        let run = self.tableau.validate_sequence(from, count).map_err(tableau_error)?;
+        self.tableau.validate_card_placement(to, &run[0]).map_err(tableau_error)?;
+
+        let empty_freecells = self.freecells.empty_cells_count();
+        let destination_empty = self.tableau.is_column_empty(to).map_err(tableau_error)?;
+        let empty_columns = self.tableau.empty_columns_count() - usize::from(destination_empty);
+        let available = (empty_freecells + 1) << empty_columns.min(20);
+        if count > available {
+            return Err(GameError::InsufficientResources {
+                needed: count,
+                available,
+                empty_freecells,
+                empty_columns,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The cards a batch of validations reads over and over, taken once.
+struct ValidationSnapshot {
+    /// Top card of each column in play.
+    column_tops: Vec<Option<Card>>,
+    /// Card in each usable freecell.
+    cells: Vec<Option<Card>>,
+}
+
+impl ValidationSnapshot {
+    fn new(game: &GameState) -> Self {
+        let column_tops = game.tableau.columns().map(|column| column.last().copied()).collect();
+        let cells = (0..game.freecells.capacity())
+            .map(|index| {
+                let location = FreecellLocation::new(index as u8).unwrap();
+                game.freecells.get_card(location).ok().flatten().copied()
+            })
+            .collect();
+        ValidationSnapshot { column_tops, cells }
+    }
+
+    /// Whether `m` is legal. Never true for a move [`GameState::is_move_valid`] rejects;
+    /// anything it cannot confirm is left to that method.
+    fn allows(&self, game: &GameState, m: &Move) -> bool {
+        let card = match m.source {
+            Location::Tableau(from) => self.column_tops.get(from.index() as usize).copied().flatten(),
+            Location::Freecell(from) => self.cells.get(from.index() as usize).copied().flatten(),
+            Location::Foundation(_) => None,
+        };
+        let Some(card) = card else {
+            return false;
+        };
+        match (m.source, m.destination) {
+            (Location::Tableau(from), Location::Tableau(to)) if from == to => false,
+            (_, Location::Tableau(to)) => match self.column_tops.get(to.index() as usize) {
+                Some(Some(top)) => top.color() != card.color() && top.is_one_higher_than(&card),
+                Some(None) => true,
+                None => false,
+            },
+            (Location::Tableau(_), Location::Freecell(to)) => matches!(self.cells.get(to.index() as usize), Some(None)),
+            (_, Location::Foundation(to)) => game.foundations.validate_card_placement(to, &card).is_ok(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+    use crate::game_state::GameStateBuilder;
+    use crate::tableau::TableauError;
+
+    fn column(index: u8) -> TableauLocation {
+        TableauLocation::new(index).unwrap()
+    }
+
+    /// A five-card run J♠ down to 7♠ in column 0, a Q♥ to move it onto in column 1,
+    /// every other column occupied by a low card, and `free` free cells.
+    fn five_card_run(free: usize) -> GameState {
+        let mut builder = GameStateBuilder::new();
+        let run = [
+            Card::new(Rank::Jack, Suit::Spades),
+            Card::new(Rank::Ten, Suit::Hearts),
+            Card::new(Rank::Nine, Suit::Clubs),
+            Card::new(Rank::Eight, Suit::Diamonds),
+            Card::new(Rank::Seven, Suit::Spades),
+        ];
+        builder.column(column(0), run).unwrap();
+        builder.column(column(1), [Card::new(Rank::Queen, Suit::Hearts)]).unwrap();
+        let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+        for index in 2..8 {
+            let rank = if index < 6 { Rank::Two } else { Rank::Three };
+            let card = Card::new(rank, suits[index as usize % 4]);
+            builder.column(column(index), [card]).unwrap();
+        }
+        let mut game = builder.state().clone();
+        game.set_freecell_capacity(free).unwrap();
+        game
+    }
+
+    #[test]
+    fn sequence_move_reports_missing_resources() {
+        assert!(five_card_run(4).validate_sequence_move(column(0), column(1), 5).is_ok());
+        assert_eq!(
+            five_card_run(2).validate_sequence_move(column(0), column(1), 5),
+            Err(GameError::InsufficientResources {
+                needed: 5,
+                available: 3,
+                empty_freecells: 2,
+                empty_columns: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn sequence_move_reports_the_breaking_pair_and_destination() {
+        let mut game = five_card_run(4);
+        game.tableau.place_card_at_no_checks(column(0), Card::new(Rank::Five, Suit::Hearts));
+        let Err(GameError::Tableau { error, .. }) = game.validate_sequence_move(column(0), column(1), 6) else {
+            panic!("expected a tableau error");
+        };
+        assert_eq!(
+            error,
+            TableauError::BrokenSequence {
+                column: 0,
+                index: 5,
+                lower: Card::new(Rank::Seven, Suit::Spades),
+                upper: Card::new(Rank::Five, Suit::Hearts),
+            }
+        );
+
+        let game = five_card_run(4);
+        assert!(matches!(
+            game.validate_sequence_move(column(0), column(5), 5),
+            Err(GameError::Tableau { error: TableauError::InvalidRank { .. }, .. })
+        ));
+    }
+
+    #[test]
+    fn card_destinations_follow_its_place_in_the_column() {
+        let jack = Card::new(Rank::Jack, Suit::Spades);
+        assert_eq!(five_card_run(4).destinations_for_card(jack), [Location::Tableau(column(1))]);
+        assert_eq!(five_card_run(2).destinations_for_card(jack), []);
+
+        let top = five_card_run(2).destinations_for_card(Card::new(Rank::Seven, Suit::Spades));
+        assert_eq!(top, five_card_run(2).legal_destinations(Location::Tableau(column(0))));
+        assert_eq!(top.len(), 2);
+        assert_eq!(five_card_run(4).destinations_for_card(Card::new(Rank::King, Suit::Clubs)), []);
+    }
+
+    #[test]
+    fn batch_validation_matches_one_at_a_time() {
+        let mut positions = vec![five_card_run(4), five_card_run(2)];
+        for seed in [1, 2, 617] {
+            let mut game = crate::generation::generate_deal(seed).unwrap();
+            positions.push(game.clone());
+            // A few plies in, so cells are occupied and foundations started.
+            for _ in 0..6 {
+                let Some(m) = game.get_available_moves().into_iter().next() else { break };
+                game.execute_move(&m).unwrap();
+                positions.push(game.clone());
+            }
+        }
+        let piles: Vec<Location> = (0..8)
+            .map(|i| Location::Tableau(TableauLocation::new(i).unwrap()))
+            .chain((0..4).map(|i| Location::Freecell(FreecellLocation::new(i).unwrap())))
+            .chain((0..4).map(|i| Location::Foundation(FoundationLocation::new(i).unwrap())))
+            .collect();
+        let candidates: Vec<Move> = piles
+            .iter()
+            .flat_map(|&from| piles.iter().map(move |&to| Move::single(from, to)))
+            .collect();
+        for game in &positions {
+            let one_at_a_time: Vec<_> = candidates.iter().map(|m| game.is_move_valid(m)).collect();
+            assert_eq!(game.validate_moves(&candidates), one_at_a_time);
+        }
+    }
+
+    #[test]
+    fn component_errors_map_to_reasons() {
+        let game = five_card_run(4);
+        let reason = |m: Move| game.is_move_valid(&m).unwrap_err().reason();
+        // 7♠ onto 2♦ in column 2: wrong rank; onto 2♣ in column 3: wrong color.
+        assert_eq!(reason(Move::tableau_to_tableau(0, 2).unwrap()), Some(InvalidMoveReason::RankMismatch));
+        assert_eq!(reason(Move::tableau_to_tableau(0, 3).unwrap()), Some(InvalidMoveReason::ColorMismatch));
+        // 7♠ onto an Ace-less foundation.
+        assert_eq!(reason(Move::tableau_to_foundation(0, 0).unwrap()), Some(InvalidMoveReason::RankMismatch));
+        assert_eq!(reason(Move::freecell_to_tableau(0, 1).unwrap()), Some(InvalidMoveReason::EmptySource));
+        assert_eq!(reason(Move::tableau_to_tableau(1, 1).unwrap()), Some(InvalidMoveReason::UnsupportedPair));
+        assert_eq!(
+            five_card_run(2).validate_sequence_move(column(0), column(1), 5).unwrap_err().reason(),
+            Some(InvalidMoveReason::CapacityExceeded)
+        );
+    }
 }
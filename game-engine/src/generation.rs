@@ -1,9 +1,11 @@
 //! Deal generation for FreeCell games, specifically implementing the Microsoft FreeCell
 //! deal algorithm for compatibility with existing solvers and game implementations.
 //!
-//! This module provides a single public function, [`generate_deal`], which takes a
-//! seed (corresponding to the "deal number" in Microsoft FreeCell) and returns a
-//! [`GameState`] representing the initial layout of the cards.
+//! The main entry point is [`generate_deal`], which takes a seed (corresponding to the
+//! "deal number" in Microsoft FreeCell) and returns a [`GameState`] representing the
+//! initial layout of the cards; [`GameState::from_seed`] is the same without importing
+//! this module. With the `rayon` feature enabled, `generate_deals` produces many deals
+//! at once as a parallel iterator.
 //!
 //! The deal generation process strictly adheres to the algorithm used in Microsoft FreeCell,
 //! ensuring that the generated game states are bit-for-bit compatible with those produced
@@ -34,7 +36,7 @@
 //! assert!(matches!(error, Err(GenerationError::InvalidSeed)));
 //! ```
 
-use crate::{Card, GameState, Rank, Suit};
+use crate::{Card, GameState, Rank, Suit, Variant};
 use std::fmt;
 
 /// Error type for deal generation operations.
@@ -42,11 +44,16 @@ use std::fmt;
 /// This enum represents all the possible error conditions that can occur
 /// during the generation of a FreeCell deal.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum GenerationError {
     /// Attempted to generate a deal with an invalid seed.
     ///
     /// Microsoft FreeCell deals typically use seeds from 1 to 32000.
     InvalidSeed,
+    /// A deal number from another program has no equivalent seed here.
+    ///
+    /// Returned by [`from_ms_deal_number`] for negative numbers and numbers of 2^31 or more.
+    SeedOutOfRange(i64),
     /// An unexpected error occurred during the deal generation process.
     ///
     /// This error indicates a logical flaw in the generation algorithm itself,
@@ -58,6 +65,7 @@ impl fmt::Display for GenerationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             GenerationError::InvalidSeed => write!(f, "Invalid seed provided for deal generation. Seeds must be positive integers (e.g., 1-32000 for Microsoft FreeCell compatibility)."),
+            GenerationError::SeedOutOfRange(deal) => write!(f, "Deal number {} is outside the range 1 to 2147483647 that Microsoft-compatible generation reproduces.", deal),
             GenerationError::DealGenerationFailed => write!(f, "An internal error occurred during deal generation. This indicates a bug in the generation algorithm."),
         }
     }
@@ -90,7 +98,9 @@ impl MicrosoftRng {
     /// # Arguments
     /// * `seed` - The initial seed value for the RNG.
     fn new(seed: u64) -> Self {
-        MicrosoftRng { state: seed }
+        // Only the low 31 bits ever influence the sequence; dropping the rest up front
+        // keeps the first multiplication from overflowing on huge seeds.
+        MicrosoftRng { state: seed % LCG_MODULUS }
     }
 
     /// Generates the next "random" value in the sequence.
@@ -221,6 +231,186 @@ pub fn generate_deal(seed: u64) -> Result<GameState, GenerationError> {
     Ok(GameState::new_with_tableau(tableau))
 }
 
+/// Generates a Double FreeCell deal from a seed.
+///
+/// Two standard decks are placed one after the other, shuffled together with the same
+/// Microsoft algorithm as [`generate_deal`], and dealt into 10 columns. There is no
+/// widely shared numbering for Double FreeCell deals, so these deal numbers are specific
+/// to this crate; they are stable across releases.
+///
+/// # Errors
+/// Returns `GenerationError::InvalidSeed` if the provided `seed` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use freecell_game_engine::generation::generate_double_deal;
+///
+/// let game = generate_double_deal(1).unwrap();
+/// assert_eq!(game.tableau().column_count(), 10);
+/// assert_eq!(game.check_integrity(), Ok(()));
+/// ```
+pub fn generate_double_deal(seed: u64) -> Result<GameState, GenerationError> {
+    if seed == 0 {
+        return Err(GenerationError::InvalidSeed);
+    }
+
+    let variant = Variant::DoubleFreecell;
+    let mut rng = MicrosoftRng::new(seed);
+    let mut deck = create_standard_deck();
+    deck.extend(create_standard_deck());
+    microsoft_shuffle(&mut deck, &mut rng);
+
+    let empty = GameState::with_variant(variant);
+    let mut tableau = empty.tableau().clone();
+    let mut column_idx = 0;
+    while let Some(card) = deck.pop() {
+        let location = crate::location::TableauLocation::new(column_idx as u8).unwrap();
+        tableau.place_card_at_no_checks(location, card);
+        column_idx = (column_idx + 1) % variant.tableau_columns();
+    }
+
+    Ok(GameState::from_components(
+        tableau,
+        *empty.freecells(),
+        empty.foundations().clone(),
+    ))
+}
+
+impl GameState {
+    /// Deals a standard game; shorthand for [`generate_deal`].
+    ///
+    /// # Errors
+    /// Returns `GenerationError::InvalidSeed` if the provided `seed` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::generation::generate_deal;
+    /// use freecell_game_engine::GameState;
+    ///
+    /// assert_eq!(GameState::from_seed(1).unwrap(), generate_deal(1).unwrap());
+    /// assert!(GameState::from_seed(0).is_err());
+    /// ```
+    pub fn from_seed(seed: u64) -> Result<Self, GenerationError> {
+        generate_deal(seed)
+    }
+
+    /// Deals a game of `variant`: [`generate_deal`] for standard FreeCell and
+    /// [`generate_double_deal`] for Double FreeCell.
+    ///
+    /// # Errors
+    /// Returns `GenerationError::InvalidSeed` if the provided `seed` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::{GameState, Variant};
+    ///
+    /// let game = GameState::from_seed_with(1, Variant::DoubleFreecell).unwrap();
+    /// assert_eq!(game.card_count(), 104);
+    /// ```
+    pub fn from_seed_with(seed: u64, variant: Variant) -> Result<Self, GenerationError> {
+        match variant {
+            Variant::Standard => generate_deal(seed),
+            Variant::DoubleFreecell => generate_double_deal(seed),
+        }
+    }
+}
+
+/// Converts a deal number as Microsoft FreeCell presents it into a seed for
+/// [`generate_deal`].
+///
+/// Microsoft FreeCell numbers its deals with a signed 32-bit integer. Positive numbers
+/// map to the identical seed. The special negative deals (-1 and -2) are not produced
+/// by this shuffle and cannot be generated.
+///
+/// # Errors
+///
+/// Returns `GenerationError::InvalidSeed` for 0 and `GenerationError::SeedOutOfRange`
+/// for negative numbers and for numbers above `i32::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use freecell_game_engine::generation::{from_ms_deal_number, GenerationError};
+///
+/// assert_eq!(from_ms_deal_number(11982), Ok(11982));
+/// assert_eq!(from_ms_deal_number(0), Err(GenerationError::InvalidSeed));
+/// assert_eq!(from_ms_deal_number(-1), Err(GenerationError::SeedOutOfRange(-1)));
+/// ```
+pub fn from_ms_deal_number(deal: i64) -> Result<u64, GenerationError> {
+    match deal {
+        0 => Err(GenerationError::InvalidSeed),
+        1..=0x7FFF_FFFF => Ok(deal as u64),
+        _ => Err(GenerationError::SeedOutOfRange(deal)),
+    }
+}
+
+/// Converts a deal stored as a `u32` by another tool into a seed for [`generate_deal`].
+///
+/// The shuffle's generator keeps only 31 bits of state, so seeds that differ by 2^31
+/// produce identical deals. This helper folds the top bit away: values below 2^31 are
+/// unchanged, and values at or above it (including negative `i32` deal numbers cast
+/// with `as u32`) map to `value - 2^31`. For example `-1i32 as u32` and `u32::MAX`
+/// both give seed 2147483647.
+///
+/// # Errors
+///
+/// Returns `GenerationError::InvalidSeed` for 0 and 2^31, which both fold to seed 0.
+///
+/// # Examples
+///
+/// ```
+/// use freecell_game_engine::generation::{from_u32_wrapping, generate_deal};
+///
+/// assert_eq!(from_u32_wrapping(617), Ok(617));
+/// assert_eq!(from_u32_wrapping(617 + (1 << 31)), Ok(617));
+/// assert_eq!(from_u32_wrapping(-1i32 as u32), Ok(0x7FFF_FFFF));
+/// assert!(from_u32_wrapping(0).is_err());
+/// ```
+pub fn from_u32_wrapping(value: u32) -> Result<u64, GenerationError> {
+    match value & 0x7FFF_FFFF {
+        0 => Err(GenerationError::InvalidSeed),
+        seed => Ok(seed as u64),
+    }
+}
+
+/// Generates many deals in parallel, pairing each seed with its deal or the reason
+/// [`generate_deal`] rejected it.
+///
+/// This is intended for benchmarks and analysis tooling that need thousands of starting
+/// positions; each deal is generated on the rayon thread pool. Every seed is yielded, so
+/// a caller can report the ones that could not be dealt instead of losing them.
+///
+/// Requires the `rayon` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rayon")]
+/// # {
+/// use freecell_game_engine::generation::{generate_deal, generate_deals};
+/// use rayon::prelude::*;
+///
+/// let deals: Vec<_> = generate_deals(0..=100).collect();
+/// assert_eq!(deals.len(), 101);
+/// assert!(deals[0].1.is_err());
+/// assert_eq!(deals[1], (1, generate_deal(1)));
+/// # }
+/// ```
+#[cfg(feature = "rayon")]
+pub fn generate_deals<I>(
+    seeds: I,
+) -> impl rayon::iter::ParallelIterator<Item = (u64, Result<GameState, GenerationError>)>
+where
+    I: rayon::iter::IntoParallelIterator<Item = u64>,
+{
+    use rayon::iter::ParallelIterator;
+
+    seeds.into_par_iter().map(|seed| (seed, generate_deal(seed)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -389,4 +579,40 @@ mod tests {
             }
         }
     }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn batch_generation_matches_single_deals_and_reports_invalid_seeds() {
+        use rayon::prelude::*;
+
+        let deals: Vec<_> = generate_deals(0..50u64).collect();
+        assert_eq!(deals.len(), 50);
+        assert_eq!(deals[0], (0, Err(GenerationError::InvalidSeed)));
+        for (seed, state) in deals.into_iter().skip(1) {
+            crate::assert_game_states_eq!(state.unwrap(), generate_deal(seed).unwrap());
+        }
+    }
+
+    #[test]
+    fn seeds_that_differ_by_two_to_the_31_deal_the_same() {
+        for seed in [1u64, 617, 11982, 0x7FFF_FFFF] {
+            assert_eq!(generate_deal(seed).unwrap(), generate_deal(seed + (1 << 31)).unwrap());
+        }
+        let wrapped = from_u32_wrapping(11982 | (1 << 31)).unwrap();
+        assert_eq!(generate_deal(wrapped).unwrap(), generate_deal(11982).unwrap());
+        // Huge seeds fold the same way instead of overflowing.
+        assert_eq!(generate_deal(u64::MAX).unwrap(), generate_deal(0x7FFF_FFFF).unwrap());
+    }
+
+    #[test]
+    fn ms_deal_numbers_outside_the_shuffled_range_are_rejected() {
+        assert_eq!(from_ms_deal_number(1), Ok(1));
+        assert_eq!(from_ms_deal_number(i32::MAX as i64), Ok(0x7FFF_FFFF));
+        assert_eq!(from_ms_deal_number(-2), Err(GenerationError::SeedOutOfRange(-2)));
+        assert_eq!(
+            from_ms_deal_number(1 << 31),
+            Err(GenerationError::SeedOutOfRange(1 << 31))
+        );
+        assert_eq!(from_u32_wrapping(1 << 31), Err(GenerationError::InvalidSeed));
+    }
 }
@@ -0,0 +1,466 @@
+//! Forgiving import of hand-typed boards.
+//!
+//! When someone wants help with a board they see in another FreeCell program, they have
+//! to type it in, and they will not match any exact format. [`import_board`] accepts
+//! roughly formatted text and returns the position together with [`ImportWarning`]s for
+//! anything it had to guess or skip.
+//!
+//! The layout follows the common solver convention of one line per area:
+//!
+//! ```text
+//! Foundations: H-2 C-A        (top card of each started pile)
+//! Freecells:   5S  -   QD
+//! : KS 3D 4C 10H
+//! : JD TC ...                 (one line per column, bottom card first)
+//! ```
+//!
+//! Within that layout the parser is lenient:
+//!
+//! - tokens may be separated by any mix of spaces, tabs and commas;
+//! - ranks may be `A`/`1`, `2`-`9`, `10`/`T`, `J`, `Q`, `K` in either case, before or
+//!   after the suit, optionally joined by `-`;
+//! - suits may be letters (`S H D C`) or symbols (`♠♥♦♣` and their outline forms);
+//! - `-`, `.`, `_`, `*` and `[]` mark empty slots, and trailing empty freecells or
+//!   columns may be left out;
+//! - column lines may start with any short label (`:`, `1:`, `Col 3:`), and lines
+//!   starting with `#` are comments.
+//!
+//! Going the other way, [`position_code`] writes a position in this layout on a single
+//! line, for handing positions between people and tools.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::import::{import_board, ImportWarning};
+//! use freecell_game_engine::location::TableauLocation;
+//! use freecell_game_engine::{Card, Rank, Suit, Variant};
+//!
+//! let text = "Freecells: [] 5♠\n\
+//!             Col 1: kh  qs, jd\n\
+//!             Col 2: 10c T♦ ??";
+//! let import = import_board(text, Variant::Standard).unwrap();
+//!
+//! let column = TableauLocation::new(1).unwrap();
+//! assert_eq!(import.state.tableau().get_card(column).unwrap(), Some(&Card::new(Rank::Ten, Suit::Diamonds)));
+//! assert!(import.warnings.contains(&ImportWarning::UnrecognizedToken { line: 3, token: "??".to_string() }));
+//! ```
+
+use std::fmt;
+
+use crate::card::{Card, Rank, Suit};
+use crate::game_state::{GameError, GameState, GameStateBuilder, IntegrityError};
+use crate::location::{FoundationLocation, FreecellLocation, Location, TableauLocation};
+use crate::variant::Variant;
+
+/// Something the importer skipped or filled in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ImportWarning {
+    /// A token that is neither a card nor an empty marker. Lines are 1-based.
+    UnrecognizedToken { line: usize, token: String },
+    /// Fewer column lines than the board has columns; the rest were left empty.
+    MissingColumns { found: usize, expected: usize },
+    /// Cards that appear nowhere on the imported board.
+    MissingCards(Vec<Card>),
+}
+
+impl fmt::Display for ImportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportWarning::UnrecognizedToken { line, token } => {
+                write!(f, "Line {}: skipped unrecognized \"{}\"", line, token)
+            }
+            ImportWarning::MissingColumns { found, expected } => write!(
+                f,
+                "Only {} of {} columns given; the rest are empty",
+                found, expected
+            ),
+            ImportWarning::MissingCards(cards) => {
+                write!(f, "{} cards are missing from the board", cards.len())
+            }
+        }
+    }
+}
+
+/// Why a board could not be imported at all.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ImportError {
+    /// More column lines than the board has columns.
+    TooManyColumns { found: usize, max: usize },
+    /// More freecell entries than the board has freecells.
+    TooManyFreecells { line: usize, found: usize, max: usize },
+    /// A card that cannot go where the text puts it, e.g. a duplicate or a foundation
+    /// out of order. Lines are 1-based.
+    InvalidPlacement { line: usize, error: GameError },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::TooManyColumns { found, max } => {
+                write!(f, "Found {} columns but the board has only {}", found, max)
+            }
+            ImportError::TooManyFreecells { line, found, max } => write!(
+                f,
+                "Line {}: found {} freecells but the board has only {}",
+                line, found, max
+            ),
+            ImportError::InvalidPlacement { line, error } => write!(f, "Line {}: {}", line, error),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// An imported position and what the importer had to assume to produce it.
+#[derive(Debug, Clone)]
+pub struct ImportedBoard {
+    pub state: GameState,
+    pub warnings: Vec<ImportWarning>,
+}
+
+/// What a single token stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Card(Card),
+    /// A foundation written with rank 0, e.g. `H-0`: no cards of that suit yet.
+    NoCardsOf(Suit),
+    Empty,
+}
+
+/// Which area a line describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Foundations,
+    Freecells,
+    Column,
+}
+
+/// Parses a hand-typed board for `variant`.
+///
+/// Missing cards are reported as a warning rather than an error, so a partly typed
+/// board can be checked and corrected.
+///
+/// # Errors
+///
+/// Returns an [`ImportError`] if the text describes more columns or freecells than the
+/// board has, or places a card where it cannot go.
+pub fn import_board(text: &str, variant: Variant) -> Result<ImportedBoard, ImportError> {
+    let rules = variant.rules();
+    let mut builder = GameStateBuilder::for_variant(variant);
+    let mut warnings = Vec::new();
+    let mut columns = 0;
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let (kind, content) = classify(trimmed);
+
+        let mut tokens = Vec::new();
+        for word in content.split(|c: char| c.is_whitespace() || c == ',').filter(|w| !w.is_empty()) {
+            match parse_token(word) {
+                Some(token) => tokens.push(token),
+                None => warnings.push(ImportWarning::UnrecognizedToken {
+                    line,
+                    token: word.to_string(),
+                }),
+            }
+        }
+
+        let place = |builder: &mut GameStateBuilder, location, card| {
+            builder
+                .place(location, card)
+                .map(|_| ())
+                .map_err(|error| ImportError::InvalidPlacement { line, error })
+        };
+        match kind {
+            LineKind::Foundations => {
+                for token in tokens {
+                    let Token::Card(top) = token else { continue };
+                    let pile = FoundationLocation::new(top.suit().foundation_index()).unwrap();
+                    for rank in 1..=top.rank() as u8 {
+                        let card = Card::new(Rank::try_from(rank).unwrap(), top.suit());
+                        place(&mut builder, Location::Foundation(pile), card)?;
+                    }
+                }
+            }
+            LineKind::Freecells => {
+                if tokens.len() > rules.freecells() {
+                    return Err(ImportError::TooManyFreecells {
+                        line,
+                        found: tokens.len(),
+                        max: rules.freecells(),
+                    });
+                }
+                for (cell, token) in tokens.into_iter().enumerate() {
+                    if let Token::Card(card) = token {
+                        let location = Location::Freecell(FreecellLocation::new(cell as u8).unwrap());
+                        place(&mut builder, location, card)?;
+                    }
+                }
+            }
+            LineKind::Column => {
+                if columns == rules.tableau_columns() {
+                    return Err(ImportError::TooManyColumns {
+                        found: columns + 1,
+                        max: rules.tableau_columns(),
+                    });
+                }
+                let location = Location::Tableau(TableauLocation::new(columns as u8).unwrap());
+                for token in tokens {
+                    if let Token::Card(card) = token {
+                        place(&mut builder, location, card)?;
+                    }
+                }
+                columns += 1;
+            }
+        }
+    }
+
+    if columns < rules.tableau_columns() {
+        warnings.push(ImportWarning::MissingColumns {
+            found: columns,
+            expected: rules.tableau_columns(),
+        });
+    }
+    if let Err(IntegrityError::MissingCards(cards)) = builder.state().check_integrity() {
+        warnings.push(ImportWarning::MissingCards(cards));
+    }
+    Ok(ImportedBoard {
+        state: builder.state().clone(),
+        warnings,
+    })
+}
+
+/// Writes `state` as a position code: the import layout on a single line, with `/` for
+/// line breaks and commas between cards, e.g. `f:2S,AH/fc:QD,-,-,-/:KS,3D/:...`.
+///
+/// A code has no spaces, so it survives being pasted into a JSON field, a chat message
+/// or a command line, and [`import_position_code`] reads it back exactly. Any tool that
+/// reads the import layout can read it after replacing `/` with newlines.
+///
+/// # Examples
+///
+/// ```
+/// use freecell_game_engine::generation::generate_deal;
+/// use freecell_game_engine::import::{import_position_code, position_code};
+/// use freecell_game_engine::Variant;
+///
+/// let game = generate_deal(1).unwrap();
+/// let code = position_code(&game);
+/// assert!(code.starts_with("f:/fc:-,-,-,-/:JD,KD,2S,4C,3S,6D,6S/"));
+/// assert_eq!(import_position_code(&code, Variant::Standard).unwrap().state, game);
+/// ```
+pub fn position_code(state: &GameState) -> String {
+    let code_of = |card: &Card| {
+        let rank = "A23456789TJQK".as_bytes()[card.rank() as usize - 1] as char;
+        let suit = match card.suit() {
+            Suit::Spades => 'S',
+            Suit::Hearts => 'H',
+            Suit::Diamonds => 'D',
+            Suit::Clubs => 'C',
+        };
+        format!("{}{}", rank, suit)
+    };
+    let foundations = (0..state.rules().foundation_piles())
+        .filter_map(|pile| FoundationLocation::new(pile as u8).ok())
+        .filter_map(|pile| state.foundations().get_card(pile).ok().flatten())
+        .map(code_of);
+    let freecells = (0..state.freecells().slot_count()).map(|cell| {
+        let card = FreecellLocation::new(cell as u8).ok().and_then(|cell| state.freecells().get_card(cell).ok().flatten());
+        card.map_or_else(|| "-".to_string(), code_of)
+    });
+    let mut lines = vec![
+        format!("f:{}", foundations.collect::<Vec<_>>().join(",")),
+        format!("fc:{}", freecells.collect::<Vec<_>>().join(",")),
+    ];
+    lines.extend(state.tableau().columns().map(|column| {
+        format!(":{}", column.iter().map(code_of).collect::<Vec<_>>().join(","))
+    }));
+    lines.join("/")
+}
+
+/// Reads a code written by [`position_code`] for `variant`. Surrounding whitespace is
+/// ignored.
+///
+/// A complete code imports without warnings; any warning means the code was cut short
+/// or edited, and the returned position is only what survived.
+///
+/// # Errors
+///
+/// Any [`ImportError`] of [`import_board`] for the decoded lines.
+pub fn import_position_code(code: &str, variant: Variant) -> Result<ImportedBoard, ImportError> {
+    import_board(&code.trim().replace('/', "\n"), variant)
+}
+
+/// Splits off a leading `label:` and decides which area the line describes.
+fn classify(line: &str) -> (LineKind, &str) {
+    let Some((label, rest)) = line.split_once(':') else {
+        return (LineKind::Column, line);
+    };
+    let label = label.trim().to_ascii_lowercase();
+    if label.len() > 12 {
+        // Too long to be a label; most likely a column with a stray colon.
+        return (LineKind::Column, line);
+    }
+    let kind = if label.starts_with("found") || label.starts_with("home") || label == "f" {
+        LineKind::Foundations
+    } else if label.starts_with("free") || label.starts_with("cell") || label == "fc" {
+        LineKind::Freecells
+    } else {
+        LineKind::Column
+    };
+    (kind, rest)
+}
+
+/// Parses one word as a card, an empty marker or a zero-height foundation.
+fn parse_token(word: &str) -> Option<Token> {
+    let word = word.trim_matches(|c| matches!(c, '[' | ']' | '(' | ')'));
+    if word.chars().all(|c| matches!(c, '-' | '.' | '_' | '*')) {
+        return Some(Token::Empty);
+    }
+    let word: String = word.chars().filter(|&c| c != '-').collect();
+    let mut chars = word.chars();
+    let first = chars.next()?;
+    let last = chars.next_back()?;
+
+    let (suit, rank) = if let Some(suit) = parse_suit(last) {
+        (suit, &word[..word.len() - last.len_utf8()])
+    } else {
+        (parse_suit(first)?, &word[first.len_utf8()..])
+    };
+    match rank.to_ascii_uppercase().as_str() {
+        "0" => Some(Token::NoCardsOf(suit)),
+        rank => Some(Token::Card(Card::new(parse_rank(rank)?, suit))),
+    }
+}
+
+fn parse_suit(c: char) -> Option<Suit> {
+    match c {
+        'S' | 's' | '♠' | '♤' => Some(Suit::Spades),
+        'H' | 'h' | '♥' | '♡' => Some(Suit::Hearts),
+        'D' | 'd' | '♦' | '♢' => Some(Suit::Diamonds),
+        'C' | 'c' | '♣' | '♧' => Some(Suit::Clubs),
+        _ => None,
+    }
+}
+
+fn parse_rank(rank: &str) -> Option<Rank> {
+    match rank {
+        "A" | "1" => Some(Rank::Ace),
+        "T" | "10" => Some(Rank::Ten),
+        "J" => Some(Rank::Jack),
+        "Q" => Some(Rank::Queen),
+        "K" => Some(Rank::King),
+        digit => digit.parse::<u8>().ok().filter(|n| (2..=9).contains(n)).and_then(|n| Rank::try_from(n).ok()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::generate_deal;
+
+    /// Writes a position in the layout the importer documents.
+    fn write_board(state: &GameState) -> String {
+        let mut text = String::from("Founds:");
+        for pile in 0..4 {
+            if let Ok(Some(card)) = state.foundations().get_card(FoundationLocation::new(pile).unwrap()) {
+                text += &format!(" {}-{}", suit_letter(card.suit()), rank_label(card.rank()));
+            }
+        }
+        text += "\nFreecells:";
+        for cell in 0..4 {
+            match state.freecells().get_card(FreecellLocation::new(cell).unwrap()) {
+                Ok(Some(card)) => text += &format!(" {}{}", rank_label(card.rank()), suit_letter(card.suit())),
+                _ => text += " -",
+            }
+        }
+        for column in state.tableau().columns() {
+            text += "\n:";
+            for card in column {
+                text += &format!(" {}{}", rank_label(card.rank()), suit_letter(card.suit()));
+            }
+        }
+        text
+    }
+
+    fn rank_label(rank: Rank) -> &'static str {
+        ["a", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "q", "K"][rank as usize - 1]
+    }
+
+    fn suit_letter(suit: Suit) -> char {
+        match suit {
+            Suit::Spades => 's',
+            Suit::Hearts => 'H',
+            Suit::Diamonds => '♦',
+            Suit::Clubs => '♣',
+        }
+    }
+
+    #[test]
+    fn mid_game_board_round_trips() {
+        let mut game = generate_deal(1).unwrap();
+        for _ in 0..20 {
+            let Some(mv) = game.get_available_moves().into_iter().next() else {
+                break;
+            };
+            game.execute_move(&mv).unwrap();
+        }
+
+        let import = import_board(&write_board(&game), Variant::Standard).unwrap();
+        assert_eq!(import.warnings, []);
+        crate::assert_game_states_eq!(import.state, game);
+    }
+
+    #[test]
+    fn position_codes_round_trip_in_one_word() {
+        let mut game = generate_deal(7).unwrap();
+        for _ in 0..30 {
+            let Some(mv) = game.get_available_moves().into_iter().next() else {
+                break;
+            };
+            game.execute_move(&mv).unwrap();
+        }
+
+        let code = position_code(&game);
+        assert!(!code.contains(char::is_whitespace));
+        let import = import_position_code(&format!(" {}\n", code), Variant::Standard).unwrap();
+        assert_eq!(import.warnings, []);
+        crate::assert_game_states_eq!(import.state, game);
+
+        let cut = import_position_code(&code[..code.len() / 2], Variant::Standard).unwrap();
+        assert!(!cut.warnings.is_empty());
+    }
+
+    #[test]
+    fn tokens_in_many_spellings() {
+        let ten_of_clubs = Some(Token::Card(Card::new(Rank::Ten, Suit::Clubs)));
+        for word in ["10C", "tc", "C10", "♣T", "10-♧", "[10c]"] {
+            assert_eq!(parse_token(word), ten_of_clubs, "{}", word);
+        }
+        assert_eq!(parse_token("H-0"), Some(Token::NoCardsOf(Suit::Hearts)));
+        assert_eq!(parse_token("[]"), Some(Token::Empty));
+        assert_eq!(parse_token("11S"), None);
+    }
+
+    #[test]
+    fn short_boards_warn_and_bad_boards_fail() {
+        let import = import_board("1: AS 2S", Variant::Standard).unwrap();
+        assert!(import.warnings.contains(&ImportWarning::MissingColumns { found: 1, expected: 8 }));
+        assert!(matches!(&import.warnings[1], ImportWarning::MissingCards(cards) if cards.len() == 50));
+
+        assert!(matches!(
+            import_board("AS\nAS", Variant::Standard),
+            Err(ImportError::InvalidPlacement { line: 2, .. })
+        ));
+        assert_eq!(
+            import_board("FC: 2S 3S 4S 5S 6S", Variant::Standard).unwrap_err(),
+            ImportError::TooManyFreecells { line: 1, found: 5, max: 4 }
+        );
+    }
+}
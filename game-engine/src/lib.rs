@@ -46,6 +46,10 @@
 //!
 //! To use this crate, you'll primarily interact with the [`GameState`] struct, which represents
 //! the current state of the game. You can create a new game, inspect its state, and execute moves.
+//! `use freecell_game_engine::prelude::*` brings in the types almost every client needs.
+//!
+//! Not everything public is meant to stay put: the [`stable`] module lists the API that
+//! follows semver, and says what may change outside it.
 //!
 //! # FreeCell Rules
 //!
@@ -87,18 +91,34 @@
 //! graphical FreeCell game or an automated solver.
 
 pub mod card;
+pub mod challenge;
+pub mod codec;
+pub mod deal_corpus;
+pub mod display;
+pub mod engine_info;
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod fixtures;
 pub mod foundations;
 pub mod freecells;
 pub mod game_state;
 pub mod generation;
+pub mod import;
 pub mod location;
+pub mod prelude;
+pub mod rules;
+pub mod stable;
 pub mod tableau;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod variant;
 pub mod r#move;
 
 // Re-export commonly used types for convenience
 pub use card::{Card, Color, Rank, Suit};
+pub use engine_info::{EngineInfo, ENGINE_INFO};
 pub use foundations::Foundations;
 pub use freecells::FreeCells;
 pub use game_state::GameState;
 pub use tableau::Tableau;
 pub use r#move::Move;
+pub use variant::Variant;
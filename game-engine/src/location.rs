@@ -9,18 +9,43 @@
 //!
 //! # Core Components
 //!
-//! - [`TableauLocation`]: A validated wrapper for a tableau column index (0-7).
-//! - [`FreecellLocation`]: A validated wrapper for a freecell index (0-3).
+//! - [`TableauLocation`]: A validated wrapper for a tableau column index (0-9).
+//! - [`FreecellLocation`]: A validated wrapper for a freecell index (0-5).
 //! - [`FoundationLocation`]: A validated wrapper for a foundation pile index (0-3).
 //! - [`Location`]: An enum that consolidates all location types, useful for
 //!   representing moves between different areas of the game.
 //! - [`LocationError`]: An error type for location-related validation failures.
+//!
+//! Indices are validated against the largest supported layout (Double FreeCell's 10
+//! columns and 6 freecells). Components reject indices beyond their own size, so a
+//! standard 8-column tableau still refuses column 8.
+//!
+//! Constant indices need no `unwrap`: [`tloc!`](crate::tloc), [`floc!`](crate::floc) and
+//! [`hloc!`](crate::hloc) check theirs while compiling, so an index out of range is a
+//! compile error rather than a panic.
+//!
+//! ```
+//! use freecell_game_engine::location::{FreecellLocation, TableauLocation};
+//! use freecell_game_engine::{floc, hloc, tloc};
+//!
+//! assert_eq!(tloc!(3), TableauLocation::new(3).unwrap());
+//! assert_eq!(floc!(0), FreecellLocation::new(0).unwrap());
+//! assert_eq!(hloc!(2).index(), 2);
+//! ```
+//!
+//! ```compile_fail
+//! let column = freecell_game_engine::tloc!(10);
+//! ```
 
 use std::fmt;
 use serde::{Deserialize, Serialize};
 
+use crate::freecells::MAX_FREECELLS;
+use crate::tableau::MAX_TABLEAU_COLUMNS;
+
 // General error for location validation
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum LocationError {
     InvalidTableauIndex(u8),
     InvalidFreecellIndex(u8),
@@ -39,59 +64,77 @@ impl fmt::Display for LocationError {
 
 impl std::error::Error for LocationError {}
 
-/// Represents a validated location in a tableau column (0-7).
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Represents a validated location in a tableau column (0-9).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TableauLocation {
     index: u8,
 }
 
 impl TableauLocation {
-    /// Creates a new `TableauLocation` if the index is valid (0-7).
-    pub fn new(index: u8) -> Result<Self, LocationError> {
-        if index < 8 {
+    /// Creates a new `TableauLocation` if the index is valid (0-9).
+    pub const fn new(index: u8) -> Result<Self, LocationError> {
+        if (index as usize) < MAX_TABLEAU_COLUMNS {
             Ok(Self { index })
         } else {
             Err(LocationError::InvalidTableauIndex(index))
         }
     }
 
+    /// Like [`new`](Self::new), but panics if the index is invalid; in a constant, that
+    /// is a compile error.
+    pub const fn at(index: u8) -> Self {
+        match Self::new(index) {
+            Ok(location) => location,
+            Err(_) => panic!("tableau index out of range"),
+        }
+    }
+
     /// Returns the raw index of the tableau column.
-    pub fn index(&self) -> u8 {
+    pub const fn index(&self) -> u8 {
         self.index
     }
 }
 
-/// Represents a validated location in a freecell (0-3).
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Represents a validated location in a freecell (0-5).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct FreecellLocation {
     index: u8,
 }
 
 impl FreecellLocation {
-    /// Creates a new `FreecellLocation` if the index is valid (0-3).
-    pub fn new(index: u8) -> Result<Self, LocationError> {
-        if index < 4 {
+    /// Creates a new `FreecellLocation` if the index is valid (0-5).
+    pub const fn new(index: u8) -> Result<Self, LocationError> {
+        if (index as usize) < MAX_FREECELLS {
             Ok(Self { index })
         } else {
             Err(LocationError::InvalidFreecellIndex(index))
         }
     }
 
+    /// Like [`new`](Self::new), but panics if the index is invalid; in a constant, that
+    /// is a compile error.
+    pub const fn at(index: u8) -> Self {
+        match Self::new(index) {
+            Ok(location) => location,
+            Err(_) => panic!("freecell index out of range"),
+        }
+    }
+
     /// Returns the raw index of the freecell.
-    pub fn index(&self) -> u8 {
+    pub const fn index(&self) -> u8 {
         self.index
     }
 }
 
 /// Represents a validated location in a foundation pile (0-3).
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct FoundationLocation {
     index: u8,
 }
 
 impl FoundationLocation {
     /// Creates a new `FoundationLocation` if the index is valid (0-3).
-    pub fn new(index: u8) -> Result<Self, LocationError> {
+    pub const fn new(index: u8) -> Result<Self, LocationError> {
         if index < 4 {
             Ok(Self { index })
         } else {
@@ -99,32 +142,72 @@ impl FoundationLocation {
         }
     }
 
+    /// Like [`new`](Self::new), but panics if the index is invalid; in a constant, that
+    /// is a compile error.
+    pub const fn at(index: u8) -> Self {
+        match Self::new(index) {
+            Ok(location) => location,
+            Err(_) => panic!("foundation index out of range"),
+        }
+    }
+
     /// Returns the raw index of the foundation pile.
-    pub fn index(&self) -> u8 {
+    pub const fn index(&self) -> u8 {
         self.index
     }
 }
 
 /// An enum that consolidates all location types.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Locations order tableau columns first, then freecells, then foundations, each by
+/// index, matching the order in which [`DirtyPiles`](crate::game_state::DirtyPiles) and
+/// [`GameState::iter_cards`](crate::GameState::iter_cards) visit piles.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Location {
     Tableau(TableauLocation),
     Freecell(FreecellLocation),
     Foundation(FoundationLocation),
 }
 
+/// A [`TableauLocation`] for a constant column index, checked at compile time.
+#[macro_export]
+macro_rules! tloc {
+    ($index:expr) => {{
+        const LOCATION: $crate::location::TableauLocation = $crate::location::TableauLocation::at($index);
+        LOCATION
+    }};
+}
+
+/// A [`FreecellLocation`] for a constant cell index, checked at compile time.
+#[macro_export]
+macro_rules! floc {
+    ($index:expr) => {{
+        const LOCATION: $crate::location::FreecellLocation = $crate::location::FreecellLocation::at($index);
+        LOCATION
+    }};
+}
+
+/// A [`FoundationLocation`] for a constant ("home") pile index, checked at compile time.
+#[macro_export]
+macro_rules! hloc {
+    ($index:expr) => {{
+        const LOCATION: $crate::location::FoundationLocation = $crate::location::FoundationLocation::at($index);
+        LOCATION
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn tableau_location_validation() {
-        for i in 0..8 {
+        for i in 0..10 {
             assert!(TableauLocation::new(i).is_ok());
         }
         assert!(matches!(
-            TableauLocation::new(8),
-            Err(LocationError::InvalidTableauIndex(8))
+            TableauLocation::new(10),
+            Err(LocationError::InvalidTableauIndex(10))
         ));
         assert!(matches!(
             TableauLocation::new(255),
@@ -134,12 +217,12 @@ mod tests {
 
     #[test]
     fn freecell_location_validation() {
-        for i in 0..4 {
+        for i in 0..6 {
             assert!(FreecellLocation::new(i).is_ok());
         }
         assert!(matches!(
-            FreecellLocation::new(4),
-            Err(LocationError::InvalidFreecellIndex(4))
+            FreecellLocation::new(6),
+            Err(LocationError::InvalidFreecellIndex(6))
         ));
         assert!(matches!(
             FreecellLocation::new(255),
@@ -164,9 +247,9 @@ mod tests {
 
     #[test]
     fn location_enum_creation() {
-        let tableau_loc = TableauLocation::new(0).unwrap();
-        let freecell_loc = FreecellLocation::new(1).unwrap();
-        let foundation_loc = FoundationLocation::new(2).unwrap();
+        let tableau_loc = tloc!(0);
+        let freecell_loc = floc!(1);
+        let foundation_loc = hloc!(2);
 
         let loc1 = Location::Tableau(tableau_loc);
         let loc2 = Location::Freecell(freecell_loc);
@@ -176,4 +259,12 @@ mod tests {
         assert_eq!(loc2, Location::Freecell(FreecellLocation::new(1).unwrap()));
         assert_eq!(loc3, Location::Foundation(FoundationLocation::new(2).unwrap()));
     }
+
+    #[test]
+    fn constant_locations_match_checked_ones() {
+        assert_eq!(tloc!(9), TableauLocation::new(9).unwrap());
+        assert_eq!(floc!(5), FreecellLocation::new(5).unwrap());
+        assert_eq!(hloc!(3), FoundationLocation::new(3).unwrap());
+        assert!(std::panic::catch_unwind(|| TableauLocation::at(std::hint::black_box(10))).is_err());
+    }
 }
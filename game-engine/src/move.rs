@@ -36,7 +36,11 @@
 use crate::location::{Location, TableauLocation, FreecellLocation, FoundationLocation, LocationError};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+mod builder;
+
+pub use builder::{f, h, t, MoveBuildError, MoveBuilder, Pile, ValidatedMove};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Move {
     pub source: Location,
     pub destination: Location,
@@ -95,6 +99,22 @@ impl Move {
     pub fn destination(&self) -> Location {
         self.destination
     }
+
+    /// Whether this move takes a card from or puts a card on `location`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::location::{Location, TableauLocation};
+    /// use freecell_game_engine::Move;
+    ///
+    /// let m = Move::tableau_to_freecell(3, 0).unwrap();
+    /// assert!(m.affects(Location::Tableau(TableauLocation::new(3).unwrap())));
+    /// assert!(!m.affects(Location::Tableau(TableauLocation::new(4).unwrap())));
+    /// ```
+    pub fn affects(&self, location: Location) -> bool {
+        self.source == location || self.destination == location
+    }
 }
 
 impl std::fmt::Display for Move {
@@ -0,0 +1,405 @@
+//! Building moves from raw pile indices, checked against a position.
+//!
+//! Front ends take pile numbers from a click, a drag or a typed command, and each used to
+//! repeat the same steps before touching the game: range-check the indices against the
+//! layout, tell single-card moves from runs, then ask the engine whether the move is
+//! legal. [`MoveBuilder`] does all of it in one call and hands back a [`ValidatedMove`],
+//! or a [`MoveBuildError`] saying which step failed.
+//!
+//! Piles are named with the short constructors [`t`] (tableau column), [`f`] (freecell)
+//! and [`h`] (home, a foundation pile); indices are 0-based, as everywhere in the engine.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::generation::generate_deal;
+//! use freecell_game_engine::r#move::{f, h, t, MoveBuildError};
+//! use freecell_game_engine::Move;
+//!
+//! let mut game = generate_deal(1).unwrap();
+//! let to_cell = Move::builder().from(t(3)).to(f(0)).build_validated(&game).unwrap();
+//! to_cell.execute(&mut game).unwrap();
+//!
+//! let err = Move::builder().from(t(9)).to(h(0)).build_validated(&game).unwrap_err();
+//! assert_eq!(err, MoveBuildError::OutOfRange { pile: t(9), available: 8 });
+//! ```
+
+use std::fmt;
+
+use super::Move;
+use crate::game_state::{GameError, GameState, InvalidMoveReason};
+use crate::location::{FoundationLocation, FreecellLocation, Location, TableauLocation};
+
+/// A pile named by kind and index, not yet checked against any layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pile {
+    Tableau(u8),
+    Freecell(u8),
+    Foundation(u8),
+}
+
+/// Tableau column `index`.
+pub fn t(index: u8) -> Pile {
+    Pile::Tableau(index)
+}
+
+/// Freecell `index`.
+pub fn f(index: u8) -> Pile {
+    Pile::Freecell(index)
+}
+
+/// Foundation ("home") pile `index`.
+pub fn h(index: u8) -> Pile {
+    Pile::Foundation(index)
+}
+
+impl From<Location> for Pile {
+    fn from(location: Location) -> Self {
+        match location {
+            Location::Tableau(loc) => Pile::Tableau(loc.index()),
+            Location::Freecell(loc) => Pile::Freecell(loc.index()),
+            Location::Foundation(loc) => Pile::Foundation(loc.index()),
+        }
+    }
+}
+
+impl fmt::Display for Pile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pile::Tableau(index) => write!(f, "Tableau {}", index),
+            Pile::Freecell(index) => write!(f, "Freecell {}", index),
+            Pile::Foundation(index) => write!(f, "Foundation {}", index),
+        }
+    }
+}
+
+impl Pile {
+    /// The pile as a [`Location`], if `game` has it. Freecells count only as far as the
+    /// position's capacity, which may be below its variant's.
+    fn locate(self, game: &GameState) -> Result<Location, MoveBuildError> {
+        let (index, available) = match self {
+            Pile::Tableau(index) => (index, game.rules().tableau_columns()),
+            Pile::Freecell(index) => (index, game.freecells().capacity()),
+            Pile::Foundation(index) => (index, game.rules().foundation_piles()),
+        };
+        let out_of_range = || MoveBuildError::OutOfRange { pile: self, available };
+        if index as usize >= available {
+            return Err(out_of_range());
+        }
+        let location = match self {
+            Pile::Tableau(_) => TableauLocation::new(index).map(Location::Tableau),
+            Pile::Freecell(_) => FreecellLocation::new(index).map(Location::Freecell),
+            Pile::Foundation(_) => FoundationLocation::new(index).map(Location::Foundation),
+        };
+        location.map_err(|_| out_of_range())
+    }
+}
+
+/// Why [`MoveBuilder::build_validated`] refused a move.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum MoveBuildError {
+    /// No source pile was given.
+    MissingSource,
+    /// No destination pile was given.
+    MissingDestination,
+    /// The move carries no cards.
+    ZeroCount,
+    /// The position has no such pile; `available` is how many of its kind it has.
+    OutOfRange { pile: Pile, available: usize },
+    /// More than one card was asked for off or onto a pile other than a tableau column.
+    CountNotSupported { from: Pile, to: Pile, count: usize },
+    /// The piles exist, but the rules do not allow the move in this position.
+    Illegal(GameError),
+}
+
+impl MoveBuildError {
+    /// The rule the move breaks, for [`Illegal`](MoveBuildError::Illegal) moves.
+    pub fn reason(&self) -> Option<InvalidMoveReason> {
+        match self {
+            MoveBuildError::Illegal(error) => error.reason(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MoveBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveBuildError::MissingSource => write!(f, "no source pile given"),
+            MoveBuildError::MissingDestination => write!(f, "no destination pile given"),
+            MoveBuildError::ZeroCount => write!(f, "a move must carry at least one card"),
+            MoveBuildError::OutOfRange { pile, available } => {
+                write!(f, "{} does not exist; this game has {} of its kind", pile, available)
+            }
+            MoveBuildError::CountNotSupported { from, to, count } => {
+                write!(f, "cannot move {} cards from {} to {}; only columns take runs", count, from, to)
+            }
+            MoveBuildError::Illegal(error) => write!(f, "illegal move: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for MoveBuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MoveBuildError::Illegal(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Collects the parts of a move; see [`Move::builder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveBuilder {
+    from: Option<Pile>,
+    to: Option<Pile>,
+    count: usize,
+}
+
+impl Default for MoveBuilder {
+    fn default() -> Self {
+        MoveBuilder { from: None, to: None, count: 1 }
+    }
+}
+
+impl MoveBuilder {
+    pub fn from(mut self, pile: impl Into<Pile>) -> Self {
+        self.from = Some(pile.into());
+        self
+    }
+
+    pub fn to(mut self, pile: impl Into<Pile>) -> Self {
+        self.to = Some(pile.into());
+        self
+    }
+
+    /// Cards to move; 1 unless set. Only tableau-to-tableau moves carry more.
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Checks the move against `game`: both piles given and present in its layout, a
+    /// card count the piles allow, and legal by the rules, runs included.
+    ///
+    /// # Errors
+    ///
+    /// The first check that fails, as a [`MoveBuildError`].
+    pub fn build_validated(self, game: &GameState) -> Result<ValidatedMove, MoveBuildError> {
+        let from = self.from.ok_or(MoveBuildError::MissingSource)?;
+        let to = self.to.ok_or(MoveBuildError::MissingDestination)?;
+        if self.count == 0 {
+            return Err(MoveBuildError::ZeroCount);
+        }
+        let source = from.locate(game)?;
+        let destination = to.locate(game)?;
+        let mv = Move::single(source, destination);
+        match (source, destination) {
+            _ if self.count == 1 => game.is_move_valid(&mv),
+            (Location::Tableau(from_column), Location::Tableau(to_column)) => {
+                game.validate_sequence_move(from_column, to_column, self.count)
+            }
+            _ => return Err(MoveBuildError::CountNotSupported { from, to, count: self.count }),
+        }
+        .map_err(MoveBuildError::Illegal)?;
+        Ok(ValidatedMove { mv, count: self.count })
+    }
+}
+
+/// A move [`MoveBuilder::build_validated`] found legal in the position it was given.
+///
+/// The check holds for that position only; [`execute`](ValidatedMove::execute) checks
+/// again rather than trust a token kept across other moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ValidatedMove {
+    mv: Move,
+    count: usize,
+}
+
+impl ValidatedMove {
+    /// The engine move: source and destination pile.
+    pub fn as_move(&self) -> Move {
+        self.mv
+    }
+
+    /// Cards moved.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Plays the move on `game` and returns the single-card moves it took, which undo in
+    /// reverse order. A run is carried over one card at a time, parked in the empty
+    /// freecells and columns on the way.
+    ///
+    /// # Errors
+    ///
+    /// Whatever [`GameState::execute_move`] or [`GameState::validate_sequence_move`]
+    /// reports if `game` is not the position the move was validated in. `game` is then
+    /// left as it was.
+    pub fn execute(&self, game: &mut GameState) -> Result<Vec<Move>, GameError> {
+        match (self.mv.source, self.mv.destination) {
+            (Location::Tableau(from), Location::Tableau(to)) if self.count > 1 => {
+                game.validate_sequence_move(from, to, self.count)?;
+                let columns: Vec<TableauLocation> = game.empty_columns().filter(|&column| column != to).collect();
+                let mut played = Vec::new();
+                if let Err(error) = play_run(game, from, to, self.count, &columns, &mut played) {
+                    for mv in played.iter().rev() {
+                        game.undo_move(mv);
+                    }
+                    return Err(error);
+                }
+                Ok(played)
+            }
+            _ => {
+                game.execute_move(&self.mv)?;
+                Ok(vec![self.mv])
+            }
+        }
+    }
+}
+
+/// Plays the top `count` cards of column `from` onto `to`, appending each single-card
+/// move to `played`. Runs the empty freecells can carry go through them; longer ones
+/// park their top half in the first of `columns` and move the rest with the others.
+fn play_run(
+    game: &mut GameState,
+    from: TableauLocation,
+    to: TableauLocation,
+    count: usize,
+    columns: &[TableauLocation],
+    played: &mut Vec<Move>,
+) -> Result<(), GameError> {
+    let mut play = |game: &mut GameState, source, destination| -> Result<(), GameError> {
+        let mv = Move::single(source, destination);
+        game.execute_move(&mv)?;
+        played.push(mv);
+        Ok(())
+    };
+    let cells: Vec<FreecellLocation> = game.empty_freecells().collect();
+    if count <= cells.len() + 1 {
+        let parked = &cells[..count - 1];
+        for &cell in parked {
+            play(game, Location::Tableau(from), Location::Freecell(cell))?;
+        }
+        play(game, Location::Tableau(from), Location::Tableau(to))?;
+        for &cell in parked.iter().rev() {
+            play(game, Location::Freecell(cell), Location::Tableau(to))?;
+        }
+        return Ok(());
+    }
+    // As few columns as the run needs, so its top half is never the whole run.
+    let needed = (1..=columns.len()).find(|&used| count <= (cells.len() + 1) << used).unwrap_or(columns.len());
+    let Some((&spare, rest)) = columns[..needed].split_first() else {
+        return Err(GameError::InsufficientResources {
+            needed: count,
+            available: cells.len() + 1,
+            empty_freecells: cells.len(),
+            empty_columns: 0,
+        });
+    };
+    let half = (cells.len() + 1) << rest.len();
+    play_run(game, from, spare, half, rest, played)?;
+    play_run(game, from, to, count - half, rest, played)?;
+    play_run(game, spare, to, half, rest, played)
+}
+
+impl Move {
+    /// Starts building a move from pile indices, to be checked with
+    /// [`MoveBuilder::build_validated`].
+    pub fn builder() -> MoveBuilder {
+        MoveBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Card, Rank, Suit};
+    use crate::import::import_board;
+    use crate::Variant;
+
+    /// Column 0 ends in the run Q♥ J♠ 10♥ 9♣; column 1 ends in the J♣ its last two
+    /// cards fit on.
+    fn run_onto_jack() -> GameState {
+        let board = "Foundations: 9S 9H 9D 8C\n: KS QH JS 10H 9C\n: KD QS JC\n: 10S 10D JD JH QD KH 10C QC KC";
+        import_board(board, Variant::Standard).unwrap().state
+    }
+
+    #[test]
+    fn checks_parts_and_layout_before_legality() {
+        let game = run_onto_jack();
+        let build = |builder: MoveBuilder| builder.build_validated(&game).unwrap_err();
+        assert_eq!(build(Move::builder().to(f(0))), MoveBuildError::MissingSource);
+        assert_eq!(build(Move::builder().from(t(0))), MoveBuildError::MissingDestination);
+        assert_eq!(build(Move::builder().from(t(0)).to(f(0)).count(0)), MoveBuildError::ZeroCount);
+        assert_eq!(
+            build(Move::builder().from(t(8)).to(f(0))),
+            MoveBuildError::OutOfRange { pile: t(8), available: 8 }
+        );
+        assert_eq!(
+            build(Move::builder().from(t(0)).to(h(4))),
+            MoveBuildError::OutOfRange { pile: h(4), available: 4 }
+        );
+        assert_eq!(
+            build(Move::builder().from(t(0)).to(f(0)).count(2)),
+            MoveBuildError::CountNotSupported { from: t(0), to: f(0), count: 2 }
+        );
+        assert_eq!(
+            build(Move::builder().from(f(0)).to(t(1))).reason(),
+            Some(InvalidMoveReason::EmptySource)
+        );
+    }
+
+    #[test]
+    fn validates_runs_and_plays_single_cards() {
+        let mut game = run_onto_jack();
+        let run = Move::builder().from(t(0)).to(t(1)).count(2).build_validated(&game).unwrap();
+        assert_eq!(run.count(), 2);
+        assert_eq!(run.as_move(), Move::tableau_to_tableau(0, 1).unwrap());
+        // The jack of spades below them does not go on the jack of clubs.
+        assert!(Move::builder().from(t(0)).to(t(1)).count(3).build_validated(&game).is_err());
+
+        let location = Location::Tableau(TableauLocation::new(0).unwrap());
+        let single = Move::builder().from(location).to(f(2)).build_validated(&game).unwrap();
+        assert_eq!(single.execute(&mut game).unwrap(), vec![single.as_move()]);
+        assert!(game.freecells().get_card(FreecellLocation::new(2).unwrap()).unwrap().is_some());
+    }
+
+    #[test]
+    fn runs_are_carried_through_freecells_or_empty_columns() {
+        let start = run_onto_jack();
+        for capacity in [4, 0] {
+            let mut game = start.clone();
+            game.set_freecell_capacity(capacity).unwrap();
+            let run = Move::builder().from(t(0)).to(t(1)).count(2).build_validated(&game).unwrap();
+            let played = run.execute(&mut game).unwrap();
+            assert_eq!(played.len(), 3);
+            let column = game.tableau().get_column(1).unwrap();
+            assert_eq!(column[column.len() - 2..], [Card::new(Rank::Ten, Suit::Hearts), Card::new(Rank::Nine, Suit::Clubs)]);
+            for mv in played.iter().rev() {
+                game.undo_move(mv);
+            }
+            assert_eq!(game, {
+                let mut expected = start.clone();
+                expected.set_freecell_capacity(capacity).unwrap();
+                expected
+            });
+        }
+
+        // Four cards with one free cell: half parks in the empty column while the rest go across.
+        let mut game = crate::fixtures::by_name("run_via_empty_column").unwrap().state();
+        let run = Move::builder().from(t(0)).to(t(1)).count(4).build_validated(&game).unwrap();
+        assert_eq!(run.execute(&mut game).unwrap().len(), 9);
+        assert_eq!(game.tableau().get_column(1).unwrap().len(), 5);
+        assert!(game.tableau().is_column_empty(TableauLocation::new(2).unwrap()).unwrap());
+
+        // Played on a position it was not validated in, the run is refused whole.
+        let run = Move::builder().from(t(0)).to(t(1)).count(2).build_validated(&start).unwrap();
+        let mut game = start.clone();
+        game.execute_move(&Move::tableau_to_freecell(0, 0).unwrap()).unwrap();
+        let moved = game.clone();
+        assert!(run.execute(&mut game).is_err());
+        assert_eq!(game, moved);
+    }
+}
@@ -0,0 +1,27 @@
+//! The types nearly every user of the engine needs, for a single glob import.
+//!
+//! Everything here is also in [`stable`](crate::stable) and follows its compatibility
+//! promise. The prelude only grows: a name is never removed or repointed before the next
+//! breaking release.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::prelude::*;
+//!
+//! let mut game = generate_deal(1)?;
+//! let mv = Move::tableau_to_freecell(0, 0)?;
+//! game.execute_move(&mv)?;
+//! assert!(matches!(mv.destination, Location::Freecell(_)));
+//!
+//! let again = game.execute_move(&mv).unwrap_err();
+//! assert_eq!(again.reason(), Some(InvalidMoveReason::DestinationOccupied));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub use crate::card::{Card, Color, Rank, Suit};
+pub use crate::game_state::{GameError, GameState, InvalidMoveReason};
+pub use crate::generation::{generate_deal, GenerationError};
+pub use crate::location::{FoundationLocation, FreecellLocation, Location, LocationError, TableauLocation};
+pub use crate::r#move::Move;
+pub use crate::variant::Variant;
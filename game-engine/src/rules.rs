@@ -0,0 +1,213 @@
+//! Board dimensions for a game, gathered in one place.
+//!
+//! The engine's components each know their own size: how many tableau columns are in
+//! play, how many freecells are usable, how many decks the foundations are built from.
+//! [`RulesProfile`] collects those numbers so code that lays out, packs or searches a
+//! position can ask the position for its sizes instead of assuming 8 columns, 4 cells
+//! and 52 cards.
+//!
+//! The standard-game constants elsewhere in the crate (such as
+//! [`TABLEAU_COLUMN_COUNT`](crate::tableau::TABLEAU_COLUMN_COUNT)) are defined from
+//! [`RulesProfile::STANDARD`].
+//!
+//! A profile also names the [`WinCondition`] its games are won by, so
+//! [`GameState::is_won`](crate::GameState::is_won) and everything built on it (solver
+//! termination, challenge scoring) follow the variant rather than assuming it.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::rules::RulesProfile;
+//! use freecell_game_engine::{GameState, Variant};
+//!
+//! let mut game = GameState::new();
+//! assert_eq!(game.rules(), RulesProfile::STANDARD);
+//!
+//! game.set_freecell_capacity(2).unwrap();
+//! assert_eq!(game.rules().freecells(), 2);
+//!
+//! let double = GameState::with_variant(Variant::DoubleFreecell);
+//! assert_eq!(double.rules().card_count(), 104);
+//! ```
+
+use crate::location::Location;
+use crate::tableau::{TableauError, MAX_COLUMN_HEIGHT};
+use crate::GameState;
+
+/// What a game has to reach to count as won.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum WinCondition {
+    /// Every card is on its foundation: each pile holds a full suit, or two in Double
+    /// FreeCell.
+    #[default]
+    AllCardsHome,
+    /// Every card the position holds is on its foundation. Partial deals, which play
+    /// with only part of the deck, are won this way; see
+    /// [`GameStateBuilder::partial_deal`](crate::game_state::GameStateBuilder::partial_deal).
+    PresentCardsHome,
+}
+
+impl WinCondition {
+    /// Whether `game` meets the condition.
+    pub fn is_met(self, game: &GameState) -> bool {
+        match self {
+            WinCondition::AllCardsHome => game.foundations().is_complete(),
+            WinCondition::PresentCardsHome => {
+                game.iter_cards().all(|(_, location)| matches!(location, Location::Foundation(_)))
+            }
+        }
+    }
+}
+
+/// The dimensions of a FreeCell board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RulesProfile {
+    tableau_columns: usize,
+    freecells: usize,
+    foundation_piles: usize,
+    pile_capacity: usize,
+    decks: usize,
+    win_condition: WinCondition,
+}
+
+impl RulesProfile {
+    /// Standard FreeCell: 8 columns, 4 freecells, 4 foundations of 13 cards.
+    pub const STANDARD: RulesProfile = RulesProfile {
+        tableau_columns: 8,
+        freecells: 4,
+        foundation_piles: 4,
+        pile_capacity: 13,
+        decks: 1,
+        win_condition: WinCondition::AllCardsHome,
+    };
+
+    /// Double FreeCell: 10 columns, 6 freecells, 4 foundations of 26 cards.
+    pub const DOUBLE_FREECELL: RulesProfile = RulesProfile {
+        tableau_columns: 10,
+        freecells: 6,
+        foundation_piles: 4,
+        pile_capacity: 26,
+        decks: 2,
+        win_condition: WinCondition::AllCardsHome,
+    };
+
+    pub(crate) const fn new(tableau_columns: usize, freecells: usize, decks: usize) -> Self {
+        RulesProfile {
+            tableau_columns,
+            freecells,
+            foundation_piles: Self::STANDARD.foundation_piles,
+            pile_capacity: Self::STANDARD.pile_capacity * decks,
+            decks,
+            // Every supported variant is won the same way, so the dimensions a
+            // position carries are enough to rebuild its profile. Partial deals
+            // change it with `with_win_condition`.
+            win_condition: WinCondition::AllCardsHome,
+        }
+    }
+
+    pub(crate) const fn with_win_condition(mut self, win_condition: WinCondition) -> Self {
+        self.win_condition = win_condition;
+        self
+    }
+
+    /// Number of tableau columns in play.
+    pub const fn tableau_columns(&self) -> usize {
+        self.tableau_columns
+    }
+
+    /// Number of usable freecells.
+    pub const fn freecells(&self) -> usize {
+        self.freecells
+    }
+
+    /// Number of foundation piles, one per suit.
+    pub const fn foundation_piles(&self) -> usize {
+        self.foundation_piles
+    }
+
+    /// Number of cards a foundation pile holds when complete.
+    pub const fn pile_capacity(&self) -> usize {
+        self.pile_capacity
+    }
+
+    /// Number of 52-card decks in play.
+    pub const fn decks(&self) -> usize {
+        self.decks
+    }
+
+    /// Total number of cards in the game.
+    pub const fn card_count(&self) -> usize {
+        self.foundation_piles * self.pile_capacity
+    }
+
+    /// What games under this profile are won by.
+    pub const fn win_condition(&self) -> WinCondition {
+        self.win_condition
+    }
+
+    /// The tallest a tableau column can grow in play from a deal.
+    ///
+    /// The deal puts at most `card_count / tableau_columns` cards (rounded up) in a
+    /// column, and moves only ever add a descending run on top of what is left: at most
+    /// Queen down to Ace, 12 cards, on a dealt King. A column that is emptied and refilled
+    /// holds at most a 13-card run, which is never taller. Standard FreeCell gives
+    /// 7 + 12 = 19; Double FreeCell 11 + 12 = 23.
+    ///
+    /// Positions assembled by hand are not bound by this; see
+    /// [`Tableau::place_card_at`](crate::tableau::Tableau::place_card_at) for the limit
+    /// every column is held to.
+    pub const fn max_column_height(&self) -> usize {
+        self.card_count().div_ceil(self.tableau_columns) + Self::STANDARD.pile_capacity - 1
+    }
+
+    /// Checks that play under these dimensions cannot grow a column past
+    /// [`MAX_COLUMN_HEIGHT`](crate::tableau::MAX_COLUMN_HEIGHT), which fixed-size column
+    /// storage and packers are sized by.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TableauError::ColumnHeightExceeded`] when too few columns share the
+    /// cards: one deck over 3 columns could reach 18 + 12 = 30 cards.
+    pub const fn check_column_height(&self) -> Result<(), TableauError> {
+        if self.max_column_height() > MAX_COLUMN_HEIGHT {
+            return Err(TableauError::ColumnHeightExceeded {
+                height: self.max_column_height(),
+                limit: MAX_COLUMN_HEIGHT,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, Variant};
+
+    #[test]
+    fn variants_match_their_profiles() {
+        for variant in Variant::ALL {
+            let game = GameState::with_variant(variant);
+            assert_eq!(game.rules(), variant.rules());
+            assert_eq!(game.card_count(), variant.card_count());
+        }
+        assert_eq!(RulesProfile::STANDARD.card_count(), 52);
+        assert_eq!(RulesProfile::new(10, 6, 2), RulesProfile::DOUBLE_FREECELL);
+    }
+
+    #[test]
+    fn column_height_bounds_follow_the_deal() {
+        assert_eq!(RulesProfile::STANDARD.max_column_height(), 19);
+        assert_eq!(RulesProfile::DOUBLE_FREECELL.max_column_height(), 23);
+        for variant in Variant::ALL {
+            assert_eq!(variant.rules().check_column_height(), Ok(()));
+        }
+        // Fewer freecells do not change the deal.
+        assert_eq!(RulesProfile::new(8, 2, 1).max_column_height(), 19);
+        assert_eq!(
+            RulesProfile::new(3, 4, 1).check_column_height(),
+            Err(TableauError::ColumnHeightExceeded { height: 30, limit: MAX_COLUMN_HEIGHT })
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! The engine's stable public API.
+//!
+//! The crate exposes far more than a client should lean on: search helpers such as
+//! [`StatePool`](crate::game_state::StatePool), solver heuristics and experimental
+//! displays change whenever the solver needs them to. This module lists what is meant to
+//! last. Code that only names items through `stable` (or the [`prelude`](crate::prelude),
+//! a subset of it) keeps compiling across every release that does not bump the breaking
+//! part of the version: the minor version while the crate is 0.x, the major one after.
+//!
+//! Within that promise:
+//!
+//! - error enums and other enums marked `#[non_exhaustive]` may gain variants, so
+//!   matches on them need a wildcard arm;
+//! - types may gain methods and trait implementations;
+//! - the engine has no traits for clients to implement; any added here will be sealed
+//!   with a private supertrait, so they can gain methods without a breaking release.
+//!
+//! Anything reached only through its own module, outside this list, may change in any
+//! release.
+//!
+//! # Checking a release
+//!
+//! `cargo semver-checks check-release -p freecell-game-engine` compares the crate with its
+//! last published version, and `cargo public-api -p freecell-game-engine` prints the whole
+//! public API, so diffing its output before and after an edit shows what the edit added or
+//! removed. A change to an item listed here that either tool flags needs a breaking
+//! version bump.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::stable::{import_position_code, position_code, GameState, Variant};
+//!
+//! let game = GameState::from_seed(1).unwrap();
+//! let code = position_code(&game);
+//! assert_eq!(import_position_code(&code, Variant::Standard).unwrap().state, game);
+//! ```
+
+// Cards.
+pub use crate::card::{Card, Color, Rank, Suit};
+
+// Positions and their parts.
+pub use crate::foundations::{FoundationError, Foundations};
+pub use crate::freecells::{FreeCellError, FreeCells};
+pub use crate::game_state::{GameError, GameOutcome, GameState, GameStateBuilder, IntegrityError, InvalidMoveReason};
+pub use crate::tableau::{Tableau, TableauError};
+
+// Locations.
+pub use crate::location::{FoundationLocation, FreecellLocation, Location, LocationError, TableauLocation};
+
+// Moves.
+pub use crate::r#move::{Move, MoveBuildError, MoveBuilder, Pile, ValidatedMove};
+
+// Rules and variants.
+pub use crate::rules::{RulesProfile, WinCondition};
+pub use crate::variant::Variant;
+
+// Dealing.
+pub use crate::generation::{from_ms_deal_number, generate_deal, generate_double_deal, GenerationError};
+
+// Reading and writing positions and solutions.
+pub use crate::codec::{armor_moves, dearmor_moves, decode_moves, encode_moves, CodecError};
+pub use crate::import::{import_board, import_position_code, position_code, ImportError, ImportWarning, ImportedBoard};
+
+// Build identity.
+pub use crate::engine_info::{EngineInfo, ENGINE_INFO};
@@ -2,7 +2,8 @@
 //!
 //! # Overview
 //!
-//! In FreeCell solitaire, the tableau consists of 8 columns where cards are initially dealt.
+//! In FreeCell solitaire, the tableau consists of 8 columns where cards are initially dealt
+//! (10 in Double FreeCell; see [`Tableau::with_columns`]).
 //! Cards in the tableau must be built down in descending rank and alternating colors.
 //!
 //! This module provides:
@@ -50,12 +51,34 @@
 //! itself does not enforce game rules during operations like `place_card()`.
 //! This design allows higher-level game logic to implement and control rule enforcement.
 
-use crate::card::Card;
+use crate::card::{Card, Color, Rank, Suit};
 use crate::location::TableauLocation;
+use crate::rules::RulesProfile;
 use std::fmt;
 
-/// The number of tableau columns in FreeCell.
-pub const TABLEAU_COLUMN_COUNT: usize = 8;
+/// The number of tableau columns in standard FreeCell.
+pub const TABLEAU_COLUMN_COUNT: usize = RulesProfile::STANDARD.tableau_columns();
+
+/// The most tableau columns any supported variant uses (Double FreeCell has 10).
+pub const MAX_TABLEAU_COLUMNS: usize = RulesProfile::DOUBLE_FREECELL.tableau_columns();
+
+/// The tallest a column can grow in standard FreeCell: 7 dealt cards, then Queen down to
+/// Ace stacked on a dealt King.
+pub const STANDARD_MAX_COLUMN_HEIGHT: usize = RulesProfile::STANDARD.max_column_height();
+
+/// The tallest a column can grow in any supported variant (23 in Double FreeCell).
+///
+/// Columns never hold more than this: [`Tableau::place_card_at`] refuses the card that
+/// would overflow, and [`RulesProfile::check_column_height`] rejects board dimensions
+/// whose play could get there.
+pub const MAX_COLUMN_HEIGHT: usize = {
+    let standard = RulesProfile::STANDARD.max_column_height();
+    let double = RulesProfile::DOUBLE_FREECELL.max_column_height();
+    if standard > double { standard } else { double }
+};
+
+const _: () = assert!(STANDARD_MAX_COLUMN_HEIGHT == 7 + 12);
+const _: () = assert!(MAX_COLUMN_HEIGHT <= u8::MAX as usize, "column lengths are packed into a u8");
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// Error type for tableau operations.
@@ -79,6 +102,7 @@ pub const TABLEAU_COLUMN_COUNT: usize = 8;
 /// let result = tableau.validate_card_placement(0, &Card::new(Rank::Nine, Suit::Hearts));
 /// assert!(matches!(result, Err(TableauError::InvalidColor { .. })));
 /// ```
+#[non_exhaustive]
 pub enum TableauError {
     /// Attempted to access an invalid column index.
     InvalidColumn(u8),
@@ -98,11 +122,26 @@ pub enum TableauError {
     },
     /// Attempted to perform an operation on an empty column.
     EmptyColumn(u8),
+    /// Requested a column count of zero or above [`MAX_TABLEAU_COLUMNS`].
+    InvalidColumnCount(usize),
+    /// The column already holds [`MAX_COLUMN_HEIGHT`] cards.
+    ColumnFull(u8),
+    /// Board dimensions under which a column could grow to `height` cards, past `limit`.
+    ColumnHeightExceeded { height: usize, limit: usize },
     /// No valid placement found for the card.
     InvalidPlacement { card: Card },
+    /// A run of cards is not a descending, alternating-color sequence. `index` is the
+    /// position in the column of `upper`, the first card that does not fit on `lower`,
+    /// the card directly beneath it.
+    BrokenSequence {
+        column: u8,
+        index: usize,
+        lower: Card,
+        upper: Card,
+    },
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash)]
 /// Represents the main play area with multiple columns of cards.
 ///
 /// Tableau is the main play area in a FreeCell game where cards are arranged in 8 columns.
@@ -124,7 +163,49 @@ pub enum TableauError {
 /// tableau.place_card(location, card).unwrap();
 /// ```
 pub struct Tableau {
-    columns: [Vec<Card>; TABLEAU_COLUMN_COUNT],
+    columns: [Vec<Card>; MAX_TABLEAU_COLUMNS],
+    // Number of columns in play, counted from index 0. Columns at and above this
+    // index always stay empty.
+    column_count: u8,
+    // Column indices sorted by canonical key (first card, then index), kept up to date
+    // as columns empty and refill so canonical packing does not sort on every call.
+    // Derived from `columns` alone, so equal tableaus have equal orders.
+    canonical_order: [u8; MAX_TABLEAU_COLUMNS],
+}
+
+impl Clone for Tableau {
+    fn clone(&self) -> Self {
+        let mut columns = empty_columns(self.column_count());
+        for (column, from) in columns.iter_mut().zip(&self.columns) {
+            column.extend_from_slice(from);
+        }
+        Self {
+            columns,
+            column_count: self.column_count,
+            canonical_order: self.canonical_order,
+        }
+    }
+
+    /// Copies `source` into the existing column buffers instead of allocating new ones.
+    fn clone_from(&mut self, source: &Self) {
+        for (column, from) in self.columns.iter_mut().zip(&source.columns) {
+            column.clone_from(from);
+        }
+        if self.column_count < source.column_count {
+            for column in &mut self.columns[self.column_count as usize..source.column_count()] {
+                column.reserve_exact(MAX_COLUMN_HEIGHT - column.len());
+            }
+        }
+        self.column_count = source.column_count;
+        self.canonical_order = source.canonical_order;
+    }
+}
+
+/// Column buffers for a tableau of `count` columns. Columns in play are allocated at
+/// [`MAX_COLUMN_HEIGHT`] up front, so placing a card never reallocates and moves and
+/// undos stay allocation-free.
+fn empty_columns(count: usize) -> [Vec<Card>; MAX_TABLEAU_COLUMNS] {
+    std::array::from_fn(|column| if column < count { Vec::with_capacity(MAX_COLUMN_HEIGHT) } else { Vec::new() })
 }
 
 impl Default for Tableau {
@@ -148,10 +229,48 @@ impl Tableau {
     /// ```
     pub fn new() -> Self {
         Self {
-            columns: Default::default(),
+            columns: empty_columns(TABLEAU_COLUMN_COUNT),
+            column_count: TABLEAU_COLUMN_COUNT as u8,
+            canonical_order: IDENTITY_ORDER,
         }
     }
 
+    /// Create an empty tableau with `count` columns, for variants other than the
+    /// standard 8-column game.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TableauError::InvalidColumnCount` if `count` is zero or exceeds
+    /// [`MAX_TABLEAU_COLUMNS`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::tableau::Tableau;
+    /// use freecell_game_engine::location::TableauLocation;
+    ///
+    /// let tableau = Tableau::with_columns(10).unwrap();
+    /// assert_eq!(tableau.column_count(), 10);
+    /// assert!(tableau.is_column_empty(TableauLocation::new(9).unwrap()).unwrap());
+    ///
+    /// assert!(Tableau::new().is_column_empty(TableauLocation::new(9).unwrap()).is_err());
+    /// ```
+    pub fn with_columns(count: usize) -> Result<Self, TableauError> {
+        if count == 0 || count > MAX_TABLEAU_COLUMNS {
+            return Err(TableauError::InvalidColumnCount(count));
+        }
+        Ok(Self {
+            columns: empty_columns(count),
+            column_count: count as u8,
+            canonical_order: IDENTITY_ORDER,
+        })
+    }
+
+    /// The number of columns in play (8 in standard FreeCell).
+    pub fn column_count(&self) -> usize {
+        self.column_count as usize
+    }
+
     /// Add a card to the specified column, validating placement according to FreeCell rules.
     ///
     /// This method validates that the card can be legally placed according to FreeCell tableau rules:
@@ -164,6 +283,7 @@ impl Tableau {
     /// Returns `TableauError::InvalidColumn` if the index is out of bounds.
     /// Returns `TableauError::InvalidColor` if the card color is the same as the top card.
     /// Returns `TableauError::InvalidRank` if the card rank is not one lower than the top card.
+    /// Returns `TableauError::ColumnFull` if the column already holds [`MAX_COLUMN_HEIGHT`] cards.
     ///
     /// # Examples
     ///
@@ -194,15 +314,32 @@ impl Tableau {
     ) -> Result<(), TableauError> {
         // Validate the placement before adding the card
         self.validate_card_placement(location, &card)?;
+        if self.columns[location.index() as usize].len() >= MAX_COLUMN_HEIGHT {
+            return Err(TableauError::ColumnFull(location.index()));
+        }
 
         // If validation passes, add the card to the column
-        self.columns[location.index() as usize].push(card);
+        self.place_card_at_no_checks(location, card);
         Ok(())
     }
 
     pub fn place_card_at_no_checks(&mut self, location: TableauLocation, card: Card) {
-        // If validation passes, add the card to the column
-        self.columns[location.index() as usize].push(card);
+        debug_assert!(
+            (location.index() as usize) < self.column_count(),
+            "column {} is not in play",
+            location.index()
+        );
+        let column = location.index() as usize;
+        debug_assert!(
+            self.columns[column].len() < MAX_COLUMN_HEIGHT,
+            "column {} would grow past {} cards",
+            column,
+            MAX_COLUMN_HEIGHT
+        );
+        self.columns[column].push(card);
+        if self.columns[column].len() == 1 {
+            self.reorder_column(column);
+        }
     }
 
     /// Remove and return the top card from the specified column.
@@ -232,7 +369,15 @@ impl Tableau {
     /// assert_eq!(removed_card, card);
     /// ```
     pub fn remove_card(&mut self, location: TableauLocation) -> Result<Option<Card>, TableauError> {
-        Ok(self.columns[location.index() as usize].pop())
+        if location.index() as usize >= self.column_count() {
+            return Err(TableauError::InvalidColumn(location.index()));
+        }
+        let column = location.index() as usize;
+        let card = self.columns[column].pop();
+        if card.is_some() && self.columns[column].is_empty() {
+            self.reorder_column(column);
+        }
+        Ok(card)
     }
 
     /// Get a reference to the top card in a column without removing it.
@@ -259,6 +404,9 @@ impl Tableau {
     /// assert_eq!(card_ref.suit(), Suit::Hearts);
     /// ```
     pub fn get_card(&self, location: TableauLocation) -> Result<Option<&Card>, TableauError> {
+        if location.index() as usize >= self.column_count() {
+            return Err(TableauError::InvalidColumn(location.index()));
+        }
         Ok(self.columns[location.index() as usize].last())
     }
 
@@ -300,7 +448,7 @@ impl Tableau {
         index: usize,
     ) -> Result<&Card, TableauError> {
         let column = location.index() as usize;
-        if column >= self.columns.len() {
+        if column >= self.column_count() {
             return Err(TableauError::InvalidColumn(location.index()));
         }
 
@@ -327,7 +475,7 @@ impl Tableau {
     /// assert_eq!(tableau.empty_columns_count(), 7);
     /// ```
     pub fn empty_columns_count(&self) -> usize {
-        self.columns.iter().filter(|col| col.is_empty()).count()
+        self.columns().filter(|col| col.is_empty()).count()
     }
 
     /// Check if a column is empty.
@@ -353,7 +501,7 @@ impl Tableau {
     /// ```
     pub fn is_column_empty(&self, location: TableauLocation) -> Result<bool, TableauError> {
         let column = location.index() as usize;
-        if column >= self.columns.len() {
+        if column >= self.column_count() {
             return Err(TableauError::InvalidColumn(location.index()));
         }
         Ok(self.columns[column].is_empty())
@@ -381,7 +529,7 @@ impl Tableau {
     /// ```
     pub fn column_length(&self, location: TableauLocation) -> Result<usize, TableauError> {
         let column = location.index() as usize;
-        if column >= self.columns.len() {
+        if column >= self.column_count() {
             return Err(TableauError::InvalidColumn(location.index()));
         }
         Ok(self.columns[column].len())
@@ -406,7 +554,7 @@ impl Tableau {
     /// }
     /// ```
     pub fn columns(&self) -> impl Iterator<Item = &Vec<Card>> {
-        self.columns.iter()
+        self.columns[..self.column_count()].iter()
     }
 
     /// Validates if a card can be legally placed on a tableau column according to FreeCell rules.
@@ -451,7 +599,7 @@ impl Tableau {
         card: &Card,
     ) -> Result<(), TableauError> {
         let column = location.index() as usize;
-        if column >= self.columns.len() {
+        if column >= self.column_count() {
             return Err(TableauError::InvalidColumn(location.index()));
         }
 
@@ -484,6 +632,66 @@ impl Tableau {
         }
     }
 
+    /// Returns the top `count` cards of a column if they form a movable run: each
+    /// card one rank lower than, and the opposite color of, the card beneath it.
+    ///
+    /// The run is returned bottom card first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TableauError::InvalidColumn` if the location is out of bounds,
+    /// `TableauError::InsufficientCards` if the column holds fewer than `count` cards
+    /// (or `count` is zero), and `TableauError::BrokenSequence` naming the first pair,
+    /// from the bottom of the run, that breaks the sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::tableau::{Tableau, TableauError};
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::TableauLocation;
+    ///
+    /// let mut tableau = Tableau::new();
+    /// let location = TableauLocation::new(0).unwrap();
+    /// tableau.place_card_at_no_checks(location, Card::new(Rank::Five, Suit::Clubs));
+    /// tableau.place_card_at_no_checks(location, Card::new(Rank::Nine, Suit::Spades));
+    /// tableau.place_card_at_no_checks(location, Card::new(Rank::Eight, Suit::Hearts));
+    ///
+    /// assert_eq!(tableau.validate_sequence(location, 2).unwrap().len(), 2);
+    /// assert!(matches!(
+    ///     tableau.validate_sequence(location, 3),
+    ///     Err(TableauError::BrokenSequence { index: 1, .. })
+    /// ));
+    /// ```
+    pub fn validate_sequence(
+        &self,
+        location: TableauLocation,
+        count: usize,
+    ) -> Result<&[Card], TableauError> {
+        let column = self.get_column(location.index() as usize)?;
+        if count == 0 || count > column.len() {
+            return Err(TableauError::InsufficientCards {
+                column: location.index(),
+                requested: count,
+                available: column.len(),
+            });
+        }
+        let start = column.len() - count;
+        let run = &column[start..];
+        for (offset, pair) in run.windows(2).enumerate() {
+            let (lower, upper) = (pair[0], pair[1]);
+            if lower.color() == upper.color() || !lower.is_one_higher_than(&upper) {
+                return Err(TableauError::BrokenSequence {
+                    column: location.index(),
+                    index: start + offset + 1,
+                    lower,
+                    upper,
+                });
+            }
+        }
+        Ok(run)
+    }
+
     pub fn validate_card_placement_raw(
         &self,
         column: usize,
@@ -518,6 +726,50 @@ impl Tableau {
         }
     }
 
+    /// The cards that could go on a column right now: the two one rank below its top
+    /// card and of the other color, in [`Suit`] order.
+    ///
+    /// Returns `None` for a column topped by an Ace, which takes nothing, and for an
+    /// empty column, which takes anything; [`is_column_empty`](Self::is_column_empty)
+    /// tells the two apart. Move generation can look up the two cards instead of trying
+    /// every card in play against the column, and a UI can highlight where a card may go.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TableauError::InvalidColumn` if the location is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::tableau::Tableau;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::TableauLocation;
+    ///
+    /// let mut tableau = Tableau::new();
+    /// let location = TableauLocation::new(0).unwrap();
+    /// tableau.place_card_at(location, Card::new(Rank::Ten, Suit::Hearts)).unwrap();
+    ///
+    /// let [first, second] = tableau.placeable_cards(location).unwrap().unwrap();
+    /// assert_eq!((first, second), (Card::new(Rank::Nine, Suit::Spades), Card::new(Rank::Nine, Suit::Clubs)));
+    /// for card in [first, second] {
+    ///     assert!(tableau.validate_card_placement(location, &card).is_ok());
+    /// }
+    /// ```
+    pub fn placeable_cards(&self, location: TableauLocation) -> Result<Option<[Card; 2]>, TableauError> {
+        let column = self.get_column(location.index() as usize)?;
+        let Some(top) = column.last() else {
+            return Ok(None);
+        };
+        let Ok(rank) = Rank::try_from(top.rank() as u8 - 1) else {
+            return Ok(None);
+        };
+        let suits = match top.color() {
+            Color::Red => [Suit::Spades, Suit::Clubs],
+            Color::Black => [Suit::Hearts, Suit::Diamonds],
+        };
+        Ok(Some(suits.map(|suit| Card::new(rank, suit))))
+    }
+
     /// Get all cards in a column.
     ///
     /// # Errors
@@ -541,7 +793,7 @@ impl Tableau {
     /// assert_eq!(cards[0], card);
     /// ```
     pub fn get_column(&self, column: usize) -> Result<&[Card], TableauError> {
-        if column >= self.columns.len() {
+        if column >= self.column_count() {
             return Err(TableauError::InvalidColumn(column as u8));
         }
         Ok(&self.columns[column])
@@ -587,34 +839,83 @@ impl Tableau {
     ) where
         F: Fn(&Card) -> u8,
     {
-        // Collect tableau data with minimal allocations
-        let mut tableau_data: [(u8, u8, usize); 8] = [(255, 0, 0); 8]; // (first_card, len, original_index)
-        
-        for col in 0..TABLEAU_COLUMN_COUNT {
-            let len = self.columns[col].len();
-            let first_card = if len > 0 {
-                pack_card_fn(&self.columns[col][0])
-            } else {
-                255 // Empty columns use 255 as sentinel value
-            };
-            tableau_data[col] = (first_card, len as u8, col);
-        }
-
-        // Sort tableau data by first card (empty columns go to end)
-        tableau_data.sort_unstable_by_key(|(first_card, _len, _idx)| *first_card);
-
-        // Pack sorted tableau data efficiently
+        debug_assert!(
+            self.column_count() <= TABLEAU_COLUMN_COUNT,
+            "canonical packing only supports the standard 8-column layout"
+        );
+        // Columns are already kept in canonical order (empty columns at the end)
         let mut card_idx = 0;
-        for (col_idx, (_first_card, len, original_col)) in tableau_data.iter().enumerate() {
-            tableau_lens[col_idx] = *len;
-            for i in 0..*len as usize {
-                tableau_cards[card_idx] = pack_card_fn(&self.columns[*original_col][i]);
+        for (col_idx, &original_col) in self.canonical_order[..TABLEAU_COLUMN_COUNT].iter().enumerate() {
+            let column = &self.columns[original_col as usize];
+            tableau_lens[col_idx] = column.len() as u8;
+            for card in column {
+                tableau_cards[card_idx] = pack_card_fn(card);
                 card_idx += 1;
             }
         }
     }
+
+    /// Column indices in canonical order: non-empty columns by their first (bottom) card,
+    /// suit then rank, followed by the empty columns. Ties, which only a multi-deck game
+    /// can have, go to the lower index.
+    ///
+    /// The order is maintained as cards are placed and removed, so reading it costs
+    /// nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freecell_game_engine::tableau::Tableau;
+    /// use freecell_game_engine::card::{Card, Rank, Suit};
+    /// use freecell_game_engine::location::TableauLocation;
+    ///
+    /// let mut tableau = Tableau::new();
+    /// tableau.place_card_at(TableauLocation::new(5).unwrap(), Card::new(Rank::King, Suit::Clubs)).unwrap();
+    /// tableau.place_card_at(TableauLocation::new(2).unwrap(), Card::new(Rank::Ace, Suit::Clubs)).unwrap();
+    /// assert_eq!(tableau.canonical_order(), &[2, 5, 0, 1, 3, 4, 6, 7]);
+    /// ```
+    pub fn canonical_order(&self) -> &[u8] {
+        &self.canonical_order[..self.column_count()]
+    }
+
+    /// Where `column` sorts in canonical order: its first card, or after every card if
+    /// it is empty, with the index breaking ties.
+    fn canonical_key(&self, column: usize) -> (u8, u8) {
+        let first = self.columns[column]
+            .first()
+            .map_or(u8::MAX, |card| card.suit() as u8 * 13 + card.rank() as u8);
+        (first, column as u8)
+    }
+
+    /// Moves `column` to its place in the canonical order after its first card changed.
+    /// Every other column is still in order, so one insertion pass suffices.
+    fn reorder_column(&mut self, column: usize) {
+        let mut order = self.canonical_order;
+        let key = self.canonical_key(column);
+        let mut pos = order.iter().position(|&c| c as usize == column).unwrap();
+        while pos > 0 && self.canonical_key(order[pos - 1] as usize) > key {
+            order.swap(pos - 1, pos);
+            pos -= 1;
+        }
+        while pos + 1 < order.len() && self.canonical_key(order[pos + 1] as usize) < key {
+            order.swap(pos, pos + 1);
+            pos += 1;
+        }
+        self.canonical_order = order;
+    }
 }
 
+/// Every column in index order, the canonical order of an empty tableau.
+const IDENTITY_ORDER: [u8; MAX_TABLEAU_COLUMNS] = {
+    let mut order = [0u8; MAX_TABLEAU_COLUMNS];
+    let mut i = 0;
+    while i < MAX_TABLEAU_COLUMNS {
+        order[i] = i as u8;
+        i += 1;
+    }
+    order
+};
+
 impl std::fmt::Display for TableauError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -643,9 +944,34 @@ impl std::fmt::Display for TableauError {
                 column, requested, available
             ),
             TableauError::EmptyColumn(column) => write!(f, "Column {} is empty", column),
+            TableauError::InvalidColumnCount(count) => write!(
+                f,
+                "Invalid tableau column count: {} (must be 1-{})",
+                count, MAX_TABLEAU_COLUMNS
+            ),
+            TableauError::ColumnFull(column) => write!(
+                f,
+                "Column {} already holds the maximum of {} cards",
+                column, MAX_COLUMN_HEIGHT
+            ),
+            TableauError::ColumnHeightExceeded { height, limit } => write!(
+                f,
+                "Columns could grow to {} cards in this layout, more than the engine's {}",
+                height, limit
+            ),
             TableauError::InvalidPlacement { card } => {
                 write!(f, "No valid placement found for card {}", card)
             }
+            TableauError::BrokenSequence {
+                column,
+                index,
+                lower,
+                upper,
+            } => write!(
+                f,
+                "Cards in column {} do not form a sequence: {} (position {}) cannot sit on {}",
+                column, upper, index, lower
+            ),
         }
     }
 }
@@ -655,7 +981,7 @@ impl std::error::Error for TableauError {}
 impl fmt::Debug for Tableau {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> fmt::Result {
         let mut debug_struct = f.debug_struct("Tableau");
-        for col in 0..TABLEAU_COLUMN_COUNT {
+        for col in 0..self.column_count() {
             let column_name = format!("column_{}", col);
             if self.columns[col].is_empty() {
                 debug_struct.field(&column_name, &"[empty]");
@@ -674,7 +1000,7 @@ impl fmt::Debug for Tableau {
 impl fmt::Display for Tableau {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Tableau:")?;
-        for i in 0..TABLEAU_COLUMN_COUNT {
+        for i in 0..self.column_count() {
             write!(f, "  Column {}: ", i)?;
             let is_empty = if let Ok(location) = TableauLocation::new(i as u8) {
                 self.is_column_empty(location).unwrap_or(true)
@@ -879,4 +1205,79 @@ mod tests {
         assert_eq!(tableau.remove_card(location).unwrap(), Some(card));
         assert_eq!(tableau.get_card(location).unwrap(), None);
     }
+
+    #[test]
+    fn validate_sequence_reports_the_breaking_pair() {
+        let mut tableau = Tableau::new();
+        let location = TableauLocation::new(2).unwrap();
+        for (rank, suit) in [
+            (Rank::Queen, Suit::Hearts),
+            (Rank::Ten, Suit::Spades),
+            (Rank::Nine, Suit::Diamonds),
+            (Rank::Eight, Suit::Clubs),
+        ] {
+            tableau.place_card_at_no_checks(location, Card::new(rank, suit));
+        }
+
+        assert_eq!(tableau.validate_sequence(location, 3).unwrap()[0], Card::new(Rank::Ten, Suit::Spades));
+        assert_eq!(
+            tableau.validate_sequence(location, 4),
+            Err(TableauError::BrokenSequence {
+                column: 2,
+                index: 1,
+                lower: Card::new(Rank::Queen, Suit::Hearts),
+                upper: Card::new(Rank::Ten, Suit::Spades),
+            })
+        );
+        assert_eq!(
+            tableau.validate_sequence(location, 5),
+            Err(TableauError::InsufficientCards { column: 2, requested: 5, available: 4 })
+        );
+    }
+
+    #[test]
+    fn canonical_order_follows_first_cards_through_moves() {
+        let mut tableau = Tableau::new();
+        let mut rng: u32 = 7;
+        let cards: Vec<Card> = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs]
+            .into_iter()
+            .flat_map(|suit| (1..=13).map(move |rank| Card::new(Rank::try_from(rank).unwrap(), suit)))
+            .collect();
+        let mut pool = cards;
+        for step in 0..500 {
+            rng = rng.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let column = TableauLocation::new((rng >> 16) as u8 % 8).unwrap();
+            if step % 3 == 2 || pool.is_empty() {
+                if let Some(card) = tableau.remove_card(column).unwrap() {
+                    pool.push(card);
+                }
+            } else {
+                let card = pool.swap_remove((rng >> 8) as usize % pool.len());
+                tableau.place_card_at_no_checks(column, card);
+            }
+            let mut expected: Vec<usize> = (0..8).collect();
+            expected.sort_by_key(|&c| tableau.canonical_key(c));
+            let order: Vec<usize> = tableau.canonical_order().iter().map(|&c| c as usize).collect();
+            assert_eq!(order, expected, "step {}", step);
+        }
+        assert_eq!(tableau.clone().canonical_order(), tableau.canonical_order());
+    }
+
+    #[test]
+    fn placeable_cards_are_the_two_that_fit() {
+        let mut tableau = Tableau::new();
+        let column = TableauLocation::new(0).unwrap();
+        assert_eq!(tableau.placeable_cards(column).unwrap(), None);
+
+        tableau.place_card_at_no_checks(column, Card::new(Rank::Queen, Suit::Spades));
+        let fits = tableau.placeable_cards(column).unwrap().unwrap();
+        assert_eq!(fits, [Card::new(Rank::Jack, Suit::Hearts), Card::new(Rank::Jack, Suit::Diamonds)]);
+
+        tableau.place_card_at_no_checks(column, Card::new(Rank::Ace, Suit::Hearts));
+        assert_eq!(tableau.placeable_cards(column).unwrap(), None);
+        assert!(matches!(
+            tableau.placeable_cards(TableauLocation::new(9).unwrap()),
+            Err(TableauError::InvalidColumn(9))
+        ));
+    }
 }
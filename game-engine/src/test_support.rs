@@ -0,0 +1,206 @@
+//! Assertions for tests that compare whole positions.
+//!
+//! `assert_eq!` on two [`GameState`]s prints both `Debug` dumps on failure: hundreds of
+//! lines of nested structs in which the one misplaced card is hard to find.
+//! [`assert_game_states_eq!`](crate::assert_game_states_eq) prints a [`board_diff`]
+//! instead: every pile on one line, the two boards side by side in aligned columns, and
+//! the piles that differ marked.
+//!
+//! Cards are written as in [`import_board`](crate::import::import_board) (`10H`, `KS`),
+//! columns and cells from the bottom card up. The module is only compiled with the
+//! `test-support` feature; enable it from `[dev-dependencies]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::{assert_game_states_eq, Move};
+//! use freecell_game_engine::generation::generate_deal;
+//! use freecell_game_engine::test_support::board_diff;
+//!
+//! let deal = generate_deal(1).unwrap();
+//! assert_game_states_eq!(deal.clone(), generate_deal(1).unwrap());
+//!
+//! let mut moved = deal.clone();
+//! moved.execute_move(&Move::tableau_to_freecell(0, 0).unwrap()).unwrap();
+//! let diff = board_diff(&deal, &moved).unwrap();
+//! assert!(diff.lines().any(|line| line.starts_with("* column 1")));
+//! assert!(diff.lines().any(|line| line.starts_with("* cell 1")));
+//! ```
+
+use crate::card::{Card, Rank, Suit};
+use crate::game_state::GameState;
+use crate::location::{FoundationLocation, FreecellLocation};
+
+/// Asserts that two [`GameState`]s are equal, printing a [`board_diff`] if not.
+///
+/// Takes an optional message with format arguments, like `assert_eq!`.
+#[macro_export]
+macro_rules! assert_game_states_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if *left != *right {
+                    panic!(
+                        "assertion `left == right` failed for game states\n{}",
+                        $crate::test_support::board_diff(left, right).unwrap_or_default()
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if *left != *right {
+                    panic!(
+                        "assertion `left == right` failed for game states: {}\n{}",
+                        format_args!($($arg)+),
+                        $crate::test_support::board_diff(left, right).unwrap_or_default()
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Marks a pile that differs between the two boards.
+const CHANGED: &str = "*";
+
+/// Lays `left` and `right` out side by side, one pile per line, with a `*` in front of
+/// every pile that differs. Returns `None` if the two are equal.
+///
+/// Boards with different layouts (column count, usable freecells, decks) say so on a
+/// first line and list every pile either of them has.
+pub fn board_diff(left: &GameState, right: &GameState) -> Option<String> {
+    if left == right {
+        return None;
+    }
+    let left_piles = piles(left);
+    let right_piles = piles(right);
+    let rows: Vec<(String, String, String)> = pile_labels(left, right)
+        .into_iter()
+        .map(|label| {
+            let find = |piles: &[(String, String)]| {
+                piles
+                    .iter()
+                    .find(|(name, _)| *name == label)
+                    .map_or_else(|| "(none)".to_string(), |(_, cards)| cards.clone())
+            };
+            let (l, r) = (find(&left_piles), find(&right_piles));
+            (label, l, r)
+        })
+        .collect();
+
+    let label_width = rows.iter().map(|(label, _, _)| label.len()).max().unwrap_or(0).max("pile".len());
+    let left_width = rows.iter().map(|(_, l, _)| l.len()).max().unwrap_or(0).max("left".len());
+    let mut text = String::new();
+    if left.rules() != right.rules() {
+        text.push_str(&format!("layouts differ: left {:?}, right {:?}\n", left.rules(), right.rules()));
+    }
+    text.push_str(&format!("  {:label_width$} | {:left_width$} | right\n", "pile", "left"));
+    for (label, l, r) in &rows {
+        let marker = if l == r { " " } else { CHANGED };
+        let line = format!("{} {:label_width$} | {:left_width$} | {}", marker, label, l, r);
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    Some(text)
+}
+
+/// Every pile of `game` as (label, cards), foundations first, then cells, then columns.
+fn piles(game: &GameState) -> Vec<(String, String)> {
+    let foundations = (0..game.rules().foundation_piles()).map(|index| {
+        let pile = FoundationLocation::new(index as u8).unwrap();
+        let cards = match game.foundations().get_card(pile) {
+            Ok(Some(top)) => format!("up to {}", card_code(top)),
+            _ => "-".to_string(),
+        };
+        (format!("foundation {}", index + 1), cards)
+    });
+    let cells = (0..game.freecells().capacity()).map(|index| {
+        let cell = FreecellLocation::new(index as u8).unwrap();
+        let card = game.freecells().get_card(cell).ok().flatten().map_or_else(|| "-".to_string(), card_code);
+        (format!("cell {}", index + 1), card)
+    });
+    let columns = game.tableau().columns().enumerate().map(|(index, column)| {
+        let cards: Vec<String> = column.iter().map(card_code).collect();
+        let cards = if cards.is_empty() { "-".to_string() } else { cards.join(" ") };
+        (format!("column {}", index + 1), cards)
+    });
+    foundations.chain(cells).chain(columns).collect()
+}
+
+/// Pile labels of both boards, in board order, each once. Piles only `right` has (more
+/// columns or cells) come last.
+fn pile_labels(left: &GameState, right: &GameState) -> Vec<String> {
+    let mut labels: Vec<String> = piles(left).into_iter().map(|(label, _)| label).collect();
+    for (label, _) in piles(right) {
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    labels
+}
+
+/// `card` in the notation of the board importer.
+fn card_code(card: &Card) -> String {
+    let rank = match card.rank() {
+        Rank::Ace => "A".to_string(),
+        Rank::Jack => "J".to_string(),
+        Rank::Queen => "Q".to_string(),
+        Rank::King => "K".to_string(),
+        other => (other as u8).to_string(),
+    };
+    let suit = match card.suit() {
+        Suit::Spades => 'S',
+        Suit::Hearts => 'H',
+        Suit::Diamonds => 'D',
+        Suit::Clubs => 'C',
+    };
+    format!("{}{}", rank, suit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::import_board;
+    use crate::Variant;
+
+    fn board(text: &str) -> GameState {
+        import_board(text, Variant::Standard).unwrap().state
+    }
+
+    #[test]
+    fn diff_aligns_columns_and_marks_only_changed_piles() {
+        let left = board("Foundations: 9S 9H 9D 9C\n: KS QS JS 10S\n: KH QH JH 10H\n: KD QD JD 10D\n: 10C JC QC KC");
+        let mut right = left.clone();
+        right.execute_move(&crate::Move::tableau_to_foundation(0, 0).unwrap()).unwrap();
+
+        let diff = board_diff(&left, &right).unwrap();
+        let changed: Vec<&str> = diff.lines().filter(|line| line.starts_with(CHANGED)).collect();
+        assert_eq!(changed.len(), 2, "{}", diff);
+        assert_eq!(changed[0], "* foundation 1 | up to 9S     | up to 10S");
+        assert_eq!(changed[1], "* column 1     | KS QS JS 10S | KS QS JS");
+        // Every line puts its separators in the same place.
+        let bar = diff.lines().next().unwrap().find('|').unwrap();
+        assert!(diff.lines().all(|line| line.find('|') == Some(bar)));
+
+        assert_eq!(board_diff(&left, &left.clone()), None);
+    }
+
+    #[test]
+    fn macro_passes_on_equal_states_and_reports_the_diff_otherwise() {
+        let deal = crate::generation::generate_deal(7).unwrap();
+        assert_game_states_eq!(deal, deal.clone());
+        assert_game_states_eq!(deal, deal.clone(), "seed {}", 7);
+
+        let mut moved = deal.clone();
+        moved.execute_move(&crate::Move::tableau_to_freecell(2, 0).unwrap()).unwrap();
+        let failure = std::panic::catch_unwind(|| assert_game_states_eq!(deal, moved, "after one move"));
+        let payload = failure.unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.contains("after one move"));
+        assert!(message.contains("* column 3"));
+        assert!(!message.contains("GameState {"));
+    }
+}
@@ -0,0 +1,74 @@
+//! Supported FreeCell variants and their board layouts.
+//!
+//! Standard FreeCell uses one deck, 8 tableau columns and 4 freecells. Double FreeCell
+//! uses two decks (104 cards), 10 columns and 6 freecells, and builds each foundation
+//! from Ace to King twice. The engine's components are sized for the largest layout and
+//! carry their in-play size, so a [`Variant`] is only needed when creating a board.
+//!
+//! # Examples
+//!
+//! ```
+//! use freecell_game_engine::variant::Variant;
+//! use freecell_game_engine::GameState;
+//!
+//! let game = GameState::with_variant(Variant::DoubleFreecell);
+//! assert_eq!(game.tableau().column_count(), 10);
+//! assert_eq!(game.freecells().capacity(), 6);
+//! assert_eq!(game.card_count(), 104);
+//! ```
+
+use crate::rules::RulesProfile;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A FreeCell rule set with a fixed board layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Variant {
+    /// One deck, 8 columns, 4 freecells.
+    #[default]
+    Standard,
+    /// Two decks, 10 columns, 6 freecells; foundations run Ace to King twice.
+    DoubleFreecell,
+}
+
+impl Variant {
+    /// Every supported variant.
+    pub const ALL: [Variant; 2] = [Variant::Standard, Variant::DoubleFreecell];
+
+    /// The board dimensions of a fresh game of this variant.
+    pub fn rules(self) -> RulesProfile {
+        match self {
+            Variant::Standard => RulesProfile::STANDARD,
+            Variant::DoubleFreecell => RulesProfile::DOUBLE_FREECELL,
+        }
+    }
+
+    /// Number of 52-card decks in play.
+    pub fn decks(self) -> usize {
+        self.rules().decks()
+    }
+
+    /// Number of tableau columns.
+    pub fn tableau_columns(self) -> usize {
+        self.rules().tableau_columns()
+    }
+
+    /// Number of freecells.
+    pub fn freecells(self) -> usize {
+        self.rules().freecells()
+    }
+
+    /// Total number of cards dealt.
+    pub fn card_count(self) -> usize {
+        self.rules().card_count()
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Variant::Standard => write!(f, "FreeCell"),
+            Variant::DoubleFreecell => write!(f, "Double FreeCell"),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Allocator statistics per solve (`allocator-stats` feature).
+//!
+//! With the feature enabled the binary's global allocator is mimalloc wrapped in a
+//! [`CountingAllocator`], which keeps running totals of heap traffic. A [`StatsWindow`]
+//! opened before a solve and closed after it turns those totals into an
+//! [`AllocatorStats`] record for the benchmark results, so memory-motivated changes
+//! (smaller packed states, table sizing) can be compared by numbers rather than by
+//! watching `top`.
+//!
+//! Without the feature the allocator is plain mimalloc and [`StatsWindow::open`]
+//! returns `None`, so the counters cost nothing in normal runs. All figures are
+//! process-wide: with several seeds solved in parallel they overlap.
+
+use serde::{Deserialize, Serialize};
+use std::alloc::{GlobalAlloc, Layout};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+static LIVE: AtomicU64 = AtomicU64::new(0);
+static PEAK_LIVE: AtomicU64 = AtomicU64::new(0);
+
+/// Memory figures for one solve.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocatorStats {
+    /// Total bytes requested from the allocator during the solve, including memory
+    /// freed again before it ended.
+    pub bytes_allocated: u64,
+    /// Highest number of heap bytes in use at once during the solve.
+    pub peak_heap_bytes: u64,
+    /// Peak resident set size of the process so far, as mimalloc reports it.
+    pub peak_rss_bytes: u64,
+}
+
+/// A global allocator that forwards to `A` and counts the bytes passing through it.
+pub struct CountingAllocator<A>(A);
+
+impl<A> CountingAllocator<A> {
+    pub const fn new(inner: A) -> Self {
+        CountingAllocator(inner)
+    }
+}
+
+fn record_alloc(size: usize) {
+    ALLOCATED.fetch_add(size as u64, Ordering::Relaxed);
+    let live = LIVE.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+    PEAK_LIVE.fetch_max(live, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    LIVE.fetch_sub(size as u64, Ordering::Relaxed);
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.0.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+extern "C" {
+    // Part of mimalloc's public C API. The Rust bindings only declare it behind their
+    // `extended` feature, but the library is always built with it.
+    fn mi_process_info(
+        elapsed_msecs: *mut usize,
+        user_msecs: *mut usize,
+        system_msecs: *mut usize,
+        current_rss: *mut usize,
+        peak_rss: *mut usize,
+        current_commit: *mut usize,
+        peak_commit: *mut usize,
+        page_faults: *mut usize,
+    );
+}
+
+fn peak_rss_bytes() -> u64 {
+    let mut peak_rss = 0usize;
+    let null = std::ptr::null_mut();
+    unsafe { mi_process_info(null, null, null, null, &mut peak_rss, null, null, null) };
+    peak_rss as u64
+}
+
+/// Counter readings taken when a solve starts.
+#[derive(Debug)]
+pub struct StatsWindow {
+    allocated_at_start: u64,
+}
+
+impl StatsWindow {
+    /// Starts measuring, or returns `None` if the binary was built without the
+    /// `allocator-stats` feature and the counters are not running.
+    pub fn open() -> Option<Self> {
+        if !cfg!(feature = "allocator-stats") {
+            return None;
+        }
+        // Restart the high-water mark from what is live now, so it covers this solve.
+        PEAK_LIVE.store(LIVE.load(Ordering::Relaxed), Ordering::Relaxed);
+        Some(StatsWindow {
+            allocated_at_start: ALLOCATED.load(Ordering::Relaxed),
+        })
+    }
+
+    pub fn close(self) -> AllocatorStats {
+        AllocatorStats {
+            bytes_allocated: ALLOCATED.load(Ordering::Relaxed) - self.allocated_at_start,
+            peak_heap_bytes: PEAK_LIVE.load(Ordering::Relaxed),
+            peak_rss_bytes: peak_rss_bytes(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "allocator-stats"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_sees_allocations_made_inside_it() {
+        let window = StatsWindow::open().unwrap();
+        let buffer = vec![0u8; 1 << 20];
+        let stats = window.close();
+        drop(buffer);
+
+        assert!(stats.bytes_allocated >= 1 << 20);
+        assert!(stats.peak_heap_bytes >= 1 << 20);
+        assert!(stats.peak_rss_bytes > 0);
+    }
+}
@@ -0,0 +1,444 @@
+//! Deal analysis built on top of the solver.
+//!
+//! The main measure here is the freecell ladder. A common way to grade a deal's
+//! difficulty is to replay it with fewer freecells: 4, then 3, then 2, then 1. Any
+//! solution that fits in `k` freecells also works with `k + 1`, so the ladder stops at
+//! the first rung the solver cannot clear and reports the last one it did as the deal's
+//! minimum freecell count.
+//!
+//! Rungs share work. Before searching at `k` cells, the ladder replays the last solution
+//! it found with its freecell usage packed into the first `k` cells. A solution that
+//! never needs more than `k` cells at once clears the rung without a new search. The
+//! 4-cell solution from a normal benchmark solve can be passed in to seed the ladder.
+//!
+//! [`decision_points`] rates a solution instead of a deal: it counts how many options
+//! each ply had, separating forced moves from real choices.
+//!
+//! [`estimate_reachable_states`] measures how wide a deal's state space is: it counts
+//! the distinct positions reachable from the deal, up to a cap, as a difficulty feature
+//! to set beside solve times.
+//!
+//! [`find_deal_collisions`] looks for seeds whose deals are the same game: equal once
+//! columns are reordered and suits are swapped within or between colors. Such seeds
+//! measure the solver twice on one position.
+
+use crate::bench;
+use crate::packed_state::PackedGameState;
+use freecell_game_engine::card::{Card, Suit};
+use freecell_game_engine::game_state::{GameError, GameState, GameStateBuilder};
+use freecell_game_engine::generation::generate_deal;
+use freecell_game_engine::location::{FoundationLocation, FreecellLocation, Location};
+use freecell_game_engine::r#move::Move;
+use fxhash::{FxHashMap, FxHashSet};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Freecell counts tried, in order.
+pub const FREECELL_LADDER: [usize; 4] = [4, 3, 2, 1];
+
+/// The outcome of one rung of the ladder.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LadderRung {
+    pub freecells: usize,
+    pub solved: bool,
+    pub execution_time_ms: u64,
+    pub move_count: Option<usize>,
+    /// True when an earlier rung's solution fit in this many cells, so no search ran.
+    #[serde(default)]
+    pub reused_solution: bool,
+}
+
+/// The outcome of a whole ladder run.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LadderResult {
+    /// Rungs attempted, ending at the first unsolved one (if any).
+    pub rungs: Vec<LadderRung>,
+    /// Fewest freecells the deal was solved with, or `None` if even 4 failed.
+    pub min_freecells: Option<usize>,
+}
+
+/// Returns the fewest freecells (4 down to 1) with which the solver can finish
+/// `game_state`, giving each rung that needs a fresh search `budget_secs`.
+///
+/// Returns `None` if the deal is not solved even with all four freecells.
+pub fn minimum_freecells(game_state: &GameState, budget_secs: u64) -> Option<usize> {
+    run_freecell_ladder(game_state, budget_secs, None).min_freecells
+}
+
+/// Solves `game_state` with 4, 3, 2 and 1 freecells in turn and stops at the first
+/// rung that is not solved in `timeout_secs`.
+///
+/// `known_solution`, if given, is a solution for the unrestricted deal (for example from
+/// a benchmark solve); it is tried on every rung before searching.
+pub fn run_freecell_ladder(
+    game_state: &GameState,
+    timeout_secs: u64,
+    known_solution: Option<&[Move]>,
+) -> LadderResult {
+    let mut rungs = Vec::new();
+    let mut min_freecells = None;
+    let mut best_solution: Option<Vec<Move>> = known_solution.map(|moves| moves.to_vec());
+
+    for &freecells in &FREECELL_LADDER {
+        let mut limited = game_state.clone();
+        if limited.set_freecell_capacity(freecells).is_err() {
+            // A card already sits in a cell this rung would block.
+            break;
+        }
+
+        let reused = best_solution
+            .as_deref()
+            .and_then(|solution| compact_freecells(&limited, solution));
+        let rung = match reused {
+            Some(solution) => {
+                let rung = LadderRung {
+                    freecells,
+                    solved: true,
+                    execution_time_ms: 0,
+                    move_count: Some(solution.len()),
+                    reused_solution: true,
+                };
+                best_solution = Some(solution);
+                rung
+            }
+            None => {
+                let result = bench::harness_with_timing(limited, timeout_secs);
+                let rung = LadderRung {
+                    freecells,
+                    solved: result.solved,
+                    execution_time_ms: result.execution_time.as_millis() as u64,
+                    move_count: result.solution_moves.as_ref().map(|moves| moves.len()),
+                    reused_solution: false,
+                };
+                if result.solved {
+                    best_solution = result.solution_moves;
+                }
+                rung
+            }
+        };
+
+        let solved = rung.solved;
+        rungs.push(rung);
+        if !solved {
+            break;
+        }
+        min_freecells = Some(freecells);
+    }
+    LadderResult { rungs, min_freecells }
+}
+
+/// How much choice a solution had at one ply.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecisionPoint {
+    /// Legal moves in the position before the ply.
+    pub legal_moves: usize,
+    /// Legal moves other than undoing the previous ply.
+    pub alternatives: usize,
+}
+
+impl DecisionPoint {
+    /// Whether the move played was the only sensible one.
+    pub fn is_forced(&self) -> bool {
+        self.alternatives <= 1
+    }
+}
+
+/// Replays `solution` from `start` and counts the options at every ply, so forced moves
+/// can be told apart from real choices when rating a deal or explaining a solution.
+///
+/// Playing back the move just made is never counted as a choice.
+///
+/// # Errors
+///
+/// Returns the error of the first move that cannot be played.
+pub fn decision_points(start: &GameState, solution: &[Move]) -> Result<Vec<DecisionPoint>, GameError> {
+    let mut game = start.clone();
+    let mut previous: Option<Move> = None;
+    let mut points = Vec::with_capacity(solution.len());
+    for m in solution {
+        let moves = game.get_available_moves();
+        let undo = previous.map(|p| Move::single(p.destination, p.source));
+        points.push(DecisionPoint {
+            legal_moves: moves.len(),
+            alternatives: moves.iter().filter(|&&candidate| Some(candidate) != undo).count(),
+        });
+        game.execute_move(m)?;
+        previous = Some(*m);
+    }
+    Ok(points)
+}
+
+/// How many distinct positions a deal can reach.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReachableEstimate {
+    /// Distinct positions found, the deal itself included. A lower bound unless
+    /// `exhausted` is set.
+    pub distinct_states: usize,
+    /// True when every reachable position was counted before the cap.
+    pub exhausted: bool,
+    /// Moves from the deal to the deepest position counted. Under a cap, a shallow depth
+    /// means the space widens quickly.
+    pub depth: usize,
+    /// The cap the count ran under.
+    pub limit: usize,
+}
+
+/// Counts the positions reachable from `game_state` breadth-first, stopping once `limit`
+/// distinct positions have been seen.
+///
+/// Positions are compared in canonical form, so layouts that differ only in which
+/// column or freecell holds what count once, as they do for the solver. Breadth-first
+/// order makes a capped count cover every position up to some depth rather than one
+/// deep line, which keeps counts comparable between deals.
+pub fn estimate_reachable_states(game_state: &GameState, limit: usize) -> ReachableEstimate {
+    let mut visited = FxHashSet::default();
+    visited.insert(PackedGameState::from_game_state_canonical(game_state));
+    let mut level = vec![game_state.clone()];
+    let mut depth = 0;
+
+    while !level.is_empty() {
+        let mut next = Vec::new();
+        for mut game in level {
+            for m in game.get_available_moves() {
+                if visited.len() >= limit {
+                    return ReachableEstimate {
+                        distinct_states: visited.len(),
+                        exhausted: false,
+                        depth: if next.is_empty() { depth } else { depth + 1 },
+                        limit,
+                    };
+                }
+                if game.execute_move(&m).is_err() {
+                    continue;
+                }
+                if visited.insert(PackedGameState::from_game_state_canonical(&game)) {
+                    next.push(game.clone());
+                }
+                game.undo_move(&m);
+            }
+        }
+        if !next.is_empty() {
+            depth += 1;
+        }
+        level = next;
+    }
+    ReachableEstimate {
+        distinct_states: visited.len(),
+        exhausted: true,
+        depth,
+        limit,
+    }
+}
+
+/// Suit relabelings that keep every rule intact: each maps black suits to one color and
+/// red suits to the other, indexed by the suit's `u8` value (Spades, Hearts, Diamonds,
+/// Clubs).
+const SUIT_SYMMETRIES: [[Suit; 4]; 8] = {
+    use Suit::{Clubs as C, Diamonds as D, Hearts as H, Spades as S};
+    [
+        [S, H, D, C],
+        [S, D, H, C],
+        [C, H, D, S],
+        [C, D, H, S],
+        [H, S, C, D],
+        [H, C, S, D],
+        [D, S, C, H],
+        [D, C, S, H],
+    ]
+};
+
+/// Seeds whose deals are the same game up to column order and suit symmetry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DealCollision {
+    /// In increasing order; always at least two.
+    pub seeds: Vec<u64>,
+}
+
+/// A key equal for two positions exactly when one becomes the other by reordering
+/// columns and freecells and relabeling suits in a way that keeps colors alternating.
+///
+/// The key is the least canonical [`PackedGameState`] over the eight suit relabelings,
+/// so it inherits the solver's own column and freecell canonicalization.
+pub fn deal_symmetry_key(game_state: &GameState) -> PackedGameState {
+    SUIT_SYMMETRIES
+        .iter()
+        .map(|map| PackedGameState::from_game_state_canonical(&relabel_suits(game_state, map)))
+        .min()
+        .expect("there is at least one relabeling")
+}
+
+/// Deals every seed in `seeds` and groups those with equal [`deal_symmetry_key`]s.
+/// Seeds that cannot be dealt are skipped. Collisions are returned in order of their
+/// lowest seed.
+pub fn find_deal_collisions(seeds: &[u64]) -> Vec<DealCollision> {
+    let keyed: Vec<(u64, PackedGameState)> = seeds
+        .par_iter()
+        .filter_map(|&seed| generate_deal(seed).ok().map(|deal| (seed, deal_symmetry_key(&deal))))
+        .collect();
+    let mut groups: FxHashMap<PackedGameState, Vec<u64>> = FxHashMap::default();
+    for (seed, key) in keyed {
+        groups.entry(key).or_default().push(seed);
+    }
+    let mut collisions: Vec<DealCollision> = groups
+        .into_values()
+        .map(|mut seeds| {
+            seeds.sort_unstable();
+            seeds.dedup();
+            DealCollision { seeds }
+        })
+        .filter(|collision| collision.seeds.len() > 1)
+        .collect();
+    collisions.sort_by_key(|collision| collision.seeds[0]);
+    collisions
+}
+
+/// Standard-layout `game_state` with every card's suit replaced by `map[suit]`, in the
+/// same places. Foundation piles move with their suit.
+fn relabel_suits(game_state: &GameState, map: &[Suit; 4]) -> GameState {
+    let mut builder = GameStateBuilder::new();
+    for (card, location) in game_state.iter_cards() {
+        let suit = map[card.suit() as usize];
+        let location = match location {
+            Location::Foundation(_) => Location::Foundation(FoundationLocation::new(suit.foundation_index()).unwrap()),
+            other => other,
+        };
+        builder.place(location, Card::new(card.rank(), suit)).expect("relabeling keeps the position valid");
+    }
+    builder.build().expect("relabeling keeps the position valid")
+}
+
+/// Replays `solution` on `limited`, moving every freecell card into the lowest free cell
+/// that the capacity allows. Returns the rewritten solution if it wins the game.
+fn compact_freecells(limited: &GameState, solution: &[Move]) -> Option<Vec<Move>> {
+    let mut game = limited.clone();
+    // Which compacted cell holds the card the original solution put in each cell.
+    let mut cell_map: [Option<u8>; 4] = [None; 4];
+    let mut rewritten = Vec::with_capacity(solution.len());
+
+    for m in solution {
+        let source = match m.source {
+            Location::Freecell(cell) => {
+                let mapped = cell_map[cell.index() as usize].take()?;
+                Location::Freecell(FreecellLocation::new(mapped).ok()?)
+            }
+            other => other,
+        };
+        let destination = match m.destination {
+            Location::Freecell(cell) => {
+                let free = game.empty_freecells().next()?.index();
+                cell_map[cell.index() as usize] = Some(free);
+                Location::Freecell(FreecellLocation::new(free).ok()?)
+            }
+            other => other,
+        };
+        let mapped = Move::single(source, destination);
+        game.execute_move(&mapped).ok()?;
+        rewritten.push(mapped);
+    }
+
+    if game.is_won().unwrap_or(false) {
+        Some(rewritten)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::card::Suit;
+    use freecell_game_engine::fixtures;
+
+    /// Every card on the foundations except the four Kings, which sit in column 0.
+    fn kings_left_state() -> GameState {
+        fixtures::by_name("kings_in_one_column").unwrap().state()
+    }
+
+    #[test]
+    fn trivial_deal_climbs_whole_ladder() {
+        let result = run_freecell_ladder(&kings_left_state(), 5, None);
+        assert_eq!(result.min_freecells, Some(1));
+        assert_eq!(result.rungs.len(), FREECELL_LADDER.len());
+        assert!(result.rungs.iter().all(|rung| rung.solved));
+    }
+
+    #[test]
+    fn known_solution_using_one_cell_clears_every_rung_without_search() {
+        // Park the top King in the last freecell, then play all four Kings home.
+        let solution = vec![
+            Move::tableau_to_freecell(0, 3).unwrap(),
+            Move::tableau_to_foundation(0, 2).unwrap(),
+            Move::tableau_to_foundation(0, 1).unwrap(),
+            Move::tableau_to_foundation(0, 0).unwrap(),
+            Move::freecell_to_foundation(3, 3).unwrap(),
+        ];
+        let result = run_freecell_ladder(&kings_left_state(), 5, Some(&solution));
+        assert_eq!(result.min_freecells, Some(1));
+        assert!(result.rungs.iter().all(|rung| rung.reused_solution));
+    }
+
+    #[test]
+    fn playing_back_the_previous_move_is_not_a_choice() {
+        let mut game = kings_left_state();
+        // Give the top King a column of its own so that it can be played back there.
+        game.execute_move(&Move::tableau_to_tableau(0, 1).unwrap()).unwrap();
+        let solution = [
+            Move::tableau_to_freecell(1, 0).unwrap(),
+            Move::freecell_to_foundation(0, 3).unwrap(),
+        ];
+        let points = decision_points(&game, &solution).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].alternatives, points[0].legal_moves);
+        let undo = Move::freecell_to_tableau(0, 1).unwrap();
+        game.execute_move(&solution[0]).unwrap();
+        assert!(game.is_move_valid(&undo).is_ok());
+        assert_eq!(points[1].alternatives, points[1].legal_moves - 1);
+        assert!(!points[1].is_forced());
+    }
+
+    #[test]
+    fn reachable_states_count_canonical_positions_once() {
+        let full = estimate_reachable_states(&kings_left_state(), 100_000);
+        assert!(full.exhausted);
+        // The top King can go to any freecell, any empty column or home: three
+        // positions, however many moves.
+        let first_level = estimate_reachable_states(&kings_left_state(), 4);
+        assert_eq!(first_level.distinct_states, 4);
+        assert_eq!(first_level.depth, 1);
+        assert!(!first_level.exhausted);
+        assert!(full.distinct_states > first_level.distinct_states);
+        assert!(full.depth > 1);
+
+        let start_only = estimate_reachable_states(&kings_left_state(), 1);
+        assert_eq!((start_only.distinct_states, start_only.depth, start_only.exhausted), (1, 0, false));
+    }
+
+    #[test]
+    fn relabeled_and_reordered_deals_share_a_key() {
+        let deal = generate_deal(1).unwrap();
+        let key = deal_symmetry_key(&deal);
+        for map in &SUIT_SYMMETRIES {
+            assert_eq!(deal_symmetry_key(&relabel_suits(&deal, map)), key);
+        }
+        let mut moved = deal.clone();
+        moved.execute_move(&Move::tableau_to_freecell(0, 0).unwrap()).unwrap();
+        assert_ne!(deal_symmetry_key(&moved), key);
+
+        // Swapping a red suit for a black one breaks the alternation, so it is no symmetry.
+        let recolored = relabel_suits(&deal, &[Suit::Hearts, Suit::Spades, Suit::Diamonds, Suit::Clubs]);
+        assert_ne!(deal_symmetry_key(&recolored), key);
+
+        assert!(find_deal_collisions(&[1, 2, 3, 1]).is_empty());
+    }
+
+    #[test]
+    fn compaction_rejects_solutions_that_need_more_cells() {
+        let mut limited = kings_left_state();
+        limited.set_freecell_capacity(1).unwrap();
+        let two_cells = [
+            Move::tableau_to_freecell(0, 0).unwrap(),
+            Move::tableau_to_freecell(0, 1).unwrap(),
+        ];
+        assert!(compact_freecells(&limited, &two_cells).is_none());
+    }
+}
@@ -0,0 +1,470 @@
+//! Running a strategy against the clock.
+//!
+//! Every benchmark solve goes through [`run_with_timeout`]: the strategy runs on its own
+//! thread while the caller polls it, cancels it once the timeout (or the memory limit)
+//! is hit, and turns whatever came back, a result or a panic, into a [`HarnessResult`].
+//!
+//! Strategies are plain closures taking the cancel flag, and time comes from a
+//! [`Clock`], so tests can drive the timeout with a [`ManualClock`] and a fake strategy
+//! instead of waiting on a real solve.
+
+use crate::solve;
+use freecell_game_engine::r#move::Move;
+
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct HarnessResult {
+    pub solved: bool,
+    pub execution_time: Duration,
+    pub cpu_time: Duration,
+    pub states_explored: usize,
+    pub cross_deal_hits: usize,
+    pub stale_pruned: u64,
+    pub solution_moves: Option<Vec<Move>>,
+    /// The panic message if the strategy panicked instead of returning.
+    pub panic: Option<String>,
+    /// Resident memory in bytes when the solve was cancelled for exceeding the memory
+    /// limit; `None` if the limit was not hit.
+    pub memory_limit_exceeded: Option<u64>,
+    /// Search-tree shape, when the strategy was asked to collect it.
+    pub stats: Option<solve::SolverStats>,
+    /// The most promising positions searched, when the strategy was asked to keep them.
+    pub snapshots: Vec<solve::Snapshot>,
+}
+
+/// Time source polled by [`run_with_timeout_on`].
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The wall clock; what every harness function uses outside tests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// A clock that only moves when slept on, so a timeout of an hour passes in as many
+/// polls as it takes rather than in an hour. Sleeping yields to let the strategy thread
+/// make progress between polls.
+#[derive(Debug)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock {
+            start: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Time slept on this clock so far.
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+        thread::yield_now();
+    }
+}
+
+/// How often the harness checks whether the strategy has finished.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Process-wide memory ceiling in bytes; 0 means no limit.
+static MEMORY_LIMIT_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Sets the memory ceiling enforced by every harness function, or removes it.
+///
+/// The limit applies to the resident memory of the whole process, which is what the OOM
+/// killer looks at. It is checked each time the harness polls the solver (every [`POLL_INTERVAL`]),
+/// so leave headroom below the real limit. Exceeding it cancels the solve and reports
+/// [`HarnessResult::memory_limit_exceeded`] instead of letting the process be killed.
+///
+/// An rlimit (`RLIMIT_AS`) would be exact, but an allocation that hits it aborts the
+/// process, which is the outcome this is meant to avoid.
+pub fn set_memory_limit(bytes: Option<u64>) {
+    MEMORY_LIMIT_BYTES.store(bytes.unwrap_or(0), Ordering::Relaxed);
+}
+
+fn memory_limit() -> Option<u64> {
+    match MEMORY_LIMIT_BYTES.load(Ordering::Relaxed) {
+        0 => None,
+        bytes => Some(bytes),
+    }
+}
+
+/// Returns the resident memory of this process in bytes, if the platform can report it.
+#[cfg(target_os = "linux")]
+pub fn resident_memory_bytes() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let resident_pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if page_size <= 0 {
+        return None;
+    }
+    Some(resident_pages * page_size as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn resident_memory_bytes() -> Option<u64> {
+    None
+}
+
+/// Extracts the message from a panic payload, which is a `&str` or `String` for every
+/// `panic!` with a message.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panic with a non-string payload".to_string()
+    }
+}
+
+/// Returns the CPU time consumed so far by every thread in this process.
+///
+/// Multithreaded strategies burn CPU on several cores at once, so the delta of this
+/// value across a solve is a better cross-machine comparison than wall-clock time alone.
+#[cfg(unix)]
+pub fn process_cpu_time() -> Duration {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    let rc = unsafe { libc::clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID, &mut ts) };
+    if rc != 0 {
+        return Duration::ZERO;
+    }
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+#[cfg(not(unix))]
+pub fn process_cpu_time() -> Duration {
+    Duration::ZERO
+}
+
+pub fn harness(game_state: freecell_game_engine::game_state::GameState, timeout_secs: u64) -> bool {
+    let result = harness_with_timing(game_state, timeout_secs);
+    result.solved
+}
+
+pub fn harness_with_timing(game_state: freecell_game_engine::game_state::GameState, timeout_secs: u64) -> HarnessResult {
+    run_with_timeout(timeout_secs, move |cancel_flag| {
+        solve::solve_with_cancel(game_state, cancel_flag)
+    })
+}
+
+/// Like [`harness_with_timing`], ordering moves under `ordering`.
+pub fn harness_with_policy(
+    game_state: freecell_game_engine::game_state::GameState,
+    timeout_secs: u64,
+    ordering: impl Into<solve::MoveOrdering>,
+) -> HarnessResult {
+    let ordering = ordering.into();
+    run_with_timeout(timeout_secs, move |cancel_flag| {
+        solve::solve_with_cancel_policy(game_state, cancel_flag, ordering)
+    })
+}
+
+/// Like [`harness_with_policy`], packing positions for the visited tables under `packing`.
+pub fn harness_with_packing(
+    game_state: freecell_game_engine::game_state::GameState,
+    timeout_secs: u64,
+    ordering: impl Into<solve::MoveOrdering>,
+    packing: solve::Packing,
+) -> HarnessResult {
+    let ordering = ordering.into();
+    run_with_timeout(timeout_secs, move |cancel_flag| {
+        solve::solve_with_cancel_packing(game_state, cancel_flag, ordering, packing)
+    })
+}
+
+/// Like [`harness_with_policy`], also recording the shape of the search tree in
+/// [`HarnessResult::stats`].
+pub fn harness_with_stats(
+    game_state: freecell_game_engine::game_state::GameState,
+    timeout_secs: u64,
+    ordering: impl Into<solve::MoveOrdering>,
+) -> HarnessResult {
+    let ordering = ordering.into();
+    run_with_timeout(timeout_secs, move |cancel_flag| {
+        solve::solve_with_cancel_stats(game_state, cancel_flag, ordering)
+    })
+}
+
+/// Like [`harness_with_policy`], also keeping the `keep` most promising positions in
+/// [`HarnessResult::snapshots`], and the tree shape if `collect_stats` is set.
+pub fn harness_with_snapshots(
+    game_state: freecell_game_engine::game_state::GameState,
+    timeout_secs: u64,
+    ordering: impl Into<solve::MoveOrdering>,
+    keep: usize,
+    collect_stats: bool,
+) -> HarnessResult {
+    let ordering = ordering.into();
+    run_with_timeout(timeout_secs, move |cancel_flag| {
+        solve::solve_with_cancel_snapshots(game_state, cancel_flag, ordering, keep, collect_stats)
+    })
+}
+
+/// Like [`harness_with_timing`], but the solver reuses `warm_cache` as its transposition
+/// table so states survive into the next seed (experimental).
+pub fn harness_with_timing_warm(
+    game_state: freecell_game_engine::game_state::GameState,
+    timeout_secs: u64,
+    warm_cache: Arc<Mutex<solve::WarmCache>>,
+) -> HarnessResult {
+    run_with_timeout(timeout_secs, move |cancel_flag| {
+        solve::solve_with_cancel_warm(game_state, cancel_flag, warm_cache)
+    })
+}
+
+/// Like [`harness_with_timing`], but keeps searching for shorter solutions until the
+/// timeout, calling `on_improvement` for each one. The result holds the best solution.
+pub fn harness_anytime<F>(
+    game_state: freecell_game_engine::game_state::GameState,
+    timeout_secs: u64,
+    on_improvement: F,
+) -> HarnessResult
+where
+    F: FnMut(&solve::Improvement) + Send + 'static,
+{
+    run_with_timeout(timeout_secs, move |cancel_flag| {
+        solve::solve_with_cancel_anytime(game_state, cancel_flag, on_improvement)
+    })
+}
+
+/// Like [`harness_with_timing`], but runs strategy 14, which searches breadth-first
+/// until it holds `memory_budget` bytes (by default
+/// [`DEFAULT_MEMORY_BUDGET`](crate::strategies::strat14::solve::DEFAULT_MEMORY_BUDGET))
+/// and depth-first after.
+pub fn harness_hybrid(
+    game_state: freecell_game_engine::game_state::GameState,
+    timeout_secs: u64,
+    memory_budget: Option<usize>,
+) -> HarnessResult {
+    use crate::strategies::strat14::solve::{solve_with_cancel_budget, DEFAULT_MEMORY_BUDGET};
+    run_with_timeout(timeout_secs, move |cancel_flag| {
+        solve_with_cancel_budget(game_state, cancel_flag, memory_budget.unwrap_or(DEFAULT_MEMORY_BUDGET))
+    })
+}
+
+/// Runs strategy 15, which tries subgoal plans before single moves.
+pub fn harness_subgoals(game_state: freecell_game_engine::game_state::GameState, timeout_secs: u64) -> HarnessResult {
+    use crate::strategies::strat15::solve::solve_with_cancel;
+    run_with_timeout(timeout_secs, move |cancel_flag| solve_with_cancel(game_state, cancel_flag))
+}
+
+/// Runs `solve_fn` on its own thread against the wall clock; see [`run_with_timeout_on`].
+pub fn run_with_timeout<F>(timeout_secs: u64, solve_fn: F) -> HarnessResult
+where
+    F: FnOnce(Arc<AtomicBool>) -> solve::SolverResult + Send + 'static,
+{
+    run_with_timeout_on(&SystemClock, timeout_secs, solve_fn)
+}
+
+/// Runs `solve_fn` on its own thread, polling it every [`POLL_INTERVAL`] of `clock`.
+///
+/// Once `timeout_secs` have passed, or the memory limit is exceeded, the cancel flag
+/// handed to `solve_fn` is set and the harness waits for it to return. Execution time
+/// is measured on `clock` up to completion or cancellation; CPU time is always real.
+pub fn run_with_timeout_on<C, F>(clock: &C, timeout_secs: u64, solve_fn: F) -> HarnessResult
+where
+    C: Clock + ?Sized,
+    F: FnOnce(Arc<AtomicBool>) -> solve::SolverResult + Send + 'static,
+{
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let cancel_flag_thread = cancel_flag.clone();
+    let start_time = clock.now();
+    let start_cpu_time = process_cpu_time();
+    
+    let handle = thread::spawn(move || solve_fn(cancel_flag_thread));
+    
+    let timeout = Duration::from_secs(timeout_secs);
+    let mut memory_limit_exceeded = None;
+    while clock.now() - start_time < timeout {
+        if handle.is_finished() {
+            let execution_time = clock.now() - start_time;
+            let cpu_time = process_cpu_time().saturating_sub(start_cpu_time);
+            return HarnessResult::from_outcome(handle.join(), execution_time, cpu_time, None);
+        }
+        if let (Some(limit), Some(resident)) = (memory_limit(), resident_memory_bytes()) {
+            if resident > limit {
+                memory_limit_exceeded = Some(resident);
+                break;
+            }
+        }
+        clock.sleep(POLL_INTERVAL);
+    }
+    
+    let execution_time = clock.now() - start_time;
+    cancel_flag.store(true, Ordering::SeqCst);
+    let result = handle.join();
+    let cpu_time = process_cpu_time().saturating_sub(start_cpu_time);
+    HarnessResult::from_outcome(result, execution_time, cpu_time, memory_limit_exceeded)
+}
+
+impl HarnessResult {
+    /// Converts what the strategy thread returned, a result or a panic payload.
+    fn from_outcome(
+        outcome: thread::Result<solve::SolverResult>,
+        execution_time: Duration,
+        cpu_time: Duration,
+        memory_limit_exceeded: Option<u64>,
+    ) -> Self {
+        match outcome {
+            Ok(solver_result) => HarnessResult {
+                solved: solver_result.solved,
+                execution_time,
+                cpu_time,
+                states_explored: solver_result.states_explored,
+                cross_deal_hits: solver_result.cross_deal_hits,
+                stale_pruned: solver_result.stale_pruned,
+                solution_moves: solver_result.solution_moves,
+                panic: None,
+                memory_limit_exceeded,
+                stats: solver_result.stats,
+                snapshots: solver_result.snapshots,
+            },
+            Err(payload) => HarnessResult {
+                solved: false,
+                execution_time,
+                cpu_time,
+                states_explored: 0,
+                cross_deal_hits: 0,
+                stale_pruned: 0,
+                solution_moves: None,
+                panic: Some(panic_message(&*payload)),
+                memory_limit_exceeded,
+                stats: None,
+                snapshots: Vec::new(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The memory limit is process-wide; tests that set it or that would notice it
+    /// being set hold this lock.
+    static MEMORY_LIMIT_LOCK: Mutex<()> = Mutex::new(());
+
+    fn unsolved(states_explored: usize) -> solve::SolverResult {
+        solve::SolverResult {
+            solved: false,
+            solution_moves: None,
+            states_explored,
+            cross_deal_hits: 0,
+            stale_pruned: 0,
+            stats: None,
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// A strategy that keeps searching until it is told to stop.
+    fn wait_for_cancel(cancel_flag: Arc<AtomicBool>) -> solve::SolverResult {
+        while !cancel_flag.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(1));
+        }
+        unsolved(42)
+    }
+
+    #[test]
+    fn timeout_cancels_the_strategy_and_keeps_its_result() {
+        let _guard = MEMORY_LIMIT_LOCK.lock().unwrap();
+        let clock = ManualClock::new();
+        let result = run_with_timeout_on(&clock, 3600, wait_for_cancel);
+
+        assert!(!result.solved);
+        assert_eq!(result.states_explored, 42);
+        assert_eq!(result.execution_time, Duration::from_secs(3600));
+        assert!(result.panic.is_none());
+    }
+
+    #[test]
+    fn finished_strategy_returns_before_the_timeout() {
+        let _guard = MEMORY_LIMIT_LOCK.lock().unwrap();
+        let clock = ManualClock::new();
+        let result = run_with_timeout_on(&clock, 3600, |_| solve::SolverResult {
+            solved: true,
+            solution_moves: Some(Vec::new()),
+            states_explored: 1,
+            cross_deal_hits: 0,
+            stale_pruned: 0,
+            stats: None,
+            snapshots: Vec::new(),
+        });
+
+        assert!(result.solved);
+        assert_eq!(result.solution_moves, Some(Vec::new()));
+        assert!(clock.elapsed() < Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn a_solution_found_after_cancellation_still_counts() {
+        let _guard = MEMORY_LIMIT_LOCK.lock().unwrap();
+        let clock = ManualClock::new();
+        let result = run_with_timeout_on(&clock, 1, |cancel_flag| {
+            let mut result = wait_for_cancel(cancel_flag);
+            result.solved = true;
+            result
+        });
+
+        assert!(result.solved);
+        assert_eq!(result.execution_time, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn strategy_panics_are_reported_not_swallowed() {
+        let result = run_with_timeout(5, |_| panic!("strategy bug on seed {}", 7));
+        assert!(!result.solved);
+        assert_eq!(result.panic.as_deref(), Some("strategy bug on seed 7"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn exceeding_the_memory_limit_cancels_the_solve() {
+        let _guard = MEMORY_LIMIT_LOCK.lock().unwrap();
+        let resident = resident_memory_bytes().unwrap();
+        assert!(resident > 0);
+
+        // Any running process is over a one-byte limit, so the first poll trips it.
+        set_memory_limit(Some(1));
+        let result = run_with_timeout(30, wait_for_cancel);
+        set_memory_limit(None);
+
+        assert!(!result.solved);
+        assert!(result.memory_limit_exceeded.is_some());
+        assert!(result.execution_time < Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,981 @@
+//! BenchmarkPlan: everything a seed benchmark run needs to know up front.
+//!
+//! A plan is built with [`BenchmarkPlan::builder`], from command-line arguments, or from a
+//! TOML file, and is serialized into the benchmark summary so every results file records
+//! exactly how it was produced.
+
+use crate::solve::{ColumnPolicy, MoveOrdering};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+
+/// Strategies the harness knows how to run.
+pub const KNOWN_STRATEGIES: &[&str] = &["strat13", "strat14", "strat15"];
+
+/// Error type for building a BenchmarkPlan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    EmptySeedRange,
+    InvalidSeed(u64),
+    SeedRangeOverflow { start: u64, count: u64 },
+    NoTimeoutTiers,
+    InvalidTimeout(u64),
+    UnknownStrategy(String),
+    InvalidParallelism(usize),
+    WarmCacheRequiresSequentialRun,
+    WarmCacheRequiresDefaultColumnPolicy,
+    WarmCacheExcludesTreeStats,
+    WarmCacheExcludesFreecellWeight,
+    WarmCacheExcludesUnsolvedExport,
+    WarmCacheExcludesPackingComparison,
+    RequiresStrat13 { option: String, strategy: String },
+    InvalidMemoryLimit(u64),
+    InvalidTotalBudget(u64),
+    MissingValue(String),
+    InvalidValue { flag: String, value: String },
+    UnknownArgument(String),
+    Io(String),
+    Toml(String),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::EmptySeedRange => write!(f, "Seed range must contain at least one seed"),
+            PlanError::InvalidSeed(seed) => write!(f, "Invalid seed {}: seeds start at 1", seed),
+            PlanError::SeedRangeOverflow { start, count } => {
+                write!(f, "Seed range starting at {} with {} seeds overflows u64", start, count)
+            }
+            PlanError::NoTimeoutTiers => write!(f, "At least one timeout tier is required"),
+            PlanError::InvalidTimeout(secs) => {
+                write!(f, "Invalid timeout {}s: tiers must be positive and strictly increasing", secs)
+            }
+            PlanError::UnknownStrategy(name) => {
+                write!(f, "Unknown strategy '{}', expected one of {:?}", name, KNOWN_STRATEGIES)
+            }
+            PlanError::InvalidParallelism(n) => write!(f, "Invalid parallelism {}: must be at least 1", n),
+            PlanError::WarmCacheRequiresSequentialRun => {
+                write!(f, "The warm cache experiment requires parallelism = 1")
+            }
+            PlanError::WarmCacheRequiresDefaultColumnPolicy => {
+                write!(f, "The warm cache experiment only runs the default column policy, without an A/B policy")
+            }
+            PlanError::WarmCacheExcludesTreeStats => {
+                write!(f, "The warm cache experiment cannot record search-tree statistics")
+            }
+            PlanError::WarmCacheExcludesFreecellWeight => {
+                write!(f, "The warm cache experiment cannot weigh freecell use")
+            }
+            PlanError::WarmCacheExcludesUnsolvedExport => {
+                write!(f, "The warm cache experiment cannot export unsolved positions")
+            }
+            PlanError::WarmCacheExcludesPackingComparison => {
+                write!(f, "The warm cache experiment cannot compare packings")
+            }
+            PlanError::RequiresStrat13 { option, strategy } => {
+                write!(f, "{} is only supported by strat13, not {}", option, strategy)
+            }
+            PlanError::InvalidMemoryLimit(mb) => write!(f, "Invalid memory limit {} MiB: must be at least 1", mb),
+            PlanError::InvalidTotalBudget(secs) => write!(f, "Invalid total budget {}s: must be at least 1s", secs),
+            PlanError::MissingValue(flag) => write!(f, "Missing value for {}", flag),
+            PlanError::InvalidValue { flag, value } => write!(f, "Invalid value '{}' for {}", value, flag),
+            PlanError::UnknownArgument(arg) => write!(f, "Unknown argument: {}", arg),
+            PlanError::Io(msg) => write!(f, "Failed to read plan file: {}", msg),
+            PlanError::Toml(msg) => write!(f, "Failed to parse plan file: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+/// A validated, non-empty, contiguous range of deal seeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawSeedRange")]
+pub struct SeedRange {
+    start: u64,
+    count: u64,
+}
+
+#[derive(Deserialize)]
+struct RawSeedRange {
+    start: u64,
+    count: u64,
+}
+
+impl TryFrom<RawSeedRange> for SeedRange {
+    type Error = PlanError;
+
+    fn try_from(raw: RawSeedRange) -> Result<Self, Self::Error> {
+        SeedRange::new(raw.start, raw.count)
+    }
+}
+
+impl SeedRange {
+    /// Creates a range of `count` seeds beginning at `start`.
+    pub fn new(start: u64, count: u64) -> Result<Self, PlanError> {
+        if start == 0 {
+            return Err(PlanError::InvalidSeed(start));
+        }
+        if count == 0 {
+            return Err(PlanError::EmptySeedRange);
+        }
+        if start.checked_add(count - 1).is_none() {
+            return Err(PlanError::SeedRangeOverflow { start, count });
+        }
+        Ok(Self { start, count })
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// The last seed in the range (inclusive).
+    pub fn end(&self) -> u64 {
+        self.start + self.count - 1
+    }
+
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u64> {
+        self.start..=self.end()
+    }
+}
+
+/// Where the seeds for a run come from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedSource {
+    Range(SeedRange),
+    List(Vec<u64>),
+}
+
+impl SeedSource {
+    pub fn seeds(&self) -> Vec<u64> {
+        match self {
+            SeedSource::Range(range) => range.iter().collect(),
+            SeedSource::List(seeds) => seeds.clone(),
+        }
+    }
+}
+
+impl fmt::Display for SeedSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedSource::Range(range) => write!(f, "seeds {}-{}", range.start(), range.end()),
+            SeedSource::List(seeds) => write!(f, "{} listed seeds", seeds.len()),
+        }
+    }
+}
+
+/// Where benchmark output is written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputStore {
+    pub summary_file: String,
+    pub results_dir: String,
+    /// An NDJSON file each result is appended to as its seed completes. While set, the
+    /// summary is only written at the end of the run. See [`crate::result_stream`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ndjson_file: Option<String>,
+}
+
+/// A complete description of a seed benchmark run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchmarkPlan {
+    pub seeds: Vec<SeedSource>,
+    /// Timeouts in seconds. Seeds left unsolved at one tier are retried at the next.
+    pub timeout_tiers: Vec<u64>,
+    /// Wall-clock seconds for the whole run. Instead of climbing the tiers seed by seed,
+    /// every seed gets a pass at each tier in turn, capped at its share of the time left,
+    /// and whatever is left after the last tier is shared out among the seeds still
+    /// unsolved. See [`crate::run_budget`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_budget_secs: Option<u64>,
+    pub strategy: String,
+    pub output: OutputStore,
+    /// Number of seeds solved concurrently.
+    pub parallelism: usize,
+    /// Keep the transposition table alive across seeds (experimental).
+    #[serde(default)]
+    pub warm_cache: bool,
+    /// After each seed, also replay it with 3, 2 and 1 freecells and record the minimum.
+    #[serde(default)]
+    pub freecell_ladder: bool,
+    /// For each seed, also count up to this many distinct positions reachable from the
+    /// deal and record the count as a difficulty feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reachable_state_limit: Option<usize>,
+    /// Cancel a solve once the process's resident memory exceeds this many MiB, and
+    /// record the seed as failed, instead of risking the OOM killer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit_mb: Option<u64>,
+    /// An endgame database built by `solver certify`; strat13 finishes along its
+    /// certificates as soon as it reaches a certified position.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endgame_db: Option<String>,
+    /// How strat13 orders moves around the column it just played from.
+    #[serde(default)]
+    pub column_policy: ColumnPolicy,
+    /// A/B mode: also solve every seed under this policy, with the same timeout tiers,
+    /// and record both runs side by side.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ab_column_policy: Option<ColumnPolicy>,
+    /// Prefer solutions that keep the freecells empty: strat13 pushes each move this many
+    /// places back in its order per card the cells hold after it, and every solution is
+    /// then reordered to park cards as briefly as it can. 0 turns both off.
+    #[serde(default)]
+    pub freecell_weight: u32,
+    /// For every seed that times out, record this many of the most promising positions
+    /// the last tier searched, as position codes others can pick up with `solver resume`.
+    /// 0 turns it off.
+    #[serde(default)]
+    pub unsolved_positions: usize,
+    /// Shorten every solution by re-solving stretches of up to this many moves with a
+    /// small bounded search; see [`crate::solution_minimize`]. 0 turns it off.
+    #[serde(default)]
+    pub shorten_window: usize,
+    /// Also solve every seed with exact packing, each arrangement of the piles its own
+    /// entry in the visited tables, and record it next to the canonical run to show how
+    /// much canonicalization saves.
+    #[serde(default)]
+    pub compare_packing: bool,
+    /// Record the shape of every search tree (branching, dead ends, re-expansions) next
+    /// to each seed's result. Slows the solver down.
+    #[serde(default)]
+    pub tree_stats: bool,
+    /// After the run, look for seeds whose deals are the same game up to column order and
+    /// suit symmetry, and record them in the summary.
+    #[serde(default)]
+    pub deal_collisions: bool,
+}
+
+impl BenchmarkPlan {
+    pub fn builder() -> BenchmarkPlanBuilder {
+        BenchmarkPlanBuilder::default()
+    }
+
+    /// All seeds in the plan, in order, with duplicates removed.
+    pub fn seeds(&self) -> Vec<u64> {
+        let mut seen = std::collections::HashSet::new();
+        self.seeds
+            .iter()
+            .flat_map(|source| source.seeds())
+            .filter(|seed| seen.insert(*seed))
+            .collect()
+    }
+
+    /// The longest timeout any seed can be given.
+    pub fn max_timeout_secs(&self) -> u64 {
+        self.timeout_tiers.last().copied().unwrap_or(0)
+    }
+
+    /// Parses a plan from the contents of a TOML file and validates it.
+    pub fn from_toml_str(contents: &str) -> Result<Self, PlanError> {
+        let plan: BenchmarkPlan = toml::from_str(contents).map_err(|e| PlanError::Toml(e.to_string()))?;
+        plan.validate()?;
+        Ok(plan)
+    }
+
+    /// Builds a plan from command-line arguments (excluding the program name).
+    ///
+    /// `--plan <file>` loads a TOML plan; any other flags given after it override the file.
+    pub fn from_args<I>(args: I) -> Result<Self, PlanError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut builder = BenchmarkPlanBuilder::default();
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| PlanError::MissingValue(flag.clone()));
+            match flag.as_str() {
+                "--plan" => {
+                    let path = value()?;
+                    let contents = fs::read_to_string(&path).map_err(|e| PlanError::Io(e.to_string()))?;
+                    builder = BenchmarkPlan::from_toml_str(&contents)?.into();
+                }
+                "--seeds" => {
+                    let raw = value()?;
+                    let (start, count) = parse_seed_range(&raw)
+                        .ok_or_else(|| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.clear_seeds().seed_range(start, count);
+                }
+                "--seed-list" => {
+                    let raw = value()?;
+                    let seeds = raw
+                        .split(',')
+                        .map(|s| s.trim().parse::<u64>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.clear_seeds().seed_list(seeds);
+                }
+                "--timeout" => {
+                    let raw = value()?;
+                    let tiers = raw
+                        .split(',')
+                        .map(|s| s.trim().parse::<u64>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.timeout_tiers(tiers);
+                }
+                "--total-budget" => {
+                    let raw = value()?;
+                    let secs = parse_duration_secs(&raw)
+                        .ok_or_else(|| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.total_budget_secs(secs);
+                }
+                "--strategy" => builder = builder.strategy(value()?),
+                "--summary" => builder = builder.summary_file(value()?),
+                "--results-dir" => builder = builder.results_dir(value()?),
+                "--ndjson" => builder = builder.ndjson_file(value()?),
+                "--parallelism" => {
+                    let raw = value()?;
+                    let n = raw
+                        .parse::<usize>()
+                        .map_err(|_| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.parallelism(n);
+                }
+                "--warm-cache" => builder = builder.warm_cache(true),
+                "--freecell-ladder" => builder = builder.freecell_ladder(true),
+                "--tree-stats" => builder = builder.tree_stats(true),
+                "--compare-packing" => builder = builder.compare_packing(true),
+                "--deal-collisions" => builder = builder.deal_collisions(true),
+                "--reachable-states" => {
+                    let raw = value()?;
+                    let limit = raw
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&limit| limit > 0)
+                        .ok_or_else(|| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.reachable_state_limit(limit);
+                }
+                "--memory-limit-mb" => {
+                    let raw = value()?;
+                    let mb = raw
+                        .parse::<u64>()
+                        .map_err(|_| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.memory_limit_mb(mb);
+                }
+                "--endgame-db" => builder = builder.endgame_db(value()?),
+                "--conserve-freecells" => {
+                    let raw = value()?;
+                    let weight = raw
+                        .parse::<u32>()
+                        .map_err(|_| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.freecell_weight(weight);
+                }
+                "--export-unsolved" => {
+                    let raw = value()?;
+                    let count = raw
+                        .parse::<usize>()
+                        .map_err(|_| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.unsolved_positions(count);
+                }
+                "--shorten-solutions" => {
+                    let raw = value()?;
+                    // A window under two moves has nothing to shorten.
+                    let window = raw
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&window| window >= 2)
+                        .ok_or_else(|| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = builder.shorten_window(window);
+                }
+                "--column-policy" | "--ab-column-policy" => {
+                    let raw = value()?;
+                    let policy = raw
+                        .parse::<ColumnPolicy>()
+                        .map_err(|_| PlanError::InvalidValue { flag: flag.clone(), value: raw.clone() })?;
+                    builder = if flag == "--column-policy" {
+                        builder.column_policy(policy)
+                    } else {
+                        builder.ab_column_policy(policy)
+                    };
+                }
+                _ => return Err(PlanError::UnknownArgument(flag)),
+            }
+        }
+        builder.build()
+    }
+
+    fn validate(&self) -> Result<(), PlanError> {
+        for source in &self.seeds {
+            if let SeedSource::List(seeds) = source {
+                if let Some(&seed) = seeds.iter().find(|&&s| s == 0) {
+                    return Err(PlanError::InvalidSeed(seed));
+                }
+            }
+        }
+        if self.seeds().is_empty() {
+            return Err(PlanError::EmptySeedRange);
+        }
+        if self.timeout_tiers.is_empty() {
+            return Err(PlanError::NoTimeoutTiers);
+        }
+        let mut previous = 0;
+        for &secs in &self.timeout_tiers {
+            if secs <= previous {
+                return Err(PlanError::InvalidTimeout(secs));
+            }
+            previous = secs;
+        }
+        if !KNOWN_STRATEGIES.contains(&self.strategy.as_str()) {
+            return Err(PlanError::UnknownStrategy(self.strategy.clone()));
+        }
+        if self.parallelism == 0 {
+            return Err(PlanError::InvalidParallelism(self.parallelism));
+        }
+        if self.warm_cache && self.parallelism > 1 {
+            return Err(PlanError::WarmCacheRequiresSequentialRun);
+        }
+        if self.warm_cache && (self.column_policy != ColumnPolicy::default() || self.ab_column_policy.is_some()) {
+            return Err(PlanError::WarmCacheRequiresDefaultColumnPolicy);
+        }
+        if self.warm_cache && self.tree_stats {
+            return Err(PlanError::WarmCacheExcludesTreeStats);
+        }
+        if self.warm_cache && self.freecell_weight > 0 {
+            return Err(PlanError::WarmCacheExcludesFreecellWeight);
+        }
+        if self.warm_cache && self.unsolved_positions > 0 {
+            return Err(PlanError::WarmCacheExcludesUnsolvedExport);
+        }
+        if self.warm_cache && self.compare_packing {
+            return Err(PlanError::WarmCacheExcludesPackingComparison);
+        }
+        if self.strategy != "strat13" {
+            let strat13_only = [
+                (self.warm_cache, "The warm cache experiment"),
+                (self.column_policy != ColumnPolicy::default(), "A column policy"),
+                (self.ab_column_policy.is_some(), "A/B column policy mode"),
+                (self.tree_stats, "Search-tree statistics"),
+                (self.freecell_weight > 0, "Freecell conservation"),
+                (self.unsolved_positions > 0, "Exporting unsolved positions"),
+                (self.compare_packing, "Comparing packings"),
+                (self.endgame_db.is_some(), "An endgame database"),
+            ];
+            if let Some((_, option)) = strat13_only.iter().find(|(set, _)| *set) {
+                return Err(PlanError::RequiresStrat13 {
+                    option: option.to_string(),
+                    strategy: self.strategy.clone(),
+                });
+            }
+        }
+        if self.memory_limit_mb == Some(0) {
+            return Err(PlanError::InvalidMemoryLimit(0));
+        }
+        if self.total_budget_secs == Some(0) {
+            return Err(PlanError::InvalidTotalBudget(0));
+        }
+        Ok(())
+    }
+
+    /// Bytes strat14 may hold breadth-first per solve before going depth-first: a quarter
+    /// of the memory limit shared between the concurrent solves, or `None` for strat14's
+    /// default without a limit. The rest covers the depth-first table, the moment a
+    /// growing visited set holds both its old and new tables, and the process itself.
+    pub fn hybrid_memory_budget(&self) -> Option<usize> {
+        self.memory_limit_mb.map(|mb| (mb as usize * 1024 * 1024) / 4 / self.parallelism)
+    }
+
+    /// How strat13 orders moves under this plan, with `column_policy` in place of the
+    /// plan's own (for the B side of an A/B run).
+    pub fn move_ordering(&self, column_policy: ColumnPolicy) -> MoveOrdering {
+        MoveOrdering { column_policy, freecell_weight: self.freecell_weight }
+    }
+}
+
+impl Default for BenchmarkPlan {
+    /// Seeds 1-32000, a single 120s tier, strategy 13, written to the working directory.
+    fn default() -> Self {
+        Self {
+            seeds: vec![SeedSource::Range(SeedRange { start: 1, count: 32000 })],
+            timeout_tiers: vec![120],
+            total_budget_secs: None,
+            strategy: "strat13".to_string(),
+            output: OutputStore {
+                summary_file: "benchmark_summary.json".to_string(),
+                results_dir: "results".to_string(),
+                ndjson_file: None,
+            },
+            parallelism: 1,
+            warm_cache: false,
+            freecell_ladder: false,
+            reachable_state_limit: None,
+            memory_limit_mb: None,
+            endgame_db: None,
+            column_policy: ColumnPolicy::default(),
+            ab_column_policy: None,
+            freecell_weight: 0,
+            unsolved_positions: 0,
+            shorten_window: 0,
+            compare_packing: false,
+            tree_stats: false,
+            deal_collisions: false,
+        }
+    }
+}
+
+/// Parses `START-END` (inclusive) or `START+COUNT` into (start, count).
+pub(crate) fn parse_seed_range(raw: &str) -> Option<(u64, u64)> {
+    if let Some((start, count)) = raw.split_once('+') {
+        return Some((start.trim().parse().ok()?, count.trim().parse().ok()?));
+    }
+    let (start, end) = raw.split_once('-')?;
+    let start: u64 = start.trim().parse().ok()?;
+    let end: u64 = end.trim().parse().ok()?;
+    Some((start, end.checked_sub(start)? + 1))
+}
+
+/// Parses a duration such as `8h`, `90m`, `45s` or a bare number of seconds.
+pub(crate) fn parse_duration_secs(raw: &str) -> Option<u64> {
+    let raw = raw.trim();
+    let (number, unit_secs) = match raw.char_indices().last()? {
+        (i, 'h') => (&raw[..i], 3600),
+        (i, 'm') => (&raw[..i], 60),
+        (i, 's') => (&raw[..i], 1),
+        _ => (raw, 1),
+    };
+    number.trim().parse::<u64>().ok()?.checked_mul(unit_secs)
+}
+
+/// Builder for [`BenchmarkPlan`]. Unset fields fall back to [`BenchmarkPlan::default`].
+#[derive(Debug, Clone, Default)]
+pub struct BenchmarkPlanBuilder {
+    seeds: Vec<SeedSource>,
+    seed_error: Option<PlanError>,
+    timeout_tiers: Option<Vec<u64>>,
+    total_budget_secs: Option<u64>,
+    strategy: Option<String>,
+    summary_file: Option<String>,
+    results_dir: Option<String>,
+    ndjson_file: Option<String>,
+    parallelism: Option<usize>,
+    warm_cache: bool,
+    freecell_ladder: bool,
+    reachable_state_limit: Option<usize>,
+    memory_limit_mb: Option<u64>,
+    endgame_db: Option<String>,
+    column_policy: ColumnPolicy,
+    ab_column_policy: Option<ColumnPolicy>,
+    freecell_weight: u32,
+    unsolved_positions: usize,
+    shorten_window: usize,
+    compare_packing: bool,
+    tree_stats: bool,
+    deal_collisions: bool,
+}
+
+impl BenchmarkPlanBuilder {
+    /// Adds `count` consecutive seeds starting at `start`.
+    pub fn seed_range(mut self, start: u64, count: u64) -> Self {
+        match SeedRange::new(start, count) {
+            Ok(range) => self.seeds.push(SeedSource::Range(range)),
+            Err(e) => self.seed_error = Some(e),
+        }
+        self
+    }
+
+    /// Adds an explicit list of seeds.
+    pub fn seed_list(mut self, seeds: Vec<u64>) -> Self {
+        self.seeds.push(SeedSource::List(seeds));
+        self
+    }
+
+    fn clear_seeds(mut self) -> Self {
+        self.seeds.clear();
+        self.seed_error = None;
+        self
+    }
+
+    pub fn timeout_secs(self, secs: u64) -> Self {
+        self.timeout_tiers(vec![secs])
+    }
+
+    pub fn timeout_tiers(mut self, tiers: Vec<u64>) -> Self {
+        self.timeout_tiers = Some(tiers);
+        self
+    }
+
+    pub fn total_budget_secs(mut self, secs: u64) -> Self {
+        self.total_budget_secs = Some(secs);
+        self
+    }
+
+    pub fn strategy(mut self, name: impl Into<String>) -> Self {
+        self.strategy = Some(name.into());
+        self
+    }
+
+    pub fn summary_file(mut self, path: impl Into<String>) -> Self {
+        self.summary_file = Some(path.into());
+        self
+    }
+
+    pub fn results_dir(mut self, path: impl Into<String>) -> Self {
+        self.results_dir = Some(path.into());
+        self
+    }
+
+    pub fn ndjson_file(mut self, path: impl Into<String>) -> Self {
+        self.ndjson_file = Some(path.into());
+        self
+    }
+
+    pub fn parallelism(mut self, n: usize) -> Self {
+        self.parallelism = Some(n);
+        self
+    }
+
+    pub fn warm_cache(mut self, enabled: bool) -> Self {
+        self.warm_cache = enabled;
+        self
+    }
+
+    pub fn freecell_ladder(mut self, enabled: bool) -> Self {
+        self.freecell_ladder = enabled;
+        self
+    }
+
+    pub fn reachable_state_limit(mut self, limit: usize) -> Self {
+        self.reachable_state_limit = Some(limit);
+        self
+    }
+
+    pub fn memory_limit_mb(mut self, mb: u64) -> Self {
+        self.memory_limit_mb = Some(mb);
+        self
+    }
+
+    pub fn endgame_db(mut self, path: impl Into<String>) -> Self {
+        self.endgame_db = Some(path.into());
+        self
+    }
+
+    pub fn column_policy(mut self, policy: ColumnPolicy) -> Self {
+        self.column_policy = policy;
+        self
+    }
+
+    pub fn ab_column_policy(mut self, policy: ColumnPolicy) -> Self {
+        self.ab_column_policy = Some(policy);
+        self
+    }
+
+    pub fn freecell_weight(mut self, weight: u32) -> Self {
+        self.freecell_weight = weight;
+        self
+    }
+
+    pub fn unsolved_positions(mut self, count: usize) -> Self {
+        self.unsolved_positions = count;
+        self
+    }
+
+    pub fn shorten_window(mut self, window: usize) -> Self {
+        self.shorten_window = window;
+        self
+    }
+
+    pub fn compare_packing(mut self, enabled: bool) -> Self {
+        self.compare_packing = enabled;
+        self
+    }
+
+    pub fn tree_stats(mut self, enabled: bool) -> Self {
+        self.tree_stats = enabled;
+        self
+    }
+
+    pub fn deal_collisions(mut self, enabled: bool) -> Self {
+        self.deal_collisions = enabled;
+        self
+    }
+
+    pub fn build(self) -> Result<BenchmarkPlan, PlanError> {
+        if let Some(e) = self.seed_error {
+            return Err(e);
+        }
+        let defaults = BenchmarkPlan::default();
+        let plan = BenchmarkPlan {
+            seeds: if self.seeds.is_empty() { defaults.seeds } else { self.seeds },
+            timeout_tiers: self.timeout_tiers.unwrap_or(defaults.timeout_tiers),
+            total_budget_secs: self.total_budget_secs,
+            strategy: self.strategy.unwrap_or(defaults.strategy),
+            output: OutputStore {
+                summary_file: self.summary_file.unwrap_or(defaults.output.summary_file),
+                results_dir: self.results_dir.unwrap_or(defaults.output.results_dir),
+                ndjson_file: self.ndjson_file,
+            },
+            parallelism: self.parallelism.unwrap_or(defaults.parallelism),
+            warm_cache: self.warm_cache,
+            freecell_ladder: self.freecell_ladder,
+            reachable_state_limit: self.reachable_state_limit,
+            memory_limit_mb: self.memory_limit_mb,
+            endgame_db: self.endgame_db,
+            column_policy: self.column_policy,
+            ab_column_policy: self.ab_column_policy,
+            freecell_weight: self.freecell_weight,
+            unsolved_positions: self.unsolved_positions,
+            shorten_window: self.shorten_window,
+            compare_packing: self.compare_packing,
+            tree_stats: self.tree_stats,
+            deal_collisions: self.deal_collisions,
+        };
+        plan.validate()?;
+        Ok(plan)
+    }
+}
+
+impl From<BenchmarkPlan> for BenchmarkPlanBuilder {
+    fn from(plan: BenchmarkPlan) -> Self {
+        Self {
+            seeds: plan.seeds,
+            seed_error: None,
+            timeout_tiers: Some(plan.timeout_tiers),
+            total_budget_secs: plan.total_budget_secs,
+            strategy: Some(plan.strategy),
+            summary_file: Some(plan.output.summary_file),
+            results_dir: Some(plan.output.results_dir),
+            ndjson_file: plan.output.ndjson_file,
+            parallelism: Some(plan.parallelism),
+            warm_cache: plan.warm_cache,
+            freecell_ladder: plan.freecell_ladder,
+            reachable_state_limit: plan.reachable_state_limit,
+            memory_limit_mb: plan.memory_limit_mb,
+            endgame_db: plan.endgame_db,
+            column_policy: plan.column_policy,
+            ab_column_policy: plan.ab_column_policy,
+            freecell_weight: plan.freecell_weight,
+            unsolved_positions: plan.unsolved_positions,
+            shorten_window: plan.shorten_window,
+            compare_packing: plan.compare_packing,
+            tree_stats: plan.tree_stats,
+            deal_collisions: plan.deal_collisions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn default_plan_matches_previous_hard_coded_run() {
+        let plan = BenchmarkPlan::builder().build().unwrap();
+        assert_eq!(plan, BenchmarkPlan::default());
+        assert_eq!(plan.seeds().len(), 32000);
+        assert_eq!(plan.max_timeout_secs(), 120);
+    }
+
+    #[test]
+    fn seed_range_rejects_zero_and_empty() {
+        assert_eq!(SeedRange::new(0, 10), Err(PlanError::InvalidSeed(0)));
+        assert_eq!(SeedRange::new(1, 0), Err(PlanError::EmptySeedRange));
+        assert!(matches!(SeedRange::new(u64::MAX, 2), Err(PlanError::SeedRangeOverflow { .. })));
+        let range = SeedRange::new(5, 3).unwrap();
+        assert_eq!(range.iter().collect::<Vec<_>>(), vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn timeout_tiers_must_increase() {
+        let result = BenchmarkPlan::builder().timeout_tiers(vec![60, 30]).build();
+        assert_eq!(result, Err(PlanError::InvalidTimeout(30)));
+    }
+
+    #[test]
+    fn warm_cache_rejected_with_parallel_run() {
+        let result = BenchmarkPlan::builder().parallelism(4).warm_cache(true).build();
+        assert_eq!(result, Err(PlanError::WarmCacheRequiresSequentialRun));
+    }
+
+    #[test]
+    fn from_args_overrides_defaults() {
+        let plan = BenchmarkPlan::from_args(args(&[
+            "--seeds", "10-19", "--timeout", "5,30", "--parallelism", "2", "--summary", "out.json",
+            "--freecell-ladder", "--memory-limit-mb", "4096", "--reachable-states", "5000",
+            "--deal-collisions", "--ndjson", "run.ndjson",
+        ]))
+        .unwrap();
+        assert_eq!(plan.seeds(), (10..=19).collect::<Vec<_>>());
+        assert_eq!(plan.timeout_tiers, vec![5, 30]);
+        assert_eq!(plan.parallelism, 2);
+        assert_eq!(plan.output.summary_file, "out.json");
+        assert_eq!(plan.output.results_dir, "results");
+        assert_eq!(plan.output.ndjson_file.as_deref(), Some("run.ndjson"));
+        assert!(plan.freecell_ladder);
+        assert_eq!(plan.memory_limit_mb, Some(4096));
+        assert_eq!(plan.reachable_state_limit, Some(5000));
+        assert!(plan.deal_collisions);
+    }
+
+    #[test]
+    fn from_args_reports_bad_input() {
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--bogus"])),
+            Err(PlanError::UnknownArgument("--bogus".to_string()))
+        );
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--timeout"])),
+            Err(PlanError::MissingValue("--timeout".to_string()))
+        );
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--strategy", "strat99"])),
+            Err(PlanError::UnknownStrategy("strat99".to_string()))
+        );
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--memory-limit-mb", "0"])),
+            Err(PlanError::InvalidMemoryLimit(0))
+        );
+    }
+
+    #[test]
+    fn column_policies_parse_for_ab_runs() {
+        let plan = BenchmarkPlan::from_args(args(&["--ab-column-policy", "quota:3"])).unwrap();
+        assert_eq!(plan.column_policy, ColumnPolicy::PreferPrevious);
+        assert_eq!(plan.ab_column_policy, Some(ColumnPolicy::Quota(3)));
+        assert!(matches!(
+            BenchmarkPlan::from_args(args(&["--column-policy", "quota:0"])),
+            Err(PlanError::InvalidValue { .. })
+        ));
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--warm-cache", "--column-policy", "quota:2"])),
+            Err(PlanError::WarmCacheRequiresDefaultColumnPolicy)
+        );
+    }
+
+    #[test]
+    fn tree_stats_are_opt_in() {
+        assert!(!BenchmarkPlan::default().tree_stats);
+        assert!(BenchmarkPlan::from_args(args(&["--tree-stats"])).unwrap().tree_stats);
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--warm-cache", "--tree-stats"])),
+            Err(PlanError::WarmCacheExcludesTreeStats)
+        );
+    }
+
+    #[test]
+    fn freecell_weight_needs_a_plain_strat13_run() {
+        let plan = BenchmarkPlan::from_args(args(&["--conserve-freecells", "3", "--column-policy", "quota:2"])).unwrap();
+        assert_eq!(plan.move_ordering(plan.column_policy), MoveOrdering { column_policy: ColumnPolicy::Quota(2), freecell_weight: 3 });
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--warm-cache", "--conserve-freecells", "1"])),
+            Err(PlanError::WarmCacheExcludesFreecellWeight)
+        );
+        assert!(matches!(
+            BenchmarkPlan::from_args(args(&["--strategy", "strat14", "--conserve-freecells", "1"])),
+            Err(PlanError::RequiresStrat13 { .. })
+        ));
+    }
+
+    #[test]
+    fn unsolved_export_needs_a_plain_strat13_run() {
+        assert_eq!(BenchmarkPlan::default().unsolved_positions, 0);
+        let plan = BenchmarkPlan::from_args(args(&["--export-unsolved", "5", "--tree-stats"])).unwrap();
+        assert_eq!(plan.unsolved_positions, 5);
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--warm-cache", "--export-unsolved", "5"])),
+            Err(PlanError::WarmCacheExcludesUnsolvedExport)
+        );
+        assert!(matches!(
+            BenchmarkPlan::from_args(args(&["--strategy", "strat14", "--export-unsolved", "5"])),
+            Err(PlanError::RequiresStrat13 { .. })
+        ));
+    }
+
+    #[test]
+    fn shortening_takes_a_window_of_two_moves_or_more() {
+        assert_eq!(BenchmarkPlan::default().shorten_window, 0);
+        let plan = BenchmarkPlan::from_args(args(&["--strategy", "strat14", "--shorten-solutions", "8"])).unwrap();
+        assert_eq!(plan.shorten_window, 8);
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--shorten-solutions", "1"])),
+            Err(PlanError::InvalidValue { flag: "--shorten-solutions".to_string(), value: "1".to_string() })
+        );
+    }
+
+    #[test]
+    fn packing_comparison_needs_a_plain_strat13_run() {
+        assert!(!BenchmarkPlan::default().compare_packing);
+        assert!(BenchmarkPlan::from_args(args(&["--compare-packing", "--tree-stats"])).unwrap().compare_packing);
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--warm-cache", "--compare-packing"])),
+            Err(PlanError::WarmCacheExcludesPackingComparison)
+        );
+        assert!(matches!(
+            BenchmarkPlan::from_args(args(&["--strategy", "strat15", "--compare-packing"])),
+            Err(PlanError::RequiresStrat13 { .. })
+        ));
+    }
+
+    #[test]
+    fn endgame_databases_are_for_strat13() {
+        assert_eq!(BenchmarkPlan::default().endgame_db, None);
+        let plan = BenchmarkPlan::from_args(args(&["--endgame-db", "endgames.db"])).unwrap();
+        assert_eq!(plan.endgame_db.as_deref(), Some("endgames.db"));
+        assert!(matches!(
+            BenchmarkPlan::from_args(args(&["--strategy", "strat14", "--endgame-db", "endgames.db"])),
+            Err(PlanError::RequiresStrat13 { .. })
+        ));
+    }
+
+    #[test]
+    fn strat14_runs_without_strat13_options() {
+        let plan = BenchmarkPlan::from_args(args(&["--strategy", "strat14", "--memory-limit-mb", "4096", "--parallelism", "2"]))
+            .unwrap();
+        assert_eq!(plan.hybrid_memory_budget(), Some(512 * 1024 * 1024));
+        assert_eq!(BenchmarkPlan::default().hybrid_memory_budget(), None);
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--strategy", "strat14", "--tree-stats"])),
+            Err(PlanError::RequiresStrat13 {
+                option: "Search-tree statistics".to_string(),
+                strategy: "strat14".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn strat15_is_a_known_strategy() {
+        let plan = BenchmarkPlan::from_args(args(&["--strategy", "strat15"])).unwrap();
+        assert_eq!(plan.strategy, "strat15");
+        assert!(matches!(
+            BenchmarkPlan::from_args(args(&["--strategy", "strat15", "--column-policy", "quota:2"])),
+            Err(PlanError::RequiresStrat13 { .. })
+        ));
+    }
+
+    #[test]
+    fn total_budget_takes_a_unit() {
+        let plan = BenchmarkPlan::from_args(args(&["--total-budget", "8h"])).unwrap();
+        assert_eq!(plan.total_budget_secs, Some(8 * 3600));
+        assert_eq!(parse_duration_secs("90m"), Some(5400));
+        assert_eq!(parse_duration_secs("45s"), Some(45));
+        assert_eq!(parse_duration_secs("600"), Some(600));
+        assert_eq!(parse_duration_secs("h"), None);
+        assert!(matches!(
+            BenchmarkPlan::from_args(args(&["--total-budget", "8d"])),
+            Err(PlanError::InvalidValue { .. })
+        ));
+        assert_eq!(
+            BenchmarkPlan::from_args(args(&["--total-budget", "0m"])),
+            Err(PlanError::InvalidTotalBudget(0))
+        );
+    }
+
+    #[test]
+    fn plan_round_trips_through_toml() {
+        let plan = BenchmarkPlan::builder()
+            .seed_range(1, 100)
+            .seed_list(vec![617, 11982])
+            .timeout_tiers(vec![10, 60])
+            .ab_column_policy(ColumnPolicy::Quota(4))
+            .build()
+            .unwrap();
+        let text = toml::to_string(&plan).unwrap();
+        assert_eq!(BenchmarkPlan::from_toml_str(&text).unwrap(), plan);
+    }
+}
@@ -0,0 +1,156 @@
+//! A small bounded search for short questions about a position.
+//!
+//! Hints, solvability probes and deadlock checks all ask the same kind of question: can
+//! some goal be reached from here within a few moves? [`bounded_search`] answers it with
+//! an iterative deepening search that stops at a depth and a node budget, so a question
+//! out of easy reach costs little and comes back undecided rather than hanging the
+//! caller. The line it finds is the shortest one, and a search that ends without one
+//! says whether it looked everywhere within the depth or ran out of nodes first.
+//!
+//! What counts as reaching the goal, and which moves are worth trying on the way, is up
+//! to the [`SearchGoal`]; any `FnMut(&GameState) -> bool` is one that tries every legal
+//! move. Positions are remembered exactly ([`GameState::fingerprint`]), not up to column
+//! order, so goals may name particular columns and cells.
+
+use freecell_game_engine::r#move::Move;
+use freecell_game_engine::GameState;
+use fxhash::FxHashMap;
+
+/// How far a [`bounded_search`] may go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchBudget {
+    /// Longest line of moves tried.
+    pub max_depth: usize,
+    /// Positions expanded at most, over every deepening pass.
+    pub max_nodes: usize,
+}
+
+/// What a [`bounded_search`] looks for.
+pub trait SearchGoal {
+    /// Whether `game`, reached from the start by `line`, is what the search is after.
+    fn is_met(&mut self, game: &GameState, line: &[Move]) -> bool;
+
+    /// The moves to try from `game`, reached by `line`, best first.
+    fn moves(&mut self, game: &GameState, _line: &[Move]) -> Vec<Move> {
+        game.get_available_moves()
+    }
+
+    /// Whether to give up; asked at every position.
+    fn cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl<F: FnMut(&GameState) -> bool> SearchGoal for F {
+    fn is_met(&mut self, game: &GameState, _line: &[Move]) -> bool {
+        self(game)
+    }
+}
+
+/// How a [`bounded_search`] ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchOutcome {
+    /// The shortest line found that reaches the goal; empty if the start meets it.
+    Found(Vec<Move>),
+    /// No line of at most `max_depth` moves reaches the goal.
+    Unreachable,
+    /// The node budget ran out first; the goal may or may not be in reach.
+    OutOfNodes,
+    /// The goal called the search off.
+    Cancelled,
+}
+
+/// What [`bounded_search`] found, and what it cost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchReport {
+    pub outcome: SearchOutcome,
+    /// Positions expanded.
+    pub explored: usize,
+}
+
+/// Looks for the shortest line from `state` to a position where `goal` is met, within
+/// `budget`.
+pub fn bounded_search<G: SearchGoal>(state: &GameState, budget: SearchBudget, goal: G) -> SearchReport {
+    let mut search = Search { goal, max_nodes: budget.max_nodes, explored: 0, seen: FxHashMap::default() };
+    let mut game = state.clone();
+    let mut path = Vec::new();
+    for depth in 0..=budget.max_depth {
+        search.seen.clear();
+        if search.search(&mut game, &mut path, depth) {
+            return SearchReport { outcome: SearchOutcome::Found(path), explored: search.explored };
+        }
+        if search.goal.cancelled() {
+            return SearchReport { outcome: SearchOutcome::Cancelled, explored: search.explored };
+        }
+        if search.explored >= search.max_nodes {
+            return SearchReport { outcome: SearchOutcome::OutOfNodes, explored: search.explored };
+        }
+    }
+    SearchReport { outcome: SearchOutcome::Unreachable, explored: search.explored }
+}
+
+struct Search<G> {
+    goal: G,
+    max_nodes: usize,
+    explored: usize,
+    /// Remaining depth each position was last searched with.
+    seen: FxHashMap<u64, usize>,
+}
+
+impl<G: SearchGoal> Search<G> {
+    /// Searches up to `depth` more moves. On success `path` holds the line; otherwise it
+    /// is left as it was.
+    fn search(&mut self, game: &mut GameState, path: &mut Vec<Move>, depth: usize) -> bool {
+        if self.goal.is_met(game, path) {
+            return true;
+        }
+        if depth == 0 || self.explored >= self.max_nodes || self.goal.cancelled() {
+            return false;
+        }
+        let fingerprint = game.fingerprint();
+        if self.seen.get(&fingerprint).is_some_and(|&searched| searched >= depth) {
+            return false;
+        }
+        self.seen.insert(fingerprint, depth);
+        self.explored += 1;
+
+        for m in self.goal.moves(game, path) {
+            if game.execute_move(&m).is_err() {
+                continue;
+            }
+            path.push(m);
+            if self.search(game, path, depth - 1) {
+                return true;
+            }
+            path.pop();
+            game.undo_move(&m);
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::fixtures;
+
+    #[test]
+    fn finds_the_shortest_line_or_says_why_not() {
+        let game = fixtures::by_name("kings_left").unwrap().state();
+        let won = |game: &GameState| game.is_won().unwrap_or(false);
+        let budget = SearchBudget { max_depth: 6, max_nodes: 10_000 };
+
+        let report = bounded_search(&game, budget, won);
+        let SearchOutcome::Found(moves) = report.outcome else { panic!("kings_left is won in four moves") };
+        assert_eq!(moves.len(), 4);
+        let mut replayed = game.clone();
+        moves.iter().for_each(|m| replayed.execute_move(m).unwrap());
+        assert!(won(&replayed));
+
+        assert_eq!(bounded_search(&game, budget, |_: &GameState| true).outcome, SearchOutcome::Found(Vec::new()));
+        let short = SearchBudget { max_depth: 3, ..budget };
+        assert_eq!(bounded_search(&game, short, won).outcome, SearchOutcome::Unreachable);
+        let starved = bounded_search(&game, SearchBudget { max_nodes: 3, ..budget }, won);
+        assert_eq!((starved.outcome, starved.explored), (SearchOutcome::OutOfNodes, 3));
+    }
+}
@@ -0,0 +1,109 @@
+//! `solver certify <path>`: build an endgame database from solved deals.
+//!
+//! Solves every seed in the range and keeps the endgame positions of each solution as
+//! certificates ([`Certificates`]), then writes them to `path` for `--endgame-db`. Seeds
+//! that time out add nothing; more seeds and longer timeouts give a larger database.
+
+use crate::bench;
+use crate::benchmark_plan::{parse_seed_range, PlanError, SeedRange};
+use crate::endgame_db::Certificates;
+use freecell_game_engine::generation::generate_deal;
+
+/// Settings for building a database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertifyCommand {
+    pub path: String,
+    pub seeds: SeedRange,
+    pub timeout_secs: u64,
+}
+
+impl CertifyCommand {
+    /// Parses the arguments following `certify`: the output path, then optional
+    /// `--seeds <range>` (default 1-100) and `--timeout <secs>` (default 10).
+    pub fn from_args<I>(args: I) -> Result<Self, PlanError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut path = None;
+        let mut seeds = SeedRange::new(1, 100)?;
+        let mut timeout_secs = 10;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| PlanError::MissingValue(arg.clone()));
+            match arg.as_str() {
+                "--seeds" => {
+                    let raw = value()?;
+                    let (start, count) = parse_seed_range(&raw)
+                        .ok_or_else(|| PlanError::InvalidValue { flag: arg.clone(), value: raw.clone() })?;
+                    seeds = SeedRange::new(start, count)?;
+                }
+                "--timeout" => {
+                    let raw = value()?;
+                    timeout_secs = raw
+                        .parse::<u64>()
+                        .map_err(|_| PlanError::InvalidValue { flag: arg.clone(), value: raw.clone() })?;
+                    if timeout_secs == 0 {
+                        return Err(PlanError::InvalidTimeout(0));
+                    }
+                }
+                flag if flag.starts_with("--") => return Err(PlanError::UnknownArgument(arg)),
+                _ if path.is_none() => path = Some(arg),
+                _ => return Err(PlanError::UnknownArgument(arg)),
+            }
+        }
+        let path = path.ok_or_else(|| PlanError::MissingValue("certify".to_string()))?;
+        Ok(CertifyCommand { path, seeds, timeout_secs })
+    }
+}
+
+/// Solves the seeds and writes the database. Writing nothing, because no seed was
+/// solved, is an error.
+pub fn run(command: &CertifyCommand) -> Result<(), String> {
+    let mut certificates = Certificates::new();
+    let mut solved = 0;
+    for seed in command.seeds.iter() {
+        let game = generate_deal(seed).map_err(|e| format!("Could not deal seed {}: {}", seed, e))?;
+        let result = bench::harness_with_timing(game.clone(), command.timeout_secs);
+        if let Some(moves) = result.solution_moves.filter(|_| result.solved) {
+            if certificates.add_solution(&game, &moves) {
+                solved += 1;
+            }
+        }
+    }
+    if certificates.is_empty() {
+        return Err(format!("No seed solved within {}s; nothing to certify", command.timeout_secs));
+    }
+    certificates
+        .write(&command.path)
+        .map_err(|e| format!("Could not write {}: {}", command.path, e))?;
+    println!(
+        "Certified {} endgame positions from {} of {} seeds in {}",
+        certificates.len(),
+        solved,
+        command.seeds.len(),
+        command.path
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_a_path_and_options() {
+        let command = CertifyCommand::from_args(args(&["db.bin", "--seeds", "5-9", "--timeout", "3"])).unwrap();
+        assert_eq!(command.path, "db.bin");
+        assert_eq!((command.seeds.start(), command.seeds.len()), (5, 5));
+        assert_eq!(command.timeout_secs, 3);
+        assert_eq!(CertifyCommand::from_args(args(&[])), Err(PlanError::MissingValue("certify".to_string())));
+        assert_eq!(
+            CertifyCommand::from_args(args(&["db.bin", "--seeds", "0-3"])),
+            Err(PlanError::InvalidSeed(0))
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! `solver deal-corpus`: regenerate or check the checked-in deal fingerprints.
+//!
+//! The corpus itself, its format and the spot-check the engine's tests run live in
+//! [`freecell_game_engine::deal_corpus`]. `generate` rewrites the file from this build's
+//! generator, for when the deal algorithm changes on purpose; `verify` deals every seed
+//! in the file and reports the ones that no longer match.
+
+use crate::benchmark_plan::PlanError;
+use freecell_game_engine::deal_corpus::{DealCorpus, CORPUS_SEEDS};
+use std::fs;
+
+/// Where the corpus is checked in, relative to the workspace root.
+pub const DEFAULT_CORPUS_PATH: &str = "game-engine/testdata/deal_corpus.txt";
+
+/// Mismatching seeds printed before the rest are only counted.
+const MISMATCHES_SHOWN: usize = 20;
+
+/// What `solver deal-corpus` was asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DealCorpusCommand {
+    /// Deal [`CORPUS_SEEDS`] and write the corpus to `path`.
+    Generate { path: String },
+    /// Re-deal every seed in the corpus at `path` and compare.
+    Verify { path: String },
+}
+
+impl DealCorpusCommand {
+    /// Parses the arguments following `deal-corpus`: `generate [path]` or
+    /// `verify [path]`, with the path defaulting to [`DEFAULT_CORPUS_PATH`].
+    pub fn from_args<I>(args: I) -> Result<Self, PlanError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        let action = args.next().ok_or_else(|| PlanError::MissingValue("deal-corpus".to_string()))?;
+        let path = args.next().unwrap_or_else(|| DEFAULT_CORPUS_PATH.to_string());
+        if let Some(arg) = args.next() {
+            return Err(PlanError::UnknownArgument(arg));
+        }
+        match action.as_str() {
+            "generate" => Ok(DealCorpusCommand::Generate { path }),
+            "verify" => Ok(DealCorpusCommand::Verify { path }),
+            _ => Err(PlanError::UnknownArgument(action)),
+        }
+    }
+}
+
+/// Runs the command. Mismatches are reported as an error so the exit code shows them.
+pub fn run(command: &DealCorpusCommand) -> Result<(), String> {
+    match command {
+        DealCorpusCommand::Generate { path } => {
+            let corpus = DealCorpus::generate(CORPUS_SEEDS).map_err(|e| format!("Could not deal the corpus: {}", e))?;
+            fs::write(path, corpus.to_text()).map_err(|e| format!("Could not write {}: {}", path, e))?;
+            println!("Wrote {} deal fingerprints to {}", corpus.entries.len(), path);
+            Ok(())
+        }
+        DealCorpusCommand::Verify { path } => {
+            let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+            let corpus = DealCorpus::parse(&text).map_err(|e| format!("Could not parse {}: {}", path, e))?;
+            if !corpus.matches_engine() {
+                return Err(format!(
+                    "{} was made with deal algorithm {}; regenerate it with `deal-corpus generate`",
+                    path, corpus.deal_algorithm
+                ));
+            }
+            let mismatches = corpus.mismatches();
+            if mismatches.is_empty() {
+                println!("All {} deals in {} match", corpus.entries.len(), path);
+                return Ok(());
+            }
+            let mut report = format!("{} of {} deals in {} changed:", mismatches.len(), corpus.entries.len(), path);
+            for mismatch in mismatches.iter().take(MISMATCHES_SHOWN) {
+                report.push_str(&format!("\n  {}", mismatch));
+            }
+            if mismatches.len() > MISMATCHES_SHOWN {
+                report.push_str(&format!("\n  ... and {} more", mismatches.len() - MISMATCHES_SHOWN));
+            }
+            Err(report)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_both_actions_with_an_optional_path() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+        assert_eq!(
+            DealCorpusCommand::from_args(args("verify")),
+            Ok(DealCorpusCommand::Verify { path: DEFAULT_CORPUS_PATH.to_string() })
+        );
+        assert_eq!(
+            DealCorpusCommand::from_args(args("generate out.txt")),
+            Ok(DealCorpusCommand::Generate { path: "out.txt".to_string() })
+        );
+        assert!(DealCorpusCommand::from_args(args("check")).is_err());
+        assert!(DealCorpusCommand::from_args(args("verify a b")).is_err());
+        assert!(DealCorpusCommand::from_args(Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Exact solver for endgame positions.
+//!
+//! Once only a handful of cards are left outside the foundations, the general search
+//! spends most of its time shuffling them around heuristically. At that point the
+//! remaining state space is small enough to search exhaustively. [`solve_endgame`] does a
+//! breadth-first search that either returns a shortest finishing sequence or proves the
+//! position is lost.
+//!
+//! Visited positions are keyed by [`EndgameKey`], a fixed-size encoding that only has
+//! room for the few cards left in play. It ignores column and freecell order so that
+//! positions that differ only in where an empty column or cell is are searched once.
+
+use freecell_game_engine::card::Card;
+use freecell_game_engine::foundations::FOUNDATION_COUNT;
+use freecell_game_engine::location::FoundationLocation;
+use freecell_game_engine::r#move::Move;
+use freecell_game_engine::GameState;
+use fxhash::FxHashSet;
+use std::collections::VecDeque;
+
+/// Positions with at most this many cards outside the foundations count as endgames;
+/// the same limit [`GameState::phase`] uses.
+pub use freecell_game_engine::game_state::ENDGAME_CARD_LIMIT;
+
+/// Upper bound on positions visited by one endgame search, as a guard against
+/// pathological positions near the card limit.
+const MAX_ENDGAME_STATES: usize = 50_000;
+
+/// Result of an endgame search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EndgameOutcome {
+    /// Moves that finish the game from the searched position, as few as possible.
+    Solved(Vec<Move>),
+    /// No sequence of moves finishes the game from the searched position.
+    Unsolvable,
+    /// The position has too many cards left, or the search hit its state limit.
+    Inconclusive,
+}
+
+/// Number of cards not yet on the foundations.
+pub fn cards_remaining(game: &GameState) -> usize {
+    game.card_count() - game.foundations().total_cards()
+}
+
+/// Canonical encoding of an endgame position.
+///
+/// Layout: top foundation rank per suit (4 bytes), occupied freecells sorted (4 bytes,
+/// 0 for empty), then each non-empty tableau column bottom to top followed by a 0, with
+/// columns sorted. Cards are stored as 1–52.
+///
+/// Only standard games fit: one deck, four freecells and eight columns. With at most
+/// [`ENDGAME_CARD_LIMIT`] cards in play the tableau then takes at most 12 card bytes and
+/// 8 column ends, 28 bytes in all. Double FreeCell's two decks and six freecells would
+/// need a wider key, and a new [`endgame_db`](crate::endgame_db) format with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EndgameKey([u8; 32]);
+
+fn card_id(card: &Card) -> u8 {
+    card.suit().foundation_index() * 13 + card.rank() as u8
+}
+
+impl EndgameKey {
+    /// Encodes `game`, which must be a standard game with at most
+    /// [`ENDGAME_CARD_LIMIT`] cards in play.
+    pub fn from_game_state(game: &GameState) -> Self {
+        debug_assert!(cards_remaining(game) <= ENDGAME_CARD_LIMIT);
+        let mut bytes = [0u8; 32];
+
+        for pile in 0..FOUNDATION_COUNT as u8 {
+            let location = FoundationLocation::new(pile).unwrap();
+            if let Ok(Some(top)) = game.foundations().get_card(location) {
+                bytes[top.suit().foundation_index() as usize] = top.rank() as u8;
+            }
+        }
+
+        let mut cells: Vec<u8> = game.freecells().occupied_cells().map(|(_, card)| card_id(card)).collect();
+        cells.sort_unstable();
+        debug_assert!(cells.len() <= 4, "{} occupied freecells do not fit an EndgameKey", cells.len());
+        bytes[4..4 + cells.len()].copy_from_slice(&cells);
+
+        let mut columns: Vec<Vec<u8>> = game
+            .tableau()
+            .columns()
+            .filter(|column| !column.is_empty())
+            .map(|column| column.iter().map(card_id).collect())
+            .collect();
+        columns.sort_unstable();
+        let mut offset = 8;
+        for column in columns {
+            bytes[offset..offset + column.len()].copy_from_slice(&column);
+            offset += column.len() + 1;
+        }
+        EndgameKey(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// Searches `game` exhaustively if it is an endgame.
+///
+/// Positions that are [trivially winnable](GameState::is_trivially_winnable) are
+/// finished without a search, however many cards they have left. Otherwise returns
+/// [`EndgameOutcome::Inconclusive`] without searching when more than
+/// [`ENDGAME_CARD_LIMIT`] cards are in play.
+pub fn solve_endgame(game: &GameState) -> EndgameOutcome {
+    if game.is_trivially_winnable() {
+        return EndgameOutcome::Solved(game.auto_finish());
+    }
+    if cards_remaining(game) > ENDGAME_CARD_LIMIT {
+        return EndgameOutcome::Inconclusive;
+    }
+
+    // Each discovered position records its parent's node index and the move from it.
+    let mut nodes: Vec<(Option<usize>, Move)> = Vec::new();
+    let mut seen = FxHashSet::default();
+    seen.insert(EndgameKey::from_game_state(game));
+    let mut queue = VecDeque::from([(game.clone(), None)]);
+
+    while let Some((state, node)) = queue.pop_front() {
+        if state.is_won().unwrap_or(false) {
+            return EndgameOutcome::Solved(path_to(&nodes, node));
+        }
+        for m in state.get_available_moves() {
+            let mut next = state.clone();
+            if next.execute_move(&m).is_err() {
+                continue;
+            }
+            if !seen.insert(EndgameKey::from_game_state(&next)) {
+                continue;
+            }
+            if seen.len() > MAX_ENDGAME_STATES {
+                return EndgameOutcome::Inconclusive;
+            }
+            nodes.push((node, m));
+            queue.push_back((next, Some(nodes.len() - 1)));
+        }
+    }
+    EndgameOutcome::Unsolvable
+}
+
+/// Walks parent links back from `node` and returns the moves in play order.
+fn path_to(nodes: &[(Option<usize>, Move)], mut node: Option<usize>) -> Vec<Move> {
+    let mut moves = Vec::new();
+    while let Some(index) = node {
+        let (parent, m) = nodes[index];
+        moves.push(m);
+        node = parent;
+    }
+    moves.reverse();
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::card::{Rank, Suit};
+    use freecell_game_engine::game_state::GameStateBuilder;
+    use freecell_game_engine::generation::generate_deal;
+    use freecell_game_engine::location::{Location, TableauLocation};
+
+    const SUITS: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    /// Builds each suit's foundation up to `tops[suit]`.
+    fn with_foundations(tops: [u8; 4]) -> GameStateBuilder {
+        let mut builder = GameStateBuilder::new();
+        for (pile, suit) in SUITS.into_iter().enumerate() {
+            let location = Location::Foundation(FoundationLocation::new(pile as u8).unwrap());
+            for rank in 1..=tops[pile] {
+                builder.place(location, Card::new(Rank::try_from(rank).unwrap(), suit)).unwrap();
+            }
+        }
+        builder
+    }
+
+    fn column(index: u8) -> TableauLocation {
+        TableauLocation::new(index).unwrap()
+    }
+
+    #[test]
+    fn finishes_kings_in_four_moves() {
+        let mut builder = with_foundations([12; 4]);
+        builder.column(column(0), SUITS.map(|suit| Card::new(Rank::King, suit))).unwrap();
+        let game = builder.build().unwrap();
+
+        let EndgameOutcome::Solved(moves) = solve_endgame(&game) else {
+            panic!("kings-only endgame should be solved");
+        };
+        assert_eq!(moves.len(), 4);
+        let mut replay = game.clone();
+        for m in &moves {
+            replay.execute_move(m).unwrap();
+        }
+        assert!(replay.is_won().unwrap());
+    }
+
+    #[test]
+    fn proves_blocked_position_unsolvable() {
+        // Spades 5..K across all eight columns with the 5 buried and no freecells:
+        // every exposed card is black and none can reach the foundation.
+        let mut builder = with_foundations([4, 13, 13, 13]);
+        builder
+            .column(column(0), [Rank::Five, Rank::Six].map(|rank| Card::new(rank, Suit::Spades)))
+            .unwrap();
+        for (index, rank) in (7..=13u8).enumerate() {
+            let card = Card::new(Rank::try_from(rank).unwrap(), Suit::Spades);
+            builder.column(column(index as u8 + 1), [card]).unwrap();
+        }
+        let mut game = builder.build().unwrap();
+        game.set_freecell_capacity(0).unwrap();
+
+        assert_eq!(solve_endgame(&game), EndgameOutcome::Unsolvable);
+    }
+
+    #[test]
+    fn agrees_with_engine_fixtures() {
+        use freecell_game_engine::fixtures::{self, Expectation};
+
+        for fixture in fixtures::all() {
+            let game = fixture.state();
+            if cards_remaining(&game) > ENDGAME_CARD_LIMIT {
+                continue;
+            }
+            match (fixture.expectation, solve_endgame(&game)) {
+                (Expectation::Solvable { min_moves }, EndgameOutcome::Solved(moves)) => {
+                    if let Some(min_moves) = min_moves {
+                        assert_eq!(moves.len(), min_moves, "{}", fixture.name);
+                    }
+                }
+                (Expectation::Unsolvable, EndgameOutcome::Unsolvable) => {}
+                (_, outcome) => panic!("{}: unexpected {:?}", fixture.name, outcome),
+            }
+        }
+    }
+
+    #[test]
+    fn ordered_columns_finish_past_the_card_limit() {
+        let mut builder = with_foundations([0; 4]);
+        for (index, suit) in SUITS.into_iter().enumerate() {
+            let cards = (1..=13u8).rev().map(|rank| Card::new(Rank::try_from(rank).unwrap(), suit));
+            builder.column(column(index as u8), cards).unwrap();
+        }
+        let game = builder.build().unwrap();
+
+        let EndgameOutcome::Solved(moves) = solve_endgame(&game) else {
+            panic!("ordered columns should be finished without a search");
+        };
+        assert_eq!(moves.len(), 52);
+    }
+
+    #[test]
+    fn opening_deal_is_not_an_endgame() {
+        let game = generate_deal(1).unwrap();
+        assert_eq!(solve_endgame(&game), EndgameOutcome::Inconclusive);
+    }
+
+    #[test]
+    fn key_ignores_column_order() {
+        let kings = SUITS.map(|suit| Card::new(Rank::King, suit));
+        let mut left = with_foundations([12; 4]);
+        left.column(column(0), kings).unwrap();
+        let mut right = with_foundations([12; 4]);
+        right.column(column(5), kings).unwrap();
+
+        assert_eq!(
+            EndgameKey::from_game_state(&left.build().unwrap()),
+            EndgameKey::from_game_state(&right.build().unwrap())
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! A read-only database of endgame positions known to be won.
+//!
+//! Every solution passes through a string of endgame positions on its way home, and each
+//! of them is proven winnable by the moves that follow it. `solver certify` solves a
+//! range of deals and keeps those positions as certificates: the position's
+//! [`EndgameKey`] and how many moves the known line still needed. With a database
+//! [installed](install), strat13 stops as soon as it reaches a certified position and
+//! finishes along certificates ([`EndgameDb::finish`]) instead of searching on.
+//!
+//! The file is a magic header followed by fixed-size records sorted by key, so it is
+//! memory-mapped rather than read and looked up by binary search in place: a large
+//! database costs no load time, and every process that maps it shares the pages.
+
+use crate::endgame::{cards_remaining, EndgameKey, ENDGAME_CARD_LIMIT};
+use freecell_game_engine::r#move::Move;
+use freecell_game_engine::GameState;
+use fxhash::FxHashMap;
+use memmap2::Mmap;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs::File;
+use std::sync::OnceLock;
+
+/// The first bytes of every database file; the last byte is the format version.
+const MAGIC: &[u8; 8] = b"FCEGDB\0\x01";
+const KEY_SIZE: usize = 32;
+/// A key, then the moves the certified line takes to win.
+const RECORD_SIZE: usize = KEY_SIZE + 1;
+
+/// Error type for reading or writing an endgame database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EndgameDbError {
+    Io(String),
+    /// The file does not start with the database header.
+    NotADatabase,
+    /// The file ends partway through a record.
+    Truncated,
+}
+
+impl fmt::Display for EndgameDbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndgameDbError::Io(msg) => write!(f, "Endgame database I/O failed: {}", msg),
+            EndgameDbError::NotADatabase => write!(f, "Not an endgame database"),
+            EndgameDbError::Truncated => write!(f, "Endgame database is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for EndgameDbError {}
+
+impl From<std::io::Error> for EndgameDbError {
+    fn from(e: std::io::Error) -> Self {
+        EndgameDbError::Io(e.to_string())
+    }
+}
+
+/// A memory-mapped database file.
+pub struct EndgameDb {
+    map: Mmap,
+}
+
+impl EndgameDb {
+    /// Maps the database at `path` and checks its header and size.
+    pub fn open(path: &str) -> Result<Self, EndgameDbError> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is only read. The file is a build artifact nothing writes
+        // to while solvers run; if something did, lookups would return wrong answers,
+        // which `finish` guards against by replaying every move it returns.
+        let map = unsafe { Mmap::map(&file)? };
+        if !map.starts_with(MAGIC) {
+            return Err(EndgameDbError::NotADatabase);
+        }
+        if !(map.len() - MAGIC.len()).is_multiple_of(RECORD_SIZE) {
+            return Err(EndgameDbError::Truncated);
+        }
+        Ok(EndgameDb { map })
+    }
+
+    /// Number of certified positions.
+    pub fn len(&self) -> usize {
+        self.records().len() / RECORD_SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn records(&self) -> &[u8] {
+        &self.map[MAGIC.len()..]
+    }
+
+    /// Moves the certified line from `game` takes to win, or `None` if `game` is not
+    /// certified.
+    pub fn distance(&self, game: &GameState) -> Option<u8> {
+        if cards_remaining(game) > ENDGAME_CARD_LIMIT {
+            return None;
+        }
+        let key = EndgameKey::from_game_state(game);
+        let records = self.records();
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let middle = (low + high) / 2;
+            let record = &records[middle * RECORD_SIZE..(middle + 1) * RECORD_SIZE];
+            match record[..KEY_SIZE].cmp(key.as_bytes()) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Some(record[KEY_SIZE]),
+            }
+        }
+        None
+    }
+
+    /// Moves that win from `game` if it is certified: at each step a move to a certified
+    /// position closer to the win. Certificates are stored up to column and freecell
+    /// order, so the moves are found again in `game`'s own layout rather than stored.
+    pub fn finish(&self, game: &GameState) -> Option<Vec<Move>> {
+        let mut distance = self.distance(game)?;
+        let mut game = game.clone();
+        let mut moves = Vec::with_capacity(distance as usize);
+        while !game.is_won().unwrap_or(false) {
+            let (m, next) = game.get_available_moves().into_iter().find_map(|m| {
+                let mut next = game.clone();
+                next.execute_move(&m).ok()?;
+                let closer = if next.is_won().unwrap_or(false) { 0 } else { self.distance(&next)? };
+                (closer < distance).then_some((m, closer))
+            })?;
+            game.execute_move(&m).ok()?;
+            moves.push(m);
+            distance = next;
+        }
+        Some(moves)
+    }
+}
+
+/// Endgame positions collected from winning lines, for [`Certificates::write`].
+#[derive(Debug, Default)]
+pub struct Certificates {
+    distances: FxHashMap<EndgameKey, u8>,
+}
+
+impl Certificates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+
+    /// Certifies every endgame position `solution` passes through from `start`, keeping
+    /// the shortest line known for each. Adds nothing and returns false if `solution`
+    /// does not win.
+    pub fn add_solution(&mut self, start: &GameState, solution: &[Move]) -> bool {
+        let mut game = start.clone();
+        let mut positions = Vec::new();
+        for (played, m) in solution.iter().enumerate() {
+            if cards_remaining(&game) <= ENDGAME_CARD_LIMIT {
+                positions.push((EndgameKey::from_game_state(&game), solution.len() - played));
+            }
+            if game.execute_move(m).is_err() {
+                return false;
+            }
+        }
+        if !game.is_won().unwrap_or(false) {
+            return false;
+        }
+        // Lines too long for a record are left out; endgames never come close.
+        for (key, distance) in positions.into_iter().filter_map(|(key, d)| Some((key, u8::try_from(d).ok()?))) {
+            let known = self.distances.entry(key).or_insert(distance);
+            *known = (*known).min(distance);
+        }
+        true
+    }
+
+    /// Writes the database file to `path`.
+    pub fn write(&self, path: &str) -> Result<(), EndgameDbError> {
+        let mut records: Vec<(&EndgameKey, u8)> = self.distances.iter().map(|(key, &d)| (key, d)).collect();
+        records.sort_unstable_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+        let mut bytes = Vec::with_capacity(MAGIC.len() + records.len() * RECORD_SIZE);
+        bytes.extend_from_slice(MAGIC);
+        for (key, distance) in records {
+            bytes.extend_from_slice(key.as_bytes());
+            bytes.push(distance);
+        }
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+}
+
+/// The database every solve in this process consults.
+static INSTALLED: OnceLock<EndgameDb> = OnceLock::new();
+
+/// Makes `db` the database strat13 consults. Only the first call has an effect; returns
+/// whether it was this one.
+pub fn install(db: EndgameDb) -> bool {
+    INSTALLED.set(db).is_ok()
+}
+
+/// The installed database, if any.
+pub fn installed() -> Option<&'static EndgameDb> {
+    INSTALLED.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::fixtures;
+    use freecell_game_engine::import::import_board;
+    use freecell_game_engine::Variant;
+
+    #[test]
+    fn certified_positions_finish_in_any_column_order() {
+        // The four kings left, the black ones stacked in column 0.
+        let start = fixtures::by_name("kings_left").unwrap().state();
+        let solution = [
+            Move::tableau_to_freecell(0, 0).unwrap(),
+            Move::tableau_to_foundation(0, 0).unwrap(),
+            Move::freecell_to_foundation(0, 3).unwrap(),
+            Move::tableau_to_foundation(1, 1).unwrap(),
+            Move::tableau_to_foundation(2, 2).unwrap(),
+        ];
+        let mut certificates = Certificates::new();
+        assert!(!certificates.add_solution(&start, &solution[..4]));
+        assert!(certificates.is_empty());
+        assert!(certificates.add_solution(&start, &solution));
+        assert_eq!(certificates.len(), 5);
+
+        let path = std::env::temp_dir().join(format!("solver-endgame-db-test-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        certificates.write(path).unwrap();
+        let db = EndgameDb::open(path).unwrap();
+        assert_eq!(db.len(), 5);
+        assert_eq!(db.distance(&start), Some(5));
+
+        // The same position with the columns in another order.
+        let moved = "Foundations: QS QH QD QC\n: KD\n:\n: KH\n: KS KC\n";
+        let moved = import_board(moved, Variant::Standard).unwrap().state;
+        let finish = db.finish(&moved).unwrap();
+        assert_eq!(finish.len(), 5);
+        let mut game = moved.clone();
+        finish.iter().for_each(|m| game.execute_move(m).unwrap());
+        assert!(game.is_won().unwrap());
+
+        let uncertified = "Foundations: QS QH QD QC\n: KC KS\n: KH\n: KD\n";
+        assert_eq!(db.finish(&import_board(uncertified, Variant::Standard).unwrap().state), None);
+
+        std::fs::write(path, b"FCEGDB\0\x01abc").unwrap();
+        assert_eq!(EndgameDb::open(path).err(), Some(EndgameDbError::Truncated));
+        std::fs::write(path, b"not a database").unwrap();
+        assert_eq!(EndgameDb::open(path).err(), Some(EndgameDbError::NotADatabase));
+        std::fs::remove_file(path).unwrap();
+    }
+}
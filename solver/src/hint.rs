@@ -0,0 +1,207 @@
+//! Hints for a player, at three levels of detail.
+//!
+//! A [`HintLevel::Nudge`] only names the pile the next move starts from, a
+//! [`HintLevel::Move`] gives the move itself and a [`HintLevel::Plan`] the next
+//! [`PLAN_LENGTH`] moves. More detailed hints get a bigger solver budget, since a
+//! player asking for a plan is prepared to wait for a good one.
+//!
+//! Endgames are answered exactly and immediately. Elsewhere the hint follows strat15's
+//! subgoal planner to the nearest milestone it can reach, an ace home or a column
+//! emptied, which takes milliseconds; only when none is in reach does the solver run,
+//! for at most the level's budget. A [`HintEngine`] remembers the last line it found, so
+//! a player who follows the hints gets the next ones without another search.
+
+use crate::bench;
+use crate::bounded_search::{SearchBudget, SearchOutcome};
+use crate::endgame::{self, EndgameOutcome};
+use crate::strategies::strat15::subgoal;
+use freecell_game_engine::location::Location;
+use freecell_game_engine::r#move::Move;
+use freecell_game_engine::GameState;
+use std::sync::atomic::AtomicBool;
+
+/// Moves in a [`Hint::Plan`], fewer when the game or the line towards a milestone ends
+/// sooner.
+pub const PLAN_LENGTH: usize = 5;
+
+/// How far the subgoal planner looks for a milestone to hint towards.
+const PLANNER_BUDGET: SearchBudget = SearchBudget { max_depth: 12, max_nodes: 20_000 };
+
+/// How much a hint gives away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HintLevel {
+    /// Which pile to look at.
+    Nudge,
+    /// The exact next move.
+    Move,
+    /// The next few moves.
+    Plan,
+}
+
+impl HintLevel {
+    /// Default solver budget in seconds for hints at this level.
+    pub fn default_budget_secs(self) -> u64 {
+        match self {
+            HintLevel::Nudge => 1,
+            HintLevel::Move => 2,
+            HintLevel::Plan => 5,
+        }
+    }
+}
+
+/// A hint, as returned by [`HintEngine::hint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hint {
+    /// The pile the next move starts from.
+    Nudge(Location),
+    Move(Move),
+    /// Up to [`PLAN_LENGTH`] moves, in order.
+    Plan(Vec<Move>),
+}
+
+/// Answers hint requests, reusing the last solution while the player follows it.
+#[derive(Debug, Clone)]
+pub struct HintEngine {
+    budgets_secs: [u64; 3],
+    /// The position the last solution starts from, and the solution.
+    known: Option<(GameState, Vec<Move>)>,
+}
+
+impl HintEngine {
+    /// An engine using each level's [`default_budget_secs`](HintLevel::default_budget_secs).
+    pub fn new() -> Self {
+        HintEngine {
+            budgets_secs: [
+                HintLevel::Nudge.default_budget_secs(),
+                HintLevel::Move.default_budget_secs(),
+                HintLevel::Plan.default_budget_secs(),
+            ],
+            known: None,
+        }
+    }
+
+    /// Sets the solver budget for hints at `level`.
+    pub fn set_budget_secs(&mut self, level: HintLevel, secs: u64) {
+        self.budgets_secs[level as usize] = secs;
+    }
+
+    /// A hint for `game` at `level`, or `None` if the game is already won or no solution
+    /// was found within the level's budget.
+    pub fn hint(&mut self, game: &GameState, level: HintLevel) -> Option<Hint> {
+        if game.is_won().unwrap_or(false) {
+            return None;
+        }
+        let moves = match self.known_continuation(game) {
+            Some(moves) => moves,
+            None => {
+                let solution = self.solve(game, level)?;
+                self.known = Some((game.clone(), solution.clone()));
+                solution
+            }
+        };
+        let first = *moves.first()?;
+        Some(match level {
+            HintLevel::Nudge => Hint::Nudge(first.source),
+            HintLevel::Move => Hint::Move(first),
+            HintLevel::Plan => Hint::Plan(moves.into_iter().take(PLAN_LENGTH).collect()),
+        })
+    }
+
+    /// The rest of the remembered solution if `game` lies on it.
+    fn known_continuation(&self, game: &GameState) -> Option<Vec<Move>> {
+        let (start, solution) = self.known.as_ref()?;
+        let mut state = start.clone();
+        for (played, m) in solution.iter().enumerate() {
+            if state == *game {
+                return Some(solution[played..].to_vec());
+            }
+            state.execute_move(m).ok()?;
+        }
+        None
+    }
+
+    fn solve(&self, game: &GameState, level: HintLevel) -> Option<Vec<Move>> {
+        match endgame::solve_endgame(game) {
+            EndgameOutcome::Solved(moves) => return Some(moves),
+            EndgameOutcome::Unsolvable => return None,
+            EndgameOutcome::Inconclusive => {}
+        }
+        if let Some(moves) = plan_milestone(game) {
+            return Some(moves);
+        }
+        let result = bench::harness_with_timing(game.clone(), self.budgets_secs[level as usize]);
+        result.solution_moves.filter(|_| result.solved)
+    }
+}
+
+/// The line to the first milestone strat15's planner reaches from `game`, unless it ends
+/// in an endgame known to be lost.
+fn plan_milestone(game: &GameState) -> Option<Vec<Move>> {
+    let running = AtomicBool::new(false);
+    subgoal::candidates(game).into_iter().find_map(|goal| {
+        let SearchOutcome::Found(moves) = subgoal::plan(game, goal, PLANNER_BUDGET, &running).outcome else {
+            return None;
+        };
+        let mut end = game.clone();
+        moves.iter().try_for_each(|m| end.execute_move(m)).ok()?;
+        let lost = endgame::solve_endgame(&end) == EndgameOutcome::Unsolvable;
+        (!moves.is_empty() && !lost).then_some(moves)
+    })
+}
+
+impl Default for HintEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::fixtures;
+
+    #[test]
+    fn levels_reveal_more_of_the_same_solution() {
+        let game = fixtures::by_name("kings_in_freecells").unwrap().state();
+        let mut engine = HintEngine::new();
+
+        let Some(Hint::Plan(plan)) = engine.hint(&game, HintLevel::Plan) else {
+            panic!("expected a plan");
+        };
+        assert_eq!(plan.len(), 4);
+        assert_eq!(engine.hint(&game, HintLevel::Move), Some(Hint::Move(plan[0])));
+        assert_eq!(engine.hint(&game, HintLevel::Nudge), Some(Hint::Nudge(plan[0].source)));
+
+        // Following the plan keeps answering from the remembered solution.
+        let mut next = game.clone();
+        next.execute_move(&plan[0]).unwrap();
+        assert_eq!(engine.hint(&next, HintLevel::Move), Some(Hint::Move(plan[1])));
+    }
+
+    #[test]
+    fn mid_game_hints_head_for_a_milestone_without_solving() {
+        let game = freecell_game_engine::generation::generate_deal(1).unwrap();
+        assert_eq!(endgame::solve_endgame(&game), EndgameOutcome::Inconclusive);
+        let mut engine = HintEngine::new();
+        // No time for the solver: only the planner can answer.
+        engine.set_budget_secs(HintLevel::Plan, 0);
+
+        let milestone = plan_milestone(&game).expect("deal 1 has an ace within reach");
+        let Some(Hint::Plan(plan)) = engine.hint(&game, HintLevel::Plan) else {
+            panic!("expected a plan");
+        };
+        assert_eq!(plan, milestone.into_iter().take(PLAN_LENGTH).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lost_positions_get_no_hint() {
+        let fixture = fixtures::all()
+            .iter()
+            .find(|fixture| {
+                fixture.expectation == fixtures::Expectation::Unsolvable
+                    && endgame::solve_endgame(&fixture.state()) == EndgameOutcome::Unsolvable
+            })
+            .unwrap();
+        assert_eq!(HintEngine::new().hint(&fixture.state(), HintLevel::Plan), None);
+    }
+}
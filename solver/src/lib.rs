@@ -1,3 +1,12 @@
+pub mod endgame;
+pub mod endgame_db;
 mod game_prep;
 mod strategies;
+pub mod analysis;
+pub mod bench;
+pub mod bounded_search;
+pub mod hint;
 pub mod packed_state;
+pub mod solve_api;
+
+use strategies::strat13::solve;
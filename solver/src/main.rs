@@ -4,20 +4,52 @@
 //! shared game-engine library.
 #![allow(dead_code)]
 #![allow(unused)]
+#[cfg(not(feature = "allocator-stats"))]
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+#[cfg(feature = "allocator-stats")]
+#[global_allocator]
+static GLOBAL: alloc_stats::CountingAllocator<mimalloc::MiMalloc> =
+    alloc_stats::CountingAllocator::new(mimalloc::MiMalloc);
 
+mod alloc_stats;
+mod analysis;
+mod bench;
+mod benchmark_plan;
+mod bounded_search;
+mod certify;
+mod deal_corpus;
+mod endgame;
+mod endgame_db;
 mod game_prep;
-mod harness;
 pub mod packed_state;
+mod replay_diff;
+mod result_stream;
+mod resume;
+mod run_budget;
+mod run_bundle;
+mod search_tree;
+mod solution_minimize;
+mod solution_reorder;
+mod solve_api;
+mod solve_board;
 mod strategies;
+mod watch;
 
-use freecell_game_engine::generation::generate_deal;
+use freecell_game_engine::display::SolutionPhases;
+use freecell_game_engine::game_state::GameState;
+use freecell_game_engine::{EngineInfo, ENGINE_INFO};
+use freecell_game_engine::generation::{generate_deal, generate_deals, GenerationError};
 use freecell_game_engine::r#move::Move;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::time::Duration;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use benchmark_plan::BenchmarkPlan;
 use strategies::strat13::solve;
 
 #[derive(Debug, Clone)]
@@ -31,22 +63,161 @@ struct GameResult {
     seed: u64,
     solved: bool,
     execution_time_ms: u64,
+    #[serde(default)]
+    cpu_time_ms: u64,
+    #[serde(default)]
+    states_explored: usize,
+    #[serde(default)]
+    stale_pruned: u64, // Queued positions dropped unexpanded as already visited
+    #[serde(default)]
+    timeout_secs: Option<u64>, // Timeout of the last tier attempted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    warm_cache: Option<WarmCacheStats>, // Only set when the warm cache experiment is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    freecell_ladder: Option<analysis::LadderResult>, // Only set when the plan runs the ladder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reachable_states: Option<analysis::ReachableEstimate>, // Only set when the plan sets a reachable-state limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    allocator: Option<alloc_stats::AllocatorStats>, // Only set in builds with the allocator-stats feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ab_run: Option<AbRun>, // Only set in A/B mode: the same seed under the plan's B column policy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exact_packing: Option<PackingRun>, // Only set when the plan compares packings: the same seed packed exactly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tree_stats: Option<solve::SolverStats>, // Only set when the plan records tree shape; from the last tier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shortening: Option<solution_minimize::Shortening>, // Only set for solved seeds when the plan shortens solutions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    freecell_usage: Option<solution_reorder::ConservedUsage>, // Only set for solved seeds when the plan conserves freecells
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unsolved_positions: Option<Vec<resume::UnsolvedPosition>>, // Only set for unsolved seeds when the plan exports them; best first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    failure: Option<SeedFailure>, // Set when the seed has no measurement; see FailureKind
     timestamp: String,
     move_count: Option<usize>, // None if not solved
 }
 
+impl GameResult {
+    /// A result for a seed that produced no measurement.
+    fn failed(seed: u64, kind: FailureKind, message: String) -> Self {
+        GameResult {
+            seed,
+            solved: false,
+            execution_time_ms: 0,
+            cpu_time_ms: 0,
+            states_explored: 0,
+            stale_pruned: 0,
+            timeout_secs: None,
+            warm_cache: None,
+            freecell_ladder: None,
+            reachable_states: None,
+            allocator: None,
+            ab_run: None,
+            exact_packing: None,
+            tree_stats: None,
+            shortening: None,
+            freecell_usage: None,
+            unsolved_positions: None,
+            failure: Some(SeedFailure { kind, message }),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            move_count: None,
+        }
+    }
+}
+
+/// Why a seed has no measurement.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum FailureKind {
+    /// `generate_deal` rejected the seed.
+    Generation,
+    /// The strategy or the benchmark itself panicked while handling the seed.
+    Panic,
+    /// The solve was cancelled for going over the plan's memory limit.
+    MemoryLimitExceeded,
+    /// The plan's total budget ran out before the seed was attempted.
+    BudgetExhausted,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SeedFailure {
+    kind: FailureKind,
+    message: String,
+}
+
+/// Per-seed measurements from the experimental warm cache mode.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct WarmCacheStats {
+    cross_deal_hits: usize,
+    entries_after_solve: usize,
+}
+
+/// The seed solved again with [`solve::Packing::Exact`], to set against the canonical
+/// run it sits next to. Times and states are from the last tier attempted.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct PackingRun {
+    solved: bool,
+    execution_time_ms: u64,
+    states_explored: usize,
+    timeout_secs: Option<u64>,
+}
+
+/// The B side of an A/B run: the seed solved again under another column policy, with the
+/// same timeout tiers. Times and states are from the last tier attempted, as for A.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AbRun {
+    column_policy: solve::ColumnPolicy,
+    solved: bool,
+    execution_time_ms: u64,
+    states_explored: usize,
+    timeout_secs: Option<u64>,
+    move_count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tree_stats: Option<solve::SolverStats>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct DetailedGameResult {
+    #[serde(default)]
+    engine: Option<EngineInfo>,
     seed: u64,
     solved: bool,
     execution_time_ms: u64,
+    cpu_time_ms: u64,
+    states_explored: usize,
     timestamp: String,
     solution_moves: Option<Vec<Move>>, // None if not solved
     move_count: Option<usize>, // None if not solved
+    #[serde(default)]
+    tiers: Vec<TierTiming>, // One per timeout tier attempted, in order
+    #[serde(default)]
+    solution_found_at_ms: Option<u64>, // Since the first tier started; None if not solved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    decision_points: Option<Vec<analysis::DecisionPoint>>, // One per solution move; None if not solved
+}
+
+/// One timeout tier's attempt at a seed.
+///
+/// Strategies hand back the whole solution at once, so there is no per-move timeline;
+/// these show where the seed's time went and how close each tier came to being enough.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct TierTiming {
+    timeout_secs: u64,
+    started_at_ms: u64, // Since the first tier started
+    execution_time_ms: u64,
+    cpu_time_ms: u64,
+    states_explored: usize,
+    solved: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct BenchmarkResults {
+    #[serde(default)]
+    plan: Option<BenchmarkPlan>, // Provenance: the plan that produced these results
+    #[serde(default)]
+    engine: Option<EngineInfo>, // Provenance: the engine whose deals and packing produced them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deal_collisions: Option<Vec<analysis::DealCollision>>, // Only set by the final save of a plan that asks for them
     results: Vec<GameResult>,
     summary: BenchmarkSummary,
 }
@@ -55,16 +226,30 @@ struct BenchmarkResults {
 struct BenchmarkSummary {
     total_games: usize,
     solved_games: usize,
-    failed_games: usize,
+    failed_games: usize, // Attempted but not solved
+    #[serde(default)]
+    errored_games: usize, // Not dealt, panicked or over the memory limit; excluded from the averages
     average_time_ms: f64,
+    #[serde(default)]
+    average_cpu_time_ms: f64,
     timeout_secs: u64,
 }
 
-fn save_results_to_json(results: &Vec<GameResult>, filename: &str, timeout_secs: u64) {
-    let solved_count = results.iter().filter(|r| r.solved).count();
-    let failed_count = results.len() - solved_count;
-    let avg_time = if !results.is_empty() {
-        results.iter().map(|r| r.execution_time_ms as f64).sum::<f64>() / results.len() as f64
+fn save_results_to_json(
+    results: &[GameResult],
+    plan: &BenchmarkPlan,
+    deal_collisions: Option<&[analysis::DealCollision]>,
+) {
+    let measured: Vec<&GameResult> = results.iter().filter(|r| r.failure.is_none()).collect();
+    let solved_count = measured.iter().filter(|r| r.solved).count();
+    let failed_count = measured.len() - solved_count;
+    let avg_time = if !measured.is_empty() {
+        measured.iter().map(|r| r.execution_time_ms as f64).sum::<f64>() / measured.len() as f64
+    } else {
+        0.0
+    };
+    let avg_cpu_time = if !measured.is_empty() {
+        measured.iter().map(|r| r.cpu_time_ms as f64).sum::<f64>() / measured.len() as f64
     } else {
         0.0
     };
@@ -73,17 +258,22 @@ fn save_results_to_json(results: &Vec<GameResult>, filename: &str, timeout_secs:
         total_games: results.len(),
         solved_games: solved_count,
         failed_games: failed_count,
+        errored_games: results.len() - measured.len(),
         average_time_ms: avg_time,
-        timeout_secs,
+        average_cpu_time_ms: avg_cpu_time,
+        timeout_secs: plan.max_timeout_secs(),
     };
 
     let benchmark_results = BenchmarkResults {
-        results: results.clone(),
+        plan: Some(plan.clone()),
+        engine: Some(ENGINE_INFO),
+        deal_collisions: deal_collisions.map(<[_]>::to_vec),
+        results: results.to_vec(),
         summary,
     };
 
     let json_string = serde_json::to_string_pretty(&benchmark_results).unwrap();
-    fs::write(filename, json_string).expect("Failed to write JSON file");
+    fs::write(&plan.output.summary_file, json_string).expect("Failed to write JSON file");
     // println!("Results saved to {}", filename);
 }
 
@@ -105,106 +295,591 @@ fn save_detailed_game_result(detailed_result: &DetailedGameResult, results_dir:
 fn load_existing_results(filename: &str) -> Vec<GameResult> {
     if let Ok(contents) = fs::read_to_string(filename) {
         if let Ok(benchmark_results) = serde_json::from_str::<BenchmarkResults>(&contents) {
+            // Files from before engine stamping used the same deals as revision 1.
+            if let Some(engine) = &benchmark_results.engine {
+                if !ENGINE_INFO.is_compatible_with(engine) {
+                    println!(
+                        "Ignoring results in {}: written by incompatible engine {}",
+                        filename, engine.version
+                    );
+                    return Vec::new();
+                }
+            }
             return benchmark_results.results;
         }
     }
     Vec::new()
 }
 
-fn do_seed_benchmark() {
-    let allowed_timeout_secs = 120; // 2 minutes per game 
-    let start_seed = 1u64;
-    let max_seeds = 32000u64; // Test first 100 seeds
-    let results_filename = "benchmark_summary.json";
-    let results_dir = "results";
+/// Solves one dealt seed, escalating through `timeout_tiers` until it is solved or the
+/// last tier is exhausted. A strategy panic ends the seed with a failure record and no
+/// detailed result.
+fn run_seed(
+    seed: u64,
+    game_state: GameState,
+    timeout_tiers: &[u64],
+    plan: &BenchmarkPlan,
+    warm_cache: &Arc<Mutex<solve::WarmCache>>,
+) -> Option<(GameResult, Option<DetailedGameResult>)> {
+    let mut harness_result = None;
+    let mut timeout_secs = 0;
+    let mut tiers = Vec::new();
+    let seed_start = Instant::now();
+    let stats_window = alloc_stats::StatsWindow::open();
+    for &tier_secs in timeout_tiers {
+        timeout_secs = tier_secs;
+        let started_at_ms = seed_start.elapsed().as_millis() as u64;
+        let result = if plan.strategy == "strat14" {
+            bench::harness_hybrid(game_state.clone(), tier_secs, plan.hybrid_memory_budget())
+        } else if plan.strategy == "strat15" {
+            bench::harness_subgoals(game_state.clone(), tier_secs)
+        } else if plan.warm_cache {
+            bench::harness_with_timing_warm(game_state.clone(), tier_secs, warm_cache.clone())
+        } else if plan.unsolved_positions > 0 {
+            let ordering = plan.move_ordering(plan.column_policy);
+            bench::harness_with_snapshots(game_state.clone(), tier_secs, ordering, plan.unsolved_positions, plan.tree_stats)
+        } else if plan.tree_stats {
+            bench::harness_with_stats(game_state.clone(), tier_secs, plan.move_ordering(plan.column_policy))
+        } else {
+            bench::harness_with_policy(game_state.clone(), tier_secs, plan.move_ordering(plan.column_policy))
+        };
+        if let Some(message) = result.panic {
+            return Some((GameResult::failed(seed, FailureKind::Panic, message), None));
+        }
+        if let Some(resident) = result.memory_limit_exceeded {
+            let message = format!(
+                "resident memory reached {} MiB (limit {} MiB)",
+                resident / (1024 * 1024),
+                plan.memory_limit_mb.unwrap_or(0)
+            );
+            return Some((GameResult::failed(seed, FailureKind::MemoryLimitExceeded, message), None));
+        }
+        let solved = result.solved;
+        tiers.push(TierTiming {
+            timeout_secs: tier_secs,
+            started_at_ms,
+            execution_time_ms: result.execution_time.as_millis() as u64,
+            cpu_time_ms: result.cpu_time.as_millis() as u64,
+            states_explored: result.states_explored,
+            solved,
+        });
+        harness_result = Some(result);
+        if solved {
+            break;
+        }
+    }
+    let mut harness_result = harness_result?;
+    // Closed before the ladder so its replays do not count towards this solve.
+    let allocator = stats_window.map(alloc_stats::StatsWindow::close);
+    let ab_run = plan.ab_column_policy.map(|policy| run_ab_policy(&game_state, plan, policy));
+    let exact_packing = plan.compare_packing.then(|| run_exact_packing(&game_state, plan));
+    
+    let warm_cache_stats = if plan.warm_cache {
+        Some(WarmCacheStats {
+            cross_deal_hits: harness_result.cross_deal_hits,
+            entries_after_solve: warm_cache.lock().unwrap().entry_count(),
+        })
+    } else {
+        None
+    };
+    // Shortened before the reordering, which keeps the length it is given.
+    let shortening = if plan.shorten_window > 0 {
+        harness_result
+            .solution_moves
+            .as_deref()
+            .and_then(|moves| solution_minimize::shorten(&game_state, moves, plan.shorten_window))
+            .map(|(moves, shortening)| {
+                harness_result.solution_moves = Some(moves);
+                shortening
+            })
+    } else {
+        None
+    };
+    // Reordered before the ladder, which can reuse a solution that parks fewer cards.
+    let freecell_usage = if plan.freecell_weight > 0 {
+        harness_result
+            .solution_moves
+            .as_deref()
+            .and_then(|moves| solution_reorder::conserve_freecells(&game_state, moves))
+            .map(|(moves, usage)| {
+                harness_result.solution_moves = Some(moves);
+                usage
+            })
+    } else {
+        None
+    };
+    let freecell_ladder = if plan.freecell_ladder {
+        Some(analysis::run_freecell_ladder(
+            &game_state,
+            plan.max_timeout_secs(),
+            harness_result.solution_moves.as_deref(),
+        ))
+    } else {
+        None
+    };
+    let reachable_states = plan
+        .reachable_state_limit
+        .map(|limit| analysis::estimate_reachable_states(&game_state, limit));
+    let execution_time_ms = harness_result.execution_time.as_millis() as u64;
+    let cpu_time_ms = harness_result.cpu_time.as_millis() as u64;
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    
+    // Create summary result for the master file
+    let summary_result = GameResult {
+        seed,
+        solved: harness_result.solved,
+        execution_time_ms,
+        cpu_time_ms,
+        states_explored: harness_result.states_explored,
+        stale_pruned: harness_result.stale_pruned,
+        timeout_secs: Some(timeout_secs),
+        warm_cache: warm_cache_stats,
+        freecell_ladder,
+        reachable_states,
+        allocator,
+        ab_run,
+        exact_packing,
+        tree_stats: harness_result.stats.clone(),
+        shortening,
+        freecell_usage,
+        unsolved_positions: (plan.unsolved_positions > 0 && !harness_result.solved)
+            .then(|| harness_result.snapshots.iter().map(resume::UnsolvedPosition::from).collect()),
+        failure: None,
+        timestamp: timestamp.clone(),
+        move_count: harness_result.solution_moves.as_ref().map(|moves| moves.len()),
+    };
+    
+    // Create detailed result for individual file
+    let detailed_result = DetailedGameResult {
+        engine: Some(ENGINE_INFO),
+        seed,
+        solved: harness_result.solved,
+        execution_time_ms,
+        cpu_time_ms,
+        states_explored: harness_result.states_explored,
+        timestamp,
+        solution_moves: harness_result.solution_moves.clone(),
+        move_count: harness_result.solution_moves.as_ref().map(|moves| moves.len()),
+        solution_found_at_ms: tiers
+            .last()
+            .filter(|tier| tier.solved)
+            .map(|tier| tier.started_at_ms + tier.execution_time_ms),
+        tiers,
+        decision_points: harness_result
+            .solution_moves
+            .as_deref()
+            .and_then(|moves| analysis::decision_points(&game_state, moves).ok()),
+    };
+    
+    Some((summary_result, Some(detailed_result)))
+}
+
+/// Solves `game_state` under `policy`, tier by tier like the A run, for A/B mode.
+fn run_ab_policy(game_state: &GameState, plan: &BenchmarkPlan, policy: solve::ColumnPolicy) -> AbRun {
+    let mut run = AbRun {
+        column_policy: policy,
+        solved: false,
+        execution_time_ms: 0,
+        states_explored: 0,
+        timeout_secs: None,
+        move_count: None,
+        tree_stats: None,
+    };
+    for &tier_secs in &plan.timeout_tiers {
+        let result = if plan.tree_stats {
+            bench::harness_with_stats(game_state.clone(), tier_secs, plan.move_ordering(policy))
+        } else {
+            bench::harness_with_policy(game_state.clone(), tier_secs, plan.move_ordering(policy))
+        };
+        run.solved = result.solved;
+        run.execution_time_ms = result.execution_time.as_millis() as u64;
+        run.states_explored = result.states_explored;
+        run.timeout_secs = Some(tier_secs);
+        run.move_count = result.solution_moves.as_ref().map(|moves| moves.len());
+        run.tree_stats = result.stats;
+        // A panic or the memory limit counts as unsolved; retrying at a longer tier won't help.
+        if result.solved || result.panic.is_some() || result.memory_limit_exceeded.is_some() {
+            break;
+        }
+    }
+    run
+}
+
+/// Solves `game_state` with exact packing, tier by tier like the canonical run.
+fn run_exact_packing(game_state: &GameState, plan: &BenchmarkPlan) -> PackingRun {
+    let mut run = PackingRun { solved: false, execution_time_ms: 0, states_explored: 0, timeout_secs: None };
+    for &tier_secs in &plan.timeout_tiers {
+        let ordering = plan.move_ordering(plan.column_policy);
+        let result = bench::harness_with_packing(game_state.clone(), tier_secs, ordering, solve::Packing::Exact);
+        run.solved = result.solved;
+        run.execution_time_ms = result.execution_time.as_millis() as u64;
+        run.states_explored = result.states_explored;
+        run.timeout_secs = Some(tier_secs);
+        if result.solved || result.panic.is_some() || result.memory_limit_exceeded.is_some() {
+            break;
+        }
+    }
+    run
+}
+
+/// Prints how much canonical packing saved over every seed solved both ways: in total,
+/// then grouped by how many states the exact run needed, so easy and hard deals show apart.
+fn print_packing_summary(results: &[GameResult]) {
+    let pairs: Vec<(&GameResult, &PackingRun)> = results
+        .iter()
+        .filter(|r| r.failure.is_none())
+        .filter_map(|r| r.exact_packing.as_ref().map(|exact| (r, exact)))
+        .collect();
+    let canonical_solved = pairs.iter().filter(|(canonical, _)| canonical.solved).count();
+    let exact_solved = pairs.iter().filter(|(_, exact)| exact.solved).count();
+    let both: Vec<_> = pairs.iter().filter(|(canonical, exact)| canonical.solved && exact.solved).collect();
+    let ratio = |canonical: usize, exact: usize| exact as f64 / canonical.max(1) as f64;
+    let canonical_states: usize = both.iter().map(|(canonical, _)| canonical.states_explored).sum();
+    let exact_states: usize = both.iter().map(|(_, exact)| exact.states_explored).sum();
+    let canonical_ms: u64 = both.iter().map(|(canonical, _)| canonical.execution_time_ms).sum();
+    let exact_ms: u64 = both.iter().map(|(_, exact)| exact.execution_time_ms).sum();
+    println!("Packing over {} seeds: canonical solved {}, exact solved {}", pairs.len(), canonical_solved, exact_solved);
+    println!("  Solved by both: {}; states canonical {}, exact {} ({:.2}x); time canonical {}ms, exact {}ms",
+             both.len(), canonical_states, exact_states, ratio(canonical_states, exact_states), canonical_ms, exact_ms);
+    // Grouped by the order of magnitude of the exact run's state count.
+    let mut groups: Vec<(u32, usize, usize, usize)> = Vec::new();
+    for (canonical, exact) in &both {
+        let magnitude = exact.states_explored.max(1).ilog10();
+        match groups.iter_mut().find(|(m, ..)| *m == magnitude) {
+            Some((_, seeds, c, e)) => {
+                *seeds += 1;
+                *c += canonical.states_explored;
+                *e += exact.states_explored;
+            }
+            None => groups.push((magnitude, 1, canonical.states_explored, exact.states_explored)),
+        }
+    }
+    groups.sort_by_key(|&(magnitude, ..)| magnitude);
+    for (magnitude, seeds, canonical, exact) in groups {
+        println!("  Exact runs of 1e{} states or more: {} seeds, exact explored {:.2}x the canonical states",
+                 magnitude, seeds, ratio(canonical, exact));
+    }
+}
+
+/// Prints how the A and B column policies compare over every seed run in A/B mode.
+fn print_ab_summary(results: &[GameResult], plan: &BenchmarkPlan) {
+    let pairs: Vec<(&GameResult, &AbRun)> = results
+        .iter()
+        .filter(|r| r.failure.is_none())
+        .filter_map(|r| r.ab_run.as_ref().map(|ab| (r, ab)))
+        .collect();
+    let a_solved = pairs.iter().filter(|(a, _)| a.solved).count();
+    let b_solved = pairs.iter().filter(|(_, b)| b.solved).count();
+    let both: Vec<_> = pairs.iter().filter(|(a, b)| a.solved && b.solved).collect();
+    let b_fewer_states = both.iter().filter(|(a, b)| b.states_explored < a.states_explored).count();
+    let b_time_ms: u64 = both.iter().map(|(_, b)| b.execution_time_ms).sum();
+    let a_time_ms: u64 = both.iter().map(|(a, _)| a.execution_time_ms).sum();
+    println!("A/B over {} seeds:", pairs.len());
+    println!("  A ({}) solved {}, B ({}) solved {}",
+             plan.column_policy, a_solved,
+             plan.ab_column_policy.map(|p| p.to_string()).unwrap_or_default(), b_solved);
+    println!("  Solved by both: {}; B explored fewer states on {}; total time A {}ms, B {}ms",
+             both.len(), b_fewer_states, a_time_ms, b_time_ms);
+    if plan.tree_stats {
+        print_tree_stats("A", pairs.iter().filter_map(|(a, _)| a.tree_stats.as_ref()));
+        print_tree_stats("B", pairs.iter().filter_map(|(_, b)| b.tree_stats.as_ref()));
+    }
+}
+
+/// Prints the search-tree shape totalled over `stats`, one line under `label`, then how
+/// many solves reached each milestone and their median time to it.
+fn print_tree_stats<'a>(label: &str, stats: impl Iterator<Item = &'a solve::SolverStats>) {
+    let stats: Vec<_> = stats.collect();
+    let mut total = solve::SolverStats::default();
+    stats.iter().for_each(|s| total.merge(s));
+    let show = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.1}", v));
+    println!("  Tree shape {}: branching {}, solution depth {}, dead-end depth {}, {} re-expansions of {} expanded",
+             label,
+             show(total.average_branching()),
+             show(total.average_solution_depth()),
+             show(total.average_dead_end_depth()),
+             total.re_expansions,
+             total.expanded);
+    let milestones: Vec<String> = solve::MILESTONE_CARDS
+        .iter()
+        .map(|&cards| {
+            let mut times: Vec<u64> = stats
+                .iter()
+                .filter_map(|s| s.milestones.iter().find(|m| m.foundation_cards == cards))
+                .map(|m| m.elapsed_ms)
+                .collect();
+            times.sort_unstable();
+            match times.get(times.len() / 2) {
+                Some(median) => format!("{} home in {} of {} (median {}ms)", cards, times.len(), stats.len(), median),
+                None => format!("{} home in 0 of {}", cards, stats.len()),
+            }
+        })
+        .collect();
+    println!("  Milestones {}: {}", label, milestones.join(", "));
+}
+
+/// Prints the groups of seeds that deal the same game, or that there are none.
+fn print_deal_collisions(collisions: &[analysis::DealCollision]) {
+    if collisions.is_empty() {
+        println!("Deal collisions: none; every seed deals a distinct game");
+        return;
+    }
+    let duplicates: usize = collisions.iter().map(|collision| collision.seeds.len() - 1).sum();
+    println!("Deal collisions: {} groups, {} seeds repeat an earlier deal", collisions.len(), duplicates);
+    for collision in collisions {
+        let seeds: Vec<String> = collision.seeds.iter().map(u64::to_string).collect();
+        println!("  seeds {}", seeds.join(", "));
+    }
+}
+
+fn do_seed_benchmark(plan: &BenchmarkPlan) {
+    let results_filename = plan.output.summary_file.as_str();
+    let results_dir = plan.output.results_dir.as_str();
+    let warm_cache = Arc::new(Mutex::new(solve::WarmCache::new()));
     
-    // Load existing results if any
+    // Load existing results if any. Failed seeds are retried: whatever broke them may
+    // have been fixed since.
     let mut results = load_existing_results(results_filename);
+    // A streamed run may have got further than its last summary.
+    let mut stream = None;
+    if let Some(ndjson_file) = &plan.output.ndjson_file {
+        for streamed in result_stream::read_results::<GameResult>(ndjson_file) {
+            match results.iter_mut().find(|r| r.seed == streamed.seed) {
+                Some(existing) => *existing = streamed,
+                None => results.push(streamed),
+            }
+        }
+        match result_stream::ResultStream::open(ndjson_file, plan) {
+            Ok(opened) => stream = Some(opened),
+            Err(e) => println!("Warning: could not open {} ({}); results will not be streamed", ndjson_file, e),
+        }
+    }
+    results.retain(|r| r.failure.is_none());
     let mut processed_seeds: HashMap<u64, bool> = results.iter()
         .map(|r| (r.seed, true))
         .collect();
     
-    println!("Starting seed benchmark (seeds {}-{}, timeout: {}s)", 
-             start_seed, start_seed + max_seeds - 1, allowed_timeout_secs);
+    let seeds = plan.seeds();
+    let total_seeds = seeds.len();
+    let pending: Vec<u64> = seeds.into_iter()
+        .filter(|seed| !processed_seeds.contains_key(seed))
+        .collect();
+    
+    println!("Starting seed benchmark ({}, timeouts: {:?}s, strategy: {}, parallelism: {})",
+             plan.seeds.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", "),
+             plan.timeout_tiers, plan.strategy, plan.parallelism);
+    if let Some(secs) = plan.total_budget_secs {
+        println!("Total budget: {}s, shared out over passes at each tier", secs);
+    }
+    if let Some(b_policy) = plan.ab_column_policy {
+        println!("A/B mode: column policy {} (A) against {} (B)", plan.column_policy, b_policy);
+    }
+    bench::set_memory_limit(plan.memory_limit_mb.map(|mb| mb * 1024 * 1024));
+    if plan.memory_limit_mb.is_some() && bench::resident_memory_bytes().is_none() {
+        println!("Warning: memory use cannot be measured on this platform; the memory limit is not enforced");
+    }
+    if let Some(path) = &plan.endgame_db {
+        match endgame_db::EndgameDb::open(path) {
+            Ok(db) if db.is_empty() => println!("Warning: endgame database {} holds no certificates", path),
+            Ok(db) => {
+                println!("Endgame database: {} certified positions from {}", db.len(), path);
+                endgame_db::install(db);
+            }
+            Err(e) => {
+                eprintln!("Could not open endgame database {}: {}", path, e);
+                std::process::exit(2);
+            }
+        }
+    }
+    if plan.parallelism > 1 {
+        println!("Note: CPU time is process-wide, so per-seed cpu_time_ms overlaps when seeds run in parallel");
+    }
     // println!("Summary will be saved to: {}", results_filename);
     // println!("Detailed results will be saved to: {}/", results_dir);
     
-    for seed in start_seed..start_seed + max_seeds {
-        // Skip if already processed
-        if processed_seeds.contains_key(&seed) {
-            // println!("Seed {} already processed, skipping", seed);
-            continue;
-        }
-        
-        // println!("Testing seed {} ({}/{})...", seed, seed - start_seed + 1, max_seeds);
-        
-        let game_state = match generate_deal(seed) {
-            Ok(state) => state,
+    // Deal every pending seed up front, in parallel, instead of inside each worker.
+    let dealt: Vec<(u64, Result<GameState, GenerationError>)> =
+        generate_deals(pending.par_iter().copied()).collect();
+    let mut deals = Vec::with_capacity(dealt.len());
+    for (seed, deal) in dealt {
+        match deal {
+            Ok(game_state) => deals.push((seed, game_state)),
             Err(e) => {
-                println!("Failed to generate deal for seed {}: {:?}", seed, e);
-                continue;
+                println!("Failed to generate deal for seed {}: {}", seed, e);
+                results.push(GameResult::failed(seed, FailureKind::Generation, e.to_string()));
             }
-        };
-        
-        let harness_result = harness::harness_with_timing(game_state, allowed_timeout_secs);
-        let execution_time_ms = harness_result.execution_time.as_millis() as u64;
-        let timestamp = chrono::Utc::now().to_rfc3339();
-        
-        // Create summary result for the master file
-        let summary_result = GameResult {
-            seed,
-            solved: harness_result.solved,
-            execution_time_ms,
-            timestamp: timestamp.clone(),
-            move_count: harness_result.solution_moves.as_ref().map(|moves| moves.len()),
-        };
-        
-        // Create detailed result for individual file
-        let detailed_result = DetailedGameResult {
-            seed,
-            solved: harness_result.solved,
-            execution_time_ms,
-            timestamp,
-            solution_moves: harness_result.solution_moves.clone(),
-            move_count: harness_result.solution_moves.as_ref().map(|moves| moves.len()),
-        };
-        
-        // Save detailed result to individual file
-        save_detailed_game_result(&detailed_result, results_dir);
-        
-        results.push(summary_result);
-        processed_seeds.insert(seed, true);
-        
-        if harness_result.solved {
-            if let Some(ref moves) = harness_result.solution_moves {
-                // println!("✓ Seed {} solved in {}ms with {} moves", seed, execution_time_ms, moves.len());
-            } else {
-                // println!("✓ Seed {} solved in {}ms", seed, execution_time_ms);
-            }
-        } else {
-            // println!("✗ Seed {} failed/timeout after {}ms", seed, execution_time_ms);
         }
-        
-        // Print progress every 100 seeds
-        if (seed - start_seed + 1) % 100 == 0 {
-            println!("Progress: {} / {} seeds completed", seed - start_seed + 1, max_seeds);
+    }
+    
+    // Without a budget there is a single pass, each seed climbing the tiers on its own.
+    // With one, every pass gives the seeds still unsolved a single capped tier.
+    let budget = plan.total_budget_secs.map(|secs| run_budget::RunBudget::start(secs, plan.parallelism));
+    let mut deals: Vec<(u64, GameState, u64)> =
+        deals.into_iter().map(|(seed, game_state)| (seed, game_state, 0)).collect();
+    let dealt_seeds: Vec<u64> = deals.iter().map(|(seed, _, _)| *seed).collect();
+    let mut completed = processed_seeds.len().min(total_seeds);
+    let mut pass = 0;
+    while !deals.is_empty() {
+        // The cap each unsolved seed was last given, for the next pass.
+        let mut unsolved = HashMap::new();
+        {
+            let tier = plan.timeout_tiers.get(pass).copied();
+            let jobs: Box<dyn Iterator<Item = (u64, GameState, Vec<u64>)> + Send + '_> = match &budget {
+                Some(budget) => Box::new(
+                    budget.pass(&deals, tier).map(|(seed, game_state, cap)| (seed, game_state, vec![cap])),
+                ),
+                None => Box::new(
+                    deals.iter().map(|(seed, game_state, _)| (*seed, game_state.clone(), plan.timeout_tiers.clone())),
+                ),
+            };
+            let next_seed = Mutex::new(jobs);
+            let (sender, receiver) = mpsc::channel();
+
+            thread::scope(|scope| {
+                for _ in 0..plan.parallelism {
+                    let sender = sender.clone();
+                    let next_seed = &next_seed;
+                    let warm_cache = &warm_cache;
+                    scope.spawn(move || loop {
+                        let (seed, game_state, tiers) = match next_seed.lock().unwrap().next() {
+                            Some(job) => job,
+                            None => break,
+                        };
+                        // A panic outside the solver thread (e.g. in the ladder analysis) must not
+                        // take the whole run down with it.
+                        let seed_results =
+                            panic::catch_unwind(AssertUnwindSafe(|| run_seed(seed, game_state, &tiers, plan, warm_cache)))
+                                .unwrap_or_else(|payload| {
+                                    let message = bench::panic_message(&*payload);
+                                    Some((GameResult::failed(seed, FailureKind::Panic, message), None))
+                                });
+                        if let Some(seed_results) = seed_results {
+                            if sender.send(seed_results).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+                drop(sender);
+
+                for (summary_result, detailed_result) in receiver {
+                    let seed = summary_result.seed;
+
+                    // Save detailed result to individual file
+                    if let Some(detailed_result) = &detailed_result {
+                        save_detailed_game_result(detailed_result, results_dir);
+                    }
+
+                    if let Some(failure) = &summary_result.failure {
+                        println!("Seed {} errored ({:?}): {}", seed, failure.kind, failure.message);
+                    } else if summary_result.solved {
+                        // println!("✓ Seed {} solved in {}ms", seed, summary_result.execution_time_ms);
+                    } else {
+                        // println!("✗ Seed {} failed/timeout after {}ms", seed, summary_result.execution_time_ms);
+                        unsolved.insert(seed, summary_result.timeout_secs.unwrap_or(0));
+                    }
+
+                    if let Some(stream) = &mut stream {
+                        if let Err(e) = stream.append(&summary_result) {
+                            println!("Warning: failed to stream result for seed {}: {}", seed, e);
+                        }
+                    }
+                    if processed_seeds.insert(seed, true).is_some() {
+                        // A later pass of a budgeted run replaces the seed's earlier result.
+                        if let Some(existing) = results.iter_mut().find(|r| r.seed == seed) {
+                            *existing = summary_result;
+                        }
+                        continue;
+                    }
+                    results.push(summary_result);
+                    completed += 1;
+
+                    // Print progress every 100 seeds
+                    if completed.is_multiple_of(100) {
+                        println!("Progress: {} / {} seeds completed", completed, total_seeds);
+                    }
+
+                    // Save summary results after every 10 games, unless they are being streamed
+                    if stream.is_none() && results.len().is_multiple_of(10) {
+                        save_results_to_json(&results, plan, None);
+                    }
+                }
+            });
+        }
+
+        let Some(budget) = &budget else { break };
+        pass += 1;
+        if pass > plan.timeout_tiers.len() || budget.cap(1, None).is_none() {
+            break;
         }
-        
-        // Save summary results after every 10 games or if this is the last one
-        if results.len() % 10 == 0 || seed == start_seed + max_seeds - 1 {
-            save_results_to_json(&results, results_filename, allowed_timeout_secs);
+        deals.retain_mut(|(seed, _, last_cap)| match unsolved.get(seed) {
+            Some(&cap) => {
+                *last_cap = cap;
+                true
+            }
+            None => false,
+        });
+        if !deals.is_empty() {
+            println!(
+                "Pass {}: revisiting {} unsolved seeds with {}s of the budget left",
+                pass + 1,
+                deals.len(),
+                budget.remaining().as_secs()
+            );
         }
     }
     
+    // Seeds the budget never reached still get a result, so the run accounts for every seed.
+    let never_attempted: Vec<u64> =
+        dealt_seeds.into_iter().filter(|seed| !processed_seeds.contains_key(seed)).collect();
+    if !never_attempted.is_empty() {
+        println!("Warning: the budget ran out before {} seeds were attempted", never_attempted.len());
+    }
+    for seed in never_attempted {
+        let skipped = GameResult::failed(
+            seed,
+            FailureKind::BudgetExhausted,
+            "The total budget ran out before the seed was attempted".to_string(),
+        );
+        if let Some(stream) = &mut stream {
+            if let Err(e) = stream.append(&skipped) {
+                println!("Warning: failed to stream result for seed {}: {}", seed, e);
+            }
+        }
+        results.push(skipped);
+    }
+    
     // Final save and summary
-    save_results_to_json(&results, results_filename, allowed_timeout_secs);
+    let deal_collisions = plan.deal_collisions.then(|| analysis::find_deal_collisions(&plan.seeds()));
+    save_results_to_json(&results, plan, deal_collisions.as_deref());
     
     let solved_count = results.iter().filter(|r| r.solved).count();
     println!("\n=== Benchmark Complete ===");
     println!("Total games tested: {}", results.len());
     println!("Games solved: {} ({:.1}%)", solved_count, 
              (solved_count as f64 / results.len() as f64) * 100.0);
-    println!("Games failed/timeout: {}", results.len() - solved_count);
+    let errored_count = results.iter().filter(|r| r.failure.is_some()).count();
+    println!("Games failed/timeout: {}", results.len() - solved_count - errored_count);
+    if errored_count > 0 {
+        println!("Games errored (not dealt, panicked, over the memory limit or out of budget): {}", errored_count);
+    }
+    println!("Stale queue items pruned: {}", results.iter().map(|r| r.stale_pruned).sum::<u64>());
+    if plan.warm_cache {
+        let cache = warm_cache.lock().unwrap();
+        println!("Warm cache: {} cross-deal hits over {} solves, {} states retained",
+                 cache.total_cross_deal_hits(), cache.solves(), cache.entry_count());
+    }
+    if plan.compare_packing {
+        print_packing_summary(&results);
+    }
+    if plan.ab_column_policy.is_some() {
+        print_ab_summary(&results, plan);
+    } else if plan.tree_stats {
+        print_tree_stats("over all seeds", results.iter().filter_map(|r| r.tree_stats.as_ref()));
+    }
+    if let Some(collisions) = &deal_collisions {
+        print_deal_collisions(collisions);
+    }
     println!("Summary saved to: {}", results_filename);
     println!("Detailed results saved to: {}/", results_dir);
 }
@@ -229,7 +904,7 @@ fn do_benchmark() {
         for m in &subset_moves_to_apply {
             game_state.execute_move(m).unwrap();
         }
-        let result = harness::harness(game_state.clone(), allowed_timeout_secs);
+        let result = bench::harness(game_state.clone(), allowed_timeout_secs);
         if result {
             println!("Succeeded with {} moves undone", move_count_to_undue);
             move_count_to_undue += 1;
@@ -250,11 +925,14 @@ fn do_adhoc() {
     let game_state = generate_deal(seed).unwrap();
 
     // Example of solving the game using strategy 1
-    let harness_result = harness::harness_with_timing(game_state, allowed_timeout_secs);
+    let harness_result = bench::harness_with_timing(game_state.clone(), allowed_timeout_secs);
     let execution_time_ms = harness_result.execution_time.as_millis() as u64;
     if harness_result.solved {
             if let Some(ref moves) = harness_result.solution_moves {
                 println!("✓ Seed {} solved in {}ms with {} moves", seed, execution_time_ms, moves.len());
+                if let Ok(phases) = SolutionPhases::new(&game_state, moves) {
+                    println!("{}", phases);
+                }
             } else {
                 println!("✓ Seed {} solved in {}ms", seed, execution_time_ms);
             }
@@ -264,10 +942,113 @@ fn do_adhoc() {
 }
 
 fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("watch") {
+        args.next();
+        match watch::WatchOptions::from_args(args) {
+            Ok(options) => watch::run(&options),
+            Err(e) => {
+                eprintln!("Invalid watch arguments: {}", e);
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+    if args.peek().map(String::as_str) == Some("tree") {
+        args.next();
+        let result = search_tree::TreeOptions::from_args(args)
+            .map_err(|e| format!("Invalid tree arguments: {}", e))
+            .and_then(|options| search_tree::run(&options));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    if args.peek().map(String::as_str) == Some("deal-corpus") {
+        args.next();
+        let result = deal_corpus::DealCorpusCommand::from_args(args)
+            .map_err(|e| format!("Invalid deal-corpus arguments: {}", e))
+            .and_then(|command| deal_corpus::run(&command));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    if args.peek().map(String::as_str) == Some("bundle") {
+        args.next();
+        let result = run_bundle::BundleCommand::from_args(args)
+            .map_err(|e| format!("Invalid bundle arguments: {}", e))
+            .and_then(|command| run_bundle::run(&command));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    if args.peek().map(String::as_str) == Some("resume") {
+        args.next();
+        let result = resume::ResumeCommand::from_args(args)
+            .map_err(|e| format!("Invalid resume arguments: {}", e))
+            .and_then(|command| resume::run(&command));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    if args.peek().map(String::as_str) == Some("certify") {
+        args.next();
+        let result = certify::CertifyCommand::from_args(args)
+            .map_err(|e| format!("Invalid certify arguments: {}", e))
+            .and_then(|command| certify::run(&command));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    if args.peek().map(String::as_str) == Some("solve") {
+        args.next();
+        let result = solve_board::SolveCommand::from_args(args)
+            .map_err(|e| format!("Invalid solve arguments: {}", e))
+            .and_then(|command| solve_board::run(&command));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    if args.peek().map(String::as_str) == Some("replay-diff") {
+        args.next();
+        let result = replay_diff::ReplayDiffCommand::from_args(args)
+            .map_err(|e| format!("Invalid replay-diff arguments: {}", e))
+            .and_then(|command| replay_diff::run(&command));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+
     println!("FreeCell Solver starting...");
 
     // Run new seed benchmark to test solver across multiple game seeds
-    do_seed_benchmark();
+    let plan = match BenchmarkPlan::from_args(args) {
+        Ok(plan) => plan,
+        Err(e) => {
+            eprintln!("Invalid benchmark plan: {}", e);
+            std::process::exit(2);
+        }
+    };
+    do_seed_benchmark(&plan);
 
     // Alternative benchmarks (commented out):
     // do_benchmark();  // Original benchmark testing move undoing
@@ -1,16 +1,33 @@
 //! PackedGameState: A compact, bit-packed representation of a FreeCell game state for fast hashing and equality.
 //!
 //! Used primarily by solver components for efficient state comparison.
+//!
+//! For two positions that fit the packed layout and have the same
+//! [`rules`](GameState::rules):
+//!
+//! - [`from_game_state`](PackedGameState::from_game_state) is equal exactly when the
+//!   positions are `==`;
+//! - [`from_game_state_canonical`](PackedGameState::from_game_state_canonical) is equal
+//!   exactly when they are [`eq_canonical`](GameState::eq_canonical), provided both hold
+//!   the whole deck. It keeps only the ranks on the foundations, which a full deck
+//!   determines from the cards still in play.
 
-use freecell_game_engine::{foundations::FOUNDATION_COUNT, tableau::TABLEAU_COLUMN_COUNT, Card, Foundations, FreeCells, GameState, Rank, Suit, Tableau};
+use freecell_game_engine::tableau::MAX_COLUMN_HEIGHT;
+use freecell_game_engine::{rules::RulesProfile, Card, Foundations, FreeCells, GameState, Rank, Suit, Tableau};
+use std::collections::HashSet;
+use std::hash::BuildHasher;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Ordered by raw bytes, which is only meaningful for sorting and deduplication.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct PackedGameState {
     // 52 cards, 6 bits each (0 = empty, 1-52 = card id)
     tableau_cards: [u8; 52], // 0 means unused slot
     tableau_lens: [u8; 8],   // Number of cards in each column
     freecells: [u8; 4],      // 0 = empty, 1-52 = card id
     foundations: [u8; 4],    // Top rank in each foundation (0 = empty, 1-13)
+    // Suit of each foundation pile, 2 bits per pile from pile 0 up; an empty pile stores
+    // its own index. Always 0 in the canonical form.
+    foundation_suits: u8,
 }
 
 /// Error type for unpacking a PackedGameState
@@ -43,6 +60,9 @@ impl PackedGameState {
         let mut idx = 0;
         for col in 0..8 {
             let len = self.tableau_lens[col] as usize;
+            if len > MAX_COLUMN_HEIGHT {
+                return Err(UnpackError::InvalidTableauLength);
+            }
             if idx + len > self.tableau_cards.len() {
                 return Err(UnpackError::NotEnoughTableauCards);
             }
@@ -77,7 +97,8 @@ impl PackedGameState {
                 return Err(UnpackError::InvalidFoundationRank(top_rank));
             }
             if top_rank > 0 {
-                let suit = Suit::try_from(i as u8).map_err(|_| UnpackError::InvalidSuit(i as u8))?;
+                let suit_index = (self.foundation_suits >> (2 * i)) & 0b11;
+                let suit = Suit::try_from(suit_index).map_err(|_| UnpackError::InvalidSuit(suit_index))?;
                 for r in 1..=top_rank {
                     let rank = Rank::try_from(r).map_err(|_| UnpackError::InvalidRank(r))?;
                     let card = Card::new(rank, suit);
@@ -94,12 +115,15 @@ impl PackedGameState {
 impl PackedGameState {
     /// Convert a GameState into a PackedGameState
     pub fn from_game_state(gs: &GameState) -> Self {
+        let rules = gs.rules();
+        assert!(fits_packed_layout(&rules), "PackedGameState only holds standard games");
         let mut tableau_cards = [0u8; 52];
         let mut tableau_lens = [0u8; 8];
         let mut idx = 0;
-        for (col, len_ref) in tableau_lens.iter_mut().enumerate().take(TABLEAU_COLUMN_COUNT) {
+        for (col, len_ref) in tableau_lens.iter_mut().enumerate().take(rules.tableau_columns()) {
             let location = freecell_game_engine::location::TableauLocation::new(col as u8).unwrap();
             let len = gs.tableau().column_length(location).unwrap_or(0);
+            debug_assert!(len <= MAX_COLUMN_HEIGHT, "column {} holds {} cards", col, len);
             *len_ref = len as u8;
             for i in 0..len {
                 if let Ok(card) = gs.tableau().get_card_at(location, i) {
@@ -109,20 +133,24 @@ impl PackedGameState {
             }
         }
         let mut freecells = [0u8; 4];
-        for i in 0..freecell_game_engine::freecells::FREECELL_COUNT {
+        for (i, cell) in freecells.iter_mut().enumerate().take(rules.freecells()) {
             let location = freecell_game_engine::location::FreecellLocation::new(i as u8).unwrap();
-            freecells[i] = gs.freecells().get_card(location).unwrap_or(None).map_or(0, pack_card);
+            *cell = gs.freecells().get_card(location).unwrap_or(None).map_or(0, pack_card);
         }
         let mut foundations = [0u8; 4];
-        for i in 0..FOUNDATION_COUNT {
+        let mut foundation_suits = 0u8;
+        for (i, rank) in foundations.iter_mut().enumerate().take(rules.foundation_piles()) {
             let location = freecell_game_engine::location::FoundationLocation::new(i as u8).unwrap();
-            foundations[i] = gs.foundations().get_card(location).unwrap_or(None).map_or(0, |c| c.rank() as u8);
+            let top = gs.foundations().get_card(location).unwrap_or(None);
+            *rank = top.map_or(0, |c| c.rank() as u8);
+            foundation_suits |= top.map_or(i as u8, |c| c.suit() as u8) << (2 * i);
         }
         PackedGameState {
             tableau_cards,
             tableau_lens,
             freecells,
             foundations,
+            foundation_suits,
         }
     }
 
@@ -131,6 +159,7 @@ impl PackedGameState {
     /// freecells, and foundations to create a canonical ordering.
     /// Optimized version with reduced allocations and fewer sorting operations.
     pub fn from_game_state_canonical(gs: &GameState) -> Self {
+        assert!(fits_packed_layout(&gs.rules()), "PackedGameState only holds standard games");
         // Pre-allocate arrays to avoid repeated allocations
         let mut tableau_cards = [0u8; 52];
         let mut tableau_lens = [0u8; 8];
@@ -151,10 +180,54 @@ impl PackedGameState {
             tableau_lens,
             freecells,
             foundations,
+            foundation_suits: 0,
         }
     }
 }
 
+/// Batch operations for frontiers: lists of states waiting to be expanded.
+impl PackedGameState {
+    /// Bytes one packed state occupies.
+    pub const SIZE_BYTES: usize = std::mem::size_of::<PackedGameState>();
+
+    /// Sorts `states` and removes duplicates, returning how many were removed.
+    pub fn sort_and_dedup(states: &mut Vec<PackedGameState>) -> usize {
+        let before = states.len();
+        states.sort_unstable();
+        states.dedup();
+        before - states.len()
+    }
+
+    /// Removes every state already in `visited`, returning how many were removed.
+    pub fn retain_unvisited<S: BuildHasher>(
+        states: &mut Vec<PackedGameState>,
+        visited: &HashSet<PackedGameState, S>,
+    ) -> usize {
+        let before = states.len();
+        states.retain(|state| !visited.contains(state));
+        before - states.len()
+    }
+
+    /// Heap bytes held by `states`, counting spare capacity.
+    pub fn frontier_memory_bytes(states: &Vec<PackedGameState>) -> usize {
+        states.capacity() * Self::SIZE_BYTES
+    }
+
+    /// Approximate heap bytes held by `visited`: its slots plus the one control byte per
+    /// slot the standard table keeps. Capacity, not length, is what is allocated.
+    pub fn visited_memory_bytes<S>(visited: &HashSet<PackedGameState, S>) -> usize {
+        visited.capacity() * (Self::SIZE_BYTES + 1)
+    }
+}
+
+/// Whether a game with these dimensions fits the fixed-size packed arrays.
+fn fits_packed_layout(rules: &RulesProfile) -> bool {
+    let standard = RulesProfile::STANDARD;
+    rules.decks() == standard.decks()
+        && rules.tableau_columns() <= standard.tableau_columns()
+        && rules.freecells() <= standard.freecells()
+}
+
 /// Packs a card into a 1-based id: 1..52 (0 = empty)
 fn pack_card(card: &Card) -> u8 {
     let suit = card.suit() as u8; // 0..3
@@ -165,7 +238,7 @@ fn pack_card(card: &Card) -> u8 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use freecell_game_engine::{GameState, Card, Rank, Suit};
+    use freecell_game_engine::{floc, hloc, tloc, GameState, Card, Move, Rank, Suit};
 
     #[test]
     fn round_trip_default_state() {
@@ -180,21 +253,21 @@ mod tests {
         let mut tableau = Tableau::new();
         let card1 = Card::new(Rank::Ace, Suit::Hearts);
         let card2 = Card::new(Rank::King, Suit::Spades);
-        let location0 = freecell_game_engine::location::TableauLocation::new(0).unwrap();
-        let location1 = freecell_game_engine::location::TableauLocation::new(1).unwrap();
+        let location0 = tloc!(0);
+        let location1 = tloc!(1);
         tableau.place_card_at(location0, card1).unwrap();
         tableau.place_card_at(location1, card2).unwrap();
 
         let mut freecells = FreeCells::new();
         let card3 = Card::new(Rank::Queen, Suit::Diamonds);
-        let location = freecell_game_engine::location::FreecellLocation::new(0).unwrap();
+        let location = floc!(0);
         freecells.place_card_at(location, card3).unwrap();
 
         let mut foundations = Foundations::new();
         for r in 1..=3 {
             let rank = Rank::try_from(r).unwrap();
             let card = Card::new(rank, Suit::Diamonds);
-            let location = freecell_game_engine::location::FoundationLocation::new(2).unwrap();
+            let location = hloc!(2);
             foundations.place_card_at(location, card).unwrap();
         }
 
@@ -204,6 +277,12 @@ mod tests {
         assert_eq!(gs, unpacked, "Complex state should round-trip");
     }
 
+    #[test]
+    #[should_panic(expected = "only holds standard games")]
+    fn refuses_games_wider_than_the_layout() {
+        PackedGameState::from_game_state(&GameState::with_variant(freecell_game_engine::Variant::DoubleFreecell));
+    }
+
     #[test]
     fn error_on_invalid_card_id() {
         let mut packed = PackedGameState::from_game_state(&GameState::default());
@@ -221,6 +300,81 @@ mod tests {
         assert!(matches!(result, Err(UnpackError::InvalidFoundationRank(42))));
     }
 
+    #[test]
+    fn frontier_dedup_and_visited_filtering() {
+        let empty = PackedGameState::from_game_state(&GameState::default());
+        let deal = PackedGameState::from_game_state(&freecell_game_engine::generation::generate_deal(1).unwrap());
+        let mut frontier = vec![deal.clone(), empty.clone(), deal.clone(), empty.clone()];
+
+        assert_eq!(PackedGameState::sort_and_dedup(&mut frontier), 2);
+        assert_eq!(frontier.len(), 2);
+
+        let visited: fxhash::FxHashSet<_> = [empty].into_iter().collect();
+        assert_eq!(PackedGameState::retain_unvisited(&mut frontier, &visited), 1);
+        assert_eq!(frontier, [deal]);
+        assert!(PackedGameState::frontier_memory_bytes(&frontier) >= PackedGameState::SIZE_BYTES);
+        assert!(PackedGameState::visited_memory_bytes(&visited) > PackedGameState::SIZE_BYTES);
+    }
+
+    #[test]
+    fn round_trip_keeps_the_suit_of_each_foundation_pile() {
+        let mut foundations = Foundations::new();
+        let pile = hloc!(2);
+        foundations.place_card_at(pile, Card::new(Rank::Ace, Suit::Spades)).unwrap();
+        let gs = GameState::from_components(Tableau::new(), FreeCells::new(), foundations);
+        assert_eq!(PackedGameState::from_game_state(&gs).to_game_state().unwrap(), gs);
+    }
+
+    /// Positions a few random plies from a deal, plus the same card parked in each
+    /// freecell and two moves played in either order.
+    fn walk_positions(seed: u64) -> Vec<GameState> {
+        let deal = freecell_game_engine::generation::generate_deal(seed).unwrap();
+        let play = |moves: &[Move]| {
+            let mut game = deal.clone();
+            for m in moves {
+                game.execute_move(m).unwrap();
+            }
+            game
+        };
+        let mut positions: Vec<GameState> =
+            (0..4).map(|cell| play(&[Move::tableau_to_freecell(0, cell).unwrap()])).collect();
+        let first = Move::tableau_to_freecell(0, 0).unwrap();
+        let second = Move::tableau_to_freecell(1, 1).unwrap();
+        positions.push(play(&[first, second]));
+        positions.push(play(&[second, first]));
+
+        let mut rng = seed;
+        for _ in 0..6 {
+            let mut game = deal.clone();
+            for _ in 0..6 {
+                let moves = game.get_available_moves();
+                if moves.is_empty() {
+                    break;
+                }
+                rng = rng.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                game.execute_move(&moves[(rng >> 33) as usize % moves.len()]).unwrap();
+                positions.push(game.clone());
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn packed_equality_matches_state_equality() {
+        let positions = walk_positions(3);
+        for a in &positions {
+            for b in &positions {
+                let plain = PackedGameState::from_game_state(a) == PackedGameState::from_game_state(b);
+                assert_eq!(plain, a == b, "plain form disagrees with ==\n{}\n{}", a, b);
+                let canonical =
+                    PackedGameState::from_game_state_canonical(a) == PackedGameState::from_game_state_canonical(b);
+                assert_eq!(canonical, a.eq_canonical(b), "canonical form disagrees with eq_canonical\n{}\n{}", a, b);
+            }
+        }
+        assert!(positions[0] != positions[1] && positions[0].eq_canonical(&positions[1]));
+        assert_eq!(positions[4], positions[5]);
+    }
+
     #[test]
     fn canonical_form_same_for_equivalent_states() {
         // Create two game states that are isomorphic but have different column arrangements
@@ -231,8 +385,8 @@ mod tests {
         let card_king_spades = Card::new(Rank::King, Suit::Spades);
         
         // State 1: Ace in col 0, King in col 1
-        let loc0 = freecell_game_engine::location::TableauLocation::new(0).unwrap();
-        let loc1 = freecell_game_engine::location::TableauLocation::new(1).unwrap();
+        let loc0 = tloc!(0);
+        let loc1 = tloc!(1);
         tableau1.place_card_at(loc0, card_ace_hearts).unwrap();
         tableau1.place_card_at(loc1, card_king_spades).unwrap();
         
@@ -260,8 +414,8 @@ mod tests {
         let card_ace_hearts = Card::new(Rank::Ace, Suit::Hearts);  // Higher ID: 1*13+1=14
         let card_king_spades = Card::new(Rank::King, Suit::Spades); // Lower ID: 0*13+13=13
         
-        let loc0 = freecell_game_engine::location::FreecellLocation::new(0).unwrap();
-        let loc3 = freecell_game_engine::location::FreecellLocation::new(3).unwrap();
+        let loc0 = floc!(0);
+        let loc3 = floc!(3);
         
         // Place Ace (higher ID) in first freecell, King (lower ID) in last freecell
         freecells.place_card_at(loc0, card_ace_hearts).unwrap();
@@ -285,13 +439,13 @@ mod tests {
         
         // Place cards in foundations in non-sorted order
         // Foundation 0: Hearts with 3 cards (rank 3 on top)
-        let hearts_foundation = freecell_game_engine::location::FoundationLocation::new(0).unwrap();
+        let hearts_foundation = hloc!(0);
         foundations.place_card_at(hearts_foundation, Card::new(Rank::Ace, Suit::Hearts)).unwrap();
         foundations.place_card_at(hearts_foundation, Card::new(Rank::Two, Suit::Hearts)).unwrap();
         foundations.place_card_at(hearts_foundation, Card::new(Rank::Three, Suit::Hearts)).unwrap();
         
         // Foundation 2: Clubs with 1 card (rank 1 on top)
-        let clubs_foundation = freecell_game_engine::location::FoundationLocation::new(2).unwrap();
+        let clubs_foundation = hloc!(2);
         foundations.place_card_at(clubs_foundation, Card::new(Rank::Ace, Suit::Clubs)).unwrap();
         
         let gs = GameState::from_components(Tableau::new(), FreeCells::new(), foundations);
@@ -0,0 +1,284 @@
+//! `solver replay-diff`: catch silent rule changes by replaying recorded solutions.
+//!
+//! Refactoring validation or execution can change what a move does without failing a
+//! single test. `replay-diff record` replays a saved solution (a detailed result file
+//! from a benchmark run) and stores a trace: a fingerprint of the position after every
+//! move, or the reason the move was rejected. `replay-diff check` replays the same moves
+//! under the current build, optionally with fewer freecells, and reports the first ply
+//! whose outcome differs from the trace.
+//!
+//! Fingerprints ([`GameState::fingerprint`]) hash each card's suit, rank and place on
+//! the board rather than the engine's own `Hash`, so they only change when the position
+//! does. Rejections are compared by the fact of the rejection, not by the wording of
+//! the error.
+
+use crate::benchmark_plan::PlanError;
+use freecell_game_engine::generation::generate_deal;
+use freecell_game_engine::r#move::Move;
+use freecell_game_engine::{EngineInfo, GameState, ENGINE_INFO};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+
+/// What one step of a replay did. Step 0 is the deal itself.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepOutcome {
+    Played { fingerprint: u64 },
+    Rejected { error: String },
+}
+
+impl StepOutcome {
+    fn matches(&self, other: &StepOutcome) -> bool {
+        match (self, other) {
+            (StepOutcome::Played { fingerprint: a }, StepOutcome::Played { fingerprint: b }) => a == b,
+            (StepOutcome::Rejected { .. }, StepOutcome::Rejected { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for StepOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepOutcome::Played { fingerprint } => write!(f, "position {:016x}", fingerprint),
+            StepOutcome::Rejected { error } => write!(f, "rejected: {}", error),
+        }
+    }
+}
+
+/// A replayed solution and what every step of it did.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReplayTrace {
+    pub engine: EngineInfo,
+    pub seed: u64,
+    pub freecells: usize,
+    pub moves: Vec<Move>,
+    /// The deal, then one entry per move played. Ends early at the first rejection.
+    pub steps: Vec<StepOutcome>,
+}
+
+impl ReplayTrace {
+    /// Deals `seed` with `freecells` usable cells and replays `moves` on it.
+    pub fn record(seed: u64, freecells: usize, moves: Vec<Move>) -> Result<Self, String> {
+        let mut game = generate_deal(seed).map_err(|e| format!("Could not deal {}: {}", seed, e))?;
+        game.set_freecell_capacity(freecells)
+            .map_err(|e| format!("Could not limit the deal to {} freecells: {}", freecells, e))?;
+        let mut steps = vec![StepOutcome::Played { fingerprint: game.fingerprint() }];
+        for m in &moves {
+            match game.execute_move(m) {
+                Ok(()) => steps.push(StepOutcome::Played { fingerprint: game.fingerprint() }),
+                Err(e) => {
+                    steps.push(StepOutcome::Rejected { error: e.to_string() });
+                    break;
+                }
+            }
+        }
+        Ok(ReplayTrace {
+            engine: ENGINE_INFO,
+            seed,
+            freecells,
+            moves,
+            steps,
+        })
+    }
+
+    /// The first step at which `self` and `other` disagree, if any.
+    pub fn first_divergence(&self, other: &ReplayTrace) -> Option<Divergence> {
+        let steps = self.steps.len().max(other.steps.len());
+        (0..steps).find_map(|step| {
+            let expected = self.steps.get(step);
+            let actual = other.steps.get(step);
+            let same = matches!((expected, actual), (Some(a), Some(b)) if a.matches(b));
+            (!same).then(|| Divergence {
+                step,
+                played: step.checked_sub(1).and_then(|i| self.moves.get(i).copied()),
+                expected: expected.cloned(),
+                actual: actual.cloned(),
+            })
+        })
+    }
+}
+
+/// Where two replays of the same moves first disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// 0 for the deal, otherwise the number of moves played.
+    pub step: usize,
+    /// The move that led to `step`; `None` for the deal.
+    pub played: Option<Move>,
+    /// `None` when that replay had already stopped.
+    pub expected: Option<StepOutcome>,
+    pub actual: Option<StepOutcome>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |outcome: &Option<StepOutcome>| match outcome {
+            Some(outcome) => outcome.to_string(),
+            None => "replay already stopped".to_string(),
+        };
+        match self.played {
+            Some(m) => writeln!(f, "First divergence after move {} ({}):", self.step, m)?,
+            None => writeln!(f, "The deals differ:")?,
+        }
+        writeln!(f, "  recorded: {}", describe(&self.expected))?;
+        write!(f, "  now:      {}", describe(&self.actual))
+    }
+}
+
+/// The parts of a detailed result file a replay needs.
+#[derive(Deserialize)]
+struct SolutionFile {
+    seed: u64,
+    solution_moves: Option<Vec<Move>>,
+}
+
+/// What `solver replay-diff` was asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayDiffCommand {
+    /// Replay the solution in a detailed result file and write the trace.
+    Record { solution: String, trace: String },
+    /// Replay a recorded trace's moves and compare, with `freecells` cells if given.
+    Check { trace: String, freecells: Option<usize> },
+}
+
+impl ReplayDiffCommand {
+    /// Parses the arguments following `replay-diff`: either
+    /// `record <result-file> <trace-file>` or `check <trace-file> [--freecells <n>]`.
+    pub fn from_args<I>(args: I) -> Result<Self, PlanError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        let mut positional = |name: &str| args.next().ok_or_else(|| PlanError::MissingValue(name.to_string()));
+        let command = match positional("replay-diff")?.as_str() {
+            "record" => ReplayDiffCommand::Record {
+                solution: positional("record")?,
+                trace: positional("record")?,
+            },
+            "check" => ReplayDiffCommand::Check {
+                trace: positional("check")?,
+                freecells: None,
+            },
+            other => return Err(PlanError::UnknownArgument(other.to_string())),
+        };
+        match command {
+            ReplayDiffCommand::Check { trace, .. } => {
+                let mut freecells = None;
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--freecells" => {
+                            let raw = args.next().ok_or_else(|| PlanError::MissingValue("--freecells".to_string()))?;
+                            freecells = Some(raw.parse().map_err(|_| PlanError::InvalidValue {
+                                flag: "--freecells".to_string(),
+                                value: raw,
+                            })?);
+                        }
+                        _ => return Err(PlanError::UnknownArgument(arg)),
+                    }
+                }
+                Ok(ReplayDiffCommand::Check { trace, freecells })
+            }
+            record => match args.next() {
+                Some(arg) => Err(PlanError::UnknownArgument(arg)),
+                None => Ok(record),
+            },
+        }
+    }
+}
+
+/// Runs the command. A divergence is reported as an error so the exit code shows it.
+pub fn run(command: &ReplayDiffCommand) -> Result<(), String> {
+    match command {
+        ReplayDiffCommand::Record { solution, trace } => {
+            let text = fs::read_to_string(solution).map_err(|e| format!("Could not read {}: {}", solution, e))?;
+            let file: SolutionFile =
+                serde_json::from_str(&text).map_err(|e| format!("Could not parse {}: {}", solution, e))?;
+            let moves = file
+                .solution_moves
+                .ok_or_else(|| format!("{} holds no solution for seed {}", solution, file.seed))?;
+            let recorded = ReplayTrace::record(file.seed, 4, moves)?;
+            let json = serde_json::to_string_pretty(&recorded).expect("replay traces serialize");
+            fs::write(trace, json).map_err(|e| format!("Could not write {}: {}", trace, e))?;
+            println!("Recorded {} steps of seed {} to {}", recorded.steps.len(), recorded.seed, trace);
+            Ok(())
+        }
+        ReplayDiffCommand::Check { trace, freecells } => {
+            let text = fs::read_to_string(trace).map_err(|e| format!("Could not read {}: {}", trace, e))?;
+            let recorded: ReplayTrace =
+                serde_json::from_str(&text).map_err(|e| format!("Could not parse {}: {}", trace, e))?;
+            if !ENGINE_INFO.is_compatible_with(&recorded.engine) {
+                return Err(format!(
+                    "{} was recorded with engine {}, whose deals differ from this build's",
+                    trace, recorded.engine.version
+                ));
+            }
+            let replayed = ReplayTrace::record(
+                recorded.seed,
+                freecells.unwrap_or(recorded.freecells),
+                recorded.moves.clone(),
+            )?;
+            match recorded.first_divergence(&replayed) {
+                Some(divergence) => Err(divergence.to_string()),
+                None => {
+                    println!("No divergence in {} steps of seed {}", recorded.steps.len(), recorded.seed);
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parks the top cards of columns 0-3 in all four freecells.
+    fn fill_freecells() -> Vec<Move> {
+        (0..4).map(|i| Move::tableau_to_freecell(i, i).unwrap()).collect()
+    }
+
+    #[test]
+    fn identical_replays_do_not_diverge() {
+        let a = ReplayTrace::record(1, 4, fill_freecells()).unwrap();
+        let b = ReplayTrace::record(1, 4, fill_freecells()).unwrap();
+        assert_eq!(a.steps.len(), 5);
+        assert_eq!(a.first_divergence(&b), None);
+    }
+
+    #[test]
+    fn fewer_freecells_diverge_at_the_first_blocked_cell() {
+        let recorded = ReplayTrace::record(1, 4, fill_freecells()).unwrap();
+        let replayed = ReplayTrace::record(1, 3, fill_freecells()).unwrap();
+
+        let divergence = recorded.first_divergence(&replayed).unwrap();
+        assert_eq!(divergence.step, 4);
+        assert_eq!(divergence.played, Some(Move::tableau_to_freecell(3, 3).unwrap()));
+        assert!(matches!(divergence.actual, Some(StepOutcome::Rejected { .. })));
+    }
+
+    #[test]
+    fn fingerprints_follow_positions_not_history() {
+        let mut game = generate_deal(1).unwrap();
+        let start = game.fingerprint();
+        let m = Move::tableau_to_freecell(0, 0).unwrap();
+        game.execute_move(&m).unwrap();
+        assert_ne!(game.fingerprint(), start);
+        game.undo_move(&m);
+        assert_eq!(game.fingerprint(), start);
+    }
+
+    #[test]
+    fn parses_both_subcommands() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+        assert_eq!(
+            ReplayDiffCommand::from_args(args("check t.json --freecells 2")).unwrap(),
+            ReplayDiffCommand::Check { trace: "t.json".to_string(), freecells: Some(2) }
+        );
+        assert!(matches!(
+            ReplayDiffCommand::from_args(args("record 7.json")),
+            Err(PlanError::MissingValue(_))
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+//! Benchmark results streamed to an NDJSON file, one line per seed as it completes.
+//!
+//! The summary JSON is rewritten as a whole, so a run killed between saves loses the
+//! seeds finished since the last one, and a monitor cannot follow it without re-reading
+//! the file. With a stream, every result is appended the moment it arrives: `tail -f`
+//! shows progress, and a resumed run picks up every seed that made it to disk.
+//!
+//! A new stream starts with a header line holding the engine and the plan, so a stream
+//! written by an incompatible engine is ignored on resume. Every following line is one
+//! seed's result in the summary's format. A line cut short by a crash is skipped.
+
+use crate::benchmark_plan::BenchmarkPlan;
+use freecell_game_engine::{EngineInfo, ENGINE_INFO};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+
+/// The first line of a stream.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StreamHeader {
+    pub engine: EngineInfo,
+    pub plan: BenchmarkPlan,
+}
+
+/// An NDJSON file open for appending.
+pub struct ResultStream {
+    file: File,
+}
+
+impl ResultStream {
+    /// Opens `path` for appending, creating it with a [`StreamHeader`] if it is new or empty.
+    pub fn open(path: &str, plan: &BenchmarkPlan) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut stream = ResultStream { file };
+        if stream.file.metadata()?.len() == 0 {
+            stream.append(&StreamHeader {
+                engine: ENGINE_INFO,
+                plan: plan.clone(),
+            })?;
+        }
+        Ok(stream)
+    }
+
+    /// Writes `record` as one line. The line goes out in a single write, so readers never
+    /// see half of it unless the process dies mid-write.
+    pub fn append<T: Serialize>(&mut self, record: &T) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record).map_err(io::Error::other)?;
+        line.push(b'\n');
+        self.file.write_all(&line)
+    }
+}
+
+/// Reads every result line of the stream at `path`. A missing file, or one written by an
+/// incompatible engine, yields nothing; lines that do not parse as `T` are skipped.
+pub fn read_results<T: DeserializeOwned>(path: &str) -> Vec<T> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut lines = contents.lines();
+    if let Some(header) = lines.next().and_then(|line| serde_json::from_str::<StreamHeader>(line).ok()) {
+        if !ENGINE_INFO.is_compatible_with(&header.engine) {
+            println!("Ignoring results in {}: written by incompatible engine {}", path, header.engine.version);
+            return Vec::new();
+        }
+    }
+    lines.filter_map(|line| serde_json::from_str(line).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Record {
+        seed: u64,
+        solved: bool,
+    }
+
+    #[test]
+    fn appends_after_a_header_and_skips_torn_lines() {
+        let path = std::env::temp_dir().join(format!("solver-stream-test-{}.ndjson", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let _ = fs::remove_file(&path);
+        let plan = BenchmarkPlan::default();
+
+        let mut stream = ResultStream::open(&path, &plan).unwrap();
+        stream.append(&Record { seed: 1, solved: true }).unwrap();
+        drop(stream);
+        // Reopening appends without a second header.
+        let mut stream = ResultStream::open(&path, &plan).unwrap();
+        stream.append(&Record { seed: 2, solved: false }).unwrap();
+        drop(stream);
+        // A crash mid-line leaves a fragment behind.
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"seed\": 3, \"sol").unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let records: Vec<Record> = read_results(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(contents.lines().filter(|line| line.contains("\"engine\"")).count(), 1);
+        assert_eq!(
+            records,
+            [Record { seed: 1, solved: true }, Record { seed: 2, solved: false }]
+        );
+        assert!(read_results::<Record>(&path).is_empty());
+    }
+}
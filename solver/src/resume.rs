@@ -0,0 +1,163 @@
+//! `solver resume <code>`: pick up a position a benchmark run gave up on.
+//!
+//! With `--export-unsolved K`, every seed that times out records the K most promising
+//! positions its last tier searched, as [`UnsolvedPosition`]s holding a position code
+//! ([`position_code`]). The code is one word, so it can be shared as is: pasted into
+//! another tool that reads the import layout, worked on by hand, or handed back to
+//! `solver resume` for a longer solve. A resumed solve that fails again can export its
+//! own most promising positions, so a hard deal can be chipped away at in turns.
+
+use crate::benchmark_plan::PlanError;
+use crate::bench;
+use crate::solve;
+use freecell_game_engine::display::{SolutionPhases, TerminalBoard};
+use freecell_game_engine::import::{import_position_code, position_code};
+use freecell_game_engine::Variant;
+use serde::{Deserialize, Serialize};
+
+/// A position from an unsolved search, as recorded in the results.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnsolvedPosition {
+    /// Column inversions left; lower is closer to solved.
+    pub score: i32,
+    pub foundation_cards: usize,
+    /// Moves from the deal to this position.
+    pub depth: usize,
+    /// The position, for [`import_position_code`] or `solver resume`.
+    pub code: String,
+}
+
+impl From<&solve::Snapshot> for UnsolvedPosition {
+    fn from(snapshot: &solve::Snapshot) -> Self {
+        UnsolvedPosition {
+            score: snapshot.score,
+            foundation_cards: snapshot.foundation_cards,
+            depth: snapshot.path.len(),
+            code: position_code(&snapshot.state),
+        }
+    }
+}
+
+/// Settings for a resumed solve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumeCommand {
+    pub code: String,
+    pub timeout_secs: u64,
+    /// Print this many positions to continue from if the solve fails again.
+    pub unsolved_positions: usize,
+}
+
+impl ResumeCommand {
+    /// Parses the arguments following `resume`: a position code, then optional
+    /// `--timeout <secs>` and `--export-unsolved <count>`.
+    pub fn from_args<I>(args: I) -> Result<Self, PlanError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut code = None;
+        let mut command = ResumeCommand {
+            code: String::new(),
+            timeout_secs: 60,
+            unsolved_positions: 0,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut number = |flag: &str| {
+                let raw = args.next().ok_or_else(|| PlanError::MissingValue(flag.to_string()))?;
+                raw.parse::<u64>()
+                    .map_err(|_| PlanError::InvalidValue { flag: flag.to_string(), value: raw })
+            };
+            match arg.as_str() {
+                "--timeout" => {
+                    command.timeout_secs = number("--timeout")?;
+                    if command.timeout_secs == 0 {
+                        return Err(PlanError::InvalidTimeout(0));
+                    }
+                }
+                "--export-unsolved" => command.unsolved_positions = number("--export-unsolved")? as usize,
+                flag if flag.starts_with("--") => return Err(PlanError::UnknownArgument(arg)),
+                _ if code.is_none() => code = Some(arg),
+                _ => return Err(PlanError::UnknownArgument(arg)),
+            }
+        }
+        command.code = code.ok_or_else(|| PlanError::MissingValue("resume".to_string()))?;
+        Ok(command)
+    }
+}
+
+/// Imports the code and solves from it. A code that does not describe a complete
+/// position is an error; so is a solve that fails, so the exit code shows it.
+pub fn run(command: &ResumeCommand) -> Result<(), String> {
+    let import = import_position_code(&command.code, Variant::Standard)
+        .map_err(|e| format!("Could not import position code: {}", e))?;
+    if let Some(warning) = import.warnings.first() {
+        return Err(format!("Incomplete position code: {}", warning));
+    }
+    let state = import.state;
+    println!("{}", TerminalBoard::new(&state));
+    println!("Solving for up to {}s...", command.timeout_secs);
+
+    let result = bench::harness_with_snapshots(
+        state.clone(),
+        command.timeout_secs,
+        solve::MoveOrdering::default(),
+        command.unsolved_positions,
+        false,
+    );
+    let elapsed_ms = result.execution_time.as_millis();
+    match result.solution_moves {
+        Some(moves) if result.solved => {
+            println!("\nSolved in {}ms, {} moves:", elapsed_ms, moves.len());
+            match SolutionPhases::new(&state, &moves) {
+                Ok(phases) => println!("{}", phases),
+                Err(e) => return Err(format!("Solution does not replay: {}", e)),
+            }
+            Ok(())
+        }
+        _ => {
+            for position in result.snapshots.iter().map(UnsolvedPosition::from) {
+                println!(
+                    "score {:>3}, {:>2} home, {:>3} moves in: {}",
+                    position.score, position.foundation_cards, position.depth, position.code
+                );
+            }
+            Err(format!("No solution found within {}s", command.timeout_secs))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::generation::generate_deal;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_a_code_and_options() {
+        let command = ResumeCommand::from_args(args(&["f:/fc:-/:KS", "--timeout", "5", "--export-unsolved", "3"])).unwrap();
+        assert_eq!(
+            command,
+            ResumeCommand { code: "f:/fc:-/:KS".to_string(), timeout_secs: 5, unsolved_positions: 3 }
+        );
+        assert_eq!(ResumeCommand::from_args(args(&[])), Err(PlanError::MissingValue("resume".to_string())));
+        assert_eq!(ResumeCommand::from_args(args(&["x", "--timeout", "0"])), Err(PlanError::InvalidTimeout(0)));
+        assert!(run(&ResumeCommand::from_args(args(&["f:/fc:-/:KS"])).unwrap()).is_err());
+    }
+
+    #[test]
+    fn snapshots_become_codes_that_import_back() {
+        let mut state = generate_deal(3).unwrap();
+        let path = vec![state.get_available_moves()[0]];
+        state.execute_move(&path[0]).unwrap();
+        let snapshot = solve::Snapshot { score: 9, foundation_cards: 0, path, state: state.clone() };
+
+        let position = UnsolvedPosition::from(&snapshot);
+        assert_eq!(position.depth, 1);
+        let import = import_position_code(&position.code, Variant::Standard).unwrap();
+        assert_eq!(import.warnings, []);
+        assert_eq!(import.state, state);
+    }
+}
@@ -0,0 +1,119 @@
+//! Sharing a wall-clock budget for a whole benchmark run out among its seeds.
+//!
+//! With fixed timeout tiers, every seed climbs all the tiers before the next one starts,
+//! so a run's length depends on how many seeds are hard, and picking the tiers means
+//! either wasting time on seeds that would have been solved sooner or starving the hard
+//! ones. With `--total-budget`, the run goes in passes instead. The first pass gives
+//! every seed the first tier, each later pass gives the seeds still unsolved the next
+//! one, and a last pass shares whatever is left among the seeds that beat every tier.
+//!
+//! Each seed's cap is taken as it is dealt out: its tier, but no more than its share of
+//! the time left between the seeds still to come in the pass. Easy seeds that finish
+//! early leave their time to the seeds after them, and a pass that would overrun the
+//! budget shortens its caps instead, down to a second a seed. A seed whose share is no
+//! longer than the cap it was last given is skipped, since it would only repeat the same
+//! search.
+
+use freecell_game_engine::GameState;
+use std::time::{Duration, Instant};
+
+/// The time left for a run that started with `total_secs` on `parallelism` threads.
+#[derive(Debug, Clone, Copy)]
+pub struct RunBudget {
+    start: Instant,
+    total: Duration,
+    parallelism: usize,
+}
+
+impl RunBudget {
+    pub fn start(total_secs: u64, parallelism: usize) -> Self {
+        RunBudget { start: Instant::now(), total: Duration::from_secs(total_secs), parallelism }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.total.saturating_sub(self.start.elapsed())
+    }
+
+    /// The cap for the next of `seeds_left` seeds in a pass at `tier` (`None` for the
+    /// last pass), at least a second, or `None` once less than a second is left.
+    pub fn cap(&self, seeds_left: usize, tier: Option<u64>) -> Option<u64> {
+        share(self.remaining(), self.parallelism, seeds_left, tier)
+    }
+
+    /// The seeds of one pass, each with its cap, as the workers take them. `deals` holds
+    /// each seed with the cap it was last given (0 for none). The pass ends early once the
+    /// budget is spent.
+    pub fn pass<'a>(
+        &'a self,
+        deals: &'a [(u64, GameState, u64)],
+        tier: Option<u64>,
+    ) -> impl Iterator<Item = (u64, GameState, u64)> + Send + 'a {
+        let mut next = 0;
+        std::iter::from_fn(move || loop {
+            let index = next;
+            next += 1;
+            let (seed, game_state, last_cap) = deals.get(index)?;
+            let cap = self.cap(deals.len() - index, tier)?;
+            if cap > *last_cap {
+                return Some((*seed, game_state.clone(), cap));
+            }
+        })
+    }
+}
+
+/// A seed's share of `remaining`, split between `seeds_left` seeds on `parallelism`
+/// threads, capped at `tier` and at `remaining` itself. Never under a second, the
+/// shortest timeout a solve takes, so a budget shorter than a second a seed runs the
+/// seeds it can in turn instead of none.
+fn share(remaining: Duration, parallelism: usize, seeds_left: usize, tier: Option<u64>) -> Option<u64> {
+    let remaining = remaining.as_secs();
+    if remaining == 0 {
+        return None;
+    }
+    let secs = (remaining * parallelism as u64 / seeds_left.max(1) as u64).clamp(1, remaining);
+    Some(tier.map_or(secs, |tier| secs.min(tier.max(1))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::generation::generate_deal;
+
+    #[test]
+    fn seeds_get_their_share_of_the_time_left() {
+        let hours = |h: u64| Duration::from_secs(h * 3600);
+        // 8h on 4 threads is 3.6s a seed over 32000 seeds: under a 10s first tier.
+        assert_eq!(share(hours(8), 4, 32000, Some(10)), Some(3));
+        assert_eq!(share(hours(8), 4, 100, Some(10)), Some(10));
+        assert_eq!(share(hours(8), 4, 100, None), Some(1152));
+        // Fewer seeds than threads cannot run past the end of the budget.
+        assert_eq!(share(Duration::from_secs(60), 4, 1, None), Some(60));
+        assert_eq!(share(Duration::from_millis(999), 1, 10, Some(10)), None);
+    }
+
+    #[test]
+    fn more_seeds_than_budget_seconds_still_run() {
+        // 8h on one thread is under a second a seed over 32000 seeds.
+        assert_eq!(share(Duration::from_secs(8 * 3600), 1, 32000, Some(10)), Some(1));
+        assert_eq!(share(Duration::from_secs(5), 1, 10, Some(10)), Some(1));
+
+        let game_state = generate_deal(1).unwrap();
+        let deals: Vec<_> = (1..=10).map(|seed| (seed, game_state.clone(), 0)).collect();
+        let budget = RunBudget::start(5, 1);
+        let caps: Vec<u64> = budget.pass(&deals, Some(10)).map(|(_, _, cap)| cap).collect();
+        // Every seed is offered a cap; none of the time was used, so the last get more.
+        assert_eq!(caps.len(), 10);
+        assert_eq!(caps[..8], [1; 8]);
+    }
+
+    #[test]
+    fn passes_skip_seeds_their_share_would_not_help() {
+        let game_state = generate_deal(1).unwrap();
+        let deals = vec![(1, game_state.clone(), 0), (2, game_state.clone(), 50), (3, game_state, 0)];
+        let budget = RunBudget::start(100, 1);
+        let pass: Vec<(u64, u64)> = budget.pass(&deals, None).map(|(seed, _, cap)| (seed, cap)).collect();
+        // Seed 2 is offered at most 100s / 2, no more than it had already.
+        assert_eq!(pass.iter().map(|&(seed, _)| seed).collect::<Vec<_>>(), vec![1, 3]);
+        assert!(pass[0].1 >= 33 && pass[1].1 > pass[0].1);
+    }
+}
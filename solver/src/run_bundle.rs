@@ -0,0 +1,274 @@
+//! `solver bundle`: pack a finished benchmark run into one `.tar.gz`.
+//!
+//! A run leaves its summary (which records the plan it ran under), one detailed result
+//! per seed and whatever logs were captured from its output scattered over the working
+//! directory. A bundle gathers them into a single archive that can be attached to an
+//! issue, with a `manifest.json` at its root listing every file, its role and its size,
+//! and the engine that wrote it.
+//!
+//! Inside the archive the summary is `summary.json`, the detailed results are
+//! `results/<seed>.json`, logs are under `logs/` and a plan file, if given, under `plan/`.
+//! Unpacked, the summary and results directory can be passed straight back to a
+//! benchmark run with `--summary` and `--results-dir`, for example to add the B side of an
+//! A/B comparison.
+
+use crate::benchmark_plan::{BenchmarkPlan, PlanError};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use freecell_game_engine::{EngineInfo, ENGINE_INFO};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::Path;
+
+/// Name of the manifest at the root of every bundle.
+pub const MANIFEST_NAME: &str = "manifest.json";
+
+/// Where the bundle is written when `--output` is not given.
+pub const DEFAULT_BUNDLE_PATH: &str = "benchmark_run.tar.gz";
+
+/// What `solver bundle` was asked to pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleCommand {
+    pub summary_file: String,
+    pub results_dir: String,
+    /// A plan file the run was started with, if any.
+    pub plan_file: Option<String>,
+    pub log_files: Vec<String>,
+    pub output: String,
+}
+
+impl BundleCommand {
+    /// Parses the arguments following `bundle`: `--summary`, `--results-dir`, `--plan`,
+    /// `--log` (repeatable) and `--output`. The summary and results directory default to
+    /// those of [`BenchmarkPlan::default`].
+    pub fn from_args<I>(args: I) -> Result<Self, PlanError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let defaults = BenchmarkPlan::default().output;
+        let mut command = BundleCommand {
+            summary_file: defaults.summary_file,
+            results_dir: defaults.results_dir,
+            plan_file: None,
+            log_files: Vec::new(),
+            output: DEFAULT_BUNDLE_PATH.to_string(),
+        };
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| PlanError::MissingValue(flag.clone()));
+            match flag.as_str() {
+                "--summary" => command.summary_file = value()?,
+                "--results-dir" => command.results_dir = value()?,
+                "--plan" => command.plan_file = Some(value()?),
+                "--log" => command.log_files.push(value()?),
+                "--output" => command.output = value()?,
+                _ => return Err(PlanError::UnknownArgument(flag)),
+            }
+        }
+        Ok(command)
+    }
+}
+
+/// What a bundled file is.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileRole {
+    Plan,
+    Summary,
+    Result,
+    Log,
+}
+
+/// One file in a bundle.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BundledFile {
+    /// Path inside the archive.
+    pub path: String,
+    /// Path the file was read from.
+    pub source: String,
+    pub role: FileRole,
+    pub bytes: u64,
+}
+
+/// The `manifest.json` at the root of a bundle.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundleManifest {
+    pub created: String,
+    pub engine: EngineInfo,
+    /// The summary's aggregate counts, copied so a bundle can be told apart at a glance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<serde_json::Value>,
+    pub files: Vec<BundledFile>,
+}
+
+/// Writes the bundle and returns its manifest.
+///
+/// # Errors
+///
+/// Fails if the summary is missing, a named plan or log file cannot be read, or the
+/// archive cannot be written. A missing results directory is not an error: a run whose
+/// seeds all failed writes none.
+pub fn write_bundle(command: &BundleCommand) -> Result<BundleManifest, String> {
+    let mut files = Vec::new();
+    if let Some(plan) = &command.plan_file {
+        files.push(bundled_file(plan, "plan", FileRole::Plan)?);
+    }
+    let mut summary = bundled_file(&command.summary_file, "", FileRole::Summary)?;
+    summary.path = "summary.json".to_string();
+    files.push(summary);
+    let summary_text = fs::read_to_string(&command.summary_file)
+        .map_err(|e| format!("Could not read {}: {}", command.summary_file, e))?;
+    files.extend(result_files(&command.results_dir)?);
+    for log in &command.log_files {
+        files.push(bundled_file(log, "logs", FileRole::Log)?);
+    }
+
+    let manifest = BundleManifest {
+        created: chrono::Utc::now().to_rfc3339(),
+        engine: ENGINE_INFO,
+        summary: serde_json::from_str::<serde_json::Value>(&summary_text)
+            .ok()
+            .and_then(|results| results.get("summary").cloned()),
+        files,
+    };
+
+    let output = File::create(&command.output).map_err(|e| format!("Could not create {}: {}", command.output, e))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(output, Compression::default()));
+    let write_error = |e: std::io::Error| format!("Could not write {}: {}", command.output, e);
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    archive
+        .append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())
+        .map_err(write_error)?;
+    for file in &manifest.files {
+        archive.append_path_with_name(&file.source, &file.path).map_err(write_error)?;
+    }
+    archive.into_inner().and_then(|encoder| encoder.finish()).map_err(write_error)?;
+    Ok(manifest)
+}
+
+/// Runs the command and reports what was packed.
+pub fn run(command: &BundleCommand) -> Result<(), String> {
+    let manifest = write_bundle(command)?;
+    let results = manifest.files.iter().filter(|file| file.role == FileRole::Result).count();
+    let bytes: u64 = manifest.files.iter().map(|file| file.bytes).sum();
+    println!(
+        "Bundled {} files ({} per-seed results, {} bytes before compression) into {}",
+        manifest.files.len(),
+        results,
+        bytes,
+        command.output
+    );
+    Ok(())
+}
+
+/// Describes `source` as a file stored under `dir` in the archive, keeping its name.
+fn bundled_file(source: &str, dir: &str, role: FileRole) -> Result<BundledFile, String> {
+    let metadata = fs::metadata(source).map_err(|e| format!("Could not read {}: {}", source, e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", source));
+    }
+    let name = Path::new(source)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| source.to_string());
+    Ok(BundledFile {
+        path: if dir.is_empty() { name } else { format!("{}/{}", dir, name) },
+        source: source.to_string(),
+        role,
+        bytes: metadata.len(),
+    })
+}
+
+/// Every `.json` file in `results_dir`, in seed order where the names are seeds.
+fn result_files(results_dir: &str) -> Result<Vec<BundledFile>, String> {
+    let entries = match fs::read_dir(results_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Could not read {}: {}", results_dir, e)),
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort_by_key(|path| {
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+        (stem.parse::<u64>().unwrap_or(u64::MAX), stem)
+    });
+    paths
+        .iter()
+        .map(|path| bundled_file(&path.to_string_lossy(), "results", FileRole::Result))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_repeated_logs_and_defaults() {
+        let command = BundleCommand::from_args(args(&["--log", "a.log", "--log", "b.log", "--output", "x.tar.gz"])).unwrap();
+        assert_eq!(command.summary_file, "benchmark_summary.json");
+        assert_eq!(command.results_dir, "results");
+        assert_eq!(command.log_files, vec!["a.log", "b.log"]);
+        assert_eq!(command.output, "x.tar.gz");
+        assert_eq!(
+            BundleCommand::from_args(args(&["--log"])),
+            Err(PlanError::MissingValue("--log".to_string()))
+        );
+    }
+
+    #[test]
+    fn bundle_holds_manifest_summary_results_and_logs() {
+        let dir = std::env::temp_dir().join(format!("solver-bundle-test-{}", std::process::id()));
+        let results = dir.join("results");
+        fs::create_dir_all(&results).unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+        fs::write(path("summary.json"), r#"{"results": [], "summary": {"total_games": 2}}"#).unwrap();
+        fs::write(results.join("10.json"), "{}").unwrap();
+        fs::write(results.join("9.json"), "{}").unwrap();
+        fs::write(results.join("notes.txt"), "skipped").unwrap();
+        fs::write(path("run.log"), "Progress: 2 / 2").unwrap();
+
+        let command = BundleCommand {
+            summary_file: path("summary.json"),
+            results_dir: results.to_string_lossy().into_owned(),
+            plan_file: None,
+            log_files: vec![path("run.log")],
+            output: path("run.tar.gz"),
+        };
+        let manifest = write_bundle(&command).unwrap();
+        assert_eq!(manifest.summary.unwrap()["total_games"], 2);
+
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(&command.output).unwrap()));
+        let mut names = Vec::new();
+        let mut bundled_manifest = String::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().into_owned();
+            if name == MANIFEST_NAME {
+                entry.read_to_string(&mut bundled_manifest).unwrap();
+            }
+            names.push(name);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            names,
+            ["manifest.json", "summary.json", "results/9.json", "results/10.json", "logs/run.log"]
+        );
+        let reread: BundleManifest = serde_json::from_str(&bundled_manifest).unwrap();
+        assert_eq!(reread.files, manifest.files);
+        assert_eq!(reread.files[3].role, FileRole::Log);
+        assert_eq!(reread.files[3].bytes, 15);
+    }
+}
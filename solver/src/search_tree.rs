@@ -0,0 +1,309 @@
+//! `solver tree <seed|board-file>`: dump the search tree for a small position.
+//!
+//! The real solver is multi-threaded and explores millions of states, so its tree cannot
+//! be drawn. This module replays the same search (strat13's move ordering, heuristic and
+//! canonical-state duplicate detection) on a single thread under tiny depth and node
+//! limits, and records every node it visits. The result renders as Graphviz DOT or as a
+//! nested JSON hierarchy, which is useful for teaching material and for seeing why the
+//! move ordering struggles on a hand-built position.
+
+use crate::benchmark_plan::PlanError;
+use crate::packed_state::PackedGameState;
+use crate::solve::{get_tableau_column, sort_moves_by_lowest_needed_cards};
+use freecell_game_engine::game_state::heuristics::score_state;
+use freecell_game_engine::generation::generate_deal;
+use freecell_game_engine::import::import_board;
+use freecell_game_engine::r#move::Move;
+use freecell_game_engine::{GameState, Variant};
+use fxhash::FxHashSet;
+use serde::Serialize;
+use std::fmt::Write;
+use std::fs;
+
+/// Output format for a dumped tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeFormat {
+    Dot,
+    Json,
+}
+
+/// Settings for `solver tree`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeOptions {
+    /// A deal number, or the path of a board file in the format `import_board` accepts.
+    pub target: String,
+    pub format: TreeFormat,
+    pub limits: TreeLimits,
+}
+
+impl TreeOptions {
+    /// Parses the arguments following `tree`: the target, then optional
+    /// `--format dot|json`, `--depth <n>` and `--nodes <n>`.
+    pub fn from_args<I>(args: I) -> Result<Self, PlanError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut target = None;
+        let mut format = TreeFormat::Dot;
+        let mut limits = TreeLimits::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| args.next().ok_or_else(|| PlanError::MissingValue(flag.to_string()));
+            let invalid = |flag: &str, raw: String| PlanError::InvalidValue { flag: flag.to_string(), value: raw };
+            match arg.as_str() {
+                "--format" => {
+                    let raw = value("--format")?;
+                    format = match raw.as_str() {
+                        "dot" => TreeFormat::Dot,
+                        "json" => TreeFormat::Json,
+                        _ => return Err(invalid("--format", raw)),
+                    };
+                }
+                "--depth" => {
+                    let raw = value("--depth")?;
+                    limits.max_depth = raw.parse().map_err(|_| invalid("--depth", raw))?;
+                }
+                "--nodes" => {
+                    let raw = value("--nodes")?;
+                    limits.max_nodes = raw.parse().ok().filter(|&n| n > 0).ok_or_else(|| invalid("--nodes", raw))?;
+                }
+                flag if flag.starts_with("--") => return Err(PlanError::UnknownArgument(arg)),
+                _ if target.is_none() => target = Some(arg),
+                _ => return Err(PlanError::UnknownArgument(arg)),
+            }
+        }
+        let target = target.ok_or_else(|| PlanError::MissingValue("tree".to_string()))?;
+        Ok(TreeOptions { target, format, limits })
+    }
+}
+
+/// How much of the search to record. Both limits are deliberately small by default:
+/// the output is meant to be read by a person.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeLimits {
+    /// Moves from the root beyond which nodes are recorded but not expanded.
+    pub max_depth: usize,
+    /// Total nodes to record, root included.
+    pub max_nodes: usize,
+}
+
+impl Default for TreeLimits {
+    fn default() -> Self {
+        TreeLimits {
+            max_depth: 4,
+            max_nodes: 200,
+        }
+    }
+}
+
+/// Why the search stopped at a node, or `Expanded` if it went on to the children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeOutcome {
+    Expanded,
+    Won,
+    /// The canonical state was already visited elsewhere in the tree.
+    Duplicate,
+    /// No legal moves.
+    DeadEnd,
+    DepthLimit,
+    /// The node budget ran out before this node could be expanded.
+    NodeLimit,
+}
+
+/// One visited position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    pub parent: Option<usize>,
+    /// The move that led here from the parent; `None` for the root.
+    pub via: Option<Move>,
+    pub depth: usize,
+    /// `score_state` for this position; lower is closer to solved.
+    pub score: i32,
+    pub outcome: NodeOutcome,
+}
+
+/// The recorded search, in visiting order. Node 0 is the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchTree {
+    pub nodes: Vec<TreeNode>,
+}
+
+/// Nested form of a node for the JSON export.
+#[derive(Serialize)]
+struct JsonNode {
+    id: usize,
+    #[serde(rename = "move", skip_serializing_if = "Option::is_none")]
+    via: Option<String>,
+    score: i32,
+    outcome: NodeOutcome,
+    children: Vec<JsonNode>,
+}
+
+impl SearchTree {
+    /// Explores `game` depth-first in the solver's move order, recording nodes until
+    /// `limits` are reached.
+    pub fn explore(game: &GameState, limits: TreeLimits) -> Self {
+        let mut tree = SearchTree { nodes: Vec::new() };
+        let mut seen = FxHashSet::default();
+        tree.visit(game.clone(), None, None, 0, None, limits, &mut seen);
+        tree
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit(
+        &mut self,
+        mut game: GameState,
+        parent: Option<usize>,
+        via: Option<Move>,
+        depth: usize,
+        previous_tableau_column: Option<u8>,
+        limits: TreeLimits,
+        seen: &mut FxHashSet<PackedGameState>,
+    ) {
+        let id = self.nodes.len();
+        let outcome = if game.is_won().unwrap_or(false) {
+            NodeOutcome::Won
+        } else if !seen.insert(PackedGameState::from_game_state_canonical(&game)) {
+            NodeOutcome::Duplicate
+        } else if depth >= limits.max_depth {
+            NodeOutcome::DepthLimit
+        } else {
+            NodeOutcome::Expanded
+        };
+        self.nodes.push(TreeNode {
+            parent,
+            via,
+            depth,
+            score: score_state(&game),
+            outcome,
+        });
+        if outcome != NodeOutcome::Expanded {
+            return;
+        }
+
+        let moves = sort_moves_by_lowest_needed_cards(game.get_available_moves(), &game, previous_tableau_column);
+        if moves.is_empty() {
+            self.nodes[id].outcome = NodeOutcome::DeadEnd;
+            return;
+        }
+        for m in moves {
+            if self.nodes.len() >= limits.max_nodes {
+                // Only a node with no recorded children counts as cut off.
+                if self.nodes.len() == id + 1 {
+                    self.nodes[id].outcome = NodeOutcome::NodeLimit;
+                }
+                return;
+            }
+            if game.execute_move(&m).is_ok() {
+                self.visit(game.clone(), Some(id), Some(m), depth + 1, get_tableau_column(&m.source), limits, seen);
+                game.undo_move(&m);
+            }
+        }
+    }
+
+    /// Renders the tree as a Graphviz digraph. Nodes show their heuristic score and
+    /// edges the move taken; nodes that ended the search are shaded by outcome.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph search {\n    node [shape=box, fontname=\"monospace\"];\n");
+        for (id, node) in self.nodes.iter().enumerate() {
+            let style = match node.outcome {
+                NodeOutcome::Expanded => "",
+                NodeOutcome::Won => ", style=filled, fillcolor=palegreen",
+                NodeOutcome::Duplicate => ", style=dashed",
+                NodeOutcome::DeadEnd => ", style=filled, fillcolor=lightpink",
+                NodeOutcome::DepthLimit | NodeOutcome::NodeLimit => ", style=filled, fillcolor=lightgray",
+            };
+            let _ = writeln!(dot, "    n{} [label=\"#{} score {}\"{}];", id, id, node.score, style);
+            if let (Some(parent), Some(via)) = (node.parent, node.via) {
+                let _ = writeln!(dot, "    n{} -> n{} [label=\"{}\"];", parent, id, via);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the tree as nested JSON objects with `id`, `move`, `score`, `outcome`
+    /// and `children`.
+    pub fn to_json(&self) -> String {
+        let mut children = vec![Vec::new(); self.nodes.len()];
+        for (id, node) in self.nodes.iter().enumerate() {
+            if let Some(parent) = node.parent {
+                children[parent].push(id);
+            }
+        }
+        let root = self.json_node(0, &children);
+        serde_json::to_string_pretty(&root).expect("search tree serializes")
+    }
+
+    fn json_node(&self, id: usize, children: &[Vec<usize>]) -> JsonNode {
+        let node = &self.nodes[id];
+        JsonNode {
+            id,
+            via: node.via.map(|m| m.to_string()),
+            score: node.score,
+            outcome: node.outcome,
+            children: children[id].iter().map(|&child| self.json_node(child, children)).collect(),
+        }
+    }
+}
+
+/// Loads the target position, explores it and prints the tree to stdout.
+pub fn run(options: &TreeOptions) -> Result<(), String> {
+    let game = load_target(&options.target)?;
+    let tree = SearchTree::explore(&game, options.limits);
+    match options.format {
+        TreeFormat::Dot => print!("{}", tree.to_dot()),
+        TreeFormat::Json => println!("{}", tree.to_json()),
+    }
+    Ok(())
+}
+
+fn load_target(target: &str) -> Result<GameState, String> {
+    if let Ok(seed) = target.parse::<u64>() {
+        return generate_deal(seed).map_err(|e| format!("Could not deal {}: {}", seed, e));
+    }
+    let text = fs::read_to_string(target).map_err(|e| format!("Could not read {}: {}", target, e))?;
+    let import = import_board(&text, Variant::Standard).map_err(|e| format!("Could not import board: {}", e))?;
+    for warning in &import.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(import.state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_bound_the_recorded_tree() {
+        let game = generate_deal(1).unwrap();
+        let limits = TreeLimits {
+            max_depth: 2,
+            max_nodes: 25,
+        };
+        let tree = SearchTree::explore(&game, limits);
+
+        assert!(tree.nodes.len() <= limits.max_nodes);
+        assert_eq!(tree.nodes[0].parent, None);
+        assert!(tree.nodes.iter().all(|n| n.depth <= limits.max_depth));
+        for node in &tree.nodes[1..] {
+            let parent = &tree.nodes[node.parent.unwrap()];
+            assert_eq!(parent.depth + 1, node.depth);
+            assert_eq!(parent.outcome, NodeOutcome::Expanded);
+        }
+    }
+
+    #[test]
+    fn exports_mention_every_node() {
+        let game = generate_deal(1).unwrap();
+        let tree = SearchTree::explore(&game, TreeLimits { max_depth: 1, max_nodes: 5 });
+
+        let dot = tree.to_dot();
+        assert_eq!(dot.matches(" -> ").count(), tree.nodes.len() - 1);
+
+        let json: serde_json::Value = serde_json::from_str(&tree.to_json()).unwrap();
+        assert_eq!(json["id"], 0);
+        assert_eq!(json["children"].as_array().unwrap().len(), tree.nodes.len() - 1);
+    }
+}
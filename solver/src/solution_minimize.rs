@@ -0,0 +1,155 @@
+//! Shortening a solution by re-solving short stretches of it.
+//!
+//! The search stops at the first solution it finds, and that solution usually wanders:
+//! a card goes to a freecell and straight back, a run is moved one way and then another.
+//! [`shorten`] walks the solution from the end towards the start and, at each position,
+//! runs a small [`bounded_search`] for a quicker way to any of the positions the solution
+//! reaches within the next `window` moves. When one is found, the moves in between are
+//! spliced out for the shorter line.
+//!
+//! Positions are matched exactly, not up to column order, so the moves after a splice
+//! still name the right columns. Every spliced solution is replayed from the start and
+//! kept only if it still wins, and passes repeat until one shortens nothing.
+
+use crate::bounded_search::{bounded_search, SearchBudget, SearchGoal, SearchOutcome};
+use freecell_game_engine::r#move::Move;
+use freecell_game_engine::GameState;
+use fxhash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Positions a single gap search may expand.
+const GAP_NODE_BUDGET: usize = 20_000;
+
+/// A solution's length before and after [`shorten`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shortening {
+    pub original: usize,
+    pub shortened: usize,
+}
+
+/// Shortens `solution` by replacing stretches of at most `window` moves with shorter
+/// ones, or returns it unchanged if none is found. `None` if `solution` does not win
+/// from `start`.
+pub fn shorten(start: &GameState, solution: &[Move], window: usize) -> Option<(Vec<Move>, Shortening)> {
+    let mut positions = replay(start, solution)?;
+    let mut moves = solution.to_vec();
+    // Every splice removes a move, so this bound is never reached; it only caps the
+    // work on pathological inputs.
+    for _ in 0..solution.len() {
+        let mut spliced = false;
+        // Backwards, so a splice only changes positions the pass has already left.
+        for from in (0..moves.len()).rev() {
+            let Some((to, gap)) = shortcut(&positions, from, window) else {
+                continue;
+            };
+            let candidate = [&moves[..from], &gap[..], &moves[to..]].concat();
+            if let Some(replayed) = replay(start, &candidate) {
+                moves = candidate;
+                positions = replayed;
+                spliced = true;
+            }
+        }
+        if !spliced {
+            break;
+        }
+    }
+    let shortening = Shortening { original: solution.len(), shortened: moves.len() };
+    Some((moves, shortening))
+}
+
+/// The positions `solution` passes through from `start`, `start` included, if it wins.
+fn replay(start: &GameState, solution: &[Move]) -> Option<Vec<GameState>> {
+    let mut game = start.clone();
+    let mut positions = Vec::with_capacity(solution.len() + 1);
+    positions.push(game.clone());
+    for m in solution {
+        game.execute_move(m).ok()?;
+        positions.push(game.clone());
+    }
+    game.is_won().unwrap_or(false).then_some(positions)
+}
+
+/// Looks for a line from `positions[from]` to a later `positions[to]`, at most `window`
+/// moves on, that is shorter than the `to - from` moves the solution takes. Returns `to`
+/// and the line.
+fn shortcut(positions: &[GameState], from: usize, window: usize) -> Option<(usize, Vec<Move>)> {
+    let last = (from + window).min(positions.len() - 1);
+    // Two moves or more apart, or there is nothing to save. Later positions overwrite
+    // earlier ones, so a position the solution revisits maps to its last visit.
+    let targets: FxHashMap<u64, usize> =
+        (from + 2..=last).map(|index| (positions[index].fingerprint(), index)).collect();
+    if targets.is_empty() {
+        return None;
+    }
+    let gap = Gap { positions, from, targets: &targets };
+    let budget = SearchBudget { max_depth: last - from - 1, max_nodes: GAP_NODE_BUDGET };
+    let SearchOutcome::Found(line) = bounded_search(&positions[from], budget, gap).outcome else {
+        return None;
+    };
+    let mut end = positions[from].clone();
+    line.iter().try_for_each(|m| end.execute_move(m)).ok()?;
+    Some((reached(positions, &targets, &end)?, line))
+}
+
+/// A line from `positions[from]` to one of `targets` that is shorter than the solution's.
+struct Gap<'a> {
+    positions: &'a [GameState],
+    from: usize,
+    /// Fingerprints of the positions to reach, to their index in `positions`.
+    targets: &'a FxHashMap<u64, usize>,
+}
+
+impl SearchGoal for Gap<'_> {
+    fn is_met(&mut self, game: &GameState, line: &[Move]) -> bool {
+        reached(self.positions, self.targets, game).is_some_and(|to| line.len() < to - self.from)
+    }
+}
+
+/// The index in `positions` of the target `game` is, if any.
+fn reached(positions: &[GameState], targets: &FxHashMap<u64, usize>, game: &GameState) -> Option<usize> {
+    let &to = targets.get(&game.fingerprint())?;
+    (*game == positions[to]).then_some(to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::fixtures;
+
+    #[test]
+    fn splices_out_detours() {
+        let start = fixtures::by_name("kings_left").unwrap().state();
+        let home = |column| Move::tableau_to_foundation(column, column).unwrap();
+        // The club king goes by a freecell, an empty column and another freecell.
+        let solution = [
+            Move::tableau_to_freecell(0, 0).unwrap(),
+            Move::freecell_to_tableau(0, 5).unwrap(),
+            Move::tableau_to_freecell(5, 1).unwrap(),
+            Move::freecell_to_foundation(1, 3).unwrap(),
+            home(0),
+            home(1),
+            home(2),
+        ];
+        let (moves, shortening) = shorten(&start, &solution, 8).unwrap();
+        assert_eq!(shortening, Shortening { original: 7, shortened: 4 });
+        assert!(replay(&start, &moves).is_some());
+
+        let (again, _) = shorten(&start, &moves, 8).unwrap();
+        assert_eq!(again, moves);
+        assert_eq!(shorten(&start, &solution[..6], 8), None);
+    }
+
+    #[test]
+    fn shortens_a_strat13_solution() {
+        // A real solve rather than a fixture: strat13's detours are what the plan's
+        // shortening is for. Its search is multi-threaded, so the solution varies from
+        // run to run, but deal 8's have plenty to cut (165 moves to 134 in one run).
+        let start = freecell_game_engine::generation::generate_deal(8).unwrap();
+        let solution = crate::bench::harness_with_timing(start.clone(), 60).solution_moves.unwrap();
+
+        let (moves, shortening) = shorten(&start, &solution, 4).unwrap();
+        assert_eq!(shortening, Shortening { original: solution.len(), shortened: moves.len() });
+        assert!(moves.len() < solution.len());
+        assert!(replay(&start, &moves).is_some());
+    }
+}
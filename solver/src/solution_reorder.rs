@@ -0,0 +1,124 @@
+//! Reordering a solution so it keeps the freecells emptier.
+//!
+//! The search takes the first solution it finds, and that solution often parks cards in
+//! the freecells long before it needs the room, or leaves them there long after they
+//! could go home. Players find such solutions hard to follow. [`conserve_freecells`]
+//! rewrites one by swapping neighbouring moves: a move into a cell is played later, a
+//! move out of one earlier, whenever the swap still wins and uses the cells less.
+//!
+//! Every candidate is verified by replaying it from the start, so the result is always a
+//! solution of the same length made of the same moves.
+
+use freecell_game_engine::location::Location;
+use freecell_game_engine::r#move::Move;
+use freecell_game_engine::GameState;
+use serde::{Deserialize, Serialize};
+
+/// How a solution uses the freecells. Ordered peak first, so a solution that never holds
+/// more than two cards beats one that holds three for a single ply.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FreecellUsage {
+    /// Most cards in the freecells at once.
+    pub peak: usize,
+    /// Cards in the freecells after each move, summed over the solution.
+    pub card_plies: usize,
+}
+
+/// A solution's freecell use before and after [`conserve_freecells`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConservedUsage {
+    pub original: FreecellUsage,
+    pub conserved: FreecellUsage,
+}
+
+/// Replays `solution` from `start` and measures its freecell use. `None` if a move is
+/// illegal or the game is not won at the end.
+pub fn freecell_usage(start: &GameState, solution: &[Move]) -> Option<FreecellUsage> {
+    let mut game = start.clone();
+    let mut usage = FreecellUsage { peak: occupied(&game), card_plies: 0 };
+    for m in solution {
+        game.execute_move(m).ok()?;
+        let cards = occupied(&game);
+        usage.peak = usage.peak.max(cards);
+        usage.card_plies += cards;
+    }
+    game.is_won().unwrap_or(false).then_some(usage)
+}
+
+/// Reorders `solution` to lower its [`FreecellUsage`], or returns it unchanged if no
+/// swap helps. `None` if `solution` does not win from `start`.
+///
+/// Passes over the solution until one makes no swap. Each pass tries every pair of
+/// neighbours where the first fills a cell or the second empties one, and keeps a swap
+/// if the whole solution still wins with lower usage.
+pub fn conserve_freecells(start: &GameState, solution: &[Move]) -> Option<(Vec<Move>, ConservedUsage)> {
+    let original = freecell_usage(start, solution)?;
+    let mut moves = solution.to_vec();
+    let mut usage = original;
+    // Every kept swap lowers the usage, so this bound is never reached in practice; it
+    // only caps the work on pathological inputs.
+    for _ in 0..solution.len() {
+        let mut swapped = false;
+        for i in 0..moves.len().saturating_sub(1) {
+            if !fills_cell(&moves[i]) && !empties_cell(&moves[i + 1]) {
+                continue;
+            }
+            moves.swap(i, i + 1);
+            match freecell_usage(start, &moves) {
+                Some(candidate) if candidate < usage => {
+                    usage = candidate;
+                    swapped = true;
+                }
+                _ => moves.swap(i, i + 1),
+            }
+        }
+        if !swapped {
+            break;
+        }
+    }
+    Some((moves, ConservedUsage { original, conserved: usage }))
+}
+
+fn occupied(game: &GameState) -> usize {
+    game.freecells().capacity() - game.freecells().empty_cells_count()
+}
+
+fn fills_cell(m: &Move) -> bool {
+    matches!(m.destination, Location::Freecell(_)) && !matches!(m.source, Location::Freecell(_))
+}
+
+fn empties_cell(m: &Move) -> bool {
+    matches!(m.source, Location::Freecell(_)) && !matches!(m.destination, Location::Freecell(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::fixtures;
+
+    #[test]
+    fn parks_cards_as_late_as_the_solution_allows() {
+        let start = fixtures::by_name("kings_left").unwrap().state();
+        // The club and heart kings go through the cells, all parking done up front.
+        let wasteful = [
+            Move::tableau_to_freecell(0, 0).unwrap(),
+            Move::tableau_to_freecell(1, 1).unwrap(),
+            Move::tableau_to_foundation(0, 0).unwrap(),
+            Move::tableau_to_foundation(2, 2).unwrap(),
+            Move::freecell_to_foundation(0, 3).unwrap(),
+            Move::freecell_to_foundation(1, 1).unwrap(),
+        ];
+        let (moves, usage) = conserve_freecells(&start, &wasteful).unwrap();
+        assert_eq!(usage.original, FreecellUsage { peak: 2, card_plies: 8 });
+        assert!(usage.conserved < usage.original);
+        assert_eq!(usage.conserved.peak, 1);
+        assert_eq!(freecell_usage(&start, &moves), Some(usage.conserved));
+        let mut sorted = moves.clone();
+        sorted.sort();
+        let mut expected = wasteful.to_vec();
+        expected.sort();
+        assert_eq!(sorted, expected);
+
+        assert!(conserve_freecells(&start, &wasteful[..5]).is_none());
+    }
+}
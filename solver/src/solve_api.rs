@@ -0,0 +1,237 @@
+//! The JSON schema for a single solve: a [`SolveRequest`] in, a [`SolveResponse`] out.
+//!
+//! Anything that wants a solution without reading the solver's console output (`solver
+//! solve --json`, a service built on this library, a web frontend) exchanges these
+//! messages. Both carry a `version`, [`SCHEMA_VERSION`]. Within a version, fields are
+//! only ever added, with defaults, so an older reader still understands a newer message;
+//! a request for a version this solver does not know is answered with
+//! [`Outcome::Invalid`] instead of being guessed at.
+//!
+//! A request names the position as a `seed` (a Microsoft deal) or a `board`, which is
+//! either a position code or anything [`import_board`] reads:
+//!
+//! ```json
+//! {"version": 1, "seed": 617, "options": {"timeout_secs": 10}}
+//! {"version": 1, "board": "Foundations: QS QH QD QC\n: KS KC\n: KH\n: KD\n"}
+//! ```
+//!
+//! `options` and every field in it may be left out. The response's `outcome` is one of
+//! `solved`, `timed_out`, `invalid` or `failed`; `solution` is only present when solved,
+//! each move in the same form as the benchmark's detailed results:
+//!
+//! ```json
+//! {"version": 1, "outcome": "solved",
+//!  "solution": [{"source": {"Tableau": {"index": 0}}, "destination": {"Foundation": {"index": 3}}}, ...],
+//!  "stats": {"elapsed_ms": 100, "states_explored": 0}}
+//! ```
+//!
+//! `messages` holds the board import's warnings, or why a request was invalid or a solve
+//! failed; it is left out when empty.
+
+use crate::bench;
+use freecell_game_engine::generation::generate_deal;
+use freecell_game_engine::import::{import_board, import_position_code};
+use freecell_game_engine::r#move::Move;
+use freecell_game_engine::{GameState, Variant};
+use serde::{Deserialize, Serialize};
+
+/// The schema version this solver reads and writes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A position to solve, and how.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SolveRequest {
+    pub version: u32,
+    #[serde(flatten)]
+    pub position: Position,
+    #[serde(default)]
+    pub options: SolveOptions,
+}
+
+/// Where a request's position comes from, as a `seed` or `board` field.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Position {
+    Seed(u64),
+    /// A position code or an import layout.
+    Board(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SolveOptions {
+    /// Give up after this many seconds.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions { timeout_secs: default_timeout_secs() }
+    }
+}
+
+/// How a solve ended.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Solved,
+    /// No solution was found within the timeout.
+    TimedOut,
+    /// The request could not be read, or named a position that does not exist.
+    Invalid,
+    /// The solver itself broke down; `messages` says how.
+    Failed,
+}
+
+/// The answer to a [`SolveRequest`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SolveResponse {
+    pub version: u32,
+    pub outcome: Outcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solution: Option<Vec<Move>>,
+    #[serde(default)]
+    pub stats: SolveStats,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    pub elapsed_ms: u64,
+    pub states_explored: usize,
+}
+
+impl SolveRequest {
+    /// A request for Microsoft deal `seed` with the default options.
+    pub fn seed(seed: u64) -> Self {
+        SolveRequest { version: SCHEMA_VERSION, position: Position::Seed(seed), options: SolveOptions::default() }
+    }
+
+    /// A request for the position in `text` with the default options.
+    pub fn board(text: impl Into<String>) -> Self {
+        SolveRequest { version: SCHEMA_VERSION, position: Position::Board(text.into()), options: SolveOptions::default() }
+    }
+}
+
+impl Position {
+    /// Deals or imports the position, returning it with the import's warnings. A board
+    /// that is a single word with a `/` in it is read as a position code.
+    pub fn load(&self) -> Result<(GameState, Vec<String>), String> {
+        match self {
+            Position::Seed(seed) => generate_deal(*seed)
+                .map(|state| (state, Vec::new()))
+                .map_err(|e| format!("Could not deal {}: {}", seed, e)),
+            Position::Board(text) => {
+                let text = text.trim();
+                let import = if !text.contains(char::is_whitespace) && text.contains('/') {
+                    import_position_code(text, Variant::Standard)
+                } else {
+                    import_board(text, Variant::Standard)
+                };
+                let import = import.map_err(|e| format!("Could not import board: {}", e))?;
+                let warnings = import.warnings.iter().map(ToString::to_string).collect();
+                Ok((import.state, warnings))
+            }
+        }
+    }
+}
+
+impl SolveResponse {
+    /// The response to a request that could not be served.
+    pub fn invalid(message: impl Into<String>) -> Self {
+        SolveResponse {
+            version: SCHEMA_VERSION,
+            outcome: Outcome::Invalid,
+            solution: None,
+            stats: SolveStats::default(),
+            messages: vec![message.into()],
+        }
+    }
+}
+
+/// Answers `request`, solving with strat13.
+pub fn solve(request: &SolveRequest) -> SolveResponse {
+    if request.version == 0 || request.version > SCHEMA_VERSION {
+        return SolveResponse::invalid(format!(
+            "Unsupported schema version {}: this solver reads version {}",
+            request.version, SCHEMA_VERSION
+        ));
+    }
+    if request.options.timeout_secs == 0 {
+        return SolveResponse::invalid("timeout_secs must be at least 1");
+    }
+    match request.position.load() {
+        Ok((state, warnings)) => {
+            let mut response = solve_position(state, &request.options);
+            response.messages.splice(0..0, warnings);
+            response
+        }
+        Err(message) => SolveResponse::invalid(message),
+    }
+}
+
+/// Solves an already loaded position under `options`.
+pub fn solve_position(state: GameState, options: &SolveOptions) -> SolveResponse {
+    let result = bench::harness_with_timing(state, options.timeout_secs);
+    let (outcome, messages) = match result.panic {
+        Some(message) => (Outcome::Failed, vec![format!("The solver panicked: {}", message)]),
+        None if result.solved => (Outcome::Solved, Vec::new()),
+        None => (Outcome::TimedOut, Vec::new()),
+    };
+    SolveResponse {
+        version: SCHEMA_VERSION,
+        outcome,
+        solution: result.solution_moves.filter(|_| outcome == Outcome::Solved),
+        stats: SolveStats {
+            elapsed_ms: result.execution_time.as_millis() as u64,
+            states_explored: result.states_explored,
+        },
+        messages,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::fixtures;
+    use freecell_game_engine::import::position_code;
+    use serde_json::json;
+
+    fn kings_left() -> String {
+        position_code(&fixtures::by_name("kings_left").unwrap().state())
+    }
+
+    #[test]
+    fn requests_have_a_stable_shape() {
+        let request = SolveRequest::seed(617);
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value, json!({"version": 1, "seed": 617, "options": {"timeout_secs": 60}}));
+
+        let parsed: SolveRequest = serde_json::from_value(json!({"version": 1, "board": kings_left()})).unwrap();
+        assert_eq!(parsed, SolveRequest::board(kings_left()));
+        assert!(serde_json::from_value::<SolveRequest>(json!({"seed": 617})).is_err());
+    }
+
+    #[test]
+    fn responses_report_the_outcome() {
+        let response = solve(&SolveRequest::board(kings_left()));
+        assert_eq!(response.outcome, Outcome::Solved);
+        assert_eq!(response.solution.as_ref().map(Vec::len), Some(4));
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(value["outcome"], "solved");
+        assert!(value.get("messages").is_none());
+        // A layout that only lists three columns gets a warning about the rest.
+        let layout = solve(&SolveRequest::board("Foundations: KS KH QD KC\n: KD\n"));
+        assert_eq!((layout.outcome, layout.messages.len()), (Outcome::Solved, 1));
+
+        let future = SolveRequest { version: SCHEMA_VERSION + 1, ..SolveRequest::seed(1) };
+        assert_eq!(solve(&future).outcome, Outcome::Invalid);
+        let duplicate = solve(&SolveRequest::board(": AS AS\n"));
+        assert_eq!((duplicate.outcome, duplicate.messages.len()), (Outcome::Invalid, 1));
+    }
+}
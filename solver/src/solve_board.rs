@@ -0,0 +1,192 @@
+//! `solver solve <seed>` or `solver solve --board <path|->`: solve a single position.
+//!
+//! Benchmarks only ever start from Microsoft deals, but positions also come from other
+//! tools: a board typed in from another FreeCell program, a position code from
+//! `--export-unsolved`, a generator's output. `--board` reads the position from a file, or
+//! from stdin given `-`, so it can be piped straight in. The text may be a position code
+//! ([`import_position_code`]) or anything [`import_board`] accepts; the import's warnings
+//! go to stderr.
+//!
+//! With `--json`, the board and phases are left out and the [`SolveResponse`] is printed
+//! instead, and `--request <path|->` takes a whole [`SolveRequest`] in place of the other
+//! arguments, so a service or frontend can drive the solver through [`crate::solve_api`]
+//! alone.
+//!
+//! [`import_position_code`]: freecell_game_engine::import::import_position_code
+//! [`import_board`]: freecell_game_engine::import::import_board
+
+use crate::benchmark_plan::PlanError;
+use crate::solve_api::{self, Outcome, Position, SolveOptions, SolveRequest, SolveResponse, SCHEMA_VERSION};
+use freecell_game_engine::display::{SolutionPhases, TerminalBoard};
+use std::io::Read;
+
+/// Where the position to solve comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardSource {
+    Seed(u64),
+    /// A board file, or stdin for `-`.
+    Board(String),
+    /// A [`SolveRequest`] as JSON, from a file or stdin for `-`; its options replace
+    /// `--timeout`.
+    Request(String),
+}
+
+/// Settings for a single solve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveCommand {
+    pub source: BoardSource,
+    pub timeout_secs: u64,
+    /// Print the [`SolveResponse`] as JSON instead of the board and solution.
+    pub json: bool,
+}
+
+impl SolveCommand {
+    /// Parses the arguments following `solve`: a seed, `--board <path|->` or `--request
+    /// <path|->`, then an optional `--timeout <secs>` (default 60) and `--json`. A request
+    /// always answers in JSON.
+    pub fn from_args<I>(args: I) -> Result<Self, PlanError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut source = None;
+        let mut timeout_secs = 60;
+        let mut json = false;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| args.next().ok_or_else(|| PlanError::MissingValue(flag.to_string()));
+            match arg.as_str() {
+                "--board" if source.is_none() => source = Some(BoardSource::Board(value("--board")?)),
+                "--request" if source.is_none() => {
+                    source = Some(BoardSource::Request(value("--request")?));
+                    json = true;
+                }
+                "--json" => json = true,
+                "--timeout" => {
+                    let raw = value("--timeout")?;
+                    timeout_secs = raw
+                        .parse::<u64>()
+                        .map_err(|_| PlanError::InvalidValue { flag: "--timeout".to_string(), value: raw })?;
+                    if timeout_secs == 0 {
+                        return Err(PlanError::InvalidTimeout(0));
+                    }
+                }
+                flag if flag.starts_with("--") => return Err(PlanError::UnknownArgument(arg)),
+                _ if source.is_none() => {
+                    let seed = arg.parse::<u64>().map_err(|_| PlanError::InvalidValue {
+                        flag: "solve".to_string(),
+                        value: arg.clone(),
+                    })?;
+                    source = Some(BoardSource::Seed(seed));
+                }
+                _ => return Err(PlanError::UnknownArgument(arg)),
+            }
+        }
+        let source = source.ok_or_else(|| PlanError::MissingValue("solve".to_string()))?;
+        Ok(SolveCommand { source, timeout_secs, json })
+    }
+
+    /// The request this command stands for.
+    fn request(&self) -> Result<SolveRequest, String> {
+        let position = match &self.source {
+            BoardSource::Seed(seed) => Position::Seed(*seed),
+            BoardSource::Board(path) => Position::Board(read_input(path)?),
+            BoardSource::Request(path) => {
+                return serde_json::from_str(&read_input(path)?).map_err(|e| format!("Could not read the request: {}", e));
+            }
+        };
+        let options = SolveOptions { timeout_secs: self.timeout_secs };
+        Ok(SolveRequest { version: SCHEMA_VERSION, position, options })
+    }
+}
+
+/// Reads a file, or stdin for `-`.
+fn read_input(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| format!("Could not read stdin: {}", e))?;
+        Ok(text)
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))
+    }
+}
+
+/// Loads the position and solves it. Without `--json`, a solve that fails is an error, so
+/// the exit code shows it; with it, the response says so.
+pub fn run(command: &SolveCommand) -> Result<(), String> {
+    if command.json {
+        let response = match command.request() {
+            Ok(request) => solve_api::solve(&request),
+            Err(message) => SolveResponse::invalid(message),
+        };
+        let text = serde_json::to_string(&response).map_err(|e| format!("Could not write the response: {}", e))?;
+        println!("{}", text);
+        return Ok(());
+    }
+
+    let request = command.request()?;
+    let (state, warnings) = request.position.load()?;
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    println!("{}", TerminalBoard::new(&state));
+    println!("Solving for up to {}s...", request.options.timeout_secs);
+
+    let response = solve_api::solve_position(state.clone(), &request.options);
+    match (response.outcome, response.solution) {
+        (Outcome::Solved, Some(moves)) => {
+            println!("\nSolved in {}ms, {} moves:", response.stats.elapsed_ms, moves.len());
+            match SolutionPhases::new(&state, &moves) {
+                Ok(phases) => println!("{}", phases),
+                Err(e) => return Err(format!("Solution does not replay: {}", e)),
+            }
+            Ok(())
+        }
+        (Outcome::Failed, _) => Err(response.messages.join("; ")),
+        _ => Err(format!("No solution found within {}s", request.options.timeout_secs)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::fixtures;
+    use freecell_game_engine::import::position_code;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_a_seed_or_a_board() {
+        let command = SolveCommand::from_args(args(&["--board", "-", "--timeout", "5"])).unwrap();
+        assert_eq!(command, SolveCommand { source: BoardSource::Board("-".to_string()), timeout_secs: 5, json: false });
+        assert!(SolveCommand::from_args(args(&["--request", "req.json"])).unwrap().json);
+        assert_eq!(SolveCommand::from_args(args(&["7"])).unwrap().source, BoardSource::Seed(7));
+        assert_eq!(SolveCommand::from_args(args(&[])), Err(PlanError::MissingValue("solve".to_string())));
+        assert_eq!(SolveCommand::from_args(args(&["7", "--board", "-"])), Err(PlanError::UnknownArgument("--board".to_string())));
+        assert!(matches!(SolveCommand::from_args(args(&["deal"])), Err(PlanError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn boards_read_as_codes_or_layouts() {
+        let state = fixtures::by_name("kings_left").unwrap().state();
+        let code = Position::Board(format!("{}\n", position_code(&state)));
+        assert_eq!(code.load().unwrap().0, state);
+        let layout = Position::Board("Foundations: KS KH KD KC\n".to_string());
+        assert!(layout.load().unwrap().0.is_won().unwrap());
+
+        let path = std::env::temp_dir().join(format!("solver-solve-board-test-{}", std::process::id()));
+        std::fs::write(&path, position_code(&state)).unwrap();
+        let mut command =
+            SolveCommand { source: BoardSource::Board(path.to_str().unwrap().to_string()), timeout_secs: 5, json: false };
+        let result = run(&command);
+        command.source = BoardSource::Request(path.to_str().unwrap().to_string());
+        command.json = true;
+        let request = command.request();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result, Ok(()));
+        assert!(request.unwrap_err().starts_with("Could not read the request"));
+    }
+}
@@ -1,13 +1,34 @@
+//! Search strategies. strat13 to strat15 are the active ones, strat14 and strat15 built
+//! on the shared depth-first loop in [`search_core`]. strat13's multi-threaded search has
+//! not been moved onto it yet and keeps its own loop. strat1 to strat12 are retired and
+//! only built with the `archive` feature.
+
+pub mod search_core;
+
+#[cfg(feature = "archive")]
 pub mod strat1;
+#[cfg(feature = "archive")]
 pub mod strat2;
+#[cfg(feature = "archive")]
 pub mod strat3;
+#[cfg(feature = "archive")]
 pub mod strat4;
+#[cfg(feature = "archive")]
 pub mod strat5;
+#[cfg(feature = "archive")]
 pub mod strat6;
+#[cfg(feature = "archive")]
 pub mod strat7;
+#[cfg(feature = "archive")]
 pub mod strat8;
+#[cfg(feature = "archive")]
 pub mod strat9;
+#[cfg(feature = "archive")]
 pub mod strat10;
+#[cfg(feature = "archive")]
 pub mod strat11;
+#[cfg(feature = "archive")]
 pub mod strat12;
 pub mod strat13;
+pub mod strat14;
+pub mod strat15;
@@ -0,0 +1,173 @@
+//! The depth-first loop the active strategies share.
+//!
+//! Every depth-first strategy since strat7 repeats the same bookkeeping at each position:
+//! stop on cancellation or past the depth bound, stop on a win, prune positions already on
+//! the path or in the LRU transposition table, hand small endgames to the endgame solver,
+//! and play each child onto one game and path, undoing it if the child fails. [`DfsCore`]
+//! holds that bookkeeping so a strategy only decides which lines to try at a position:
+//! [`DfsCore::enter`] runs the checks, [`try_line`] plays and undoes a line of moves, and
+//! [`DfsCore::leave`] takes the position back off the path.
+//!
+//! strat14 and strat15 run on it. strat13 has not been moved onto it yet and still
+//! carries its own copy of these checks: its workers share a work queue and one
+//! transposition table across threads, which a single path and LRU table do not cover.
+//! Until `DfsCore` can run against strat13's shared table, a change to the checks here
+//! has to be made in strat13's `expand_work_item` as well.
+
+use crate::endgame::{self, EndgameOutcome};
+use crate::packed_state::PackedGameState;
+use crate::strategies::strat13::solve::{get_tableau_column, order_moves, ColumnPolicy, SolverResult};
+use freecell_game_engine::{r#move::Move, GameState};
+use fxhash::{FxBuildHasher, FxHashSet};
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Depth bound for the search; no FreeCell solution comes close.
+pub const MAX_DEPTH: usize = 1000;
+
+/// What [`DfsCore::enter`] decided about a position.
+pub enum Visit {
+    /// The position is won, or the endgame solver finished it; the path holds the
+    /// solution.
+    Solved,
+    /// Nothing to search here: cancelled, too deep, unsolvable, or seen already.
+    Pruned,
+    /// Search the position's children, then [`leave`](DfsCore::leave) with this key.
+    Expand(PackedGameState),
+}
+
+/// Visited tracking and cancellation for one depth-first search.
+pub struct DfsCore<'a> {
+    cache: LruCache<PackedGameState, (), FxBuildHasher>,
+    /// Positions on the current path, which the cache may already have forgotten.
+    ancestors: FxHashSet<PackedGameState>,
+    cancel_flag: &'a AtomicBool,
+    /// Positions expanded.
+    pub states_explored: usize,
+}
+
+impl<'a> DfsCore<'a> {
+    /// A search remembering up to `cache_entries` positions besides its path.
+    pub fn new(cache_entries: NonZeroUsize, cancel_flag: &'a AtomicBool) -> Self {
+        DfsCore {
+            cache: LruCache::with_hasher(cache_entries, FxBuildHasher::default()),
+            ancestors: FxHashSet::default(),
+            cancel_flag,
+            states_explored: 0,
+        }
+    }
+
+    pub fn cancel_flag(&self) -> &'a AtomicBool {
+        self.cancel_flag
+    }
+
+    /// Runs the checks every position gets, in order, and enters `game` onto the path if
+    /// it is to be expanded. On [`Visit::Solved`] the endgame's moves, if any, have been
+    /// appended to `path`.
+    pub fn enter(&mut self, game: &GameState, path: &mut Vec<Move>) -> Visit {
+        if self.cancel_flag.load(Ordering::SeqCst) || path.len() > MAX_DEPTH {
+            return Visit::Pruned;
+        }
+        if game.is_won().unwrap_or(false) {
+            return Visit::Solved;
+        }
+        let packed = PackedGameState::from_game_state_canonical(game);
+        if self.ancestors.contains(&packed) || self.cache.put(packed.clone(), ()).is_some() {
+            return Visit::Pruned;
+        }
+        // Only new positions get here, so transpositions never rerun the endgame solver.
+        match endgame::solve_endgame(game) {
+            EndgameOutcome::Solved(rest) if path.len() + rest.len() <= MAX_DEPTH => {
+                path.extend(rest);
+                return Visit::Solved;
+            }
+            EndgameOutcome::Solved(_) | EndgameOutcome::Unsolvable => return Visit::Pruned,
+            EndgameOutcome::Inconclusive => {}
+        }
+        self.states_explored += 1;
+        self.ancestors.insert(packed.clone());
+        Visit::Expand(packed)
+    }
+
+    /// Takes a position [`enter`](Self::enter) expanded back off the path.
+    pub fn leave(&mut self, packed: &PackedGameState) {
+        self.ancestors.remove(packed);
+    }
+}
+
+/// `game`'s moves in strat13's order, preferring the column `path` last moved from.
+pub fn ordered_moves(game: &GameState, path: &[Move]) -> Vec<Move> {
+    let previous_column = path.last().and_then(|m| get_tableau_column(&m.source));
+    order_moves(game.get_available_moves(), game, ColumnPolicy::default(), previous_column, 0)
+}
+
+/// Plays `moves` onto `game` and `path`, then runs `search` on `strategy` from there. If
+/// a move is illegal or `search` fails, everything played is undone and `path` is left
+/// as it was.
+pub fn try_line<S>(
+    strategy: &mut S,
+    game: &mut GameState,
+    path: &mut Vec<Move>,
+    moves: &[Move],
+    search: impl FnOnce(&mut S, &mut GameState, &mut Vec<Move>) -> bool,
+) -> bool {
+    let depth = path.len();
+    for m in moves {
+        if game.execute_move(m).is_err() {
+            break;
+        }
+        path.push(*m);
+    }
+    if path.len() - depth == moves.len() && search(strategy, game, path) {
+        return true;
+    }
+    for m in path.drain(depth..).rev() {
+        game.undo_move(&m);
+    }
+    false
+}
+
+/// The result for a depth-first search that ended with `solution`.
+pub fn solver_result(solution: Option<Vec<Move>>, states_explored: usize) -> SolverResult {
+    SolverResult {
+        solved: solution.is_some(),
+        solution_moves: solution,
+        states_explored,
+        cross_deal_hits: 0,
+        stale_pruned: 0,
+        stats: None,
+        snapshots: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::fixtures;
+
+    #[test]
+    fn lines_are_undone_when_they_fail() {
+        let start = fixtures::by_name("kings_left").unwrap().state();
+        let mut game = start.clone();
+        let mut path = Vec::new();
+        let to_cell = Move::tableau_to_freecell(0, 0).unwrap();
+        let illegal = Move::freecell_to_tableau(1, 1).unwrap();
+
+        assert!(!try_line(&mut (), &mut game, &mut path, &[to_cell, illegal], |_, _, _| true));
+        assert!(!try_line(&mut (), &mut game, &mut path, &[to_cell], |_, _, _| false));
+        assert!(path.is_empty());
+        assert_eq!(game, start);
+
+        let flag = AtomicBool::new(false);
+        let mut core = DfsCore::new(NonZeroUsize::new(16).unwrap(), &flag);
+        let Visit::Solved = core.enter(&game, &mut path) else { panic!("kings_left is an endgame") };
+        assert_eq!(path.len(), 4);
+        // Reached again, it is a transposition: pruned without solving the endgame again.
+        let mut again = Vec::new();
+        assert!(matches!(core.enter(&game, &mut again), Visit::Pruned));
+        assert!(again.is_empty());
+        flag.store(true, Ordering::SeqCst);
+        assert!(matches!(core.enter(&game, &mut Vec::new()), Visit::Pruned));
+    }
+}
@@ -1,19 +1,380 @@
+use crate::endgame::{self, EndgameOutcome};
+use crate::endgame_db;
 use crate::packed_state::PackedGameState;
 use freecell_game_engine::{r#move::Move, GameState, location::Location};
 use freecell_game_engine::game_state::heuristics::score_state;
-use freecell_game_engine::{card::{Card, Rank, Suit}, location::{FoundationLocation, TableauLocation}};
+use freecell_game_engine::game_state::StatePool;
 use lru::LruCache;
-use fxhash::{FxHashMap, FxHashSet, FxBuildHasher};
+use serde::{Deserialize, Serialize};
+use fxhash::{FxHashSet, FxBuildHasher};
 use std::num::NonZeroUsize;
-use std::time::Instant;
-use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicUsize, Ordering}};
+use std::time::{Duration, Instant};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering}};
 use std::thread;
 use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Clone)]
 pub struct SolverResult {
     pub solved: bool,
     pub solution_moves: Option<Vec<Move>>,
+    pub states_explored: usize,
+    /// Global-table hits on states recorded while solving a *previous* deal.
+    /// Always zero unless the solve ran against a [`WarmCache`].
+    pub cross_deal_hits: usize,
+    /// Queued positions dropped unexpanded when a worker took them up, because another
+    /// worker had reached them while they waited.
+    pub stale_pruned: u64,
+    /// Shape of the search tree; only collected by [`solve_with_cancel_stats`].
+    pub stats: Option<SolverStats>,
+    /// The most promising positions the search expanded, best first; only kept by
+    /// [`solve_with_cancel_snapshots`].
+    pub snapshots: Vec<Snapshot>,
+}
+
+/// The shape of a search tree, for judging move-ordering changes by how the search
+/// behaved rather than by wall-clock time alone.
+///
+/// Counts cover every worker. Positions pruned as already visited are not counted
+/// anywhere: they were never expanded and are not dead ends. Queued positions dropped
+/// as stale are always counted, in [`SolverResult::stale_pruned`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolverStats {
+    /// `branching[n]` is the number of expanded positions that had `n` legal moves.
+    pub branching: Vec<u64>,
+    /// Positions whose moves were generated and searched.
+    pub expanded: u64,
+    /// Expansions of a position that had already been expanded once, because the
+    /// transposition tables had evicted it in between.
+    pub re_expansions: u64,
+    /// Positions the search backed out of without a move to try: no legal moves, proven
+    /// lost by the endgame solver, or past the depth bound.
+    pub dead_ends: u64,
+    /// Sum of the depths of those dead ends.
+    pub dead_end_depth_total: u64,
+    /// Solutions reached; one at most for a single solve.
+    pub solutions: u64,
+    /// Sum of the solutions' lengths in moves.
+    pub solution_depth_total: u64,
+    /// When the search first expanded a position with each of [`MILESTONE_CARDS`] home,
+    /// in that order; milestones never reached are left out. Where these stop, or the
+    /// gaps between them, show where a strategy stalls.
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
+}
+
+/// Cards home at which [`SolverStats::milestones`] are taken: the first card, then each
+/// quarter of the deck.
+pub const MILESTONE_CARDS: [usize; 4] = [1, 13, 26, 39];
+
+/// The first time a search expanded a position with `foundation_cards` home.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Milestone {
+    pub foundation_cards: usize,
+    /// Since the solve started.
+    pub elapsed_ms: u64,
+    /// Positions explored by all workers by then.
+    pub states_explored: u64,
+}
+
+impl SolverStats {
+    /// Mean number of legal moves per expanded position.
+    pub fn average_branching(&self) -> Option<f64> {
+        let moves: u64 = self.branching.iter().enumerate().map(|(n, &count)| n as u64 * count).sum();
+        average(moves, self.expanded)
+    }
+
+    /// Mean depth at which the search hit a dead end.
+    pub fn average_dead_end_depth(&self) -> Option<f64> {
+        average(self.dead_end_depth_total, self.dead_ends)
+    }
+
+    /// Mean length of the solutions found.
+    pub fn average_solution_depth(&self) -> Option<f64> {
+        average(self.solution_depth_total, self.solutions)
+    }
+
+    /// Adds `other`'s counts to these, e.g. to total several solves.
+    pub fn merge(&mut self, other: &SolverStats) {
+        if self.branching.len() < other.branching.len() {
+            self.branching.resize(other.branching.len(), 0);
+        }
+        for (total, &count) in self.branching.iter_mut().zip(&other.branching) {
+            *total += count;
+        }
+        self.expanded += other.expanded;
+        self.re_expansions += other.re_expansions;
+        self.dead_ends += other.dead_ends;
+        self.dead_end_depth_total += other.dead_end_depth_total;
+        self.solutions += other.solutions;
+        self.solution_depth_total += other.solution_depth_total;
+        // Milestones are not counts: the earlier of each is kept.
+        for milestone in &other.milestones {
+            match self.milestones.iter_mut().find(|held| held.foundation_cards == milestone.foundation_cards) {
+                Some(held) if held.elapsed_ms <= milestone.elapsed_ms => {}
+                Some(held) => *held = *milestone,
+                None => self.milestones.push(*milestone),
+            }
+        }
+        self.milestones.sort_by_key(|milestone| milestone.foundation_cards);
+    }
+
+    /// Records every milestone up to `foundation_cards` not reached before.
+    fn record_milestones(&mut self, foundation_cards: usize, start_time: Instant, states_explored: usize) {
+        while let Some(&cards) = MILESTONE_CARDS.get(self.milestones.len()).filter(|&&cards| cards <= foundation_cards) {
+            self.milestones.push(Milestone {
+                foundation_cards: cards,
+                elapsed_ms: start_time.elapsed().as_millis() as u64,
+                states_explored: states_explored as u64,
+            });
+        }
+    }
+
+    fn record_expansion(&mut self, branching: usize, seen_before: bool) {
+        if self.branching.len() <= branching {
+            self.branching.resize(branching + 1, 0);
+        }
+        self.branching[branching] += 1;
+        self.expanded += 1;
+        self.re_expansions += u64::from(seen_before);
+    }
+
+    fn record_dead_end(&mut self, depth: usize) {
+        self.dead_ends += 1;
+        self.dead_end_depth_total += depth as u64;
+    }
+
+    fn record_solution(&mut self, depth: usize) {
+        self.solutions += 1;
+        self.solution_depth_total += depth as u64;
+    }
+}
+
+fn average(total: u64, count: u64) -> Option<f64> {
+    (count > 0).then(|| total as f64 / count as f64)
+}
+
+/// A position the search expanded, kept so an unsolved search can hand on where it got
+/// furthest. Nothing is known about whether it can be won.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// [`score_state`] of the position; lower is closer to solved.
+    pub score: i32,
+    /// Cards already on the foundations.
+    pub foundation_cards: usize,
+    /// The moves from the start position to this one.
+    pub path: Vec<Move>,
+    pub state: GameState,
+}
+
+impl Snapshot {
+    /// Lower is more promising: fewest inversions first, then most cards home, then
+    /// deepest.
+    fn rank(&self) -> (i32, std::cmp::Reverse<usize>, std::cmp::Reverse<usize>) {
+        (self.score, std::cmp::Reverse(self.foundation_cards), std::cmp::Reverse(self.path.len()))
+    }
+}
+
+/// The best [`Snapshot`]s seen, at most `limit` of them and one per position. Each
+/// worker keeps its own and merges it into the shared one when it stops.
+#[derive(Default)]
+struct Frontier {
+    limit: usize,
+    entries: Vec<(PackedGameState, Snapshot)>,
+}
+
+impl Frontier {
+    fn new(limit: usize) -> Self {
+        Frontier { limit, entries: Vec::new() }
+    }
+
+    /// Keeps the position if it beats the worst one held. Only copies the position and
+    /// its path when it is kept.
+    fn offer(&mut self, score: i32, packed: &PackedGameState, game: &GameState, path: &[Move]) {
+        if self.limit == 0 {
+            return;
+        }
+        let foundation_cards = game.foundations().total_cards();
+        let rank = (score, std::cmp::Reverse(foundation_cards), std::cmp::Reverse(path.len()));
+        if self.entries.len() == self.limit && self.entries.last().is_some_and(|(_, worst)| rank >= worst.rank()) {
+            return;
+        }
+        if self.entries.iter().any(|(held, _)| held == packed) {
+            return;
+        }
+        let snapshot = Snapshot { score, foundation_cards, path: path.to_vec(), state: game.clone() };
+        self.insert(packed.clone(), snapshot);
+    }
+
+    fn insert(&mut self, packed: PackedGameState, snapshot: Snapshot) {
+        let at = self.entries.partition_point(|(_, held)| held.rank() <= snapshot.rank());
+        self.entries.insert(at, (packed, snapshot));
+        self.entries.truncate(self.limit);
+    }
+
+    fn merge(&mut self, other: Frontier) {
+        for (packed, snapshot) in other.entries {
+            if !self.entries.iter().any(|(held, _)| *held == packed) {
+                self.insert(packed, snapshot);
+            }
+        }
+    }
+}
+
+/// What a search records besides its result.
+#[derive(Debug, Clone, Copy, Default)]
+struct Recording {
+    /// Collect [`SolverStats`].
+    stats: bool,
+    /// Keep this many [`Snapshot`]s.
+    snapshots: usize,
+}
+
+/// A solution shorter than every one reported before it, passed to the callback of
+/// [`solve_with_cancel_anytime`].
+#[derive(Debug, Clone)]
+#[allow(dead_code)] // Read by the binary's `watch --anytime`; the library target has no reader.
+pub struct Improvement {
+    pub moves: Vec<Move>,
+    /// Time since the anytime solve started.
+    pub elapsed: Duration,
+    /// States explored across all searches so far.
+    pub states_explored: usize,
+}
+
+/// Global transposition table, bucketed by heuristic score. Each entry stores the
+/// generation (solve number) that inserted it so cross-deal hits can be told apart.
+type VisitedTables = Vec<LruCache<PackedGameState, u32, FxBuildHasher>>;
+
+/// Experimental: a global transposition table retained across consecutive solves.
+///
+/// Deals from different seeds are not expected to share positions, so this mostly exists
+/// to confirm that cross-deal hits are ~0 and to stress-test memory growth when the
+/// table is never dropped. Entries from a cancelled solve may not have been fully
+/// explored, so any reuse should be treated as a measurement rather than a speedup.
+pub struct WarmCache {
+    tables: VisitedTables,
+    generation: u32,
+    total_cross_deal_hits: usize,
+}
+
+impl WarmCache {
+    pub fn new() -> Self {
+        WarmCache {
+            tables: Vec::new(),
+            generation: 0,
+            total_cross_deal_hits: 0,
+        }
+    }
+
+    /// Number of states currently held across all score buckets.
+    pub fn entry_count(&self) -> usize {
+        self.tables.iter().map(|t| t.len()).sum()
+    }
+
+    /// Number of solves that have run against this cache.
+    pub fn solves(&self) -> u32 {
+        self.generation
+    }
+
+    /// Cross-deal hits accumulated over every solve that used this cache.
+    pub fn total_cross_deal_hits(&self) -> usize {
+        self.total_cross_deal_hits
+    }
+}
+
+impl Default for WarmCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How move ordering treats the tableau column the previous move came from.
+///
+/// Moves are first ordered by how soon they free a card the foundations need; the policy
+/// only decides what happens around the column the search has just been working on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnPolicy {
+    /// Break ties in favour of the previous move's source column, so the search keeps
+    /// digging where it just was. The original behaviour; it tends to tunnel.
+    #[default]
+    PreferPrevious,
+    /// No tie-breaking, but once this many consecutive moves have come out of the same
+    /// column, moves from it are tried after every other move.
+    Quota(usize),
+}
+
+impl fmt::Display for ColumnPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnPolicy::PreferPrevious => write!(f, "prefer-previous"),
+            ColumnPolicy::Quota(limit) => write!(f, "quota:{}", limit),
+        }
+    }
+}
+
+impl FromStr for ColumnPolicy {
+    type Err = String;
+
+    /// Parses `prefer-previous` or `quota:<n>` with `n` at least 1.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "prefer-previous" {
+            return Ok(ColumnPolicy::PreferPrevious);
+        }
+        s.strip_prefix("quota:")
+            .and_then(|n| n.parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .map(ColumnPolicy::Quota)
+            .ok_or_else(|| format!("expected prefer-previous or quota:<n>, got '{}'", s))
+    }
+}
+
+/// Everything that decides the order moves are expanded in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MoveOrdering {
+    pub column_policy: ColumnPolicy,
+    /// Cost of ending a move with cards in the freecells, for solutions that keep cells
+    /// free the way human players do. Each move is pushed back this many places in the
+    /// order per card the freecells hold after it; 0 leaves the order to the column policy.
+    pub freecell_weight: u32,
+}
+
+/// How positions are packed for the visited tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Packing {
+    /// Columns, freecells and foundations sorted, so positions that differ only in their
+    /// order are one entry ([`PackedGameState::from_game_state_canonical`]).
+    #[default]
+    Canonical,
+    /// Every pile where it lies, so each arrangement is searched on its own.
+    Exact,
+}
+
+impl Packing {
+    pub fn pack(self, game: &GameState) -> PackedGameState {
+        match self {
+            Packing::Canonical => PackedGameState::from_game_state_canonical(game),
+            Packing::Exact => PackedGameState::from_game_state(game),
+        }
+    }
+}
+
+impl fmt::Display for Packing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Packing::Canonical => write!(f, "canonical"),
+            Packing::Exact => write!(f, "exact"),
+        }
+    }
+}
+
+impl From<ColumnPolicy> for MoveOrdering {
+    fn from(column_policy: ColumnPolicy) -> Self {
+        MoveOrdering { column_policy, freecell_weight: 0 }
+    }
 }
 
 struct Counter {
@@ -27,6 +388,8 @@ struct WorkItem {
     game_state: GameState,
     path: Vec<Move>,
     previous_tableau_column: Option<u8>,
+    /// Consecutive moves out of `previous_tableau_column` leading here.
+    column_streak: usize,
     depth: usize,
 }
 
@@ -34,63 +397,53 @@ struct SharedState {
     work_queue: Mutex<VecDeque<WorkItem>>,
     solution_found: AtomicBool,
     solution: Mutex<Option<Vec<Move>>>,
-    global_visited: Mutex<Vec<LruCache<PackedGameState, (), FxBuildHasher>>>,
+    global_visited: Mutex<VisitedTables>,
+    generation: u32,
+    cross_deal_hits: AtomicUsize,
+    stale_pruned: AtomicU64,
     counter: AtomicUsize,
     start_time: Instant,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    ordering: MoveOrdering,
+    packing: Packing,
+    /// Present when the solve collects [`SolverStats`].
+    shape: Option<ShapeRecorder>,
+    /// Pooled [`Snapshot`]s; keeps nothing unless the solve asked for them.
+    frontier: Mutex<Frontier>,
+}
+
+/// Where workers pool their [`SolverStats`]. Each worker counts on its own and merges
+/// into `totals` when it stops; only the re-expansion check is shared as it goes.
+#[derive(Default)]
+struct ShapeRecorder {
+    totals: Mutex<SolverStats>,
+    expanded: Mutex<FxHashSet<PackedGameState>>,
+}
+
+impl SharedState {
+    /// Whether workers should unwind: a solution was found or the solve was cancelled.
+    fn should_stop(&self) -> bool {
+        self.solution_found.load(Ordering::SeqCst)
+            || self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+
+    /// The heuristic score and packed form `game` is looked up by in the visited tables.
+    fn key(&self, game: &GameState) -> (i32, PackedGameState) {
+        (score_state(game), self.packing.pack(game))
+    }
 }
 
 /// Helper function to extract tableau column index from a location
-fn get_tableau_column(location: &Location) -> Option<u8> {
+pub(crate) fn get_tableau_column(location: &Location) -> Option<u8> {
     match location {
         Location::Tableau(tableau_loc) => Some(tableau_loc.index()),
         _ => None,
     }
 }
 
-/// Gets the next expected rank for each suit based on what's already in foundations
-fn get_next_expected_ranks(game: &GameState) -> FxHashMap<Suit, Rank> {
-    let mut expected_ranks = FxHashMap::default();
-    
-    // Check each foundation pile (there are 4, one for each suit)
-    for foundation_index in 0..4 {
-        if let Ok(location) = FoundationLocation::new(foundation_index) {
-            if let Ok(Some(top_card)) = game.foundations().get_card(location) {
-                // If there's a card, the next expected rank is one higher
-                let next_rank_value = (top_card.rank() as u8) + 1;
-                if next_rank_value <= 13 {  // King is 13
-                    if let Ok(next_rank) = Rank::try_from(next_rank_value) {
-                        expected_ranks.insert(top_card.suit(), next_rank);
-                    }
-                }
-            } else {
-                // Empty foundation, so we need an Ace
-                // We need to determine which suit this foundation is for
-                // Since foundations don't inherently know their suit, we'll handle this
-                // by checking if any card can be placed there
-                for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
-                    let ace = Card::new(Rank::Ace, suit);
-                    if game.foundations().validate_card_placement(location, &ace).is_ok() {
-                        expected_ranks.insert(suit, Rank::Ace);
-                        break;
-                    }
-                }
-            }
-        }
-    }
-    
-    // For any suits not yet started in foundations, they need Aces
-    for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs] {
-        if !expected_ranks.contains_key(&suit) {
-            expected_ranks.insert(suit, Rank::Ace);
-        }
-    }
-    
-    expected_ranks
-}
-
 /// Finds the lowest rank card that's not yet in the foundations for each tableau column
 fn get_column_lowest_needed_ranks(game: &GameState) -> Vec<Option<u8>> {
-    let next_expected = get_next_expected_ranks(game);
+    let needed = game.needed_cards();
     let mut column_lowest_ranks = vec![None; 8]; // 8 tableau columns
     
     for column_index in 0..8 {
@@ -98,8 +451,8 @@ fn get_column_lowest_needed_ranks(game: &GameState) -> Vec<Option<u8>> {
             let mut lowest_needed_rank = 14u8; // Higher than King (13)
             
             for card in column_cards {
-                if let Some(&expected_rank) = next_expected.get(&card.suit()) {
-                    if card.rank() as u8 >= expected_rank as u8 {
+                if let Some(needed_card) = needed[card.suit().foundation_index() as usize] {
+                    if card.rank() >= needed_card.rank() {
                         // This card is needed in foundations
                         lowest_needed_rank = lowest_needed_rank.min(card.rank() as u8);
                     }
@@ -117,7 +470,7 @@ fn get_column_lowest_needed_ranks(game: &GameState) -> Vec<Option<u8>> {
 
 /// Sorts moves to prioritize columns with the lowest cards needed for foundations
 /// Falls back to tableau column preference from previous move if no clear priority
-fn sort_moves_by_lowest_needed_cards(moves: Vec<Move>, game: &GameState, previous_tableau_column: Option<u8>) -> Vec<Move> {
+pub(crate) fn sort_moves_by_lowest_needed_cards(moves: Vec<Move>, game: &GameState, previous_tableau_column: Option<u8>) -> Vec<Move> {
     let column_lowest_ranks = get_column_lowest_needed_ranks(game);
     
     let mut move_priorities: Vec<(Move, u8)> = moves.into_iter().map(|m| {
@@ -164,6 +517,59 @@ fn sort_moves_by_lowest_needed_cards(moves: Vec<Move>, game: &GameState, previou
     move_priorities.into_iter().map(|(m, _)| m).collect()
 }
 
+/// Orders moves for expansion under `policy`, given the column the previous move came
+/// from and how many moves in a row have come out of it.
+pub(crate) fn order_moves(
+    moves: Vec<Move>,
+    game: &GameState,
+    policy: ColumnPolicy,
+    previous_tableau_column: Option<u8>,
+    column_streak: usize,
+) -> Vec<Move> {
+    match policy {
+        ColumnPolicy::PreferPrevious => sort_moves_by_lowest_needed_cards(moves, game, previous_tableau_column),
+        ColumnPolicy::Quota(limit) => {
+            let mut sorted = sort_moves_by_lowest_needed_cards(moves, game, None);
+            if let Some(column) = previous_tableau_column.filter(|_| column_streak >= limit) {
+                // Stable, so both groups keep their priority order.
+                sorted.sort_by_key(|m| get_tableau_column(&m.source) == Some(column));
+            }
+            sorted
+        }
+    }
+}
+
+/// Re-sorts `moves`, already in expansion order, by their place in that order plus
+/// `weight` for every card the freecells hold after the move. The sort is stable, so
+/// moves that fill no more cells than their neighbours keep their order.
+pub(crate) fn weigh_freecell_use(moves: Vec<Move>, game: &GameState, weight: u32) -> Vec<Move> {
+    if weight == 0 {
+        return moves;
+    }
+    let occupied = game.freecells().capacity() - game.freecells().empty_cells_count();
+    let mut costed: Vec<(usize, Move)> = moves
+        .into_iter()
+        .enumerate()
+        .map(|(place, m)| {
+            let filled = usize::from(matches!(m.destination, Location::Freecell(_)));
+            let emptied = usize::from(matches!(m.source, Location::Freecell(_)));
+            (place + weight as usize * (occupied + filled - emptied), m)
+        })
+        .collect();
+    costed.sort_by_key(|(cost, _)| *cost);
+    costed.into_iter().map(|(_, m)| m).collect()
+}
+
+/// The streak after playing `m` from a position reached by `column_streak` consecutive
+/// moves out of `previous_tableau_column`.
+fn next_column_streak(previous_tableau_column: Option<u8>, column_streak: usize, m: &Move) -> usize {
+    match get_tableau_column(&m.source) {
+        Some(column) if Some(column) == previous_tableau_column => column_streak + 1,
+        Some(_) => 1,
+        None => 0,
+    }
+}
+
 /// Worker thread function that processes work items from the shared queue
 fn worker_thread(
     thread_id: usize,
@@ -173,6 +579,9 @@ fn worker_thread(
 ) {
     let mut local_ancestors = FxHashSet::default();
     let mut local_visited = Vec::new();
+    let mut pool = StatePool::new();
+    let mut stats = shared_state.shape.as_ref().map(|_| SolverStats::default());
+    let mut frontier = Frontier::new(shared_state.frontier.lock().unwrap().limit);
     
     // Initialize local visited cache
     let lru_size = NonZeroUsize::new(100_000).unwrap();
@@ -206,13 +615,27 @@ fn worker_thread(
             }
         };
         
+        // Items wait in the queue while other workers search on; one may have reached
+        // this position since, so drop it instead of expanding it again.
+        let (score, packed) = shared_state.key(&work_item.game_state);
+        if already_visited(&shared_state.global_visited, shared_state.generation, score, &packed) {
+            shared_state.stale_pruned.fetch_add(1, Ordering::Relaxed);
+            pool.release(work_item.game_state);
+            continue;
+        }
+        
         // Process the work item
         if let Some(solution) = process_work_item(
             work_item,
+            score,
+            packed,
             &mut local_ancestors,
             &mut local_visited,
             &shared_state,
             max_depth,
+            &mut pool,
+            &mut stats,
+            &mut frontier,
         ) {
             // Found a solution!
             shared_state.solution_found.store(true, Ordering::SeqCst);
@@ -222,33 +645,73 @@ fn worker_thread(
         }
     }
     
+    if let (Some(shape), Some(stats)) = (&shared_state.shape, &stats) {
+        shape.totals.lock().unwrap().merge(stats);
+    }
+    shared_state.frontier.lock().unwrap().merge(frontier);
     // println!("Worker thread {} finished", thread_id);
 }
 
-/// Process a single work item, potentially generating new work items
+/// Whether the position keyed by `score` and `packed` is in the global table, put there
+/// during this solve. Only looks: the table is updated when a position is expanded.
+fn already_visited(global_visited: &Mutex<VisitedTables>, generation: u32, score: i32, packed: &PackedGameState) -> bool {
+    let global_visited = global_visited.lock().unwrap();
+    global_visited.get(score as usize).and_then(|table| table.peek(packed)) == Some(&generation)
+}
+
+/// Process a single work item, potentially generating new work items. `score` and
+/// `packed` are the item's [`SharedState::key`]. The item's state goes back to `pool`
+/// once it has been searched.
+#[allow(clippy::too_many_arguments)]
 fn process_work_item(
     mut work_item: WorkItem,
+    score: i32,
+    packed: PackedGameState,
     local_ancestors: &mut FxHashSet<PackedGameState>,
     local_visited: &mut Vec<LruCache<PackedGameState, (), FxBuildHasher>>,
     shared_state: &Arc<SharedState>,
     max_depth: usize,
+    pool: &mut StatePool,
+    stats: &mut Option<SolverStats>,
+    frontier: &mut Frontier,
 ) -> Option<Vec<Move>> {
-    let mut game = work_item.game_state;
-    let mut path = work_item.path;
+    let result = expand_work_item(&mut work_item, score, packed, local_ancestors, local_visited, shared_state, max_depth, pool, stats, frontier);
+    pool.release(work_item.game_state);
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+fn expand_work_item(
+    work_item: &mut WorkItem,
+    score: i32,
+    packed: PackedGameState,
+    local_ancestors: &mut FxHashSet<PackedGameState>,
+    local_visited: &mut Vec<LruCache<PackedGameState, (), FxBuildHasher>>,
+    shared_state: &Arc<SharedState>,
+    max_depth: usize,
+    pool: &mut StatePool,
+    stats: &mut Option<SolverStats>,
+    frontier: &mut Frontier,
+) -> Option<Vec<Move>> {
+    let game = &mut work_item.game_state;
+    let mut path = std::mem::take(&mut work_item.path);
     
     // Limit recursion depth
     if work_item.depth > max_depth {
+        if let Some(stats) = stats {
+            stats.record_dead_end(work_item.depth);
+        }
         return None;
     }
     
     // Check if won
     if game.is_won().unwrap_or(false) {
+        if let Some(stats) = stats {
+            stats.record_solution(path.len());
+        }
         return Some(path);
     }
     
-    let score = score_state(&game);
-    let packed = PackedGameState::from_game_state_canonical(&game);
-    
     // Check local ancestors (cycle detection)
     if local_ancestors.contains(&packed) {
         return None;
@@ -262,10 +725,45 @@ fn process_work_item(
     // Check global visited states (with lock)
     {
         let mut global_visited = shared_state.global_visited.lock().unwrap();
-        if (score as usize) < global_visited.len() && global_visited[score as usize].contains(&packed) {
+        if (score as usize) < global_visited.len() {
+            if let Some(&generation) = global_visited[score as usize].peek(&packed) {
+                if generation != shared_state.generation {
+                    shared_state.cross_deal_hits.fetch_add(1, Ordering::Relaxed);
+                }
+                return None;
+            }
+        }
+        global_visited[score as usize].put(packed.clone(), shared_state.generation);
+    }
+    
+    // Transpositions were turned away above, so the endgame probes only see new positions.
+    // A certified position finishes along its certificates without searching
+    if let Some(finish) = endgame_db::installed().and_then(|db| db.finish(game)) {
+        if path.len() + finish.len() <= max_depth {
+            path.extend(finish);
+            if let Some(stats) = stats {
+                stats.record_solution(path.len());
+            }
+            return Some(path);
+        }
+    }
+
+    // Few cards left: finish or rule out the position exactly instead of searching on
+    match endgame::solve_endgame(game) {
+        EndgameOutcome::Solved(finish) if path.len() + finish.len() <= max_depth => {
+            path.extend(finish);
+            if let Some(stats) = stats {
+                stats.record_solution(path.len());
+            }
+            return Some(path);
+        }
+        EndgameOutcome::Solved(_) | EndgameOutcome::Unsolvable => {
+            if let Some(stats) = stats {
+                stats.record_dead_end(work_item.depth);
+            }
             return None;
         }
-        global_visited[score as usize].put(packed.clone(), ());
+        EndgameOutcome::Inconclusive => {}
     }
     
     // Add to local tracking
@@ -281,6 +779,9 @@ fn process_work_item(
         game.get_freecell_to_foundation_moves(&mut moves);
         if moves.is_empty() {
             println!("Thread: No moves available at winning state");
+            if let Some(stats) = stats {
+                stats.record_dead_end(work_item.depth);
+            }
             return None;
         }
         moves
@@ -288,7 +789,26 @@ fn process_work_item(
         game.get_available_moves()
     };
     
-    let sorted_moves = sort_moves_by_lowest_needed_cards(moves, &game, work_item.previous_tableau_column);
+    frontier.offer(score, &packed, game, &path);
+    
+    if let (Some(stats), Some(shape)) = (stats.as_mut(), &shared_state.shape) {
+        let seen_before = !shape.expanded.lock().unwrap().insert(packed.clone());
+        stats.record_expansion(moves.len(), seen_before);
+        let counter = shared_state.counter.load(Ordering::Relaxed);
+        stats.record_milestones(game.foundations().total_cards(), shared_state.start_time, counter);
+        if moves.is_empty() {
+            stats.record_dead_end(work_item.depth);
+        }
+    }
+    
+    let sorted_moves = order_moves(
+        moves,
+        game,
+        shared_state.ordering.column_policy,
+        work_item.previous_tableau_column,
+        work_item.column_streak,
+    );
+    let sorted_moves = weigh_freecell_use(sorted_moves, game, shared_state.ordering.freecell_weight);
     
     // Process first few moves in this thread, add rest as work items for other threads
     let (process_here, add_to_queue) = if sorted_moves.len() > 3 && work_item.depth < max_depth / 2 {
@@ -301,7 +821,7 @@ fn process_work_item(
     if !add_to_queue.is_empty() {
         let mut queue = shared_state.work_queue.lock().unwrap();
         for m in add_to_queue {
-            let mut new_game = game.clone();
+            let mut new_game = pool.acquire_clone_of(game);
             if new_game.execute_move(m).is_ok() {
                 let mut new_path = path.clone();
                 new_path.push(m.clone());
@@ -311,15 +831,20 @@ fn process_work_item(
                     game_state: new_game,
                     path: new_path,
                     previous_tableau_column: next_preferred_column,
+                    column_streak: next_column_streak(work_item.previous_tableau_column, work_item.column_streak, m),
                     depth: work_item.depth + 1,
                 });
+            } else {
+                pool.release(new_game);
             }
         }
     }
     
     // Process moves in this thread
     for m in process_here {
-        if shared_state.solution_found.load(Ordering::SeqCst) {
+        // Checked here, not only between work items: a depth-bounded search can recurse
+        // through a huge subtree before returning to the worker loop.
+        if shared_state.should_stop() {
             break;
         }
         
@@ -329,18 +854,25 @@ fn process_work_item(
             
             // Recursively process this move
             let new_work_item = WorkItem {
-                game_state: game.clone(),
+                game_state: pool.acquire_clone_of(game),
                 path: path.clone(),
                 previous_tableau_column: next_preferred_column,
+                column_streak: next_column_streak(work_item.previous_tableau_column, work_item.column_streak, m),
                 depth: work_item.depth + 1,
             };
+            let (new_score, new_packed) = shared_state.key(game);
             
             if let Some(solution) = process_work_item(
                 new_work_item,
+                new_score,
+                new_packed,
                 local_ancestors,
                 local_visited,
                 shared_state,
                 max_depth,
+                pool,
+                stats,
+                frontier,
             ) {
                 local_ancestors.remove(&packed);
                 return Some(solution);
@@ -371,6 +903,65 @@ fn process_work_item(
 pub fn solve_with_cancel(
     game_state: GameState,
     cancel_flag: Arc<AtomicBool>,
+) -> SolverResult {
+    solve_with_cancel_policy(game_state, cancel_flag, ColumnPolicy::default())
+}
+
+/// Same as [`solve_with_cancel`], ordering moves under `ordering`: a [`ColumnPolicy`]
+/// alone, or a [`MoveOrdering`] that also weighs freecell use.
+pub fn solve_with_cancel_policy(
+    game_state: GameState,
+    cancel_flag: Arc<AtomicBool>,
+    ordering: impl Into<MoveOrdering>,
+) -> SolverResult {
+    solve_fresh(game_state, cancel_flag, ordering.into(), Packing::default(), Recording::default())
+}
+
+/// Same as [`solve_with_cancel_policy`], also recording the shape of the search tree in
+/// [`SolverResult::stats`]. The bookkeeping takes a lock per expanded position and keeps
+/// every expanded state, so it is off everywhere else.
+pub fn solve_with_cancel_stats(
+    game_state: GameState,
+    cancel_flag: Arc<AtomicBool>,
+    ordering: impl Into<MoveOrdering>,
+) -> SolverResult {
+    solve_fresh(game_state, cancel_flag, ordering.into(), Packing::default(), Recording { stats: true, snapshots: 0 })
+}
+
+/// Same as [`solve_with_cancel_policy`], packing positions for the visited tables under
+/// `packing`. [`Packing::Exact`] shows how much work canonical packing saves.
+pub fn solve_with_cancel_packing(
+    game_state: GameState,
+    cancel_flag: Arc<AtomicBool>,
+    ordering: impl Into<MoveOrdering>,
+    packing: Packing,
+) -> SolverResult {
+    solve_fresh(game_state, cancel_flag, ordering.into(), packing, Recording::default())
+}
+
+/// Same as [`solve_with_cancel_policy`], also keeping the `keep` most promising positions
+/// expanded in [`SolverResult::snapshots`], and the tree shape if `collect_stats` is set.
+///
+/// A position is more promising the fewer inversions its columns hold ([`score_state`]),
+/// then the more cards it has home, then the deeper it lies. Each worker ranks its own
+/// positions without locking; the lists are merged when the workers stop.
+pub fn solve_with_cancel_snapshots(
+    game_state: GameState,
+    cancel_flag: Arc<AtomicBool>,
+    ordering: impl Into<MoveOrdering>,
+    keep: usize,
+    collect_stats: bool,
+) -> SolverResult {
+    solve_fresh(game_state, cancel_flag, ordering.into(), Packing::default(), Recording { stats: collect_stats, snapshots: keep })
+}
+
+/// Runs one search with freshly allocated transposition tables.
+fn solve_fresh(
+    game_state: GameState,
+    cancel_flag: Arc<AtomicBool>,
+    ordering: MoveOrdering,
+    packing: Packing,
+    recording: Recording,
 ) -> SolverResult {
     // println!("Solving FreeCell game using strategy 13 (Multi-threaded strat12) with cancellation support...");
     
@@ -384,13 +975,129 @@ pub fn solve_with_cancel(
         global_visited.push(LruCache::with_hasher(lru_size, FxBuildHasher::default()));
     }
     
+    let (result, _) = run_search(game_state, global_visited, 0, cancel_flag, MAX_DEPTH, ordering, packing, recording);
+    result
+}
+
+/// Depth bound for an unconstrained search; no FreeCell solution comes close.
+const MAX_DEPTH: usize = 1000;
+
+/// Anytime variant of [`solve_with_cancel`]: the first solution is reported as soon as it
+/// is found, then the search restarts with a depth bound one move shorter than the best
+/// solution so far, and so on. Every shorter solution is passed to `on_improvement` as it
+/// is found.
+///
+/// Each restart begins with empty transposition tables, because a state pruned as
+/// visited under a looser bound may lie on a shorter solution. Workers keep waiting for
+/// work until cancelled, so the solve runs until `cancel_flag` is set and then returns
+/// the best solution found. "Shorter" is relative to what this search finds; the result
+/// is not guaranteed optimal.
+pub fn solve_with_cancel_anytime<F>(
+    game_state: GameState,
+    cancel_flag: Arc<AtomicBool>,
+    mut on_improvement: F,
+) -> SolverResult
+where
+    F: FnMut(&Improvement),
+{
+    let start = Instant::now();
+    let start_score = score_state(&game_state);
+    let lru_size = NonZeroUsize::new(1_000_000).unwrap();
+    let mut best: Option<Vec<Move>> = None;
+    let mut states_explored = 0;
+    let mut max_depth = MAX_DEPTH;
+
+    loop {
+        let global_visited = (0..=start_score as usize)
+            .map(|_| LruCache::with_hasher(lru_size, FxBuildHasher::default()))
+            .collect();
+        let (result, _) = run_search(game_state.clone(), global_visited, 0, cancel_flag.clone(), max_depth, MoveOrdering::default(), Packing::default(), Recording::default());
+        states_explored += result.states_explored;
+
+        match result.solution_moves {
+            Some(moves) if result.solved => {
+                on_improvement(&Improvement {
+                    moves: moves.clone(),
+                    elapsed: start.elapsed(),
+                    states_explored,
+                });
+                if moves.is_empty() {
+                    best = Some(moves);
+                    break;
+                }
+                max_depth = moves.len() - 1;
+                best = Some(moves);
+            }
+            _ => break,
+        }
+    }
+
+    SolverResult {
+        solved: best.is_some(),
+        solution_moves: best,
+        states_explored,
+        cross_deal_hits: 0,
+        stale_pruned: 0,
+        stats: None,
+        snapshots: Vec::new(),
+    }
+}
+
+/// Same as [`solve_with_cancel`], but keeps the global transposition table in `warm_cache`
+/// so the next solve starts with every state seen so far (experimental).
+pub fn solve_with_cancel_warm(
+    game_state: GameState,
+    cancel_flag: Arc<AtomicBool>,
+    warm_cache: Arc<Mutex<WarmCache>>,
+) -> SolverResult {
+    let start_score = score_state(&game_state);
+    
+    let (global_visited, generation) = {
+        let mut cache = warm_cache.lock().unwrap();
+        let lru_size = NonZeroUsize::new(1_000_000).unwrap();
+        while cache.tables.len() <= start_score as usize {
+            cache.tables.push(LruCache::with_hasher(lru_size, FxBuildHasher::default()));
+        }
+        cache.generation += 1;
+        (std::mem::take(&mut cache.tables), cache.generation)
+    };
+    
+    let (result, global_visited) = run_search(game_state, global_visited, generation, cancel_flag, MAX_DEPTH, MoveOrdering::default(), Packing::default(), Recording::default());
+    
+    let mut cache = warm_cache.lock().unwrap();
+    cache.tables = global_visited;
+    cache.total_cross_deal_hits += result.cross_deal_hits;
+    result
+}
+
+/// Runs the multi-threaded search against the given global table and hands the table back
+/// once every worker has finished. Only solutions of at most `max_depth` moves are accepted.
+#[allow(clippy::too_many_arguments)]
+fn run_search(
+    game_state: GameState,
+    global_visited: VisitedTables,
+    generation: u32,
+    cancel_flag: Arc<AtomicBool>,
+    max_depth: usize,
+    ordering: MoveOrdering,
+    packing: Packing,
+    recording: Recording,
+) -> (SolverResult, VisitedTables) {
     let shared_state = Arc::new(SharedState {
         work_queue: Mutex::new(VecDeque::new()),
         solution_found: AtomicBool::new(false),
         solution: Mutex::new(None),
         global_visited: Mutex::new(global_visited),
+        generation,
+        cross_deal_hits: AtomicUsize::new(0),
+        stale_pruned: AtomicU64::new(0),
         counter: AtomicUsize::new(0),
         start_time: Instant::now(),
+        cancel_flag: Some(Arc::clone(&cancel_flag)),
+        ordering,
+        packing,
+        shape: recording.stats.then(ShapeRecorder::default),
+        frontier: Mutex::new(Frontier::new(recording.snapshots)),
     });
     
     // Add initial work item
@@ -400,6 +1107,7 @@ pub fn solve_with_cancel(
             game_state: game_state.clone(),
             path: Vec::new(),
             previous_tableau_column: None,
+            column_streak: 0,
             depth: 0,
         });
     }
@@ -414,7 +1122,7 @@ pub fn solve_with_cancel(
         let cancel_flag_clone = Arc::clone(&cancel_flag);
         
         let handle = thread::spawn(move || {
-            worker_thread(i, shared_state_clone, Some(cancel_flag_clone), 1000);
+            worker_thread(i, shared_state_clone, Some(cancel_flag_clone), max_depth);
         });
         handles.push(handle);
     }
@@ -425,7 +1133,15 @@ pub fn solve_with_cancel(
     }
     
     let final_count = shared_state.counter.load(Ordering::SeqCst);
+    let cross_deal_hits = shared_state.cross_deal_hits.load(Ordering::SeqCst);
+    let stale_pruned = shared_state.stale_pruned.load(Ordering::SeqCst);
     let elapsed = shared_state.start_time.elapsed();
+    let global_visited = std::mem::take(&mut *shared_state.global_visited.lock().unwrap());
+    let stats = shared_state.shape.as_ref().map(|shape| std::mem::take(&mut *shape.totals.lock().unwrap()));
+    let snapshots = std::mem::take(&mut shared_state.frontier.lock().unwrap().entries)
+        .into_iter()
+        .map(|(_, snapshot)| snapshot)
+        .collect();
     
     if shared_state.solution_found.load(Ordering::SeqCst) {
         let solution = shared_state.solution.lock().unwrap().clone();
@@ -436,10 +1152,16 @@ pub fn solve_with_cancel(
             //     elapsed,
             //     final_count
             // );
-            return SolverResult {
+            let result = SolverResult {
                 solved: true,
                 solution_moves: Some(moves),
+                states_explored: final_count,
+                cross_deal_hits,
+                stale_pruned,
+                stats,
+                snapshots,
             };
+            return (result, global_visited);
         }
     }
     
@@ -449,10 +1171,16 @@ pub fn solve_with_cancel(
     //     elapsed
     // );
     
-    SolverResult {
+    let result = SolverResult {
         solved: false,
         solution_moves: None,
-    }
+        states_explored: final_count,
+        cross_deal_hits,
+        stale_pruned,
+        stats,
+        snapshots,
+    };
+    (result, global_visited)
 }
 
 pub fn solve(game_state: GameState) {
@@ -473,8 +1201,16 @@ pub fn solve(game_state: GameState) {
         solution_found: AtomicBool::new(false),
         solution: Mutex::new(None),
         global_visited: Mutex::new(global_visited),
+        generation: 0,
+        cross_deal_hits: AtomicUsize::new(0),
+        stale_pruned: AtomicU64::new(0),
         counter: AtomicUsize::new(0),
         start_time: Instant::now(),
+        cancel_flag: None,
+        ordering: MoveOrdering::default(),
+        packing: Packing::default(),
+        shape: None,
+        frontier: Mutex::new(Frontier::default()),
     });
     
     // Add initial work item
@@ -484,6 +1220,7 @@ pub fn solve(game_state: GameState) {
             game_state: game_state.clone(),
             path: Vec::new(),
             previous_tableau_column: None,
+            column_streak: 0,
             depth: 0,
         });
     }
@@ -497,7 +1234,7 @@ pub fn solve(game_state: GameState) {
         let shared_state_clone = Arc::clone(&shared_state);
         
         let handle = thread::spawn(move || {
-            worker_thread(i, shared_state_clone, None, 1000);
+            worker_thread(i, shared_state_clone, None, MAX_DEPTH);
         });
         handles.push(handle);
     }
@@ -534,3 +1271,171 @@ pub fn solve(game_state: GameState) {
     //     elapsed
     // );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::generation::generate_deal;
+
+    #[test]
+    fn quota_demotes_the_previous_column_once_used_up() {
+        let game = generate_deal(1).unwrap();
+        let moves = game.get_available_moves();
+        let from_column = |m: &Move| get_tableau_column(&m.source) == Some(3);
+        assert!(moves.iter().any(from_column) && !moves.iter().all(from_column));
+
+        let under_quota = order_moves(moves.clone(), &game, ColumnPolicy::Quota(2), Some(3), 1);
+        assert_eq!(under_quota, sort_moves_by_lowest_needed_cards(moves.clone(), &game, None));
+
+        let spent = order_moves(moves, &game, ColumnPolicy::Quota(2), Some(3), 2);
+        let first_demoted = spent.iter().position(from_column).unwrap();
+        assert!(spent[first_demoted..].iter().all(from_column));
+    }
+
+    #[test]
+    fn freecell_weight_pushes_moves_into_cells_back() {
+        let into_cell = |m: &Move| matches!(m.destination, Location::Freecell(_));
+        // The first deal with a move that keeps the cells empty.
+        let (game, ordered) = (1..)
+            .map(|seed| {
+                let game = generate_deal(seed).unwrap();
+                let ordered = order_moves(game.get_available_moves(), &game, ColumnPolicy::default(), None, 0);
+                (game, ordered)
+            })
+            .find(|(_, ordered)| !ordered.iter().all(into_cell))
+            .unwrap();
+        assert!(ordered.iter().any(into_cell));
+        assert_eq!(weigh_freecell_use(ordered.clone(), &game, 0), ordered);
+
+        let weighed = weigh_freecell_use(ordered.clone(), &game, 100);
+        let first_into_cell = weighed.iter().position(into_cell).unwrap();
+        assert!(weighed[first_into_cell..].iter().all(into_cell));
+        // Within each group the column policy's order stands.
+        let others: Vec<Move> = ordered.iter().copied().filter(|m| !into_cell(m)).collect();
+        assert_eq!(weighed[..first_into_cell], others[..]);
+    }
+
+    #[test]
+    fn streak_counts_consecutive_moves_from_one_column() {
+        let from_3 = Move::tableau_to_freecell(3, 0).unwrap();
+        assert_eq!(next_column_streak(Some(3), 2, &from_3), 3);
+        assert_eq!(next_column_streak(Some(1), 2, &from_3), 1);
+        assert_eq!(next_column_streak(Some(3), 2, &Move::freecell_to_tableau(0, 3).unwrap()), 0);
+        assert_eq!("quota:3".parse(), Ok(ColumnPolicy::Quota(3)));
+        assert_eq!(ColumnPolicy::Quota(3).to_string(), "quota:3");
+    }
+
+    #[test]
+    fn exact_packing_tells_column_orders_apart() {
+        let game = generate_deal(1).unwrap();
+        let (reordered, _) = game.normalize();
+        assert_ne!(Packing::Exact.pack(&reordered), Packing::Exact.pack(&game));
+        assert_eq!(Packing::Canonical.pack(&reordered), Packing::Canonical.pack(&game));
+        assert_eq!(Packing::Exact.to_string(), "exact");
+    }
+
+    #[test]
+    fn stats_average_and_merge() {
+        let mut stats = SolverStats::default();
+        stats.record_expansion(2, false);
+        stats.record_expansion(4, true);
+        stats.record_dead_end(10);
+        assert_eq!(stats.average_branching(), Some(3.0));
+        assert_eq!(stats.average_dead_end_depth(), Some(10.0));
+        assert_eq!(stats.average_solution_depth(), None);
+
+        let mut other = SolverStats::default();
+        other.record_expansion(6, false);
+        other.record_solution(80);
+        stats.merge(&other);
+        assert_eq!(stats.branching, vec![0, 0, 1, 0, 1, 0, 1]);
+        assert_eq!((stats.expanded, stats.re_expansions, stats.solutions), (3, 1, 1));
+        assert_eq!(stats.average_solution_depth(), Some(80.0));
+    }
+
+    #[test]
+    fn queued_positions_are_stale_once_visited_this_solve() {
+        let game = generate_deal(1).unwrap();
+        let score = score_state(&game) as usize;
+        let tables: VisitedTables = (0..=score)
+            .map(|_| LruCache::with_hasher(NonZeroUsize::new(10).unwrap(), FxBuildHasher::default()))
+            .collect();
+        let tables = Mutex::new(tables);
+        let packed = Packing::Canonical.pack(&game);
+        assert!(!already_visited(&tables, 1, score as i32, &packed));
+
+        tables.lock().unwrap()[score].put(packed.clone(), 1);
+        assert!(already_visited(&tables, 1, score as i32, &packed));
+        // Left by an earlier deal: a cross-deal hit, counted when expanded.
+        assert!(!already_visited(&tables, 2, score as i32, &packed));
+    }
+
+    /// Searches deal 1 to `max_depth` moves, cancelling once the small tree is done.
+    fn shallow_search(max_depth: usize, recording: Recording) -> SolverResult {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let canceller = {
+            let cancel = cancel.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(300));
+                cancel.store(true, Ordering::SeqCst);
+            })
+        };
+        let game = generate_deal(1).unwrap();
+        let tables = (0..=score_state(&game) as usize)
+            .map(|_| LruCache::with_hasher(NonZeroUsize::new(1000).unwrap(), FxBuildHasher::default()))
+            .collect();
+        let (result, _) = run_search(game, tables, 0, cancel, max_depth, MoveOrdering::default(), Packing::default(), recording);
+        canceller.join().unwrap();
+        result
+    }
+
+    #[test]
+    fn milestones_keep_the_first_time_each_is_reached() {
+        let mut early = SolverStats::default();
+        early.record_milestones(14, Instant::now(), 10);
+        early.record_milestones(13, Instant::now(), 20);
+        let reached: Vec<_> = early.milestones.iter().map(|m| (m.foundation_cards, m.states_explored)).collect();
+        assert_eq!(reached, [(1, 10), (13, 10)]);
+
+        let at = |foundation_cards, elapsed_ms| Milestone { foundation_cards, elapsed_ms, states_explored: elapsed_ms };
+        early.milestones = vec![at(1, 5), at(13, 40)];
+        let mut late = SolverStats { milestones: vec![at(1, 8), at(13, 30), at(26, 90)], ..SolverStats::default() };
+        late.merge(&early);
+        assert_eq!(late.milestones, [at(1, 5), at(13, 30), at(26, 90)]);
+    }
+
+    #[test]
+    fn stats_are_only_collected_when_asked_for() {
+        assert!(shallow_search(2, Recording::default()).stats.is_none());
+
+        let stats = shallow_search(2, Recording { stats: true, snapshots: 0 }).stats.unwrap();
+        assert!(stats.expanded > 0);
+        assert_eq!(stats.expanded, stats.branching.iter().sum::<u64>());
+        assert!(stats.dead_ends > 0);
+        assert_eq!(stats.average_dead_end_depth(), Some(3.0));
+        assert_eq!(stats.solutions, 0);
+    }
+
+    #[test]
+    fn snapshots_keep_the_most_promising_distinct_positions() {
+        let result = shallow_search(3, Recording { stats: false, snapshots: 5 });
+        assert!(!result.solved);
+        assert!(shallow_search(3, Recording::default()).snapshots.is_empty());
+
+        let snapshots = result.snapshots;
+        assert_eq!(snapshots.len(), 5);
+        assert!(snapshots.windows(2).all(|pair| pair[0].rank() <= pair[1].rank()));
+        let start = generate_deal(1).unwrap();
+        for snapshot in &snapshots {
+            let mut game = start.clone();
+            for m in &snapshot.path {
+                game.execute_move(m).unwrap();
+            }
+            assert_eq!(game, snapshot.state);
+            assert_eq!(score_state(&game), snapshot.score);
+        }
+        let distinct: FxHashSet<_> =
+            snapshots.iter().map(|s| PackedGameState::from_game_state_canonical(&s.state)).collect();
+        assert_eq!(distinct.len(), snapshots.len());
+    }
+}
@@ -0,0 +1,268 @@
+//! Strategy 14: breadth-first while memory allows, depth-first after.
+//!
+//! Breadth-first search sees every position at its shallowest depth, so each
+//! transposition is caught the first time round; its cost is that every level of the
+//! tree must be held at once. Strategy 13 sidesteps that with fixed-size LRU tables that
+//! simply forget states once full. This strategy instead searches level by level and
+//! keeps an account of what the levels and the visited set hold. When the
+//! accountant reports pressure, the levels are frozen and each position not yet expanded
+//! is searched depth-first in turn, with only an LRU table sized from the same budget and
+//! the current path added on top.
+//!
+//! The frozen visited set still prunes the depth-first phase: a position in it was either
+//! expanded breadth-first, so its children are already queued, or is itself one of the
+//! queued starting points.
+
+use crate::endgame::{self, EndgameOutcome};
+use crate::packed_state::PackedGameState;
+use crate::strategies::search_core::{ordered_moves, solver_result, try_line, DfsCore, Visit};
+use crate::strategies::strat13::solve::SolverResult;
+use freecell_game_engine::{r#move::Move, GameState};
+use fxhash::FxHashSet;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// What the breadth-first phase may hold before switching, when the plan sets no memory
+/// limit to derive a budget from. Kept modest: solutions run to around a hundred moves,
+/// far deeper than any affordable breadth-first level, so the time is better spent
+/// depth-first once the shallow transpositions are covered.
+pub const DEFAULT_MEMORY_BUDGET: usize = 64 * 1024 * 1024;
+
+/// Most entries the depth-first phase's transposition table holds.
+const DFS_CACHE_ENTRIES: usize = 1_000_000;
+
+/// Rough heap bytes per depth-first table entry: the key plus the LRU's links and slot.
+const DFS_CACHE_ENTRY_BYTES: usize = PackedGameState::SIZE_BYTES + 48;
+
+/// A position in a breadth-first level: the move that reached it and the index of its
+/// parent in the level above. Positions are replayed from the root when expanded rather
+/// than stored, which keeps a node to a few bytes.
+#[derive(Clone, Copy)]
+struct Node {
+    parent: u32,
+    mv: Move,
+}
+
+/// Parent index of the nodes in the first level, whose parent is the root.
+const ROOT: u32 = u32::MAX;
+
+/// Tracks the heap the breadth-first phase holds and reports when it passes the budget.
+///
+/// Sizes come from capacities, not lengths, since that is what is allocated; the visited
+/// set is estimated with [`PackedGameState::visited_memory_bytes`].
+struct MemoryAccountant {
+    budget: usize,
+    used: usize,
+}
+
+impl MemoryAccountant {
+    fn new(budget: usize) -> Self {
+        MemoryAccountant { budget, used: 0 }
+    }
+
+    /// Re-measures the visited set and every level, the one being filled included.
+    fn measure(&mut self, visited: &FxHashSet<PackedGameState>, levels: &[Vec<Node>], next: &Vec<Node>) {
+        let node_bytes = std::mem::size_of::<Node>();
+        let level_bytes: usize = levels.iter().chain(std::iter::once(next)).map(|level| level.capacity() * node_bytes).sum();
+        self.used = PackedGameState::visited_memory_bytes(visited) + level_bytes;
+    }
+
+    /// Whether the last measurement was over budget.
+    fn under_pressure(&self) -> bool {
+        self.used > self.budget
+    }
+}
+
+/// State of the depth-first phase, shared across every starting point.
+struct DepthFirst<'a> {
+    frozen: &'a FxHashSet<PackedGameState>,
+    core: DfsCore<'a>,
+}
+
+/// Solves `game_state`, switching to depth-first once the breadth-first phase holds
+/// more than `memory_budget` bytes.
+pub fn solve_with_cancel_budget(game_state: GameState, cancel_flag: Arc<AtomicBool>, memory_budget: usize) -> SolverResult {
+    search(game_state, &cancel_flag, memory_budget)
+}
+
+fn search(root: GameState, cancel_flag: &AtomicBool, memory_budget: usize) -> SolverResult {
+    let finish = solver_result;
+    if root.is_won().unwrap_or(false) {
+        return finish(Some(Vec::new()), 0);
+    }
+
+    let mut accountant = MemoryAccountant::new(memory_budget);
+    let mut visited = FxHashSet::default();
+    visited.insert(PackedGameState::from_game_state_canonical(&root));
+    // levels[d] holds the positions d + 1 moves from the root.
+    let mut levels: Vec<Vec<Node>> = Vec::new();
+    let mut states_explored = 0;
+
+    loop {
+        let depth = levels.len();
+        let width = levels.last().map_or(1, Vec::len);
+        let mut next = Vec::new();
+        for index in 0..width {
+            if cancel_flag.load(Ordering::SeqCst) {
+                return finish(None, states_explored);
+            }
+            let parent = if depth == 0 { ROOT } else { index as u32 };
+            let path = path_to(&levels, parent);
+            let mut game = replay(&root, &path);
+            states_explored += 1;
+            match endgame::solve_endgame(&game) {
+                EndgameOutcome::Solved(rest) => return finish(Some([path, rest].concat()), states_explored),
+                EndgameOutcome::Unsolvable => continue,
+                EndgameOutcome::Inconclusive => {}
+            }
+            for m in game.get_available_moves() {
+                if game.execute_move(&m).is_err() {
+                    continue;
+                }
+                if game.is_won().unwrap_or(false) {
+                    let mut solution = path.clone();
+                    solution.push(m);
+                    return finish(Some(solution), states_explored);
+                }
+                if visited.insert(PackedGameState::from_game_state_canonical(&game)) {
+                    next.push(Node { parent, mv: m });
+                }
+                game.undo_move(&m);
+            }
+
+            accountant.measure(&visited, &levels, &next);
+            if accountant.under_pressure() {
+                // Unexpanded positions of this level first, then everything queued below.
+                let pending_here = (index + 1..width).map(|i| if depth == 0 { ROOT } else { i as u32 });
+                let mut pending_paths = pending_here.map(|i| path_to(&levels, i)).chain(next.iter().map(|node| {
+                    let mut path = path_to(&levels, node.parent);
+                    path.push(node.mv);
+                    path
+                }));
+                let mut core = DfsCore::new(dfs_cache_entries(memory_budget), cancel_flag);
+                core.states_explored = states_explored;
+                let mut dfs = DepthFirst { frozen: &visited, core };
+                let solution = pending_paths.find_map(|mut path| {
+                    let mut game = replay(&root, &path);
+                    dfs.search(&mut game, &mut path).then_some(path)
+                });
+                return finish(solution, dfs.core.states_explored);
+            }
+        }
+        if next.is_empty() {
+            return finish(None, states_explored);
+        }
+        levels.push(next);
+    }
+}
+
+/// Entries for the depth-first table: as many as half the budget again pays for, so the
+/// table does not outgrow the levels it joins.
+fn dfs_cache_entries(memory_budget: usize) -> NonZeroUsize {
+    let entries = (memory_budget / 2 / DFS_CACHE_ENTRY_BYTES).clamp(1024, DFS_CACHE_ENTRIES);
+    NonZeroUsize::new(entries).unwrap()
+}
+
+/// The moves from the root to node `index` of the deepest level, or none for [`ROOT`].
+fn path_to(levels: &[Vec<Node>], mut index: u32) -> Vec<Move> {
+    let mut path = Vec::with_capacity(levels.len());
+    for level in levels.iter().rev() {
+        if index == ROOT {
+            break;
+        }
+        let node = level[index as usize];
+        path.push(node.mv);
+        index = node.parent;
+    }
+    path.reverse();
+    path
+}
+
+fn replay(root: &GameState, path: &[Move]) -> GameState {
+    let mut game = root.clone();
+    for m in path {
+        game.execute_move(m).expect("recorded moves replay");
+    }
+    game
+}
+
+impl DepthFirst<'_> {
+    /// Searches below `game`, reached by `path`. On success `path` holds the solution;
+    /// otherwise it is left as it was.
+    fn search(&mut self, game: &mut GameState, path: &mut Vec<Move>) -> bool {
+        let packed = match self.core.enter(game, path) {
+            Visit::Solved => return true,
+            Visit::Pruned => return false,
+            Visit::Expand(packed) => packed,
+        };
+        let found = ordered_moves(game, path).into_iter().any(|m| {
+            try_line(self, game, path, &[m], |dfs, game, path| {
+                !dfs.frozen.contains(&PackedGameState::from_game_state_canonical(game)) && dfs.search(game, path)
+            })
+        });
+        self.core.leave(&packed);
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freecell_game_engine::fixtures;
+    use freecell_game_engine::import::import_board;
+    use freecell_game_engine::Variant;
+
+    /// Sixteen cards in play, too many for the endgame solver, with the ten of clubs
+    /// buried under its own jack, queen and king; a few plies deep.
+    fn buried_club() -> GameState {
+        let board = "Foundations: 9S 9H 9D 9C\n: KS QS JS 10S\n: KH QH JH 10H\n: KD QD JD 10D\n: 10C JC QC KC";
+        import_board(board, Variant::Standard).unwrap().state
+    }
+
+    fn check_solution(start: &GameState, moves: &[Move]) {
+        let mut game = start.clone();
+        for m in moves {
+            game.execute_move(m).unwrap();
+        }
+        assert!(game.is_won().unwrap());
+    }
+
+    #[test]
+    fn solves_breadth_first_when_memory_allows() {
+        let start = buried_club();
+        assert_eq!(endgame::solve_endgame(&start), EndgameOutcome::Inconclusive);
+        let result = search(start.clone(), &AtomicBool::new(false), DEFAULT_MEMORY_BUDGET);
+        check_solution(&start, result.solution_moves.as_deref().unwrap());
+    }
+
+    #[test]
+    fn switches_to_depth_first_under_pressure() {
+        let start = buried_club();
+        let result = search(start.clone(), &AtomicBool::new(false), 0);
+        check_solution(&start, result.solution_moves.as_deref().unwrap());
+    }
+
+    #[test]
+    fn dead_positions_and_cancellation_end_unsolved() {
+        let stuck = fixtures::by_name("no_legal_moves").unwrap().state();
+        assert!(!solve_with_cancel_budget(stuck, Arc::new(AtomicBool::new(false)), DEFAULT_MEMORY_BUDGET).solved);
+
+        let deal = freecell_game_engine::generation::generate_deal(1).unwrap();
+        assert!(!solve_with_cancel_budget(deal, Arc::new(AtomicBool::new(true)), DEFAULT_MEMORY_BUDGET).solved);
+    }
+
+    #[test]
+    fn accountant_counts_capacity() {
+        let mut accountant = MemoryAccountant::new(1);
+        let visited = FxHashSet::default();
+        accountant.measure(&visited, &[], &Vec::new());
+        assert_eq!(accountant.used, 0);
+        assert!(!accountant.under_pressure());
+
+        let next = Vec::with_capacity(4);
+        accountant.measure(&visited, &[], &next);
+        assert_eq!(accountant.used, 4 * std::mem::size_of::<Node>());
+        assert!(accountant.under_pressure());
+    }
+}
@@ -0,0 +1,111 @@
+//! Strategy 15: depth-first search that aims for subgoals before single moves.
+//!
+//! Strong players do not weigh every move on its own; they pick a milestone, such as
+//! emptying a column or freeing a buried ace, and work towards it. At each position this
+//! strategy asks the [`subgoal`] planner for a short line of play reaching each of a few
+//! candidate milestones, and tries those lines as single macro moves before the ordinary
+//! move-by-move branches. Reaching a milestone in one step jumps over the positions in
+//! between, which the plain search would otherwise have to order and visit one by one.
+//!
+//! Failed plans are cheap, since the planner works within a small depth and node budget;
+//! the ordinary branches that follow keep the search complete.
+
+use crate::bounded_search::{SearchBudget, SearchOutcome};
+use crate::strategies::search_core::{ordered_moves, solver_result, try_line, DfsCore, Visit};
+use crate::strategies::strat13::solve::SolverResult;
+use crate::strategies::strat15::subgoal::{self, candidates};
+use freecell_game_engine::{r#move::Move, GameState};
+use std::num::NonZeroUsize;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Most moves a plan may take, and positions the planner may expand for a single subgoal.
+const PLAN_BUDGET: SearchBudget = SearchBudget { max_depth: 12, max_nodes: 2_000 };
+
+/// Entries in the transposition table.
+const CACHE_ENTRIES: usize = 1_000_000;
+
+struct MacroSearch<'a> {
+    /// Its count of positions expanded includes the planner's.
+    core: DfsCore<'a>,
+}
+
+/// Solves `game_state`, trying subgoal plans before single moves at every position.
+pub fn solve_with_cancel(game_state: GameState, cancel_flag: Arc<AtomicBool>) -> SolverResult {
+    let mut search = MacroSearch { core: DfsCore::new(NonZeroUsize::new(CACHE_ENTRIES).unwrap(), &cancel_flag) };
+    let mut game = game_state;
+    let mut path = Vec::new();
+    let solution = search.search(&mut game, &mut path).then_some(path);
+    solver_result(solution, search.core.states_explored)
+}
+
+impl MacroSearch<'_> {
+    /// Searches below `game`, reached by `path`. On success `path` holds the solution;
+    /// otherwise it is left as it was.
+    fn search(&mut self, game: &mut GameState, path: &mut Vec<Move>) -> bool {
+        let packed = match self.core.enter(game, path) {
+            Visit::Solved => return true,
+            Visit::Pruned => return false,
+            Visit::Expand(packed) => packed,
+        };
+        let found = self.try_plans(game, path) || self.try_moves(game, path);
+        self.core.leave(&packed);
+        found
+    }
+
+    /// Plays the plan for each candidate subgoal in turn, searching on from where it ends.
+    fn try_plans(&mut self, game: &mut GameState, path: &mut Vec<Move>) -> bool {
+        for goal in candidates(game) {
+            let report = subgoal::plan(game, goal, PLAN_BUDGET, self.core.cancel_flag());
+            self.core.states_explored += report.explored;
+            // A one-move plan is tried again among the single moves anyway.
+            let SearchOutcome::Found(moves) = report.outcome else {
+                continue;
+            };
+            if moves.len() <= 1 {
+                continue;
+            }
+            if try_line(self, game, path, &moves, Self::search) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn try_moves(&mut self, game: &mut GameState, path: &mut Vec<Move>) -> bool {
+        ordered_moves(game, path).into_iter().any(|m| try_line(self, game, path, &[m], Self::search))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endgame::{self, EndgameOutcome};
+    use freecell_game_engine::fixtures;
+    use freecell_game_engine::import::import_board;
+    use freecell_game_engine::Variant;
+
+    #[test]
+    fn solves_through_subgoal_plans() {
+        // Sixteen cards in play, the ten of clubs under its own jack, queen and king.
+        let board = "Foundations: 9S 9H 9D 9C\n: KS QS JS 10S\n: KH QH JH 10H\n: KD QD JD 10D\n: 10C JC QC KC";
+        let start = import_board(board, Variant::Standard).unwrap().state;
+        assert_eq!(endgame::solve_endgame(&start), EndgameOutcome::Inconclusive);
+
+        let result = solve_with_cancel(start.clone(), Arc::new(AtomicBool::new(false)));
+        let mut game = start;
+        for m in result.solution_moves.as_deref().unwrap() {
+            game.execute_move(m).unwrap();
+        }
+        assert!(game.is_won().unwrap());
+    }
+
+    #[test]
+    fn dead_positions_and_cancellation_end_unsolved() {
+        let stuck = fixtures::by_name("no_legal_moves").unwrap().state();
+        assert!(!solve_with_cancel(stuck, Arc::new(AtomicBool::new(false))).solved);
+
+        let deal = freecell_game_engine::generation::generate_deal(1).unwrap();
+        assert!(!solve_with_cancel(deal, Arc::new(AtomicBool::new(true))).solved);
+    }
+}
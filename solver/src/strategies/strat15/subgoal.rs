@@ -0,0 +1,161 @@
+//! Subgoals for strategy 15 and the planner that reaches them.
+//!
+//! A subgoal is a milestone players aim for on purpose: empty this column, get that ace
+//! home. [`plan`] looks for a short line of play that meets one with a
+//! [`bounded_search`] that only tries moves working towards it: nothing is put back onto
+//! the column being emptied or onto the column holding the ace, and moves off that
+//! column are tried first. The search stops at a fixed depth and node budget, so a
+//! subgoal out of easy reach costs little and simply fails.
+
+use crate::bounded_search::{bounded_search, SearchBudget, SearchGoal, SearchReport};
+use crate::strategies::strat13::solve::{get_tableau_column, order_moves, ColumnPolicy};
+use freecell_game_engine::game_state::heuristics::column_clearance;
+use freecell_game_engine::location::Location;
+use freecell_game_engine::{r#move::Move, Card, GameState, Rank, Suit};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Subgoals [`candidates`] offers at most, so planning stays a small share of the search.
+const MAX_CANDIDATES: usize = 4;
+
+/// A milestone for the planner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Subgoal {
+    /// The tableau column with this index holds no cards.
+    EmptyColumn(u8),
+    /// The ace of this suit is on its foundation.
+    FreeAce(Suit),
+}
+
+impl Subgoal {
+    pub fn is_met(&self, game: &GameState) -> bool {
+        match *self {
+            Subgoal::EmptyColumn(column) => game.tableau().get_column(column as usize).is_ok_and(|cards| cards.is_empty()),
+            Subgoal::FreeAce(suit) => {
+                game.foundations().needed_cards()[suit.foundation_index() as usize] != Some(Card::new(Rank::Ace, suit))
+            }
+        }
+    }
+
+    /// The column the subgoal digs into, which no move may add cards to.
+    fn column(&self, game: &GameState) -> Option<u8> {
+        match *self {
+            Subgoal::EmptyColumn(column) => Some(column),
+            Subgoal::FreeAce(suit) => ace_column(game, suit).map(|(column, _)| column),
+        }
+    }
+}
+
+/// The column holding the ace of `suit`, and how many cards lie on top of it.
+fn ace_column(game: &GameState, suit: Suit) -> Option<(u8, usize)> {
+    let ace = Card::new(Rank::Ace, suit);
+    game.tableau().columns().enumerate().find_map(|(index, cards)| {
+        let depth = cards.iter().position(|card| *card == ace)?;
+        Some((index as u8, cards.len() - 1 - depth))
+    })
+}
+
+/// The subgoals worth planning for in `game`, most promising first: aces still in the
+/// tableau by how few cards cover them, then non-empty columns by how few cards would
+/// be left stuck in them ([`column_clearance`]). Aces in a freecell are left to the
+/// ordinary search, which sends them home in one move.
+pub fn candidates(game: &GameState) -> Vec<Subgoal> {
+    let mut aces: Vec<(usize, Subgoal)> = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs]
+        .into_iter()
+        .filter_map(|suit| ace_column(game, suit).map(|(_, covered)| (covered, Subgoal::FreeAce(suit))))
+        .collect();
+    aces.sort_by_key(|&(covered, _)| covered);
+
+    let mut columns: Vec<(usize, usize, Subgoal)> = column_clearance(game)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, clearance)| clearance.cards > 0)
+        .map(|(index, clearance)| (clearance.remaining(), clearance.cards, Subgoal::EmptyColumn(index as u8)))
+        .collect();
+    columns.sort_by_key(|&(remaining, cards, _)| (remaining, cards));
+
+    aces.into_iter()
+        .map(|(_, goal)| goal)
+        .chain(columns.into_iter().map(|(_, _, goal)| goal))
+        .take(MAX_CANDIDATES)
+        .collect()
+}
+
+/// Looks for the shortest line from `game` that meets `subgoal` within `budget`. Ends
+/// [`Cancelled`](crate::bounded_search::SearchOutcome::Cancelled) once `cancel_flag` is
+/// set.
+pub fn plan(game: &GameState, subgoal: Subgoal, budget: SearchBudget, cancel_flag: &AtomicBool) -> SearchReport {
+    bounded_search(game, budget, Planner { subgoal, column: subgoal.column(game), cancel_flag })
+}
+
+struct Planner<'a> {
+    subgoal: Subgoal,
+    /// The column no move may add cards to.
+    column: Option<u8>,
+    cancel_flag: &'a AtomicBool,
+}
+
+impl SearchGoal for Planner<'_> {
+    fn is_met(&mut self, game: &GameState, _line: &[Move]) -> bool {
+        self.subgoal.is_met(game)
+    }
+
+    fn moves(&mut self, game: &GameState, line: &[Move]) -> Vec<Move> {
+        let previous_column = line.last().and_then(|m| get_tableau_column(&m.source));
+        let mut moves = order_moves(game.get_available_moves(), game, ColumnPolicy::default(), previous_column, 0);
+        moves.retain(|m| !matches!(m.destination, Location::Tableau(to) if Some(to.index()) == self.column));
+        // Digging into the target column first; the sort is stable.
+        moves.sort_by_key(|m| get_tableau_column(&m.source) != self.column);
+        moves
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel_flag.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounded_search::SearchOutcome;
+    use freecell_game_engine::import::import_board;
+    use freecell_game_engine::Variant;
+
+    /// The ace of clubs under three cards in column 0: the 8♥ can go home, the K♦ and
+    /// the 5♠ need somewhere to wait.
+    fn buried_ace() -> GameState {
+        let board = "Foundations: 7H 9D 2S\n: AC 5S KD 8H\n: 9S\n: 10C JC QC KC\n: 2C 3C 4C 5C 6C 7C 8C 9C\n\
+                     : 3S 4S 6S 7S 8S 10S JS QS KS\n: 10H JH QH KH\n: 10D JD QD\n";
+        import_board(board, Variant::Standard).unwrap().state
+    }
+
+    #[test]
+    fn plans_the_shortest_dig_to_a_buried_ace() {
+        let game = buried_ace();
+        assert!(!Subgoal::FreeAce(Suit::Clubs).is_met(&game));
+        assert_eq!(candidates(&game)[0], Subgoal::FreeAce(Suit::Clubs));
+
+        let budget = SearchBudget { max_depth: 6, max_nodes: 10_000 };
+        let report = plan(&game, Subgoal::FreeAce(Suit::Clubs), budget, &AtomicBool::new(false));
+        let SearchOutcome::Found(moves) = report.outcome else { panic!("the ace is four moves away") };
+        assert_eq!(moves.len(), 4);
+        let mut after = game.clone();
+        for m in &moves {
+            after.execute_move(m).unwrap();
+        }
+        assert!(Subgoal::FreeAce(Suit::Clubs).is_met(&after));
+        assert!(Subgoal::EmptyColumn(0).is_met(&after));
+    }
+
+    #[test]
+    fn budgets_and_cancellation_end_plans_early() {
+        let game = buried_ace();
+        let goal = Subgoal::FreeAce(Suit::Clubs);
+        let budget = SearchBudget { max_depth: 6, max_nodes: 10_000 };
+        let running = AtomicBool::new(false);
+        let short = plan(&game, goal, SearchBudget { max_depth: 3, ..budget }, &running);
+        assert_eq!(short.outcome, SearchOutcome::Unreachable);
+        let starved = plan(&game, goal, SearchBudget { max_nodes: 5, ..budget }, &running);
+        assert_eq!((starved.outcome, starved.explored), (SearchOutcome::OutOfNodes, 5));
+        assert_eq!(plan(&game, goal, budget, &AtomicBool::new(true)).outcome, SearchOutcome::Cancelled);
+    }
+}
@@ -0,0 +1,238 @@
+//! `solver watch <board-file>`: re-solve a board every time its file changes.
+//!
+//! Meant as a live assistant while playing in another program: keep the board typed up
+//! in a text file (in the format [`import_board`] accepts), save after each move, and
+//! the solver prints a fresh line of play from the new position.
+//!
+//! The file is polled rather than watched through OS notifications, which keeps the
+//! solver free of platform-specific dependencies. Editors often write a file in several
+//! steps, so a change is only acted on once the file has stopped changing for a short
+//! debounce period. All solves share one warm cache, so positions explored for earlier
+//! versions of the board are not searched again.
+
+use crate::benchmark_plan::PlanError;
+use crate::bench;
+use crate::solve;
+use freecell_game_engine::display::SolutionPhases;
+use freecell_game_engine::import::import_board;
+use freecell_game_engine::{GameState, Variant};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Settings for a watch session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchOptions {
+    pub path: PathBuf,
+    /// Time budget for each solve. Quick by default: the board will change again soon.
+    pub timeout_secs: u64,
+    pub poll_interval: Duration,
+    /// How long the file must stay unchanged before it is read.
+    pub debounce: Duration,
+    /// Spend the whole timeout looking for shorter solutions, printing each one found.
+    pub anytime: bool,
+}
+
+impl WatchOptions {
+    /// Parses the arguments following `watch`: a file path, then optional
+    /// `--timeout <secs>`, `--poll-ms <millis>` and `--anytime`.
+    pub fn from_args<I>(args: I) -> Result<Self, PlanError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut path = None;
+        let mut options = WatchOptions {
+            path: PathBuf::new(),
+            timeout_secs: 10,
+            poll_interval: Duration::from_millis(250),
+            debounce: Duration::from_millis(500),
+            anytime: false,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut number = |flag: &str| {
+                let raw = args.next().ok_or_else(|| PlanError::MissingValue(flag.to_string()))?;
+                raw.parse::<u64>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or(PlanError::InvalidValue { flag: flag.to_string(), value: raw })
+            };
+            match arg.as_str() {
+                "--timeout" => options.timeout_secs = number("--timeout")?,
+                "--poll-ms" => options.poll_interval = Duration::from_millis(number("--poll-ms")?),
+                "--anytime" => options.anytime = true,
+                flag if flag.starts_with("--") => return Err(PlanError::UnknownArgument(arg)),
+                _ if path.is_none() => path = Some(PathBuf::from(arg)),
+                _ => return Err(PlanError::UnknownArgument(arg)),
+            }
+        }
+        options.path = path.ok_or_else(|| PlanError::MissingValue("watch".to_string()))?;
+        Ok(options)
+    }
+}
+
+/// What identifies one version of the watched file. `None` while the file is missing.
+type FileStamp = Option<(SystemTime, u64)>;
+
+fn file_stamp(path: &PathBuf) -> FileStamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Turns a stream of file stamps into "the file changed and has settled" events.
+#[derive(Debug)]
+struct ChangeDetector {
+    debounce: Duration,
+    current: FileStamp,
+    changed_at: Option<Instant>,
+}
+
+impl ChangeDetector {
+    fn new(debounce: Duration) -> Self {
+        ChangeDetector {
+            debounce,
+            current: None,
+            changed_at: None,
+        }
+    }
+
+    /// Records the stamp seen at `now` and returns true once per settled change.
+    fn observe(&mut self, stamp: FileStamp, now: Instant) -> bool {
+        if stamp != self.current {
+            self.current = stamp;
+            self.changed_at = Some(now);
+            return false;
+        }
+        match self.changed_at {
+            Some(changed_at) if stamp.is_some() && now.duration_since(changed_at) >= self.debounce => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Watches `options.path` until the process is interrupted.
+pub fn run(options: &WatchOptions) {
+    println!(
+        "Watching {} (solving for up to {}s after each change)...",
+        options.path.display(),
+        options.timeout_secs
+    );
+    let warm_cache = Arc::new(Mutex::new(solve::WarmCache::new()));
+    let mut detector = ChangeDetector::new(options.debounce);
+    let mut last_solved: Option<GameState> = None;
+
+    loop {
+        if detector.observe(file_stamp(&options.path), Instant::now()) {
+            if let Some(state) = read_board(options) {
+                if last_solved.as_ref() != Some(&state) {
+                    solve_and_print(&state, options, &warm_cache);
+                    last_solved = Some(state);
+                }
+            }
+        }
+        thread::sleep(options.poll_interval);
+    }
+}
+
+/// Reads and imports the board, printing any problems. Returns only complete boards.
+fn read_board(options: &WatchOptions) -> Option<GameState> {
+    let text = match fs::read_to_string(&options.path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", options.path.display(), e);
+            return None;
+        }
+    };
+    let import = match import_board(&text, Variant::Standard) {
+        Ok(import) => import,
+        Err(e) => {
+            eprintln!("Could not import board: {}", e);
+            return None;
+        }
+    };
+    for warning in &import.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    if let Err(e) = import.state.check_integrity() {
+        eprintln!("Not solving an incomplete board: {}", e);
+        return None;
+    }
+    Some(import.state)
+}
+
+fn solve_and_print(state: &GameState, options: &WatchOptions, warm_cache: &Arc<Mutex<solve::WarmCache>>) {
+    let timeout_secs = options.timeout_secs;
+    let result = if options.anytime {
+        // Restarts use their own tables, so the warm cache does not apply.
+        bench::harness_anytime(state.clone(), timeout_secs, |improvement| {
+            println!(
+                "  {} moves after {}ms",
+                improvement.moves.len(),
+                improvement.elapsed.as_millis()
+            );
+        })
+    } else {
+        bench::harness_with_timing_warm(state.clone(), timeout_secs, warm_cache.clone())
+    };
+    let elapsed_ms = result.execution_time.as_millis();
+    match result.solution_moves {
+        Some(moves) if result.solved => {
+            println!("\nSolved in {}ms, {} moves:", elapsed_ms, moves.len());
+            match SolutionPhases::new(state, &moves) {
+                Ok(phases) => println!("{}", phases),
+                Err(e) => eprintln!("Solution does not replay: {}", e),
+            }
+        }
+        _ => println!("\nNo solution found within {}s.", timeout_secs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn from_args_reads_path_and_flags() {
+        let options = WatchOptions::from_args(args(&["board.txt", "--timeout", "3"])).unwrap();
+        assert_eq!(options.path, PathBuf::from("board.txt"));
+        assert_eq!(options.timeout_secs, 3);
+        assert!(!options.anytime);
+        assert!(WatchOptions::from_args(args(&["--anytime", "board.txt"])).unwrap().anytime);
+
+        assert_eq!(
+            WatchOptions::from_args(args(&[])),
+            Err(PlanError::MissingValue("watch".to_string()))
+        );
+        assert!(WatchOptions::from_args(args(&["a.txt", "--poll-ms", "0"])).is_err());
+    }
+
+    #[test]
+    fn changes_fire_once_after_settling() {
+        let debounce = Duration::from_millis(500);
+        let mut detector = ChangeDetector::new(debounce);
+        let start = Instant::now();
+        let v1 = Some((SystemTime::UNIX_EPOCH, 10));
+        let v2 = Some((SystemTime::UNIX_EPOCH, 12));
+
+        assert!(!detector.observe(v1, start));
+        assert!(!detector.observe(v1, start + debounce / 2));
+        // A second write inside the debounce window restarts it.
+        assert!(!detector.observe(v2, start + debounce));
+        assert!(!detector.observe(v2, start + debounce + debounce / 2));
+        assert!(detector.observe(v2, start + debounce * 2));
+        assert!(!detector.observe(v2, start + debounce * 3));
+
+        // A deleted file never triggers.
+        assert!(!detector.observe(None, start + debounce * 4));
+        assert!(!detector.observe(None, start + debounce * 6));
+    }
+}